@@ -1,6 +1,7 @@
 use crate::console::Console;
 use crate::cvar;
 use crate::game_loop::GameLoop;
+use crate::logging;
 use crate::menu::MenuState;
 use crate::render;
 use macroquad::prelude::*;
@@ -16,6 +17,16 @@ pub struct App {
 impl App {
     pub fn new() -> Self {
         cvar::init_default_cvars();
+        logging::init("qconsole");
+        logging::set_capture_console(true);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use crate::game::weapon_defs::{self, WeaponTable};
+            match WeaponTable::load(weapon_defs::DEFAULT_WEAPONS_FILE) {
+                Ok(table) => weapon_defs::install_local(table),
+                Err(e) => crate::log_warn!(Game, "{}, using the built-in weapons", e),
+            }
+        }
         Self {
             menu_state: Some(MenuState::new()),
             game_loop: None,
@@ -49,6 +60,11 @@ impl App {
         loop {
             clear_background(BLACK);
 
+            logging::update();
+            for line in logging::drain_console() {
+                self.console.print(&format!("{}\n", line));
+            }
+
             let mut should_toggle_console =
                 is_key_pressed(KeyCode::GraveAccent) || is_key_pressed(KeyCode::F12);
 
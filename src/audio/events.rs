@@ -62,6 +62,18 @@ pub enum AudioEvent {
     GrenadeBounce {
         x: f32,
     },
+    SplashIn {
+        x: f32,
+    },
+    SplashOut {
+        x: f32,
+    },
+    Underwater {
+        x: f32,
+    },
+    Drowning {
+        x: f32,
+    },
     Award {
         award_type: AwardType,
     },
@@ -71,6 +83,12 @@ pub enum AudioEvent {
     LeadChange {
         announcement: String,
     },
+    CtfAnnouncement {
+        announcement: String,
+    },
+    RoundAnnouncement {
+        announcement: String,
+    },
     MatchStart,
     MatchEnd,
 }
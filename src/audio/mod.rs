@@ -1,3 +1,4 @@
+use crate::log_debug;
 pub mod events;
 
 use events::AudioEvent;
@@ -117,6 +118,9 @@ impl AudioSystem {
                     Weapon::Railgun => "railgun_fire",
                     Weapon::Plasmagun => "plasma_fire",
                     Weapon::BFG => "bfg_fire",
+                    Weapon::Nailgun => "nailgun_fire",
+                    Weapon::ProxLauncher => "prox_fire",
+                    Weapon::Chaingun => "chaingun_fire",
                 };
                 let volume = match weapon {
                     Weapon::MachineGun => 0.3,
@@ -128,6 +132,9 @@ impl AudioSystem {
                     Weapon::RocketLauncher => 0.6,
                     Weapon::Railgun => 0.7,
                     Weapon::BFG => 0.8,
+                    Weapon::Nailgun => 0.5,
+                    Weapon::ProxLauncher => 0.5,
+                    Weapon::Chaingun => 0.3,
                 };
                 self.play_positional(sound_name, volume, *x, listener_x);
             }
@@ -201,6 +208,24 @@ impl AudioSystem {
             AudioEvent::RailgunHit { x } => {
                 self.play_positional("railgun_hit", 0.6, *x, listener_x);
             }
+            AudioEvent::SplashIn { x } => {
+                self.play_positional("water_in", 0.5, *x, listener_x);
+            }
+            AudioEvent::SplashOut { x } => {
+                self.play_positional("water_out", 0.5, *x, listener_x);
+            }
+            AudioEvent::Underwater { x } => {
+                self.play_positional("water_under", 0.5, *x, listener_x);
+            }
+            AudioEvent::Drowning { x } => {
+                use macroquad::rand::gen_range;
+                let sound_name = if gen_range(0, 2) == 0 {
+                    "gurp1"
+                } else {
+                    "gurp2"
+                };
+                self.play_positional(sound_name, 0.6, *x, listener_x);
+            }
             AudioEvent::GrenadeBounce { x } => {
                 use macroquad::rand::gen_range;
                 let sound_name = if gen_range(0, 2) == 0 {
@@ -224,9 +249,15 @@ impl AudioSystem {
                 self.play(announcement, 0.8);
             }
             AudioEvent::LeadChange { announcement } => {
-                println!("[AUDIO] Playing LeadChange sound: {}", announcement);
+                log_debug!(Audio, "Playing LeadChange sound: {}", announcement);
                 self.play(announcement, 0.8);
             }
+            AudioEvent::CtfAnnouncement { announcement } => {
+                self.play(announcement, 0.9);
+            }
+            AudioEvent::RoundAnnouncement { announcement } => {
+                self.play(announcement, 0.9);
+            }
             AudioEvent::MatchStart => {
                 self.play("fight", 0.8);
             }
@@ -336,6 +367,24 @@ pub async fn init_audio() -> AudioSystem {
     audio
         .load_sound("gauntlet", "q3-resources/sound/weapons/melee/fstatck.wav")
         .await;
+    audio
+        .load_sound(
+            "nailgun_fire",
+            "q3-resources/sound/weapons/nailgun/wnalfire.wav",
+        )
+        .await;
+    audio
+        .load_sound(
+            "prox_fire",
+            "q3-resources/sound/weapons/proxmine/wstbfire.wav",
+        )
+        .await;
+    audio
+        .load_sound(
+            "chaingun_fire",
+            "q3-resources/sound/weapons/vulcan/vulcanf1b.wav",
+        )
+        .await;
 
     audio
         .load_sound("land", "q3-resources/sound/player/land1.wav")
@@ -343,6 +392,21 @@ pub async fn init_audio() -> AudioSystem {
     audio
         .load_sound("gib", "q3-resources/sound/player/gibsplt1.wav")
         .await;
+    audio
+        .load_sound("water_in", "q3-resources/sound/player/watr_in.wav")
+        .await;
+    audio
+        .load_sound("water_out", "q3-resources/sound/player/watr_out.wav")
+        .await;
+    audio
+        .load_sound("water_under", "q3-resources/sound/player/watr_un.wav")
+        .await;
+    audio
+        .load_sound("gurp1", "q3-resources/sound/player/gurp1.wav")
+        .await;
+    audio
+        .load_sound("gurp2", "q3-resources/sound/player/gurp2.wav")
+        .await;
 
     audio
         .load_sound("footstep1", "q3-resources/sound/player/footsteps/step1.wav")
@@ -431,9 +495,33 @@ pub async fn init_audio() -> AudioSystem {
     audio
         .load_sound("1_minute", "q3-resources/sound/feedback/1_minute.wav")
         .await;
+    audio
+        .load_sound("1_frag", "q3-resources/sound/feedback/1_frag.wav")
+        .await;
+    audio
+        .load_sound("2_frags", "q3-resources/sound/feedback/2_frags.wav")
+        .await;
+    audio
+        .load_sound("3_frags", "q3-resources/sound/feedback/3_frags.wav")
+        .await;
+    audio
+        .load_sound(
+            "sudden_death",
+            "q3-resources/sound/feedback/sudden_death.wav",
+        )
+        .await;
     audio
         .load_sound("prepare", "q3-resources/sound/feedback/prepare.wav")
         .await;
+    audio
+        .load_sound("three", "q3-resources/sound/feedback/three.wav")
+        .await;
+    audio
+        .load_sound("two", "q3-resources/sound/feedback/two.wav")
+        .await;
+    audio
+        .load_sound("one", "q3-resources/sound/feedback/one.wav")
+        .await;
 
     audio
         .load_sound(
@@ -450,6 +538,58 @@ pub async fn init_audio() -> AudioSystem {
     audio
         .load_sound("lost_the_lead", "q3-resources/sound/feedback/lostlead.wav")
         .await;
+    audio
+        .load_sound("red_leads", "q3-resources/sound/feedback/redleads.wav")
+        .await;
+    audio
+        .load_sound("blue_leads", "q3-resources/sound/feedback/blueleads.wav")
+        .await;
+    audio
+        .load_sound("teams_tied", "q3-resources/sound/feedback/teamstied.wav")
+        .await;
+
+    audio
+        .load_sound(
+            "you_have_flag",
+            "q3-resources/sound/teamplay/voc_you_flag.wav",
+        )
+        .await;
+    audio
+        .load_sound(
+            "team_has_flag",
+            "q3-resources/sound/teamplay/voc_team_flag.wav",
+        )
+        .await;
+    audio
+        .load_sound(
+            "enemy_has_flag",
+            "q3-resources/sound/teamplay/voc_enemy_flag.wav",
+        )
+        .await;
+    audio
+        .load_sound(
+            "red_scores",
+            "q3-resources/sound/teamplay/voc_red_scores.wav",
+        )
+        .await;
+    audio
+        .load_sound(
+            "blue_scores",
+            "q3-resources/sound/teamplay/voc_blue_scores.wav",
+        )
+        .await;
+    audio
+        .load_sound(
+            "red_returned",
+            "q3-resources/sound/teamplay/voc_red_returned.wav",
+        )
+        .await;
+    audio
+        .load_sound(
+            "blue_returned",
+            "q3-resources/sound/teamplay/voc_blue_returned.wav",
+        )
+        .await;
 
     audio
 }
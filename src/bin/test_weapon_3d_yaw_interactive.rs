@@ -39,6 +39,9 @@ impl Viewer {
             Weapon::Railgun => "q3-resources/models/weapons2/railgun/railgun.md3",
             Weapon::Plasmagun => "q3-resources/models/weapons2/plasma/plasma.md3",
             Weapon::BFG => "q3-resources/models/weapons2/bfg/bfg.md3",
+            Weapon::Nailgun => "q3-resources/models/weapons/nailgun/nailgun.md3",
+            Weapon::ProxLauncher => "q3-resources/models/weapons/proxmine/proxmine.md3",
+            Weapon::Chaingun => "q3-resources/models/weapons/vulcan/vulcan.md3",
         };
 
         let weapon_model = MD3Model::load_async(weapon_path).await.ok();
@@ -54,6 +57,9 @@ impl Viewer {
             Weapon::Railgun => "models/weapons2/railgun/railgun.png",
             Weapon::Plasmagun => "models/weapons2/plasma/plasma.png",
             Weapon::BFG => "models/weapons2/bfg/bfg.png",
+            Weapon::Nailgun => "models/weapons/nailgun/nailgun.png",
+            Weapon::ProxLauncher => "models/weapons/proxmine/proxmine.png",
+            Weapon::Chaingun => "models/weapons/vulcan/vulcan.png",
         };
 
         if let Some(ref weapon_mdl) = weapon_model {
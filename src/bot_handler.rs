@@ -1,5 +1,12 @@
 use crate::audio;
-use crate::game::{player::Player, weapon::Weapon, GameState};
+use crate::game::{
+    player::Player,
+    weapon::{Weapon, WEAPON_COUNT},
+    GameState,
+};
+
+/// Bots fire their holdable once they drop below this much health.
+const BOT_USE_HOLDABLE_HEALTH: i32 = 40;
 
 pub struct BotHandler;
 
@@ -14,9 +21,17 @@ impl BotHandler {
 
         let players_snapshot = game_state.players.clone();
         let mut bot_actions = Vec::new();
+        let mut bot_item_uses = Vec::new();
 
         for i in 1..game_state.players.len() {
-            if !game_state.players[i].is_bot || game_state.players[i].dead {
+            if !game_state.players[i].is_bot {
+                continue;
+            }
+            if game_state.players[i].dead {
+                if game_state.players[i].frozen {
+                    let idle = crate::game::usercmd::UserCmd::new();
+                    game_state.players[i].pmove(&idle, dt, &game_state.map, &game_state.physics);
+                }
                 continue;
             }
 
@@ -54,7 +69,7 @@ impl BotHandler {
             let bot_crouch = bot.crouch;
             let bot_refire = bot.refire;
             let bot_weapon_switch_time = bot.weapon_switch_time;
-            let bot_powerups = bot.powerups.clone();
+            let bot_powerups = bot.powerups;
             let bot_animation = bot.animation.clone();
             let bot_was_in_air = bot.was_in_air;
             let bot_respawn_timer = bot.respawn_timer;
@@ -144,6 +159,9 @@ impl BotHandler {
             if ai_want_jump {
                 bot_cmd.buttons |= crate::game::usercmd::BUTTON_JUMP;
             }
+            if bot.holdable.is_some() && bot.health < BOT_USE_HOLDABLE_HEALTH {
+                bot_cmd.buttons |= crate::game::usercmd::BUTTON_USE_ITEM;
+            }
 
             if let Some(target_id) = ai_target_player {
                 if let Some(target) = players_snapshot.iter().find(|p| p.id == target_id) {
@@ -154,7 +172,10 @@ impl BotHandler {
                 }
             }
 
-            let bot_pmove_events = bot.pmove(&bot_cmd, dt, &game_state.map);
+            if bot.use_item_pressed(&bot_cmd) {
+                bot_item_uses.push(i);
+            }
+            let bot_pmove_events = bot.pmove(&bot_cmd, dt, &game_state.map, &game_state.physics);
             for event in bot_pmove_events {
                 game_state.audio_events.push(event);
             }
@@ -179,6 +200,10 @@ impl BotHandler {
             }
         }
 
+        for idx in bot_item_uses {
+            game_state.use_holdable(idx);
+        }
+
         Self::process_bot_actions(game_state, bot_actions)
     }
 
@@ -188,8 +213,8 @@ impl BotHandler {
         bot_y: f32,
         bot_vel_y: f32,
         bot_weapon: Weapon,
-        bot_has_weapon: [bool; 9],
-        bot_ammo: [u8; 9],
+        bot_has_weapon: [bool; WEAPON_COUNT],
+        bot_ammo: [u8; WEAPON_COUNT],
         bot_name: String,
         bot_model: String,
         bot_cx: f32,
@@ -250,6 +275,8 @@ impl BotHandler {
             team: bot_team,
             dead: bot_dead,
             gibbed: bot_gibbed,
+            frozen: false,
+            thaw_progress: 0.0,
             is_bot: bot_is_bot,
             crouch: bot_crouch,
             weapon: bot_weapon,
@@ -258,9 +285,18 @@ impl BotHandler {
             refire: bot_refire,
             weapon_switch_time: bot_weapon_switch_time,
             powerups: bot_powerups,
+            holdable: None,
+            use_held: false,
+            grapple: Default::default(),
             animation: bot_animation,
             bot_ai: None,
             was_in_air: bot_was_in_air,
+            jump_time: 0.0,
+            fall_speed: 0.0,
+            fall_damage: 0,
+            water_level: 0,
+            liquid: None,
+            breath: Default::default(),
             respawn_timer: bot_respawn_timer,
             lower_frame: bot_lower_frame as usize,
             upper_frame: bot_upper_frame as usize,
@@ -313,7 +349,7 @@ impl BotHandler {
             } else if bot.weapon == Weapon::RocketLauncher && bot.ammo[4] > 0 {
                 let angle = std::f32::consts::PI / 2.0;
 
-                bot.refire = bot.weapon.refire_time_seconds();
+                bot.refire = bot.refire_time();
                 bot.ammo[4] = bot.ammo[4].saturating_sub(1);
 
                 let flip = false;
@@ -347,7 +383,7 @@ impl BotHandler {
 
                     let weapon_idx = bot.weapon as usize;
                     if bot.ammo[weapon_idx] >= bot.weapon.ammo_per_shot() || bot.weapon as u8 == 0 {
-                        bot.refire = bot.weapon.refire_time_seconds();
+                        bot.refire = bot.refire_time();
                         if bot.weapon as u8 > 0 {
                             bot.ammo[weapon_idx] =
                                 bot.ammo[weapon_idx].saturating_sub(bot.weapon.ammo_per_shot());
@@ -37,6 +37,9 @@ pub struct Console {
     pub server_bot_add_request: bool,
     pub is_connected_to_server: bool,
     pub end_match_request: bool,
+    pub ready_request: bool,
+    pub kill_request: bool,
+    pub pause_request: Option<bool>,
 }
 
 #[derive(Clone, Copy)]
@@ -74,6 +77,9 @@ impl Console {
             server_bot_add_request: false,
             is_connected_to_server: false,
             end_match_request: false,
+            ready_request: false,
+            kill_request: false,
+            pause_request: None,
         }
     }
 
@@ -346,6 +352,10 @@ impl Console {
             "help",
             "set",
             "endmatch",
+            "ready",
+            "kill",
+            "timeout",
+            "timein",
         ];
 
         let mut all_matches = Vec::new();
@@ -486,6 +496,31 @@ impl Console {
         } else if cmd == "endmatch" {
             self.end_match_request = true;
             self.print("Ending match...\n");
+        } else if cmd == "ready" {
+            self.ready_request = true;
+        } else if cmd == "kill" {
+            self.kill_request = true;
+        } else if cmd == "pause" || cmd == "timeout" {
+            self.pause_request = Some(true);
+        } else if cmd == "unpause" || cmd == "timein" {
+            self.pause_request = Some(false);
+        } else if cmd == "bind" {
+            match parts.get(2).copied() {
+                Some("+hook") if crate::input::is_bind_name(parts[1]) => {
+                    cvar::set_cvar("cl_hookkey", parts[1]);
+                    self.print(&format!("{} bound to +hook\n", parts[1]));
+                }
+                Some("+hook") => self.print(&format!("Unknown key: {}\n", parts[1])),
+                None if parts.len() == 2 => {
+                    let hook_key = cvar::get_cvar_string("cl_hookkey");
+                    if hook_key.eq_ignore_ascii_case(parts[1]) {
+                        self.print(&format!("\"{}\" = \"+hook\"\n", parts[1]));
+                    } else {
+                        self.print(&format!("\"{}\" is not bound\n", parts[1]));
+                    }
+                }
+                _ => self.print("Usage: bind <key> +hook\n"),
+            }
         } else if cmd == "help" || cmd == "?" {
             self.print("Console commands:\n");
             self.print("  clear/cls - Clear console\n");
@@ -500,6 +535,10 @@ impl Console {
             self.print("  connect <server:port> [name] - Connect to server\n");
             self.print("  disconnect - Disconnect from server\n");
             self.print("  endmatch - End current match\n");
+            self.print("  ready - Toggle ready during warmup (key: Y)\n");
+            self.print("  kill - Suicide\n");
+            self.print("  timeout/timein - Call or end a timeout on a server\n");
+            self.print("  bind <key> +hook - Grappling hook key (default MOUSE2)\n");
             self.print("  set <cvar> <value> - Set cvar\n");
             self.print("  <cvar> - Show cvar value\n");
             self.print("  <cvar> <value> - Set cvar value\n");
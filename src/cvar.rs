@@ -180,12 +180,58 @@ pub fn init_default_cvars() {
     register_cvar("m_grab", "1", CVAR_ARCHIVE);
     register_cvar("m_show_cursor", "0", CVAR_ARCHIVE);
 
+    register_cvar("cl_hookkey", "MOUSE2", CVAR_ARCHIVE);
+
     register_cvar("cl_timeNudge", "0", CVAR_ARCHIVE);
     register_cvar("cl_autoNudge", "0", CVAR_ARCHIVE);
 
+    init_log_cvars();
+    init_server_cvars();
+
     load_config();
 }
 
+pub fn init_log_cvars() {
+    register_cvar("developer", "0", 0);
+    register_cvar("logfile", "0", 0);
+    register_cvar("log_json", "0", 0);
+}
+
+pub fn init_server_cvars() {
+    register_cvar("g_filterban", "1", 0);
+    register_cvar("g_gametype", "0", 0);
+    register_cvar("g_roundlimit", "5", 0);
+    register_cvar("fraglimit", "20", 0);
+    register_cvar("capturelimit", "8", 0);
+    register_cvar("mercylimit", "0", 0);
+    register_cvar("g_overtime", "0", 0);
+    register_cvar("g_duelqueue", "1", 0);
+    register_cvar("g_mutators", "", 0);
+    register_cvar("g_warmup", "1", 0);
+    register_cvar("g_minready", "2", 0);
+    register_cvar("g_timeouts", "2", 0);
+    register_cvar("g_timeoutlen", "60", 0);
+    register_cvar("g_statsdir", "stats", 0);
+    register_cvar("g_grapple", "0", 0);
+    register_cvar("g_grapplepull", "0.5", 0);
+    register_cvar("g_weaponfile", "weapons.json", 0);
+    register_cvar("g_enforceweapons", "1", 0);
+    register_cvar("g_fallingdamage", "1", 0);
+    register_cvar("g_friendlyfire", "0", 0);
+    register_cvar("g_selfdamage", "1", 0);
+    register_cvar("g_knockback", "1600", 0);
+    register_cvar("pm_physics", "vq3", 0);
+    register_cvar("pm_gravity", "0.056", 0);
+    register_cvar("pm_accel", "0.35", 0);
+    register_cvar("pm_airaccel", "0.35", 0);
+    register_cvar("pm_maxspeed", "5", 0);
+    register_cvar("pm_airmaxspeed", "6", 0);
+    register_cvar("pm_jumpforce", "2.9", 0);
+    register_cvar("pm_aircontrol", "1", 0);
+    register_cvar("pm_doublejump", "0", 0);
+    register_cvar("pm_rampjump", "0", 0);
+}
+
 pub fn load_config() {
     #[cfg(not(target_arch = "wasm32"))]
     {
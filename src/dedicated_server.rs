@@ -1,12 +1,36 @@
+use sas::game::bg_combat::{self, Combatant, DamageRules};
 use sas::game::bg_pmove::{pmove, PmoveCmd, PmoveState};
-use sas::game::map::Map;
+use sas::game::collision;
+use sas::game::constants::WORLD_KILL_DAMAGE;
+use sas::game::ctf::{CtfPlayer, CtfState, FlagEvent};
+use sas::game::duel_queue::DuelQueue;
+use sas::game::freeze::{self, FreezePlayer};
+use sas::game::gametype::{self, Gametype, GametypeKind, MatchWinner, ScoreEntry, TeamScores};
+use sas::game::grapple::Grapple;
+use sas::game::liquid::Breath;
+use sas::game::map::{Liquid, Map};
+use sas::game::match_rules::{EndReason, LimitEvent, MatchRules, INTERMISSION_TIME};
+use sas::game::match_stats::MatchStats;
+use sas::game::means_of_death::{MeansOfDeath, WORLD_ID};
+use sas::game::mutators::Mutators;
+use sas::game::pause::{PauseEvent, PauseState};
+use sas::game::physics::PhysicsProfile;
+use sas::game::pickup::{self, Holdable, ItemHolder, PowerUps};
 use sas::game::projectile::Projectile;
-use sas::game::usercmd::UserCmd;
+use sas::game::round::{RoundEvent, RoundPlayer, RoundState};
+use sas::game::tournament::Tournament;
+use sas::game::usercmd::{UserCmd, BUTTON_HOOK, BUTTON_USE_ITEM};
+use sas::game::warmup::{WarmupEvent, WarmupState};
+use sas::game::weapon::{Weapon, WEAPON_COUNT};
+use sas::game::weapon_defs::{self, WeaponTable};
+use sas::network::ban_list::{parse_duration, DEFAULT_BAN_FILE};
 use sas::network::{
-    get_absolute_time, get_network_time, server::NetworkServer, NetMessage, NetworkConfig,
-    PlayerState, Trajectory, TrajectoryType,
+    get_network_time, server::NetworkServer, BanList, InputValidator, MetricsServer, MetricsWriter,
+    NetMessage, NetworkConfig, PlayerState, Trajectory, TrajectoryType,
 };
-use std::collections::HashMap;
+use sas::{log_debug, log_info, log_trace, log_warn};
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver};
 
 const GIB_HEALTH: i32 = -40;
 
@@ -17,6 +41,9 @@ struct DedicatedServer {
     pmove_accumulator: f32,
     last_frame_time: std::time::Instant,
     next_bot_id: u16,
+    /// Stdin commands, read once the server is started; soak runs go without.
+    console_rx: Option<Receiver<String>>,
+    metrics: Option<MetricsServer>,
 }
 
 struct GameState {
@@ -25,6 +52,29 @@ struct GameState {
     projectiles: Vec<Projectile>,
     tick: u32,
     next_projectile_id: u32,
+    gametype: Box<dyn Gametype>,
+    mutators: Mutators,
+    physics: PhysicsProfile,
+    match_time: f32,
+    ctf: CtfState,
+    round: RoundState,
+    warmup: WarmupState,
+    pause: PauseState,
+    rules: MatchRules,
+    /// Seconds left on the end-of-match scoreboard before the restart.
+    intermission: Option<f32>,
+    /// Spectators waiting for a slot when the gametype caps the players.
+    queue: DuelQueue,
+    /// Loser and challenger that swap places when the next match starts.
+    pending_rotation: Option<(u16, u16)>,
+    tournament: Option<Tournament>,
+    stats: MatchStats,
+}
+
+impl GameState {
+    fn spawn_loadout(&self) -> Option<gametype::Loadout> {
+        self.mutators.spawn_loadout(self.gametype.spawn_loadout())
+    }
 }
 
 struct ServerPlayer {
@@ -34,101 +84,1403 @@ struct ServerPlayer {
     health: i32,
     armor: i32,
     weapon: u8,
-    ammo: [u16; 10],
+    ammo: [u16; WEAPON_COUNT],
     frags: i32,
     deaths: i32,
-    powerup_quad: u16,
+    team: u8,
+    powerups: PowerUps,
+    holdable: Option<Holdable>,
+    /// Use-item button state from the last command, to act on presses only.
+    use_held: bool,
+    grapple: Grapple,
+    water_level: u8,
+    liquid: Option<Liquid>,
+    breath: Breath,
     last_cmd: UserCmd,
     pending_commands: Vec<UserCmd>,
     last_executed_time: u32,
     dead: bool,
     gibbed: bool,
+    frozen: bool,
+    thaw_progress: f32,
     respawn_timer: f32,
     corpse_timer: f32,
     is_bot: bool,
     bot_ai: Option<sas::game::bot_ai::BotAI>,
+    input_validator: InputValidator,
 }
 
-impl DedicatedServer {
-    fn new(config: NetworkConfig, map_name: String) -> Self {
-        let map = Map::load_from_file(&map_name).unwrap_or_else(|e| {
-            eprintln!("Failed to load map '{}': {}", map_name, e);
-            eprintln!("Using default map");
-            Map::new(&map_name)
-        });
+impl ServerPlayer {
+    fn apply_loadout(&mut self, loadout: &gametype::Loadout) {
+        self.health = loadout.health;
+        self.armor = loadout.armor;
+        for (ammo, &amount) in self.ammo.iter_mut().zip(loadout.ammo.iter()) {
+            *ammo = amount as u16;
+        }
+        self.weapon = loadout.weapon as u8;
+        if loadout.quad {
+            self.powerups.quad = u16::MAX;
+        }
+    }
+
+    /// True on the command where the use-item button goes down.
+    fn use_item_pressed(&mut self, buttons: u8) -> bool {
+        let down = buttons & BUTTON_USE_ITEM != 0;
+        let pressed = down && !self.use_held;
+        self.use_held = down;
+        pressed
+    }
+}
+
+impl ItemHolder for ServerPlayer {
+    fn health_mut(&mut self) -> &mut i32 {
+        &mut self.health
+    }
+
+    fn armor_mut(&mut self) -> &mut i32 {
+        &mut self.armor
+    }
+
+    fn powerups_mut(&mut self) -> &mut PowerUps {
+        &mut self.powerups
+    }
+
+    fn holdable_mut(&mut self) -> &mut Option<Holdable> {
+        &mut self.holdable
+    }
+
+    fn give_weapon(&mut self, weapon: Weapon, ammo: u16, max_ammo: u16) {
+        let slot = &mut self.ammo[weapon as usize];
+        *slot = (*slot + ammo).min(max_ammo);
+    }
+}
+
+impl DedicatedServer {
+    fn new(config: NetworkConfig, map_name: String) -> Self {
+        let mut map = Map::load_from_file(&map_name).unwrap_or_else(|e| {
+            log_warn!(Game, "Failed to load map '{}': {}", map_name, e);
+            log_warn!(Game, "Using default map");
+            Map::new(&map_name)
+        });
+        let mutators = Mutators::from_cvar();
+        let mut physics = PhysicsProfile::from_cvars();
+        mutators.apply_physics(&mut physics);
+        mutators.filter_items(&mut map.items);
+
+        log_info!(Game, "Loaded map: {}", map_name);
+        log_info!(Game, "Spawn points: {}", map.spawn_points.len());
+
+        let mut server = NetworkServer::new(config);
+        let mut ban_list = BanList::load(DEFAULT_BAN_FILE);
+        ban_list.filter_ban = sas::cvar::get_cvar_integer("g_filterban") != 0;
+        server.set_ban_list(ban_list);
+
+        let ctf = CtfState::new(&map.flags);
+
+        Self {
+            server,
+            game_state: GameState {
+                map,
+                players: HashMap::new(),
+                projectiles: Vec::new(),
+                tick: 0,
+                next_projectile_id: 1,
+                gametype: gametype::from_cvar(),
+                mutators,
+                physics,
+                match_time: 0.0,
+                ctf,
+                round: RoundState::from_cvar(),
+                warmup: WarmupState::from_cvar(),
+                pause: PauseState::from_cvar(),
+                rules: MatchRules::from_cvar(),
+                intermission: None,
+                queue: DuelQueue::from_cvar(),
+                pending_rotation: None,
+                tournament: None,
+                stats: MatchStats::new(),
+            },
+            map_name,
+            pmove_accumulator: 0.0,
+            last_frame_time: std::time::Instant::now(),
+            next_bot_id: 1000,
+            console_rx: None,
+            metrics: None,
+        }
+    }
+
+    fn start(&mut self) -> Result<(), String> {
+        self.server.start()?;
+        self.console_rx = Some(spawn_console_reader());
+        self.warn_if_unplayable();
+        println!("Dedicated server running. Press Ctrl+C to stop.");
+        Ok(())
+    }
+
+    fn run(&mut self) {
+        const FIXED_DT: f32 = 1.0 / 60.0;
+
+        loop {
+            let now = std::time::Instant::now();
+            let dt = now.duration_since(self.last_frame_time).as_secs_f32();
+            self.last_frame_time = now;
+
+            let (messages, timed_out_clients) = self.server.update();
+
+            for client_id in timed_out_clients {
+                if let Some(player) = self.game_state.players.get(&client_id) {
+                    if !player.is_bot {
+                        log_info!(
+                            Game,
+                            "Removing timed out player {} ({})",
+                            client_id,
+                            player.name
+                        );
+                        self.remove_player(client_id);
+                    }
+                }
+            }
+
+            for (client_id, msg) in messages {
+                self.handle_message(client_id, msg);
+            }
+
+            let lines: Vec<String> = self
+                .console_rx
+                .as_ref()
+                .map(|rx| rx.try_iter().collect())
+                .unwrap_or_default();
+            for line in lines {
+                self.execute_command(&line);
+            }
+
+            self.pmove_accumulator += dt;
+
+            while self.pmove_accumulator >= FIXED_DT {
+                {
+                    #[cfg(feature = "profiler")]
+                    let _scope = sas::profiler::scope("server_tick");
+                    self.run_tick(FIXED_DT);
+                }
+                #[cfg(feature = "profiler")]
+                sas::profiler::end_frame();
+                self.pmove_accumulator -= FIXED_DT;
+            }
+
+            if let Some(mut metrics) = self.metrics.take() {
+                metrics.publish(|| self.render_metrics());
+                self.metrics = Some(metrics);
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(16));
+        }
+    }
+
+    fn enable_metrics(&mut self, port: u16) -> Result<(), String> {
+        self.metrics = Some(MetricsServer::bind(&format!("127.0.0.1:{}", port))?);
+        #[cfg(feature = "profiler")]
+        if !sas::profiler::is_enabled() {
+            sas::profiler::toggle();
+        }
+        Ok(())
+    }
+
+    fn render_metrics(&self) -> String {
+        let mut metrics = MetricsWriter::new();
+        self.server.write_metrics(&mut metrics);
+
+        #[cfg(feature = "profiler")]
+        {
+            let history = sas::profiler::get_history("server_tick");
+            for (label, q) in [("0.5", 0.5), ("0.9", 0.9), ("0.99", 0.99)] {
+                metrics.gauge_with_labels(
+                    "sas_tick_duration_ms",
+                    "Server tick duration over the last profiler window",
+                    &[("quantile", label)],
+                    sas::network::metrics::percentile(&history, q),
+                );
+            }
+        }
+
+        let bots = self
+            .game_state
+            .players
+            .values()
+            .filter(|p| p.is_bot)
+            .count();
+        let humans = self.game_state.players.len() - bots;
+        metrics.gauge_with_labels(
+            "sas_players",
+            "Players in game",
+            &[("kind", "human")],
+            humans as f64,
+        );
+        metrics.gauge_with_labels(
+            "sas_players",
+            "Players in game",
+            &[("kind", "bot")],
+            bots as f64,
+        );
+        metrics.gauge(
+            "sas_projectiles_alive",
+            "Projectiles currently simulated",
+            self.game_state.projectiles.len() as f64,
+        );
+        metrics.counter(
+            "sas_ticks_total",
+            "Simulation ticks since the current map started",
+            self.game_state.tick as f64,
+        );
+        metrics.gauge(
+            "sas_match_time_seconds",
+            "Match clock, stopped during warmup, pauses and intermission",
+            self.game_state.match_time as f64,
+        );
+        metrics.gauge_with_labels(
+            "sas_match_info",
+            "Current map and gametype",
+            &[
+                ("map", self.map_name.as_str()),
+                ("gametype", self.game_state.gametype.kind().short_name()),
+            ],
+            1.0,
+        );
+
+        let mut player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        player_ids.sort();
+        for id in player_ids {
+            let player = &self.game_state.players[&id];
+            let id_str = id.to_string();
+            let labels = [("player", id_str.as_str()), ("name", player.name.as_str())];
+            metrics.gauge_with_labels(
+                "sas_player_frags",
+                "Player frags",
+                &labels,
+                player.frags as f64,
+            );
+            metrics.gauge_with_labels(
+                "sas_player_deaths",
+                "Player deaths",
+                &labels,
+                player.deaths as f64,
+            );
+        }
+
+        metrics.finish()
+    }
+
+    fn run_tick(&mut self, dt: f32) {
+        if self.game_state.pause.is_paused() {
+            self.update_pause(dt);
+            self.game_state.tick += 1;
+            if self.game_state.tick.is_multiple_of(2) {
+                self.broadcast_game_state();
+            }
+            return;
+        }
+
+        if self.game_state.intermission.is_some() {
+            self.update_intermission(dt);
+            self.game_state.tick += 1;
+            if self.game_state.tick.is_multiple_of(2) {
+                self.broadcast_game_state();
+            }
+            return;
+        }
+
+        self.update_bot_ai(dt);
+        self.simulate_physics(dt);
+        self.update_projectiles(dt);
+        self.check_collisions();
+        self.game_state.stats.end_frame();
+        self.check_item_pickups();
+        if self.game_state.gametype.uses_flags() {
+            self.update_flags(dt);
+        }
+        self.update_warmup(dt);
+        let live = self.game_state.warmup.is_live();
+        if live && self.game_state.gametype.freezes_on_death() {
+            self.update_freeze(dt);
+        }
+        if self.rounds_running() {
+            self.update_rounds(dt);
+        }
+
+        self.game_state.tick += 1;
+        if live {
+            self.game_state.match_time += dt;
+            self.update_limits();
+        }
+
+        if self.game_state.tick.is_multiple_of(2) {
+            self.broadcast_game_state();
+        }
+
+        if self.game_state.tick.is_multiple_of(300) {
+            self.log_input_validation();
+        }
+    }
+
+    fn run_soak(&mut self, config: &SoakConfig) -> SoakReport {
+        const FIXED_DT: f32 = 1.0 / 60.0;
+
+        for _ in 0..config.bots {
+            self.handle_addbot_command(0);
+        }
+
+        let mut report = SoakReport::default();
+        let total_ticks = (config.duration_secs * 60.0) as u64;
+        let ticks_per_map = ((config.map_time_secs * 60.0) as u64).max(1);
+        let mut map_index = 0;
+        let started = std::time::Instant::now();
+
+        log_info!(
+            Game,
+            "[SOAK] {} bots, {} ticks ({:.0}s simulated), maps: {}",
+            config.bots,
+            total_ticks,
+            config.duration_secs,
+            config.maps.join(", ")
+        );
+
+        for tick in 0..total_ticks {
+            if tick > 0 && tick % ticks_per_map == 0 && config.maps.len() > 1 {
+                map_index = (map_index + 1) % config.maps.len();
+                self.change_map(&config.maps[map_index]);
+                report.map_changes += 1;
+            }
+
+            self.run_tick(FIXED_DT);
+            report.ticks += 1;
+            report.peak_projectiles = report
+                .peak_projectiles
+                .max(self.game_state.projectiles.len());
+            self.check_invariants(&mut report);
+
+            if tick > 0 && tick % (60 * 60) == 0 {
+                log_info!(
+                    Game,
+                    "[SOAK] {:.0} min simulated, {:.0} ticks/s, {} violations",
+                    tick as f64 / 3600.0,
+                    tick as f64 / started.elapsed().as_secs_f64().max(0.001),
+                    report.violations
+                );
+            }
+        }
+
+        report.elapsed_secs = started.elapsed().as_secs_f64();
+        let mut frags: Vec<(u16, String, i32, i32)> = self
+            .game_state
+            .players
+            .iter()
+            .map(|(id, p)| (*id, p.name.clone(), p.frags, p.deaths))
+            .collect();
+        frags.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+        report.frags = frags;
+        report
+    }
+
+    fn check_invariants(&mut self, report: &mut SoakReport) {
+        const MAX_LOGGED_VIOLATIONS: usize = 20;
+        let mut found = Vec::new();
+
+        for (id, player) in &self.game_state.players {
+            let ps = &player.pmove_state;
+            if !ps.x.is_finite()
+                || !ps.y.is_finite()
+                || !ps.vel_x.is_finite()
+                || !ps.vel_y.is_finite()
+            {
+                found.push(format!(
+                    "p{} ({}) non-finite state pos=({}, {}) vel=({}, {})",
+                    id, player.name, ps.x, ps.y, ps.vel_x, ps.vel_y
+                ));
+                continue;
+            }
+
+            let tile_x = (ps.x / 32.0).floor() as i32;
+            let tile_y = ((ps.y - 24.0) / 16.0).floor() as i32;
+            if !player.dead && self.game_state.map.is_solid(tile_x, tile_y) {
+                if report.players_in_solid.insert(*id) {
+                    found.push(format!(
+                        "p{} ({}) inside solid tile ({}, {}) at ({:.1}, {:.1})",
+                        id, player.name, tile_x, tile_y, ps.x, ps.y
+                    ));
+                }
+            } else {
+                report.players_in_solid.remove(id);
+            }
+        }
+
+        for proj in &self.game_state.projectiles {
+            if !proj.x.is_finite() || !proj.y.is_finite() {
+                found.push(format!(
+                    "projectile {} non-finite position ({}, {})",
+                    proj.id, proj.x, proj.y
+                ));
+            }
+        }
+
+        for violation in found {
+            report.violations += 1;
+            if report.violation_log.len() < MAX_LOGGED_VIOLATIONS {
+                log_warn!(
+                    Game,
+                    "[SOAK] Violation at tick {} on {}: {}",
+                    self.game_state.tick, self.map_name, violation
+                );
+                report.violation_log.push(format!(
+                    "tick {} on {}: {}",
+                    self.game_state.tick, self.map_name, violation
+                ));
+            }
+        }
+    }
+
+    fn score_entries(&self) -> Vec<ScoreEntry> {
+        let mut entries: Vec<ScoreEntry> = self
+            .game_state
+            .players
+            .iter()
+            .map(|(id, p)| ScoreEntry {
+                id: *id,
+                team: p.team,
+                frags: p.frags,
+            })
+            .collect();
+        entries.sort_by_key(|e| e.id);
+        entries
+    }
+
+    fn award_kill(&mut self, killer_id: u16, victim_id: u16, means: MeansOfDeath) {
+        if !self.game_state.warmup.is_live() {
+            return;
+        }
+        self.game_state
+            .stats
+            .record_death(killer_id, victim_id, means);
+        let entries = self.score_entries();
+        let killer = entries.iter().find(|e| e.id == killer_id);
+        let victim = entries.iter().find(|e| e.id == victim_id);
+        if let (Some(killer), Some(victim)) = (killer, victim) {
+            let delta = self.game_state.gametype.kill_score(killer, victim);
+            if let Some(player) = self.game_state.players.get_mut(&killer_id) {
+                player.frags += delta;
+            }
+        }
+    }
+
+    fn combatant(&self, id: u16) -> Combatant {
+        self.game_state
+            .players
+            .get(&id)
+            .map(|p| Combatant {
+                id,
+                team: p.team,
+                frozen: p.frozen,
+            })
+            .unwrap_or(Combatant::outsider(id))
+    }
+
+    fn between_rounds(&self) -> bool {
+        self.rounds_running() && !self.game_state.round.is_active()
+    }
+
+    fn teammates(&self, attacker_id: u16, target_id: u16) -> bool {
+        bg_combat::teammates(
+            &*self.game_state.gametype,
+            self.combatant(attacker_id),
+            self.combatant(target_id),
+        )
+    }
+
+    fn damage_allowed(&self, attacker_id: u16, target_id: u16, rules: &DamageRules) -> bool {
+        bg_combat::damage_allowed(
+            &*self.game_state.gametype,
+            self.combatant(attacker_id),
+            self.combatant(target_id),
+            self.between_rounds(),
+            rules,
+        )
+    }
+
+    fn select_spawn(&self, player_id: u16, team: u8) -> Option<(f32, f32)> {
+        let others: Vec<(u8, (f32, f32))> = self
+            .game_state
+            .players
+            .iter()
+            .filter(|(id, p)| **id != player_id && !p.dead)
+            .map(|(_, p)| (p.team, (p.pmove_state.x, p.pmove_state.y)))
+            .collect();
+        let enemies: Vec<(f32, f32)> = others
+            .iter()
+            .filter(|(t, _)| *t == gametype::TEAM_FREE || *t != team)
+            .map(|(_, pos)| *pos)
+            .collect();
+        let occupied: Vec<(f32, f32)> = others.iter().map(|(_, pos)| *pos).collect();
+        let avoid = self
+            .game_state
+            .players
+            .get(&player_id)
+            .filter(|p| p.dead)
+            .map(|p| (p.pmove_state.x, p.pmove_state.y));
+        let query = gametype::SpawnQuery {
+            team,
+            enemies: &enemies,
+            occupied: &occupied,
+            avoid,
+            roll: (self.game_state.tick as usize)
+                .wrapping_mul(31)
+                .wrapping_add(player_id as usize),
+        };
+        let spawn_points = &self.game_state.map.spawn_points;
+        self.game_state
+            .gametype
+            .select_spawn(spawn_points, &query)
+            .map(|idx| (spawn_points[idx].x, spawn_points[idx].y))
+    }
+
+    /// Kills everyone standing where `player_id` just appeared.
+    fn telefrag(&mut self, player_id: u16) {
+        let Some((name, position)) = self
+            .game_state
+            .players
+            .get(&player_id)
+            .map(|p| (p.name.clone(), (p.pmove_state.x, p.pmove_state.y)))
+        else {
+            return;
+        };
+        let victims: Vec<u16> = self
+            .game_state
+            .players
+            .iter()
+            .filter(|(id, p)| {
+                **id != player_id
+                    && !p.dead
+                    && gametype::bodies_overlap(position, (p.pmove_state.x, p.pmove_state.y))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for victim_id in victims {
+            let Some(victim) = self.game_state.players.get_mut(&victim_id) else {
+                continue;
+            };
+            victim.health = GIB_HEALTH;
+            victim.deaths += 1;
+            victim.dead = true;
+            victim.gibbed = true;
+            victim.respawn_timer = 7.0;
+            let death_pos = (victim.pmove_state.x, victim.pmove_state.y);
+            let obituary = MeansOfDeath::Telefrag.obituary(Some(&name), &victim.name);
+            log_info!(Game, "{}", obituary);
+            self.award_kill(player_id, victim_id, MeansOfDeath::Telefrag);
+            let death_msg = NetMessage::PlayerDied {
+                player_id: victim_id,
+                killer_id: player_id,
+                gibbed: true,
+                position: death_pos,
+                velocity: (0.0, 0.0),
+                means: MeansOfDeath::Telefrag,
+            };
+            self.server.broadcast(death_msg).ok();
+        }
+    }
+
+    /// Hurts a player with something other than a weapon. Deaths cost the
+    /// victim a frag.
+    fn world_damage(
+        &mut self,
+        player_id: u16,
+        damage: i32,
+        means: MeansOfDeath,
+        rules: &DamageRules,
+    ) {
+        let Some(player) = self.game_state.players.get_mut(&player_id) else {
+            return;
+        };
+        if player.dead || player.frozen || damage <= 0 {
+            return;
+        }
+        let (died, event) = bg_combat::apply_damage(
+            &mut player.health,
+            &mut player.armor,
+            player.powerups.absorb(damage),
+            WORLD_ID,
+            player_id,
+            false,
+            rules,
+        );
+        let msg = NetMessage::PlayerDamaged {
+            target_id: player_id,
+            attacker_id: WORLD_ID,
+            damage: event.damage,
+            health_remaining: player.health,
+            knockback_x: player.pmove_state.vel_x,
+            knockback_y: player.pmove_state.vel_y,
+        };
+        self.server.broadcast(msg).ok();
+        if !died {
+            return;
+        }
+
+        player.deaths += 1;
+        player.dead = true;
+        player.respawn_timer = 7.0;
+        let gibbed = player.health <= GIB_HEALTH;
+        player.gibbed = gibbed;
+        let death_pos = (player.pmove_state.x, player.pmove_state.y);
+        let death_vel = (player.pmove_state.vel_x, player.pmove_state.vel_y);
+        log_info!(Game, "{}", means.obituary(None, &player.name));
+        self.award_kill(player_id, player_id, means);
+        let death_msg = NetMessage::PlayerDied {
+            player_id,
+            killer_id: if means == MeansOfDeath::Suicide {
+                player_id
+            } else {
+                WORLD_ID
+            },
+            gibbed,
+            position: death_pos,
+            velocity: death_vel,
+            means,
+        };
+        self.server.broadcast(death_msg).ok();
+    }
+
+    fn team_scores(&self) -> TeamScores {
+        if self.game_state.gametype.uses_flags() {
+            self.game_state.ctf.captures
+        } else if self.game_state.gametype.is_round_based() {
+            self.game_state.round.wins
+        } else {
+            TeamScores::from_entries(&self.score_entries())
+        }
+    }
+
+    fn update_flags(&mut self, dt: f32) {
+        let players: Vec<CtfPlayer> = self
+            .game_state
+            .players
+            .iter()
+            .map(|(id, p)| CtfPlayer {
+                id: *id,
+                team: p.team,
+                x: p.pmove_state.x,
+                y: p.pmove_state.y,
+                alive: !p.dead,
+            })
+            .collect();
+
+        let events = self.game_state.ctf.update(dt, &players);
+        if events.is_empty() {
+            return;
+        }
+
+        for event in &events {
+            let message = event.message(|id| {
+                self.game_state
+                    .players
+                    .get(&id)
+                    .map_or_else(|| format!("Player{}", id), |p| p.name.clone())
+            });
+            log_info!(Game, "{}", message);
+        }
+
+        let status = self.flag_status(events);
+        self.server.broadcast(status).ok();
+    }
+
+    fn flag_status(&self, events: Vec<FlagEvent>) -> NetMessage {
+        NetMessage::FlagStatus {
+            flags: self.game_state.ctf.flags.clone(),
+            red_captures: self.game_state.ctf.captures.red,
+            blue_captures: self.game_state.ctf.captures.blue,
+            events,
+        }
+    }
+
+    fn update_rounds(&mut self, dt: f32) {
+        let players: Vec<RoundPlayer> = self
+            .game_state
+            .players
+            .values()
+            .map(|p| RoundPlayer {
+                team: p.team,
+                standing: !p.dead,
+            })
+            .collect();
+
+        let events = self.game_state.round.update(dt, &players);
+        if events.is_empty() {
+            return;
+        }
+
+        for event in &events {
+            if let Some(message) = event.message() {
+                log_info!(Game, "{}", message);
+            }
+            if let RoundEvent::NewRound(_) = event {
+                self.game_state.projectiles.clear();
+                let player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+                for player_id in player_ids {
+                    self.respawn_player(player_id);
+                }
+            }
+        }
+
+        let status = self.round_status(events.clone());
+        self.server.broadcast(status).ok();
+
+        if events.iter().any(|e| matches!(e, RoundEvent::MatchWon(_))) {
+            if let Some(event) = self.game_state.rules.finish(EndReason::RoundLimit) {
+                let status = self.limit_status(vec![event]);
+                self.server.broadcast(status).ok();
+            }
+            self.end_match();
+        }
+    }
+
+    fn update_freeze(&mut self, dt: f32) {
+        for player in self.game_state.players.values_mut() {
+            if player.dead && !player.frozen {
+                player.frozen = true;
+                player.gibbed = false;
+                player.thaw_progress = 0.0;
+                player.pmove_state.vel_x = 0.0;
+                player.pmove_state.vel_y = 0.0;
+            }
+        }
+
+        let mut freeze_players: Vec<FreezePlayer> = self
+            .game_state
+            .players
+            .iter()
+            .map(|(&id, p)| FreezePlayer {
+                id,
+                team: p.team,
+                x: p.pmove_state.x,
+                y: p.pmove_state.y,
+                frozen: p.frozen,
+                alive: !p.dead,
+                thaw_progress: p.thaw_progress,
+            })
+            .collect();
+        let events = freeze::update_thaw(dt, &mut freeze_players);
+
+        for fp in &freeze_players {
+            if let Some(player) = self.game_state.players.get_mut(&fp.id) {
+                player.thaw_progress = fp.thaw_progress;
+            }
+        }
+
+        for event in events {
+            let thawer_name = self
+                .game_state
+                .players
+                .get(&event.thawed_by)
+                .map(|p| p.name.clone())
+                .unwrap_or_default();
+            let Some(player) = self.game_state.players.get_mut(&event.player_id) else {
+                continue;
+            };
+            player.dead = false;
+            player.frozen = false;
+            player.thaw_progress = 0.0;
+            player.health = 100;
+            player.armor = 0;
+            player.respawn_timer = 0.0;
+
+            let message = format!("{} was thawed by {}", player.name, thawer_name);
+            log_info!(Game, "{}", message);
+            let position = (player.pmove_state.x, player.pmove_state.y);
+            self.server
+                .broadcast(NetMessage::PlayerRespawn {
+                    player_id: event.player_id,
+                    position,
+                })
+                .ok();
+            self.server
+                .broadcast(NetMessage::Chat {
+                    player_id: 0,
+                    message,
+                })
+                .ok();
+        }
+    }
+
+    /// Round-based gametypes play free respawns until warmup is over.
+    fn rounds_running(&self) -> bool {
+        self.game_state.gametype.is_round_based() && self.game_state.warmup.is_live()
+    }
+
+    fn update_warmup(&mut self, dt: f32) {
+        if self.game_state.warmup.is_live() {
+            return;
+        }
+        let mut player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        player_ids.sort();
+        for (&id, player) in &self.game_state.players {
+            if player.is_bot {
+                self.game_state.warmup.set_ready(id, true);
+            }
+        }
+
+        let ready_before = self.game_state.warmup.ready.clone();
+        let events = self.game_state.warmup.update(dt, &player_ids);
+        if events.is_empty() && ready_before == self.game_state.warmup.ready {
+            return;
+        }
+
+        for event in &events {
+            if let Some(message) = event.message() {
+                log_info!(Game, "{}", message);
+            }
+        }
+        let status = self.match_status(events.clone());
+        self.server.broadcast(status).ok();
+
+        if events.contains(&WarmupEvent::Started) {
+            self.fresh_start();
+        }
+    }
+
+    /// Runs instead of the simulation while paused; input that arrives
+    /// meanwhile is dropped so nothing replays on resume.
+    fn update_pause(&mut self, dt: f32) {
+        for player in self.game_state.players.values_mut() {
+            player.pending_commands.clear();
+        }
+
+        let events = self.game_state.pause.update(dt);
+        for event in &events {
+            match event {
+                PauseEvent::Expired => self.announce("Timeout expired"),
+                PauseEvent::Resumed => self.announce("Game resumed"),
+                _ => {}
+            }
+        }
+        if !events.is_empty() || self.game_state.tick.is_multiple_of(60) {
+            let status = self.pause_status(events);
+            self.server.broadcast(status).ok();
+        }
+    }
+
+    /// `client_id` None is the server console.
+    fn call_pause(&mut self, client_id: Option<u16>) {
+        let result = match client_id {
+            Some(id) => self.game_state.pause.call_timeout(id),
+            None => self.game_state.pause.admin_pause(),
+        };
+        match result {
+            Ok(event) => {
+                let message = match client_id {
+                    Some(id) => format!(
+                        "{} called a timeout ({} left)",
+                        self.player_name(id),
+                        self.game_state.pause.timeouts_left(id)
+                    ),
+                    None => "Game paused by the server".to_string(),
+                };
+                self.announce(&message);
+                let status = self.pause_status(vec![event]);
+                self.server.broadcast(status).ok();
+            }
+            Err(e) => self.reply(client_id, &e),
+        }
+    }
+
+    fn call_unpause(&mut self, client_id: Option<u16>) {
+        match self.game_state.pause.unpause(client_id) {
+            Ok(event) => {
+                let message = match client_id {
+                    Some(id) => format!("{} is ready to resume", self.player_name(id)),
+                    None => "Server is resuming the game".to_string(),
+                };
+                self.announce(&message);
+                let status = self.pause_status(vec![event]);
+                self.server.broadcast(status).ok();
+            }
+            Err(e) => self.reply(client_id, &e),
+        }
+    }
+
+    fn pause_status(&self, events: Vec<PauseEvent>) -> NetMessage {
+        NetMessage::PauseStatus {
+            phase: self.game_state.pause.phase,
+            events,
+        }
+    }
+
+    fn player_name(&self, player_id: u16) -> String {
+        self.game_state
+            .players
+            .get(&player_id)
+            .map(|p| p.name.clone())
+            .or_else(|| self.server.get_client_name(player_id))
+            .unwrap_or_else(|| format!("Player {}", player_id))
+    }
+
+    fn announce(&mut self, message: &str) {
+        log_info!(Game, "{}", message);
+        self.server
+            .broadcast(NetMessage::Chat {
+                player_id: 0,
+                message: message.to_string(),
+            })
+            .ok();
+    }
+
+    /// Sends a server message to one client, or prints it for the console.
+    fn reply(&mut self, client_id: Option<u16>, message: &str) {
+        match client_id {
+            Some(id) => {
+                self.server
+                    .send_to(
+                        id,
+                        NetMessage::Chat {
+                            player_id: 0,
+                            message: message.to_string(),
+                        },
+                    )
+                    .ok();
+            }
+            None => println!("{}", message),
+        }
+    }
+
+    fn set_ready(&mut self, client_id: u16, ready: bool) {
+        let Some(name) = self
+            .game_state
+            .players
+            .get(&client_id)
+            .map(|p| p.name.clone())
+        else {
+            return;
+        };
+        if !self.game_state.warmup.set_ready(client_id, ready) {
+            return;
+        }
+        let message = format!("{} is {}", name, if ready { "ready" } else { "not ready" });
+        self.announce(&message);
+        let status = self.match_status(Vec::new());
+        self.server.broadcast(status).ok();
+    }
+
+    fn match_status(&self, events: Vec<WarmupEvent>) -> NetMessage {
+        let warmup = &self.game_state.warmup;
+        NetMessage::MatchStatus {
+            phase: warmup.phase,
+            ready: warmup.ready.clone(),
+            min_ready: warmup.min_ready.min(u8::MAX as usize) as u8,
+            events,
+        }
+    }
+
+    fn update_limits(&mut self) {
+        let entries = self.score_entries();
+        let team_scores = self.team_scores();
+        let events = self.game_state.rules.update(
+            self.game_state.match_time,
+            self.game_state.gametype.time_limit(),
+            self.game_state.gametype.as_ref(),
+            &entries,
+            &team_scores,
+        );
+        if events.is_empty() {
+            return;
+        }
+
+        for event in &events {
+            if let Some(message) = event.message() {
+                log_info!(Game, "{}", message);
+            }
+        }
+        let ended = events
+            .iter()
+            .any(|e| matches!(e, LimitEvent::MatchEnded(_)));
+        let status = self.limit_status(events);
+        self.server.broadcast(status).ok();
+        if ended {
+            self.end_match();
+        }
+    }
+
+    fn update_intermission(&mut self, dt: f32) {
+        let Some(remaining) = self.game_state.intermission else {
+            return;
+        };
+        if remaining - dt > 0.0 {
+            self.game_state.intermission = Some(remaining - dt);
+        } else {
+            self.restart_match();
+        }
+    }
+
+    fn limit_status(&self, events: Vec<LimitEvent>) -> NetMessage {
+        let rules = &self.game_state.rules;
+        NetMessage::LimitStatus {
+            match_time: self.game_state.match_time,
+            overtime: rules.overtime,
+            sudden_death: rules.sudden_death,
+            ended: rules.ended,
+            events,
+        }
+    }
+
+    fn round_status(&self, events: Vec<RoundEvent>) -> NetMessage {
+        let round = &self.game_state.round;
+        NetMessage::RoundStatus {
+            phase: round.phase,
+            round: round.round,
+            red_wins: round.wins.red,
+            blue_wins: round.wins.blue,
+            events,
+        }
+    }
+
+    fn warn_if_unplayable(&self) {
+        if self.game_state.gametype.uses_flags() && !self.game_state.ctf.is_playable() {
+            log_warn!(
+                Game,
+                "Map {} has no red and blue flag bases, flags are disabled",
+                self.map_name
+            );
+        }
+    }
+
+    fn server_info(&self) -> NetMessage {
+        NetMessage::ServerInfo {
+            map_name: self.map_name.clone(),
+            gametype: self.game_state.gametype.kind() as u8,
+            mutators: self.game_state.mutators.bits(),
+            max_players: self.server.max_players(),
+            current_players: self.game_state.players.len().min(u8::MAX as usize) as u8,
+            weapons_checksum: weapon_defs::checksum(),
+            weapons: sas::cvar::get_cvar_bool("g_enforceweapons").then(weapon_defs::active),
+            physics: self.game_state.physics,
+        }
+    }
+
+    /// Rebuilds the movement profile after a pm_* or hook cvar changed and
+    /// sends it to the clients.
+    fn reload_physics(&mut self) {
+        self.game_state.physics = PhysicsProfile::from_cvars();
+        self.game_state
+            .mutators
+            .apply_physics(&mut self.game_state.physics);
+        let info = self.server_info();
+        self.server.broadcast(info).ok();
+    }
+
+    fn set_gametype(&mut self, kind: GametypeKind) {
+        log_info!(Game, "Gametype set to {}", kind.name());
+        self.game_state.gametype = gametype::create(kind);
+
+        let mut player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        player_ids.sort();
+        for player in self.game_state.players.values_mut() {
+            player.team = gametype::TEAM_FREE;
+        }
+        for player_id in player_ids {
+            let team = self.game_state.gametype.pick_team(&self.score_entries());
+            if let Some(player) = self.game_state.players.get_mut(&player_id) {
+                player.team = team;
+            }
+        }
+
+        let info = self.server_info();
+        self.server.broadcast(info).ok();
+        self.warn_if_unplayable();
+        self.restart_match();
+    }
+
+    fn set_mutators(&mut self, mutators: Mutators) {
+        log_info!(Game, "Mutators set to {}", mutators.names());
+        self.game_state.mutators = mutators;
+        mutators.apply_physics(&mut self.game_state.physics);
+
+        let info = self.server_info();
+        self.server.broadcast(info).ok();
+        let map_name = self.map_name.clone();
+        self.change_map(&map_name);
+        self.restart_match();
+    }
+
+    fn end_match(&mut self) {
+        let entries = self.score_entries();
+        let team_scores = self.team_scores();
+        let winner = self.game_state.gametype.winner(&entries, &team_scores);
+        let message = match winner {
+            MatchWinner::Player(id) => {
+                let name = self
+                    .game_state
+                    .players
+                    .get(&id)
+                    .map(|p| p.name.clone())
+                    .unwrap_or_default();
+                format!("{} wins the match", name)
+            }
+            MatchWinner::Team(team) => format!(
+                "{} team wins the match {} - {}",
+                gametype::team_name(team),
+                team_scores.get(team),
+                team_scores.get(gametype::other_team(team))
+            ),
+            MatchWinner::Draw => "The match ended in a draw".to_string(),
+        };
 
-        println!("Loaded map: {}", map_name);
-        println!("Spawn points: {}", map.spawn_points.len());
+        let reason = self.game_state.rules.ended.unwrap_or(EndReason::TimeLimit);
+        log_info!(
+            Game,
+            "{} ({}, {} on {})",
+            message,
+            reason.name(),
+            self.game_state.gametype.kind().name(),
+            self.map_name
+        );
+        self.server
+            .broadcast(NetMessage::Chat {
+                player_id: 0,
+                message,
+            })
+            .ok();
 
-        Self {
-            server: NetworkServer::new(config),
-            game_state: GameState {
-                map,
-                players: HashMap::new(),
-                projectiles: Vec::new(),
-                tick: 0,
-                next_projectile_id: 1,
-            },
-            map_name,
-            pmove_accumulator: 0.0,
-            last_frame_time: std::time::Instant::now(),
-            next_bot_id: 1000,
+        if let MatchWinner::Player(id) = winner {
+            if self.game_state.tournament.is_some() {
+                self.record_tournament_match(id);
+            } else if let Some(loser) = entries.iter().map(|e| e.id).find(|&e| e != id) {
+                if let Some(challenger) = self.game_state.queue.finish_match(loser) {
+                    self.game_state.pending_rotation = Some((loser, challenger));
+                }
+            }
         }
+
+        self.report_match_stats();
+        self.game_state.intermission = Some(INTERMISSION_TIME);
     }
 
-    fn start(&mut self) -> Result<(), String> {
-        self.server.start()?;
-        println!("Dedicated server running. Press Ctrl+C to stop.");
-        Ok(())
+    /// Writes the match to `g_statsdir` and sends everyone the stats screen.
+    fn report_match_stats(&mut self) {
+        for (id, player) in &self.game_state.players {
+            self.game_state
+                .stats
+                .set_player_info(*id, &player.name, player.team, player.frags);
+        }
+        let dir = sas::cvar::get_cvar_string("g_statsdir");
+        if !dir.is_empty() {
+            match self.game_state.stats.write_json(
+                &dir,
+                &self.map_name,
+                self.game_state.gametype.kind().short_name(),
+                self.game_state.match_time,
+            ) {
+                Ok(path) => log_info!(Game, "Match stats written to {}", path),
+                Err(e) => log_warn!(Game, "Failed to write match stats: {}", e),
+            }
+        }
+        let msg = NetMessage::MatchStats {
+            players: self.game_state.stats.to_vec(),
+        };
+        self.server.broadcast(msg).ok();
     }
 
-    fn run(&mut self) {
-        const FIXED_DT: f32 = 1.0 / 60.0;
+    fn record_tournament_match(&mut self, winner_id: u16) {
+        let winner = self.player_name(winner_id);
+        let scores: Vec<(String, i32)> = self
+            .game_state
+            .players
+            .values()
+            .map(|p| (p.name.clone(), p.frags))
+            .collect();
+        let Some(tournament) = self.game_state.tournament.as_mut() else {
+            return;
+        };
+        if let Err(e) = tournament.record(&winner, &scores) {
+            log_warn!(Game, "Tournament result not recorded: {}", e);
+            return;
+        }
+        if let Err(e) = tournament.write_results() {
+            log_warn!(Game, "Failed to write tournament results: {}", e);
+        }
 
-        loop {
-            let now = std::time::Instant::now();
-            let dt = now.duration_since(self.last_frame_time).as_secs_f32();
-            self.last_frame_time = now;
+        let message = match (tournament.champion(), tournament.current_players()) {
+            (Some(champion), _) => format!("{} wins {}!", champion, tournament.config.name),
+            (None, Some([a, b])) => {
+                format!("Next match: {} vs {} on {}", a, b, tournament.current_map())
+            }
+            (None, None) => format!("{} is over", tournament.config.name),
+        };
+        self.announce(&message);
+    }
 
-            let (messages, timed_out_clients) = self.server.update();
+    /// Puts the current tournament match on its map with exactly its two
+    /// players in the game; everyone else watches.
+    fn sync_tournament(&mut self) {
+        let Some(tournament) = self.active_tournament() else {
+            return;
+        };
+        let map = tournament.current_map().to_string();
+        if map != self.map_name {
+            self.change_map(&map);
+        }
 
-            for client_id in timed_out_clients {
-                if let Some(player) = self.game_state.players.get(&client_id) {
-                    if !player.is_bot {
-                        println!(
-                            "[{:.3}] [SERVER] Removing timed out player {} ({})",
-                            sas::network::get_network_time(),
-                            client_id,
-                            player.name
-                        );
-                        self.remove_player(client_id);
-                    }
-                }
+        let mut ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let name = self.player_name(id);
+            if self.check_slot(&name).is_err() {
+                self.demote(id);
             }
+        }
 
-            for (client_id, msg) in messages {
-                self.handle_message(client_id, msg);
+        let mut clients = self.server.client_ids();
+        clients.sort();
+        for id in clients {
+            if self.game_state.players.contains_key(&id) {
+                continue;
+            }
+            let name = self.player_name(id);
+            if self.check_slot(&name).is_ok() {
+                self.promote(id);
             }
+        }
+    }
 
-            self.pmove_accumulator += dt;
+    fn start_tournament(&mut self, tournament: Tournament) {
+        log_info!(
+            Game,
+            "Tournament {}: {} players, {:?} elimination",
+            tournament.config.name,
+            tournament.config.players.len(),
+            tournament.config.format
+        );
+        self.game_state.tournament = Some(tournament);
+        self.game_state.queue = DuelQueue::from_cvar();
+        sas::cvar::set_cvar("g_gametype", &(GametypeKind::Duel as u8).to_string());
+        self.set_gametype(GametypeKind::Duel);
+    }
 
-            while self.pmove_accumulator >= FIXED_DT {
-                self.update_bot_ai(FIXED_DT);
-                self.simulate_physics(FIXED_DT);
-                self.update_projectiles(FIXED_DT);
-                self.check_collisions();
-                self.check_item_pickups();
+    /// Swaps the last match's loser for the next challenger in the queue.
+    fn rotate_queue(&mut self) {
+        let Some((loser, challenger)) = self.game_state.pending_rotation.take() else {
+            return;
+        };
+        if !self.game_state.players.contains_key(&loser) {
+            return;
+        }
+        let loser_name = self.player_name(loser);
+        self.demote(loser);
+        if self.game_state.queue.position(loser).is_some() {
+            let position = self.game_state.queue.join(loser);
+            self.reply(Some(loser), &format!("You are #{} in the queue", position));
+        } else {
+            self.reply(Some(loser), "You lost - type 'queue' to play again");
+        }
+        if self.promote(challenger) {
+            let challenger_name = self.player_name(challenger);
+            self.announce(&format!("{} steps in for {}", challenger_name, loser_name));
+        } else {
+            self.fill_open_slots();
+        }
+    }
 
-                self.game_state.tick += 1;
+    fn join_queue(&mut self, client_id: u16) {
+        if self.game_state.players.contains_key(&client_id) {
+            self.reply(Some(client_id), "You are already playing");
+            return;
+        }
+        if self.active_tournament().is_some() {
+            self.reply(Some(client_id), "Tournament matches follow the bracket");
+            return;
+        }
+        let position = self.game_state.queue.join(client_id);
+        if self.fill_open_slots() {
+            self.restart_match();
+            return;
+        }
+        self.reply(
+            Some(client_id),
+            &format!("You are #{} in the queue", position),
+        );
+    }
 
-                if self.game_state.tick % 2 == 0 {
-                    self.broadcast_game_state();
-                }
+    /// Back to warmup (when enabled) with a clean slate.
+    fn restart_match(&mut self) {
+        self.game_state.intermission = None;
+        self.rotate_queue();
+        self.sync_tournament();
+        self.game_state.pause.reset();
+        let status = self.pause_status(Vec::new());
+        self.server.broadcast(status).ok();
+        self.game_state.warmup = WarmupState::from_cvar();
+        let status = self.match_status(Vec::new());
+        self.server.broadcast(status).ok();
+        self.fresh_start();
+    }
 
-                self.pmove_accumulator -= FIXED_DT;
+    /// Resets the clock, items, flags, rounds and scores and respawns everyone.
+    fn fresh_start(&mut self) {
+        self.game_state.match_time = 0.0;
+        self.game_state.rules.reset();
+        self.game_state.stats.reset();
+        let status = self.limit_status(Vec::new());
+        self.server.broadcast(status).ok();
+        self.game_state.map.reset_items();
+        self.game_state.projectiles.clear();
+        self.game_state.ctf.reset();
+        let status = self.flag_status(Vec::new());
+        self.server.broadcast(status).ok();
+        self.game_state.round.reset();
+        let status = self.round_status(Vec::new());
+        self.server.broadcast(status).ok();
+
+        let player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        for player_id in player_ids {
+            if let Some(player) = self.game_state.players.get_mut(&player_id) {
+                player.frags = 0;
+                player.deaths = 0;
+            }
+            self.respawn_player(player_id);
+        }
+    }
+
+    fn change_map(&mut self, map_name: &str) {
+        let mut map = match Map::load_from_file(map_name) {
+            Ok(map) => map,
+            Err(e) => {
+                log_warn!(Game, "Failed to load map '{}': {}", map_name, e);
+                return;
             }
+        };
 
-            std::thread::sleep(std::time::Duration::from_millis(16));
+        log_info!(Game, "Changing map to {}", map_name);
+
+        self.game_state.mutators.filter_items(&mut map.items);
+        self.game_state.ctf = CtfState::new(&map.flags);
+        self.game_state.map = map;
+        self.game_state.projectiles.clear();
+        self.map_name = map_name.to_string();
+        self.warn_if_unplayable();
+
+        self.server
+            .broadcast(NetMessage::MapChange {
+                map_name: self.map_name.clone(),
+            })
+            .ok();
+        self.game_state.warmup = WarmupState::from_cvar();
+        let status = self.match_status(Vec::new());
+        self.server.broadcast(status).ok();
+        self.game_state.pause.reset();
+        let status = self.pause_status(Vec::new());
+        self.server.broadcast(status).ok();
+
+        let player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        for player_id in player_ids {
+            if let Some(player) = self.game_state.players.get_mut(&player_id) {
+                player.pending_commands.clear();
+                player.corpse_timer = 0.0;
+            }
+            self.respawn_player(player_id);
         }
     }
 
@@ -137,24 +1489,15 @@ impl DedicatedServer {
 
         match &msg {
             NetMessage::ConnectRequest { player_name, .. } => {
-                println!(
-                    "[{:.3}] [SERVER] Client {} connecting: {}",
-                    sas::network::get_network_time(),
-                    client_id,
-                    player_name
-                );
+                log_info!(Net, "Client {} connecting: {}", client_id, player_name);
                 self.add_player(client_id, player_name.clone());
             }
             NetMessage::Disconnect { reason, .. } => {
-                println!(
-                    "[{:.3}] [SERVER] Client {} disconnected: {}",
-                    sas::network::get_network_time(),
-                    client_id,
-                    reason
-                );
+                log_info!(Net, "Client {} disconnected: {}", client_id, reason);
                 self.remove_player(client_id);
             }
             NetMessage::PlayerInput {
+                input_sequence,
                 move_forward,
                 move_right,
                 angle,
@@ -162,14 +1505,14 @@ impl DedicatedServer {
                 server_time,
                 ..
             } => {
-                self.update_player_input(
-                    client_id,
-                    *move_forward,
-                    *move_right,
-                    *angle,
-                    *buttons,
-                    *server_time,
-                );
+                let cmd = sas::network::PlayerInputCmd {
+                    move_forward: *move_forward,
+                    move_right: *move_right,
+                    angle: *angle,
+                    buttons: *buttons,
+                    server_time: *server_time,
+                };
+                self.update_player_input(client_id, *input_sequence, cmd);
             }
             NetMessage::PlayerInputBatch { commands, .. } => {
                 self.update_player_input_batch(client_id, commands.clone());
@@ -180,9 +1523,12 @@ impl DedicatedServer {
                 direction,
                 ..
             } => {
-                println!(
-                    "[{:.3}] [SERVER] Player {} shot weapon {} at ({:.1}, {:.1})",
-                    sas::network::get_network_time(),
+                if self.game_state.pause.is_paused() || self.game_state.intermission.is_some() {
+                    return;
+                }
+                log_debug!(
+                    Game,
+                    "Player {} shot weapon {} at ({:.1}, {:.1})",
                     client_id,
                     weapon,
                     origin.0,
@@ -193,6 +1539,20 @@ impl DedicatedServer {
             NetMessage::Chat { message, .. } => {
                 if message.starts_with("addbot") {
                     self.handle_addbot_command(client_id);
+                } else if message == "ready" {
+                    let ready = !self.game_state.warmup.is_ready(client_id);
+                    self.set_ready(client_id, ready);
+                } else if message == "notready" {
+                    self.set_ready(client_id, false);
+                } else if message == "pause" || message == "timeout" {
+                    self.call_pause(Some(client_id));
+                } else if message == "unpause" || message == "timein" {
+                    self.call_unpause(Some(client_id));
+                } else if message == "queue" {
+                    self.join_queue(client_id);
+                } else if message == "kill" {
+                    let rules = DamageRules::from_cvars(self.game_state.mutators);
+                    self.world_damage(client_id, WORLD_KILL_DAMAGE, MeansOfDeath::Suicide, &rules);
                 } else {
                     self.handle_chat(client_id, message.clone());
                 }
@@ -206,16 +1566,136 @@ impl DedicatedServer {
         self.add_player_internal(client_id, name, false);
     }
 
-    fn add_player_internal(&mut self, client_id: u16, name: String, is_bot: bool) {
-        let spawn_pos = if !self.game_state.map.spawn_points.is_empty() {
-            let spawn_idx = (client_id as usize) % self.game_state.map.spawn_points.len();
-            let spawn = &self.game_state.map.spawn_points[spawn_idx];
-            (spawn.x, spawn.y)
+    fn add_player_internal(&mut self, client_id: u16, name: String, is_bot: bool) -> bool {
+        if let Err(reason) = self.check_slot(&name) {
+            if is_bot {
+                log_info!(Bot, "Rejecting bot {} ({}): {}", client_id, name, reason);
+            } else {
+                self.send_initial_state(client_id);
+                self.spectate(client_id, &reason);
+            }
+            return false;
+        }
+        if !is_bot {
+            self.send_initial_state(client_id);
+        }
+        self.spawn_new_player(client_id, name, is_bot);
+        true
+    }
+
+    /// Whether `name` may take a playing slot right now.
+    fn check_slot(&self, name: &str) -> Result<(), String> {
+        if let Some(tournament) = self.active_tournament() {
+            if !tournament.is_current_player(name) {
+                return Err(match tournament.current_players() {
+                    Some([a, b]) => format!("{} vs {} is being played", a, b),
+                    None => "the tournament is over".to_string(),
+                });
+            }
+            return Ok(());
+        }
+        match self.game_state.gametype.max_players() {
+            Some(max) if self.game_state.players.len() >= max => Err(format!(
+                "{} is limited to {} players",
+                self.game_state.gametype.kind().name(),
+                max
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn active_tournament(&self) -> Option<&Tournament> {
+        self.game_state
+            .tournament
+            .as_ref()
+            .filter(|t| !t.is_finished())
+    }
+
+    fn send_initial_state(&mut self, client_id: u16) {
+        let map_msg = NetMessage::MapChange {
+            map_name: self.map_name.clone(),
+        };
+        self.server.send_to(client_id, map_msg).ok();
+        let info = self.server_info();
+        self.server.send_to(client_id, info).ok();
+        let status = self.flag_status(Vec::new());
+        self.server.send_to(client_id, status).ok();
+        let status = self.round_status(Vec::new());
+        self.server.send_to(client_id, status).ok();
+        let status = self.match_status(Vec::new());
+        self.server.send_to(client_id, status).ok();
+        let status = self.pause_status(Vec::new());
+        self.server.send_to(client_id, status).ok();
+        let status = self.limit_status(Vec::new());
+        self.server.send_to(client_id, status).ok();
+    }
+
+    /// Keeps a connected client watching; outside tournaments they also queue.
+    fn spectate(&mut self, client_id: u16, reason: &str) {
+        let name = self.player_name(client_id);
+        if self.active_tournament().is_some() {
+            log_info!(Game, "{} is spectating: {}", name, reason);
+            self.reply(Some(client_id), &format!("Spectating, {}", reason));
+            return;
+        }
+        let position = self.game_state.queue.join(client_id);
+        log_info!(Game, "{} queued at #{}: {}", name, position, reason);
+        self.reply(
+            Some(client_id),
+            &format!(
+                "Spectating, {} - you are #{} in the queue",
+                reason, position
+            ),
+        );
+    }
+
+    /// Moves a playing client to the spectators.
+    fn demote(&mut self, client_id: u16) {
+        let Some(player) = self.game_state.players.remove(&client_id) else {
+            return;
+        };
+        let msg = NetMessage::Disconnect {
+            player_id: client_id,
+            reason: "Moved to spectators".to_string(),
+        };
+        self.server.broadcast(msg).ok();
+        if player.is_bot {
+            log_info!(Bot, "Bot {} ({}) left the game", client_id, player.name);
         } else {
-            (0.0, 0.0)
+            log_info!(Game, "{} moved to spectators", player.name);
+        }
+    }
+
+    /// Brings a connected spectator into the game.
+    fn promote(&mut self, client_id: u16) -> bool {
+        let Some(name) = self.server.get_client_name(client_id) else {
+            return false;
+        };
+        self.game_state.queue.leave(client_id);
+        self.spawn_new_player(client_id, name, false);
+        true
+    }
+
+    /// Hands open slots to the queue, in order, skipping anyone who left.
+    fn fill_open_slots(&mut self) -> bool {
+        let Some(max) = self.game_state.gametype.max_players() else {
+            return false;
         };
+        let mut filled = false;
+        while self.game_state.players.len() < max {
+            let Some(next) = self.game_state.queue.pop_next() else {
+                break;
+            };
+            filled |= self.promote(next);
+        }
+        filled
+    }
+
+    fn spawn_new_player(&mut self, client_id: u16, name: String, is_bot: bool) {
+        let team = self.game_state.gametype.pick_team(&self.score_entries());
+        let spawn_pos = self.select_spawn(client_id, team).unwrap_or((0.0, 0.0));
 
-        let player = ServerPlayer {
+        let mut player = ServerPlayer {
             name: name.clone(),
             pmove_state: PmoveState {
                 x: spawn_pos.0,
@@ -223,20 +1703,31 @@ impl DedicatedServer {
                 vel_x: 0.0,
                 vel_y: 0.0,
                 was_in_air: false,
+                jump_time: 0.0,
+                fall_speed: 0.0,
             },
             angle: 0.0,
             health: 100,
             armor: 0,
             weapon: 2,
-            ammo: [100, 50, 10, 10, 5, 0, 0, 0, 0, 0],
+            ammo: [100, 50, 10, 10, 5, 0, 0, 0, 0, 0, 0, 0],
             frags: 0,
             deaths: 0,
-            powerup_quad: 0,
+            team,
+            powerups: PowerUps::default(),
+            holdable: None,
+            use_held: false,
+            grapple: Grapple::Idle,
+            water_level: 0,
+            liquid: None,
+            breath: Breath::default(),
             last_cmd: UserCmd::new(),
             pending_commands: Vec::new(),
             last_executed_time: 0,
             dead: false,
             gibbed: false,
+            frozen: false,
+            thaw_progress: 0.0,
             respawn_timer: 0.0,
             corpse_timer: 0.0,
             is_bot,
@@ -245,16 +1736,14 @@ impl DedicatedServer {
             } else {
                 None
             },
+            input_validator: InputValidator::new(),
         };
+        if let Some(loadout) = self.game_state.spawn_loadout() {
+            player.apply_loadout(&loadout);
+        }
 
         self.game_state.players.insert(client_id, player);
-
-        if !is_bot {
-            let map_msg = NetMessage::MapChange {
-                map_name: self.map_name.clone(),
-            };
-            self.server.send_to(client_id, map_msg).ok();
-        }
+        self.telefrag(client_id);
 
         let respawn_msg = NetMessage::PlayerRespawn {
             player_id: client_id,
@@ -263,46 +1752,81 @@ impl DedicatedServer {
         self.server.broadcast(respawn_msg).ok();
 
         if is_bot {
-            println!(
+            log_info!(
+                Bot,
                 "Bot {} ({}) joined the game on map {} at ({}, {})",
-                client_id, name, self.map_name, spawn_pos.0, spawn_pos.1
+                client_id,
+                name,
+                self.map_name,
+                spawn_pos.0,
+                spawn_pos.1
             );
         } else {
-            println!(
+            log_info!(
+                Game,
                 "Player {} ({}) joined the game on map {} at ({}, {})",
-                client_id, name, self.map_name, spawn_pos.0, spawn_pos.1
+                client_id,
+                name,
+                self.map_name,
+                spawn_pos.0,
+                spawn_pos.1
             );
         }
     }
 
     fn remove_player(&mut self, client_id: u16) {
+        if self.game_state.queue.leave(client_id) {
+            log_info!(Game, "Client {} left the queue", client_id);
+        }
         if let Some(server_player) = self.game_state.players.remove(&client_id) {
-            println!(
+            log_info!(
+                Game,
                 "Player {} ({}) left the game",
-                client_id, server_player.name
+                client_id,
+                server_player.name
             );
+            if !server_player.is_bot {
+                log_info!(
+                    Net,
+                    "Input validation p{}: {}",
+                    client_id,
+                    server_player.input_validator.stats.get_summary()
+                );
+            }
 
             let msg = NetMessage::Disconnect {
                 player_id: client_id,
                 reason: "Player disconnected".to_string(),
             };
             self.server.broadcast(msg).ok();
+
+            if self.active_tournament().is_none() && self.fill_open_slots() {
+                self.restart_match();
+            }
         }
     }
 
     fn update_player_input(
         &mut self,
         client_id: u16,
-        _move_forward: f32,
-        move_right: f32,
-        angle: f32,
-        buttons: u32,
-        server_time: u32,
+        input_sequence: u32,
+        raw_cmd: sas::network::PlayerInputCmd,
     ) {
         if let Some(server_player) = self.game_state.players.get_mut(&client_id) {
-            println!(
-                "[{}] [SERVER INPUT] p{} pos=({:.1},{:.1}) move_right={:.2}",
-                get_absolute_time(),
+            let cmd = match server_player.input_validator.accept_input(
+                input_sequence,
+                &raw_cmd,
+                get_network_time(),
+            ) {
+                Some(cmd) => cmd,
+                None => return,
+            };
+            let (move_right, angle, buttons, server_time) =
+                (cmd.move_right, cmd.angle, cmd.buttons, cmd.server_time);
+
+            log_trace!(
+                Net,
+                "Input p{} pos=({:.1},{:.1}) move_right={:.2}",
                 client_id,
                 server_player.pmove_state.x,
                 server_player.pmove_state.y,
@@ -331,15 +1855,18 @@ impl DedicatedServer {
         commands: Vec<sas::network::PlayerInputCmd>,
     ) {
         if let Some(server_player) = self.game_state.players.get_mut(&client_id) {
-            for cmd in commands {
-                if cmd.server_time > server_player.last_executed_time {
-                    server_player.pending_commands.push(UserCmd {
-                        right: cmd.move_right,
-                        buttons: cmd.buttons as u8,
-                        angles: (cmd.angle, 0.0),
-                        server_time: cmd.server_time,
-                    });
-                }
+            let accepted = server_player.input_validator.accept_batch(
+                &commands,
+                server_player.last_executed_time,
+                get_network_time(),
+            );
+            for cmd in accepted {
+                server_player.pending_commands.push(UserCmd {
+                    right: cmd.move_right,
+                    buttons: cmd.buttons as u8,
+                    angles: (cmd.angle, 0.0),
+                    server_time: cmd.server_time,
+                });
             }
 
             server_player
@@ -360,7 +1887,24 @@ impl DedicatedServer {
         }
     }
 
+    fn log_input_validation(&mut self) {
+        for (client_id, player) in self.game_state.players.iter_mut() {
+            if let Some(stats) = player.input_validator.take_unlogged_stats() {
+                log_info!(
+                    Net,
+                    "Input validation p{} ({}): {}",
+                    client_id,
+                    player.name,
+                    stats.get_summary()
+                );
+            }
+        }
+    }
+
     fn trigger_respawn(&mut self, client_id: u16) {
+        if self.rounds_running() {
+            return;
+        }
         if let Some(server_player) = self.game_state.players.get_mut(&client_id) {
             if !server_player.dead {
                 return;
@@ -368,11 +1912,15 @@ impl DedicatedServer {
 
             server_player.corpse_timer = 2.0;
             self.respawn_player(client_id);
+            self.telefrag(client_id);
         }
     }
 
     fn simulate_physics(&mut self, dt: f32) {
         let player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        let round_based = self.rounds_running();
+        let mut item_uses = Vec::new();
+        let mut landings = Vec::new();
 
         for player_id in player_ids {
             if let Some(server_player) = self.game_state.players.get_mut(&player_id) {
@@ -381,33 +1929,71 @@ impl DedicatedServer {
                 }
 
                 if server_player.dead {
+                    server_player.grapple = Grapple::Idle;
+                    if server_player.frozen {
+                        let idle = PmoveCmd {
+                            move_right: 0.0,
+                            jump: false,
+                            crouch: false,
+                            haste_active: false,
+                            flight_active: false,
+                            grapple: None,
+                        };
+                        let result = pmove(
+                            &server_player.pmove_state,
+                            &idle,
+                            dt,
+                            &self.game_state.map,
+                            &self.game_state.physics,
+                        );
+                        server_player.pmove_state.x = result.new_x;
+                        server_player.pmove_state.y = result.new_y;
+                        server_player.pmove_state.vel_x = result.new_vel_x;
+                        server_player.pmove_state.vel_y = result.new_vel_y;
+                        server_player.pmove_state.was_in_air = result.new_was_in_air;
+                        server_player.pmove_state.jump_time = result.new_jump_time;
+                        server_player.pmove_state.fall_speed = result.new_fall_speed;
+                        server_player.pending_commands.clear();
+                    }
+                    if round_based {
+                        continue;
+                    }
                     server_player.respawn_timer -= dt;
                     if server_player.respawn_timer <= 0.0 {
                         server_player.corpse_timer = 2.0;
                         self.respawn_player(player_id);
+                        self.telefrag(player_id);
                     }
                     continue;
                 }
 
-                if server_player.powerup_quad > 0 {
-                    server_player.powerup_quad = server_player.powerup_quad.saturating_sub(1);
+                for powerup in server_player.powerups.active() {
+                    self.game_state
+                        .stats
+                        .add_powerup_time(player_id, powerup, dt);
                 }
+                server_player.powerups.tick(&mut server_player.health);
 
                 let commands: Vec<UserCmd> = server_player.pending_commands.drain(..).collect();
 
+                let mut use_item = false;
+                if commands.is_empty() {
+                    let buttons = server_player.last_cmd.buttons;
+                    use_item = server_player.use_item_pressed(buttons);
+                }
+                for cmd in &commands {
+                    use_item |= server_player.use_item_pressed(cmd.buttons);
+                }
+                if use_item {
+                    item_uses.push(player_id);
+                }
+
                 if !commands.is_empty() {
                     for cmd in commands {
                         if cmd.server_time <= server_player.last_executed_time {
                             continue;
                         }
 
-                        let pmove_cmd = PmoveCmd {
-                            move_right: cmd.right,
-                            jump: (cmd.buttons & 2) != 0,
-                            crouch: (cmd.buttons & 4) != 0,
-                            haste_active: false,
-                        };
-
                         let cmd_dt = if server_player.last_executed_time > 0 {
                             (cmd.server_time - server_player.last_executed_time) as f32 / 1000.0
                         } else {
@@ -416,16 +2002,50 @@ impl DedicatedServer {
 
                         let cmd_dt = cmd_dt.max(0.001).min(0.1);
 
-                        println!("[{}] [SERVER PMOVE] p{} pos=({:.1},{:.1}) dt={:.4} move_right={:.2} cmd_time={} last_exec={}",
-                            get_absolute_time(), player_id, server_player.pmove_state.x, server_player.pmove_state.y, cmd_dt, cmd.right, cmd.server_time, server_player.last_executed_time);
+                        server_player.grapple.update(
+                            (cmd.buttons & BUTTON_HOOK) != 0
+                                && self.game_state.physics.grapple_pull > 0.0,
+                            cmd.angles.0,
+                            server_player.pmove_state.x,
+                            server_player.pmove_state.y,
+                            cmd_dt,
+                            &self.game_state.map,
+                        );
+                        let pmove_cmd = PmoveCmd {
+                            move_right: cmd.right,
+                            jump: (cmd.buttons & 2) != 0,
+                            crouch: (cmd.buttons & 4) != 0,
+                            haste_active: server_player.powerups.haste > 0,
+                            flight_active: server_player.powerups.flight > 0,
+                            grapple: server_player.grapple.anchor(),
+                        };
+
+                        log_trace!(
+                            Game,
+                            "Pmove p{} pos=({:.1},{:.1}) dt={:.4} move_right={:.2} cmd_time={} last_exec={}",
+                            player_id,
+                            server_player.pmove_state.x,
+                            server_player.pmove_state.y,
+                            cmd_dt,
+                            cmd.right,
+                            cmd.server_time,
+                            server_player.last_executed_time
+                        );
 
                         let result = pmove(
                             &server_player.pmove_state,
                             &pmove_cmd,
                             cmd_dt,
                             &self.game_state.map,
+                            &self.game_state.physics,
                         );
 
+                        if result.fall_damage > 0 {
+                            landings.push((player_id, result.fall_damage));
+                        }
+                        server_player.water_level = result.water_level;
+                        server_player.liquid = result.liquid;
+
                         let mut teleported = false;
                         for teleporter in &self.game_state.map.teleporters {
                             if result.new_x >= teleporter.x
@@ -433,15 +2053,22 @@ impl DedicatedServer {
                                 && result.new_y >= teleporter.y
                                 && result.new_y <= teleporter.y + teleporter.height
                             {
-                                println!("[{:.3}] [SERVER TELEPORT] p{} from ({:.1},{:.1}) to ({:.1},{:.1})",
-                                    sas::network::get_network_time(), player_id,
-                                    result.new_x, result.new_y,
-                                    teleporter.dest_x, teleporter.dest_y);
+                                log_debug!(
+                                    Game,
+                                    "Teleport p{} from ({:.1},{:.1}) to ({:.1},{:.1})",
+                                    player_id,
+                                    result.new_x,
+                                    result.new_y,
+                                    teleporter.dest_x,
+                                    teleporter.dest_y
+                                );
                                 server_player.pmove_state.x = teleporter.dest_x;
                                 server_player.pmove_state.y = teleporter.dest_y;
                                 server_player.pmove_state.vel_x = result.new_vel_x;
                                 server_player.pmove_state.vel_y = result.new_vel_y;
                                 server_player.pmove_state.was_in_air = result.new_was_in_air;
+                                server_player.pmove_state.jump_time = result.new_jump_time;
+                                server_player.pmove_state.fall_speed = 0.0;
                                 teleported = true;
                                 break;
                             }
@@ -453,6 +2080,8 @@ impl DedicatedServer {
                             server_player.pmove_state.vel_x = result.new_vel_x;
                             server_player.pmove_state.vel_y = result.new_vel_y;
                             server_player.pmove_state.was_in_air = result.new_was_in_air;
+                            server_player.pmove_state.jump_time = result.new_jump_time;
+                            server_player.pmove_state.fall_speed = result.new_fall_speed;
                         }
 
                         server_player.last_executed_time = cmd.server_time;
@@ -462,11 +2091,22 @@ impl DedicatedServer {
                 } else {
                     let cmd = server_player.last_cmd;
 
+                    server_player.grapple.update(
+                        (cmd.buttons & BUTTON_HOOK) != 0
+                            && self.game_state.physics.grapple_pull > 0.0,
+                        cmd.angles.0,
+                        server_player.pmove_state.x,
+                        server_player.pmove_state.y,
+                        dt,
+                        &self.game_state.map,
+                    );
                     let pmove_cmd = PmoveCmd {
                         move_right: cmd.right,
                         jump: (cmd.buttons & 2) != 0,
                         crouch: (cmd.buttons & 4) != 0,
-                        haste_active: false,
+                        haste_active: server_player.powerups.haste > 0,
+                        flight_active: server_player.powerups.flight > 0,
+                        grapple: server_player.grapple.anchor(),
                     };
 
                     let current_tick = self.game_state.tick;
@@ -479,8 +2119,15 @@ impl DedicatedServer {
                         &pmove_cmd,
                         dt,
                         &self.game_state.map,
+                        &self.game_state.physics,
                     );
 
+                    if result.fall_damage > 0 {
+                        landings.push((player_id, result.fall_damage));
+                    }
+                    server_player.water_level = result.water_level;
+                    server_player.liquid = result.liquid;
+
                     let mut teleported = false;
                     for teleporter in &self.game_state.map.teleporters {
                         if result.new_x >= teleporter.x
@@ -488,9 +2135,9 @@ impl DedicatedServer {
                             && result.new_y >= teleporter.y
                             && result.new_y <= teleporter.y + teleporter.height
                         {
-                            println!(
-                                "[{:.3}] [SERVER TELEPORT] p{} from ({:.1},{:.1}) to ({:.1},{:.1})",
-                                sas::network::get_network_time(),
+                            log_debug!(
+                                Game,
+                                "Teleport p{} from ({:.1},{:.1}) to ({:.1},{:.1})",
                                 player_id,
                                 result.new_x,
                                 result.new_y,
@@ -502,6 +2149,8 @@ impl DedicatedServer {
                             server_player.pmove_state.vel_x = result.new_vel_x;
                             server_player.pmove_state.vel_y = result.new_vel_y;
                             server_player.pmove_state.was_in_air = result.new_was_in_air;
+                            server_player.pmove_state.jump_time = result.new_jump_time;
+                            server_player.pmove_state.fall_speed = 0.0;
                             teleported = true;
                             break;
                         }
@@ -513,10 +2162,75 @@ impl DedicatedServer {
                         server_player.pmove_state.vel_x = result.new_vel_x;
                         server_player.pmove_state.vel_y = result.new_vel_y;
                         server_player.pmove_state.was_in_air = result.new_was_in_air;
+                        server_player.pmove_state.jump_time = result.new_jump_time;
+                        server_player.pmove_state.fall_speed = result.new_fall_speed;
                     }
                 }
             }
         }
+
+        for player_id in item_uses {
+            self.use_holdable(player_id);
+        }
+
+        let rules = DamageRules::from_cvars(self.game_state.mutators);
+        let scale = sas::cvar::get_cvar_float("g_fallingdamage").max(0.0);
+        for (player_id, fall_damage) in landings {
+            let damage = (fall_damage as f32 * scale).round() as i32;
+            self.world_damage(player_id, damage, MeansOfDeath::Falling, &rules);
+        }
+        let crushed: Vec<u16> = self
+            .game_state
+            .players
+            .iter()
+            .filter(|(_, p)| {
+                !p.dead
+                    && collision::is_crushed(p.pmove_state.x, p.pmove_state.y, &self.game_state.map)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for player_id in crushed {
+            self.world_damage(player_id, WORLD_KILL_DAMAGE, MeansOfDeath::Crushed, &rules);
+        }
+
+        let mut liquid_hurts = Vec::new();
+        for (player_id, player) in &mut self.game_state.players {
+            if player.dead || player.frozen {
+                continue;
+            }
+            if let Some((damage, means)) =
+                player.breath.update(player.water_level, player.liquid, dt)
+            {
+                liquid_hurts.push((*player_id, damage, means));
+            }
+        }
+        for (player_id, damage, means) in liquid_hurts {
+            self.world_damage(player_id, damage, means, &rules);
+        }
+    }
+
+    fn use_holdable(&mut self, player_id: u16) {
+        let Some(player) = self.game_state.players.get_mut(&player_id) else {
+            return;
+        };
+        let team = player.team;
+        if pickup::use_holdable(player) != Some(Holdable::Teleporter) {
+            return;
+        }
+        let destination = self.select_spawn(player_id, team);
+        if let (Some((x, y)), Some(player)) =
+            (destination, self.game_state.players.get_mut(&player_id))
+        {
+            log_debug!(Game, "p{} used the personal teleporter", player_id);
+            player.pmove_state.x = x;
+            player.pmove_state.y = y;
+            player.pmove_state.vel_x = 0.0;
+            player.pmove_state.vel_y = 0.0;
+            player.pmove_state.fall_speed = 0.0;
+        }
+        if destination.is_some() {
+            self.telefrag(player_id);
+        }
     }
 
     fn handle_player_shoot(
@@ -534,7 +2248,24 @@ impl DedicatedServer {
         };
         self.server.broadcast(msg).ok();
 
-        if weapon == 3 || weapon == 4 || weapon == 5 || weapon == 6 {
+        if let Some(weapon) = Weapon::from_u8(weapon) {
+            self.game_state.stats.record_shot(client_id, weapon);
+        }
+
+        if weapon == Weapon::Nailgun as u8 {
+            for mut nail in
+                Projectile::nailgun_spread(origin.0, origin.1, direction, client_id, 0.0, 0.0)
+            {
+                nail.id = self.game_state.next_projectile_id;
+                self.game_state.next_projectile_id += 1;
+                self.game_state.projectiles.push(nail);
+            }
+        } else if weapon == 3
+            || weapon == 4
+            || weapon == 5
+            || weapon == 6
+            || weapon == Weapon::ProxLauncher as u8
+        {
             let weapon_enum: sas::game::weapon::Weapon = unsafe { std::mem::transmute(weapon) };
 
             let mut projectile = Projectile::new(
@@ -555,7 +2286,7 @@ impl DedicatedServer {
 
     fn handle_chat(&mut self, client_id: u16, message: String) {
         if let Some(name) = self.server.get_client_name(client_id) {
-            println!("[CHAT] {}: {}", name, message);
+            log_info!(Game, "{}: {}", name, message);
 
             let chat_msg = NetMessage::Chat {
                 player_id: client_id,
@@ -573,16 +2304,16 @@ impl DedicatedServer {
 
     fn check_collisions(&mut self) {
         let projectiles = self.game_state.projectiles.clone();
-        let mut explosion_events: Vec<(f32, f32, i32, f32, u16)> = Vec::new();
+        let mut explosion_events: Vec<(f32, f32, i32, f32, u16, Weapon)> = Vec::new();
 
         for proj in &projectiles {
             let explosion_radius = proj.explosion_radius();
             let has_explosion = explosion_radius > 0.0;
 
             if !proj.active && has_explosion {
-                println!(
-                    "[{:.3}] [SERVER] Projectile {} hit wall, explosion at ({:.1}, {:.1})",
-                    sas::network::get_network_time(),
+                log_debug!(
+                    Game,
+                    "Projectile {} hit wall, explosion at ({:.1}, {:.1})",
                     proj.id,
                     proj.x,
                     proj.y
@@ -593,6 +2324,7 @@ impl DedicatedServer {
                     proj.damage,
                     explosion_radius,
                     proj.owner_id,
+                    proj.weapon_type,
                 ));
                 continue;
             }
@@ -606,9 +2338,9 @@ impl DedicatedServer {
                     && proj.check_hit(player.pmove_state.x, player.pmove_state.y)
                 {
                     if has_explosion {
-                        println!(
-                            "[{:.3}] [SERVER] Projectile {} direct hit on player {}",
-                            sas::network::get_network_time(),
+                        log_debug!(
+                            Game,
+                            "Projectile {} direct hit on player {}",
                             proj.id,
                             player_id
                         );
@@ -619,6 +2351,7 @@ impl DedicatedServer {
                             proj.damage,
                             explosion_radius,
                             proj.owner_id,
+                            proj.weapon_type,
                         ));
 
                         if let Some(p) = self
@@ -630,14 +2363,14 @@ impl DedicatedServer {
                             p.active = false;
                         }
                     } else {
-                        println!(
-                            "[{:.3}] [SERVER] Projectile {} hit player {}",
-                            sas::network::get_network_time(),
-                            proj.id,
-                            player_id
-                        );
+                        log_debug!(Game, "Projectile {} hit player {}", proj.id, player_id);
 
-                        self.apply_direct_damage(proj.owner_id, *player_id, proj.damage);
+                        self.apply_direct_damage(
+                            proj.owner_id,
+                            *player_id,
+                            proj.damage,
+                            proj.weapon_type,
+                        );
 
                         if let Some(p) = self
                             .game_state
@@ -653,8 +2386,8 @@ impl DedicatedServer {
             }
         }
 
-        for (explosion_x, explosion_y, damage, radius, owner_id) in explosion_events {
-            self.apply_explosion_damage(explosion_x, explosion_y, damage, radius, owner_id);
+        for (explosion_x, explosion_y, damage, radius, owner_id, weapon) in explosion_events {
+            self.apply_explosion_damage(explosion_x, explosion_y, damage, radius, owner_id, weapon);
         }
 
         self.game_state.projectiles.retain(|p| p.active);
@@ -667,31 +2400,36 @@ impl DedicatedServer {
         damage: i32,
         radius: f32,
         owner_id: u16,
+        weapon: Weapon,
     ) {
+        let rules = DamageRules::from_cvars(self.game_state.mutators);
+        let mut dealt_total = 0;
+        let mut mirrored_total = 0;
         let player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        let combatants: Vec<Combatant> = player_ids.iter().map(|&id| self.combatant(id)).collect();
+        let (protected, teammates) = bg_combat::damage_groups(
+            &*self.game_state.gametype,
+            owner_id,
+            &combatants,
+            self.between_rounds(),
+            &rules,
+        );
 
         for player_id in player_ids {
             if let Some(player) = self.game_state.players.get_mut(&player_id) {
-                let dx = player.pmove_state.x - explosion_x;
-                let dy = player.pmove_state.y - explosion_y;
-                let dist = (dx * dx + dy * dy).sqrt();
-
-                if dist < radius {
-                    let distance_falloff = (radius - dist) / radius;
-                    let damage_points = (damage as f32 * distance_falloff).max(0.0);
-
-                    let mass = 200.0;
-                    let g_knockback = 1600.0;
-                    let knockback = damage_points.min(200.0);
-                    let knockback_scale = g_knockback * knockback / mass;
-
-                    if dist > 0.1 {
-                        let dir_x = dx / dist;
-                        let dir_y = dy / dist;
-
-                        player.pmove_state.vel_x += dir_x * knockback_scale;
-                        player.pmove_state.vel_y += dir_y * knockback_scale;
-                    }
+                if player.frozen {
+                    continue;
+                }
+                if let Some(hit) = bg_combat::splash_hit(
+                    (explosion_x, explosion_y),
+                    (player.pmove_state.x, player.pmove_state.y),
+                    damage,
+                    radius,
+                    &rules,
+                ) {
+                    let damage_points = hit.damage;
+                    player.pmove_state.vel_x += hit.push_x;
+                    player.pmove_state.vel_y += hit.push_y;
 
                     if player.dead {
                         if !player.gibbed {
@@ -706,9 +2444,25 @@ impl DedicatedServer {
                                 self.server.broadcast(gib_msg).ok();
                             }
                         }
-                    } else if player_id != owner_id {
-                        let actual_damage = damage_points as i32;
-                        player.health -= actual_damage;
+                    } else if player_id != owner_id && !protected.contains(&player_id) {
+                        let (_, event) = bg_combat::apply_damage(
+                            &mut player.health,
+                            &mut player.armor,
+                            player.powerups.absorb(damage_points as i32),
+                            owner_id,
+                            player_id,
+                            teammates.contains(&player_id),
+                            &rules,
+                        );
+                        let actual_damage = event.damage;
+                        dealt_total += actual_damage;
+                        mirrored_total += event.mirrored;
+                        self.game_state.stats.record_damage(
+                            owner_id,
+                            player_id,
+                            weapon,
+                            actual_damage,
+                        );
 
                         let msg = NetMessage::PlayerDamaged {
                             target_id: player_id,
@@ -729,12 +2483,17 @@ impl DedicatedServer {
                             let death_pos = (player.pmove_state.x, player.pmove_state.y);
                             let death_vel = (player.pmove_state.vel_x, player.pmove_state.vel_y);
 
-                            if let Some(attacker) = self.game_state.players.get_mut(&owner_id) {
-                                attacker.frags += 1;
-                            }
+                            self.award_kill(owner_id, player_id, MeansOfDeath::Weapon(weapon));
 
-                            println!("[{:.3}] [SERVER] Player {} killed by {} explosion (gibbed: {}) at ({:.1}, {:.1})",
-                                get_network_time(), player_id, owner_id, gibbed, death_pos.0, death_pos.1);
+                            log_info!(
+                                Game,
+                                "Player {} killed by {} explosion (gibbed: {}) at ({:.1}, {:.1})",
+                                player_id,
+                                owner_id,
+                                gibbed,
+                                death_pos.0,
+                                death_pos.1
+                            );
 
                             let death_msg = NetMessage::PlayerDied {
                                 player_id,
@@ -742,16 +2501,31 @@ impl DedicatedServer {
                                 gibbed,
                                 position: death_pos,
                                 velocity: death_vel,
+                                means: MeansOfDeath::Weapon(weapon),
                             };
                             self.server.broadcast(death_msg).ok();
                         }
-                    } else if owner_id == player_id {
-                        let self_damage = (damage_points * 0.5) as i32;
-                        player.health -= self_damage;
+                    } else if owner_id == player_id && !protected.contains(&player_id) {
+                        let (_, event) = bg_combat::apply_damage(
+                            &mut player.health,
+                            &mut player.armor,
+                            player.powerups.absorb(damage_points as i32),
+                            owner_id,
+                            player_id,
+                            false,
+                            &rules,
+                        );
+                        let self_damage = event.damage;
+                        self.game_state.stats.record_damage(
+                            owner_id,
+                            player_id,
+                            weapon,
+                            self_damage,
+                        );
 
-                        println!(
-                            "[{:.3}] [SERVER] Rocketjump! Player {} dmg={} vel=({:.2}, {:.2})",
-                            sas::network::get_network_time(),
+                        log_debug!(
+                            Game,
+                            "Rocketjump! Player {} dmg={} vel=({:.2}, {:.2})",
                             player_id,
                             self_damage,
                             player.pmove_state.vel_x,
@@ -777,9 +2551,11 @@ impl DedicatedServer {
                             let death_pos = (player.pmove_state.x, player.pmove_state.y);
                             let death_vel = (player.pmove_state.vel_x, player.pmove_state.vel_y);
 
-                            println!(
-                                "[{:.3}] [SERVER] Player {} suicide (gibbed: {}) at ({:.1}, {:.1})",
-                                get_network_time(),
+                            self.award_kill(player_id, player_id, MeansOfDeath::Weapon(weapon));
+
+                            log_info!(
+                                Game,
+                                "Player {} suicide (gibbed: {}) at ({:.1}, {:.1})",
                                 player_id,
                                 gibbed,
                                 death_pos.0,
@@ -792,6 +2568,7 @@ impl DedicatedServer {
                                 gibbed,
                                 position: death_pos,
                                 velocity: death_vel,
+                                means: MeansOfDeath::Weapon(weapon),
                             };
                             self.server.broadcast(death_msg).ok();
                         }
@@ -799,11 +2576,30 @@ impl DedicatedServer {
                 }
             }
         }
+        self.vampire_heal(owner_id, dealt_total);
+        self.world_damage(
+            owner_id,
+            mirrored_total,
+            MeansOfDeath::Weapon(weapon),
+            &rules,
+        );
     }
 
-    fn apply_direct_damage(&mut self, attacker_id: u16, target_id: u16, damage: i32) {
+    fn apply_direct_damage(
+        &mut self,
+        attacker_id: u16,
+        target_id: u16,
+        damage: i32,
+        weapon: Weapon,
+    ) {
+        let rules = DamageRules::from_cvars(self.game_state.mutators);
+        if !self.damage_allowed(attacker_id, target_id, &rules) {
+            return;
+        }
+        let teammates = self.teammates(attacker_id, target_id);
+
         if let Some(target) = self.game_state.players.get_mut(&target_id) {
-            if target.dead && !target.gibbed {
+            if target.dead {
                 let was_gibbed = target.gibbed;
                 target.health -= damage;
                 if target.health <= GIB_HEALTH && !was_gibbed {
@@ -818,7 +2614,19 @@ impl DedicatedServer {
                 return;
             }
 
-            target.health -= damage;
+            let (_, event) = bg_combat::apply_damage(
+                &mut target.health,
+                &mut target.armor,
+                target.powerups.absorb(damage),
+                attacker_id,
+                target_id,
+                teammates,
+                &rules,
+            );
+            let (damage, mirrored) = (event.damage, event.mirrored);
+            self.game_state
+                .stats
+                .record_damage(attacker_id, target_id, weapon, damage);
 
             let msg = NetMessage::PlayerDamaged {
                 target_id,
@@ -839,13 +2647,11 @@ impl DedicatedServer {
                 let death_pos = (target.pmove_state.x, target.pmove_state.y);
                 let death_vel = (target.pmove_state.vel_x, target.pmove_state.vel_y);
 
-                if let Some(attacker) = self.game_state.players.get_mut(&attacker_id) {
-                    attacker.frags += 1;
-                }
+                self.award_kill(attacker_id, target_id, MeansOfDeath::Weapon(weapon));
 
-                println!(
-                    "[{:.3}] [SERVER] Player {} killed by {} (gibbed: {}) at ({:.1}, {:.1})",
-                    get_network_time(),
+                log_info!(
+                    Game,
+                    "Player {} killed by {} (gibbed: {}) at ({:.1}, {:.1})",
                     target_id,
                     attacker_id,
                     gibbed,
@@ -859,29 +2665,55 @@ impl DedicatedServer {
                     gibbed,
                     position: death_pos,
                     velocity: death_vel,
+                    means: MeansOfDeath::Weapon(weapon),
                 };
                 self.server.broadcast(death_msg).ok();
             }
+            self.vampire_heal(attacker_id, damage);
+            self.world_damage(attacker_id, mirrored, MeansOfDeath::Weapon(weapon), &rules);
+        }
+    }
+
+    fn vampire_heal(&mut self, attacker_id: u16, dealt: i32) {
+        let mutators = self.game_state.mutators;
+        if let Some(attacker) = self.game_state.players.get_mut(&attacker_id) {
+            if !attacker.dead {
+                attacker.health = mutators.vampire_heal(attacker.health, dealt);
+            }
         }
     }
 
     fn respawn_player(&mut self, player_id: u16) {
+        let Some(team) = self.game_state.players.get(&player_id).map(|p| p.team) else {
+            return;
+        };
+        let spawn_point = self.select_spawn(player_id, team);
+        let loadout = self.game_state.spawn_loadout();
         if let Some(player) = self.game_state.players.get_mut(&player_id) {
-            let spawn_idx = (player_id as usize) % self.game_state.map.spawn_points.len().max(1);
-            if let Some(spawn_point) = self.game_state.map.spawn_points.get(spawn_idx) {
-                player.pmove_state.x = spawn_point.x;
-                player.pmove_state.y = spawn_point.y;
+            if let Some((spawn_x, spawn_y)) = spawn_point {
+                player.pmove_state.x = spawn_x;
+                player.pmove_state.y = spawn_y;
                 player.pmove_state.vel_x = 0.0;
                 player.pmove_state.vel_y = 0.0;
+                player.pmove_state.fall_speed = 0.0;
                 player.health = 100;
                 player.dead = false;
                 player.gibbed = false;
+                player.frozen = false;
+                player.thaw_progress = 0.0;
                 player.respawn_timer = 0.0;
                 player.armor = 0;
+                player.powerups.clear();
+                player.holdable = None;
+                player.grapple = Grapple::Idle;
+                player.breath = Breath::default();
+                if let Some(loadout) = &loadout {
+                    player.apply_loadout(loadout);
+                }
 
                 let respawn_msg = NetMessage::PlayerRespawn {
                     player_id,
-                    position: (spawn_point.x, spawn_point.y),
+                    position: (spawn_x, spawn_y),
                 };
                 self.server.broadcast(respawn_msg).ok();
             }
@@ -889,132 +2721,397 @@ impl DedicatedServer {
     }
 
     fn check_item_pickups(&mut self) {
-        let player_ids: Vec<u16> = self.game_state.players.keys().copied().collect();
+        pickup::tick_respawns(&mut self.game_state.map.items);
+        if !self.game_state.gametype.items_enabled() {
+            return;
+        }
 
-        for player_id in player_ids {
-            if let Some(player) = self.game_state.players.get(&player_id) {
-                let px = player.pmove_state.x;
-                let py = player.pmove_state.y;
-
-                for item in &mut self.game_state.map.items {
-                    if !item.active {
-                        if item.respawn_time > 0 {
-                            item.respawn_time -= 1;
-                        } else {
-                            item.active = true;
+        for (player_id, player) in &mut self.game_state.players {
+            if player.dead {
+                continue;
+            }
+            let (px, py) = (player.pmove_state.x, player.pmove_state.y);
+            for item in &mut self.game_state.map.items {
+                if !item.active || !pickup::in_reach(px, py, item) {
+                    continue;
+                }
+                if !pickup::touch(player, item.item_type) {
+                    continue;
+                }
+                item.active = false;
+                item.respawn_time = pickup::respawn_time(item.item_type);
+                self.game_state
+                    .stats
+                    .record_pickup(*player_id, item.item_type);
+                if pickup::kind(item.item_type) == pickup::PickupKind::Powerup {
+                    log_info!(Game, "Player {} picked up {:?}", player_id, item.item_type);
+                }
+            }
+        }
+    }
+
+    fn execute_command(&mut self, line: &str) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() {
+            return;
+        }
+
+        let cmd = parts[0].to_lowercase();
+        match cmd.as_str() {
+            "kick" => {
+                if parts.len() < 2 {
+                    println!("Usage: kick <id|name>");
+                    return;
+                }
+                let target = parts[1..].join(" ");
+                match self.find_player(&target) {
+                    Some(client_id) => self.kick_player(client_id, "Kicked by admin"),
+                    None => println!("No player matching '{}'", target),
+                }
+            }
+            "ban" => {
+                if parts.len() < 2 {
+                    println!("Usage: ban <ip/mask> [duration]");
+                    return;
+                }
+                let duration = match parts.get(2).map(|d| parse_duration(d)) {
+                    Some(Ok(secs)) => Some(secs),
+                    Some(Err(e)) => {
+                        println!("{}", e);
+                        return;
+                    }
+                    None => None,
+                };
+                match self.server.ban_list_mut().add(parts[1], duration) {
+                    Ok(entry) => {
+                        match duration {
+                            Some(secs) => println!("Banned {} for {}s", entry.mask_string(), secs),
+                            None => println!("Banned {} permanently", entry.mask_string()),
                         }
-                        continue;
+                        self.enforce_ban_list();
                     }
-
-                    let dx = px - item.x;
-                    let dy = py - item.y;
-                    if (dx * dx + dy * dy).sqrt() < 24.0 {
-                        use sas::game::map::ItemType::*;
-
-                        let mut picked_up = false;
-
-                        if let Some(player) = self.game_state.players.get_mut(&player_id) {
-                            match item.item_type {
-                                Health25 => {
-                                    if player.health < 100 {
-                                        player.health = (player.health + 25).min(100);
-                                        picked_up = true;
-                                        item.respawn_time = 300;
-                                    }
-                                }
-                                Health50 => {
-                                    if player.health < 100 {
-                                        player.health = (player.health + 50).min(100);
-                                        picked_up = true;
-                                        item.respawn_time = 300;
-                                    }
-                                }
-                                Health100 => {
-                                    if player.health < 200 {
-                                        player.health = (player.health + 100).min(200);
-                                        picked_up = true;
-                                        item.respawn_time = 300;
-                                    }
-                                }
-                                Armor50 => {
-                                    if player.armor < 100 {
-                                        player.armor = (player.armor + 50).min(100);
-                                        picked_up = true;
-                                        item.respawn_time = 300;
-                                    }
-                                }
-                                Armor100 => {
-                                    if player.armor < 200 {
-                                        player.armor = (player.armor + 100).min(200);
-                                        picked_up = true;
-                                        item.respawn_time = 300;
-                                    }
-                                }
-                                RocketLauncher => {
-                                    picked_up = true;
-                                    item.respawn_time = 300;
-                                }
-                                LightningGun => {
-                                    picked_up = true;
-                                    item.respawn_time = 300;
-                                }
-                                Railgun => {
-                                    picked_up = true;
-                                    item.respawn_time = 300;
-                                }
-                                Plasmagun => {
-                                    picked_up = true;
-                                    item.respawn_time = 300;
-                                }
-                                Shotgun => {
-                                    picked_up = true;
-                                    item.respawn_time = 300;
-                                }
-                                GrenadeLauncher => {
-                                    picked_up = true;
-                                    item.respawn_time = 300;
-                                }
-                                BFG => {
-                                    picked_up = true;
-                                    item.respawn_time = 600;
-                                }
-                                Quad => {
-                                    player.powerup_quad = 1800;
-                                    picked_up = true;
-                                    item.respawn_time = 7200;
-                                    println!(
-                                        "[{:.3}] [SERVER] Player {} picked up QUAD DAMAGE!",
-                                        sas::network::get_network_time(),
-                                        player_id
-                                    );
-                                }
-                                Regen => {
-                                    picked_up = true;
-                                    item.respawn_time = 7200;
-                                }
-                                Battle => {
-                                    picked_up = true;
-                                    item.respawn_time = 7200;
-                                }
-                                Flight => {
-                                    picked_up = true;
-                                    item.respawn_time = 3600;
-                                }
-                                Haste => {
-                                    picked_up = true;
-                                    item.respawn_time = 7200;
-                                }
-                                Invis => {
-                                    picked_up = true;
-                                    item.respawn_time = 7200;
-                                }
+                    Err(e) => println!("{}", e),
+                }
+            }
+            "unban" => {
+                if parts.len() < 2 {
+                    println!("Usage: unban <ip/mask>");
+                    return;
+                }
+                match self.server.ban_list_mut().remove(parts[1]) {
+                    Ok(entry) => println!("Removed ban {}", entry.mask_string()),
+                    Err(e) => println!("{}", e),
+                }
+            }
+            "listbans" => {
+                self.server.ban_list_mut().prune_expired();
+                let ban_list = self.server.ban_list();
+                println!(
+                    "g_filterban {} ({} entries)",
+                    if ban_list.filter_ban { 1 } else { 0 },
+                    ban_list.entries.len()
+                );
+                let now = sas::network::ban_list::unix_time();
+                for (i, entry) in ban_list.entries.iter().enumerate() {
+                    if entry.is_permanent() {
+                        println!("{:3}: {} permanent", i, entry.mask_string());
+                    } else {
+                        println!(
+                            "{:3}: {} expires in {}s",
+                            i,
+                            entry.mask_string(),
+                            entry.expires.saturating_sub(now)
+                        );
+                    }
+                }
+            }
+            "g_filterban" => {
+                if parts.len() < 2 {
+                    println!(
+                        "\"g_filterban\" is \"{}\"",
+                        sas::cvar::get_cvar_string("g_filterban")
+                    );
+                    return;
+                }
+                sas::cvar::set_cvar("g_filterban", parts[1]);
+                self.server.ban_list_mut().filter_ban =
+                    sas::cvar::get_cvar_integer("g_filterban") != 0;
+                self.enforce_ban_list();
+            }
+            "g_roundlimit" => {
+                if parts.len() < 2 {
+                    println!(
+                        "\"g_roundlimit\" is \"{}\"",
+                        sas::cvar::get_cvar_string("g_roundlimit")
+                    );
+                    return;
+                }
+                sas::cvar::set_cvar("g_roundlimit", parts[1]);
+                self.game_state.round.round_limit =
+                    sas::cvar::get_cvar_integer("g_roundlimit").max(0);
+            }
+            "g_gametype" => {
+                if parts.len() < 2 {
+                    println!(
+                        "\"g_gametype\" is \"{}\" ({})",
+                        sas::cvar::get_cvar_string("g_gametype"),
+                        self.game_state.gametype.kind().name()
+                    );
+                    return;
+                }
+                match GametypeKind::parse(parts[1]) {
+                    Some(kind) => {
+                        sas::cvar::set_cvar("g_gametype", &(kind as u8).to_string());
+                        self.set_gametype(kind);
+                    }
+                    None => println!(
+                        "Unknown gametype '{}' (ffa, duel, tdm, ctf, ca, ft)",
+                        parts[1]
+                    ),
+                }
+            }
+            "g_mutators" => {
+                if parts.len() < 2 {
+                    println!(
+                        "\"g_mutators\" is \"{}\" ({})",
+                        sas::cvar::get_cvar_string("g_mutators"),
+                        self.game_state.mutators.names()
+                    );
+                    return;
+                }
+                let value = parts[1..].join(",");
+                match Mutators::parse(&value) {
+                    Ok(mutators) => {
+                        sas::cvar::set_cvar("g_mutators", &value);
+                        self.set_mutators(mutators);
+                    }
+                    Err(e) => println!("{}", e),
+                }
+            }
+            "g_warmup" | "g_minready" => {
+                if parts.len() < 2 {
+                    println!("\"{}\" is \"{}\"", cmd, sas::cvar::get_cvar_string(&cmd));
+                    return;
+                }
+                sas::cvar::set_cvar(&cmd, parts[1]);
+                println!("{} will apply on the next map_restart", cmd);
+            }
+            "tournament" => {
+                if parts.len() < 2 {
+                    match &self.game_state.tournament {
+                        Some(tournament) => {
+                            for line in tournament.status_lines() {
+                                println!("{}", line);
                             }
                         }
-
-                        if picked_up {
-                            item.active = false;
-                        }
+                        None => println!("No tournament running"),
+                    }
+                    return;
+                }
+                match Tournament::load(parts[1]) {
+                    Ok(tournament) => self.start_tournament(tournament),
+                    Err(e) => println!("{}", e),
+                }
+            }
+            "queue" => {
+                let waiting: Vec<String> = self
+                    .game_state
+                    .queue
+                    .waiting()
+                    .iter()
+                    .map(|&id| self.player_name(id))
+                    .collect();
+                println!(
+                    "Queue ({}): {}",
+                    self.game_state.queue.rotation.name(),
+                    if waiting.is_empty() {
+                        "empty".to_string()
+                    } else {
+                        waiting.join(", ")
+                    }
+                );
+            }
+            "g_duelqueue" => {
+                if parts.len() < 2 {
+                    println!(
+                        "\"g_duelqueue\" is \"{}\" ({})",
+                        sas::cvar::get_cvar_string("g_duelqueue"),
+                        self.game_state.queue.rotation.name()
+                    );
+                    return;
+                }
+                sas::cvar::set_cvar("g_duelqueue", parts[1]);
+                self.game_state.queue.rotation = DuelQueue::from_cvar().rotation;
+            }
+            "g_grapple" | "g_grapplepull" => {
+                if parts.len() < 2 {
+                    println!("\"{}\" is \"{}\"", cmd, sas::cvar::get_cvar_string(&cmd));
+                    return;
+                }
+                sas::cvar::set_cvar(&cmd, parts[1]);
+                self.reload_physics();
+                log_info!(
+                    Game,
+                    "Grappling hook pull {}",
+                    self.game_state.physics.grapple_pull
+                );
+            }
+            "pm_physics" | "pm_gravity" | "pm_accel" | "pm_airaccel" | "pm_maxspeed"
+            | "pm_airmaxspeed" | "pm_jumpforce" | "pm_aircontrol" | "pm_doublejump"
+            | "pm_rampjump" => {
+                if parts.len() < 2 {
+                    println!("\"{}\" is \"{}\"", cmd, sas::cvar::get_cvar_string(&cmd));
+                    return;
+                }
+                sas::cvar::set_cvar(&cmd, parts[1]);
+                self.reload_physics();
+                log_info!(Game, "Physics {}", sas::cvar::get_cvar_string("pm_physics"));
+            }
+            "g_fallingdamage" => {
+                if parts.len() < 2 {
+                    println!(
+                        "\"g_fallingdamage\" is \"{}\"",
+                        sas::cvar::get_cvar_string("g_fallingdamage")
+                    );
+                    return;
+                }
+                sas::cvar::set_cvar("g_fallingdamage", parts[1]);
+                log_info!(Game, "Falling damage scale {}", parts[1]);
+            }
+            "g_friendlyfire" | "g_selfdamage" | "g_knockback" => {
+                if parts.len() < 2 {
+                    println!("\"{}\" is \"{}\"", cmd, sas::cvar::get_cvar_string(&cmd));
+                    return;
+                }
+                sas::cvar::set_cvar(&cmd, parts[1]);
+                let rules = DamageRules::from_cvars(self.game_state.mutators);
+                log_info!(
+                    Game,
+                    "Damage rules: friendly fire {:?}, self damage {}, knockback {}",
+                    rules.friendly_fire,
+                    rules.self_damage,
+                    rules.knockback
+                );
+            }
+            "g_enforceweapons" => {
+                if parts.len() < 2 {
+                    println!(
+                        "\"g_enforceweapons\" is \"{}\"",
+                        sas::cvar::get_cvar_string("g_enforceweapons")
+                    );
+                    return;
+                }
+                sas::cvar::set_cvar("g_enforceweapons", parts[1]);
+                let info = self.server_info();
+                self.server.broadcast(info).ok();
+            }
+            "reload_weapons" => {
+                let path = sas::cvar::get_cvar_string("g_weaponfile");
+                match WeaponTable::load(&path) {
+                    Ok(table) => {
+                        weapon_defs::install_local(table);
+                        log_info!(Game, "Weapon table {:08x}", weapon_defs::checksum());
+                        let info = self.server_info();
+                        self.server.broadcast(info).ok();
                     }
+                    Err(e) => log_warn!(Game, "Weapon table not reloaded: {}", e),
+                }
+            }
+            "g_statsdir" => {
+                if parts.len() < 2 {
+                    println!(
+                        "\"g_statsdir\" is \"{}\"",
+                        sas::cvar::get_cvar_string("g_statsdir")
+                    );
+                    return;
+                }
+                sas::cvar::set_cvar("g_statsdir", parts[1]);
+            }
+            "map_restart" => {
+                self.restart_match();
+            }
+            "pause" | "timeout" => {
+                self.call_pause(None);
+            }
+            "unpause" | "timein" => {
+                self.call_unpause(None);
+            }
+            "fraglimit" | "capturelimit" | "mercylimit" | "g_overtime" => {
+                if parts.len() < 2 {
+                    println!("\"{}\" is \"{}\"", cmd, sas::cvar::get_cvar_string(&cmd));
+                    return;
+                }
+                sas::cvar::set_cvar(&cmd, parts[1]);
+                let rules = MatchRules::from_cvar();
+                self.game_state.rules.fraglimit = rules.fraglimit;
+                self.game_state.rules.capturelimit = rules.capturelimit;
+                self.game_state.rules.mercylimit = rules.mercylimit;
+                self.game_state.rules.overtime_length = rules.overtime_length;
+            }
+            "g_timeouts" | "g_timeoutlen" => {
+                if parts.len() < 2 {
+                    println!("\"{}\" is \"{}\"", cmd, sas::cvar::get_cvar_string(&cmd));
+                    return;
+                }
+                sas::cvar::set_cvar(&cmd, parts[1]);
+                let pause = PauseState::from_cvar();
+                self.game_state.pause.max_timeouts = pause.max_timeouts;
+                self.game_state.pause.max_duration = pause.max_duration;
+            }
+            "developer" | "logfile" | "log_json" => {
+                if parts.len() < 2 {
+                    println!("\"{}\" is \"{}\"", cmd, sas::cvar::get_cvar_string(&cmd));
+                    return;
+                }
+                sas::cvar::set_cvar(&cmd, parts[1]);
+                sas::logging::update();
+            }
+            "addbot" => {
+                self.handle_addbot_command(0);
+            }
+            _ => {
+                println!("Unknown command: {}", cmd);
+            }
+        }
+    }
+
+    fn find_player(&self, target: &str) -> Option<u16> {
+        if let Ok(id) = target.parse::<u16>() {
+            if self.game_state.players.contains_key(&id) {
+                return Some(id);
+            }
+        }
+
+        self.game_state
+            .players
+            .iter()
+            .find(|(_, p)| p.name.eq_ignore_ascii_case(target))
+            .map(|(id, _)| *id)
+    }
+
+    fn kick_player(&mut self, client_id: u16, reason: &str) {
+        let name = match self.game_state.players.get(&client_id) {
+            Some(player) => player.name.clone(),
+            None => return,
+        };
+
+        log_info!(Game, "Kicking {} ({}): {}", client_id, name, reason);
+
+        self.server.disconnect_client(client_id, reason.to_string());
+        self.remove_player(client_id);
+
+        let chat_msg = NetMessage::Chat {
+            player_id: 0,
+            message: format!("{} was kicked", name),
+        };
+        self.server.broadcast(chat_msg).ok();
+    }
+
+    fn enforce_ban_list(&mut self) {
+        for client_id in self.server.client_ids() {
+            if let Some(addr) = self.server.get_client_addr(client_id) {
+                if let Err(reason) = self.server.ban_list().check(&addr.ip()) {
+                    self.kick_player(client_id, &reason);
                 }
             }
         }
@@ -1035,11 +3132,13 @@ impl DedicatedServer {
         ];
         let bot_name = bot_names[bot_id as usize % bot_names.len()].to_string();
 
-        self.add_player_internal(bot_id, bot_name.clone(), true);
+        if !self.add_player_internal(bot_id, bot_name.clone(), true) {
+            return;
+        }
 
-        println!(
-            "[{:.3}] [SERVER] Bot {} added by client {}",
-            sas::network::get_network_time(),
+        log_info!(
+            Bot,
+            "Bot {} added by client {}",
             bot_name,
             requesting_client_id
         );
@@ -1168,9 +3267,11 @@ impl DedicatedServer {
             armor: sp.armor,
             frags: sp.frags,
             deaths: sp.deaths,
-            team: 0,
+            team: sp.team,
             dead: sp.dead,
             gibbed: false,
+            frozen: sp.frozen,
+            thaw_progress: sp.thaw_progress,
             is_bot: sp.is_bot,
             crouch: (sp.last_cmd.buttons & 4) != 0,
             weapon,
@@ -1184,6 +3285,9 @@ impl DedicatedServer {
                 sp.ammo[6] as u8,
                 sp.ammo[7] as u8,
                 sp.ammo[8] as u8,
+                sp.ammo[9] as u8,
+                sp.ammo[10] as u8,
+                sp.ammo[11] as u8,
             ],
             has_weapon: [
                 true,
@@ -1195,17 +3299,22 @@ impl DedicatedServer {
                 sp.ammo[6] > 0,
                 sp.ammo[7] > 0,
                 sp.ammo[8] > 0,
+                sp.ammo[9] > 0,
+                sp.ammo[10] > 0,
+                sp.ammo[11] > 0,
             ],
             refire: 0.0,
             weapon_switch_time: 0.0,
-            powerups: sas::game::player::PowerUps {
-                quad: sp.powerup_quad,
-                regen: 0,
-                battle: 0,
-                flight: 0,
-                haste: 0,
-                invis: 0,
-            },
+            powerups: sp.powerups,
+            holdable: sp.holdable,
+            use_held: sp.use_held,
+            grapple: sp.grapple,
+            jump_time: sp.pmove_state.jump_time,
+            fall_speed: sp.pmove_state.fall_speed,
+            fall_damage: 0,
+            water_level: sp.water_level,
+            liquid: sp.liquid,
+            breath: sp.breath,
             animation: sas::game::animation::PlayerAnimation::new(),
             bot_ai: None,
             was_in_air: sp.pmove_state.was_in_air,
@@ -1266,11 +3375,17 @@ impl DedicatedServer {
                     ammo: server_player.ammo,
                     frags: server_player.frags,
                     deaths: server_player.deaths,
-                    powerup_quad: server_player.powerup_quad,
+                    team: server_player.team,
+                    powerups: server_player.powerups,
+                    holdable: server_player.holdable,
+                    grapple: server_player.grapple,
+                    jump_time: server_player.pmove_state.jump_time,
                     on_ground,
                     is_crouching,
                     is_attacking,
                     is_dead: server_player.dead,
+                    is_frozen: server_player.frozen,
+                    thaw_progress: server_player.thaw_progress,
                 }
             })
             .collect();
@@ -1286,6 +3401,10 @@ impl DedicatedServer {
                     sas::game::weapon::Weapon::Plasmagun => TrajectoryType::Linear,
                     sas::game::weapon::Weapon::BFG => TrajectoryType::Linear,
                     sas::game::weapon::Weapon::GrenadeLauncher => TrajectoryType::Gravity,
+                    sas::game::weapon::Weapon::ProxLauncher if proj.stuck => {
+                        TrajectoryType::Stationary
+                    }
+                    sas::game::weapon::Weapon::ProxLauncher => TrajectoryType::Gravity,
                     _ => TrajectoryType::Linear,
                 };
 
@@ -1311,8 +3430,9 @@ impl DedicatedServer {
         static mut LAST_PROJ_PRINT: f64 = 0.0;
         unsafe {
             if get_network_time() - LAST_PROJ_PRINT > 2.0 {
-                println!(
-                    "[SERVER] Broadcasting {} projectiles (total active: {})",
+                log_debug!(
+                    Net,
+                    "Broadcasting {} projectiles (total active: {})",
                     projectile_states.len(),
                     self.game_state
                         .projectiles
@@ -1330,25 +3450,190 @@ impl DedicatedServer {
     }
 }
 
+struct SoakConfig {
+    bots: usize,
+    duration_secs: f64,
+    map_time_secs: f64,
+    maps: Vec<String>,
+}
+
+#[derive(Default)]
+struct SoakReport {
+    ticks: u64,
+    elapsed_secs: f64,
+    map_changes: u32,
+    peak_projectiles: usize,
+    violations: u64,
+    violation_log: Vec<String>,
+    players_in_solid: HashSet<u16>,
+    frags: Vec<(u16, String, i32, i32)>,
+}
+
+impl SoakReport {
+    fn print(&self) {
+        println!("=================================");
+        println!("  Soak Test Summary");
+        println!("=================================");
+        println!(
+            "Ticks: {} ({:.0}s simulated in {:.1}s)",
+            self.ticks,
+            self.ticks as f64 / 60.0,
+            self.elapsed_secs
+        );
+        println!(
+            "Ticks/s: {:.0}",
+            self.ticks as f64 / self.elapsed_secs.max(0.001)
+        );
+        println!("Map changes: {}", self.map_changes);
+        println!("Peak projectiles: {}", self.peak_projectiles);
+        println!("Frags:");
+        for (id, name, frags, deaths) in &self.frags {
+            println!(
+                "  {:5} {:12} {:5} frags {:5} deaths",
+                id, name, frags, deaths
+            );
+        }
+        println!("Invariant violations: {}", self.violations);
+        for violation in &self.violation_log {
+            println!("  {}", violation);
+        }
+        println!("=================================");
+    }
+}
+
+fn spawn_console_reader() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let stdin = std::io::stdin();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match stdin.read_line(&mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if tx.send(line.trim().to_string()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    rx
+}
+
 fn main() {
+    sas::cvar::init_server_cvars();
+    sas::cvar::init_log_cvars();
+    sas::logging::init("qconsole_server");
+
     let mut config = NetworkConfig::default();
     let mut map_name = "0-arena".to_string();
 
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        if let Ok(port) = args[1].parse::<u16>() {
+    let mut soak = false;
+    let mut metrics_port: Option<u16> = None;
+    let mut tournament: Option<Tournament> = None;
+    let mut soak_config = SoakConfig {
+        bots: 8,
+        duration_secs: 600.0,
+        map_time_secs: 300.0,
+        maps: Vec::new(),
+    };
+
+    let mut args: Vec<String> = Vec::new();
+    let mut arg_iter = std::env::args().skip(1);
+    while let Some(arg) = arg_iter.next() {
+        match arg.as_str() {
+            "--soak" => soak = true,
+            "--gametype" => match arg_iter.next().as_deref().and_then(GametypeKind::parse) {
+                Some(kind) => sas::cvar::set_cvar("g_gametype", &(kind as u8).to_string()),
+                None => eprintln!("Unknown gametype (ffa, duel, tdm, ctf, ca, ft)"),
+            },
+            "--mutators" => match arg_iter.next() {
+                Some(value) => match Mutators::parse(&value) {
+                    Ok(_) => sas::cvar::set_cvar("g_mutators", &value),
+                    Err(e) => eprintln!("{}", e),
+                },
+                None => eprintln!("--mutators needs a list, e.g. instagib,lowgrav"),
+            },
+            "--tournament" => match arg_iter.next().map(|path| Tournament::load(&path)) {
+                Some(Ok(loaded)) => tournament = Some(loaded),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+                None => eprintln!("--tournament needs a bracket file"),
+            },
+            "--weapons" => match arg_iter.next() {
+                Some(path) => sas::cvar::set_cvar("g_weaponfile", &path),
+                None => eprintln!("--weapons needs a weapons file"),
+            },
+            "--metrics-port" => {
+                metrics_port = arg_iter.next().and_then(|v| v.parse().ok());
+            }
+            "--bots" => {
+                if let Some(n) = arg_iter.next().and_then(|v| v.parse().ok()) {
+                    soak_config.bots = n;
+                }
+            }
+            "--duration" => {
+                if let Some(secs) = arg_iter.next().and_then(|v| v.parse().ok()) {
+                    soak_config.duration_secs = secs;
+                }
+            }
+            "--map-time" => {
+                if let Some(secs) = arg_iter.next().and_then(|v| v.parse().ok()) {
+                    soak_config.map_time_secs = secs;
+                }
+            }
+            "--maps" => {
+                if let Some(maps) = arg_iter.next() {
+                    soak_config.maps = maps.split(',').map(|m| m.trim().to_string()).collect();
+                }
+            }
+            _ => args.push(arg),
+        }
+    }
+
+    if !args.is_empty() {
+        if let Ok(port) = args[0].parse::<u16>() {
             config.server_port = port;
         }
     }
 
-    if args.len() > 2 {
-        if let Ok(max_players) = args[2].parse::<u8>() {
+    if args.len() > 1 {
+        if let Ok(max_players) = args[1].parse::<u8>() {
             config.max_players = max_players;
         }
     }
 
-    if args.len() > 3 {
-        map_name = args[3].clone();
+    if args.len() > 2 {
+        map_name = args[2].clone();
+    }
+    match WeaponTable::load(&sas::cvar::get_cvar_string("g_weaponfile")) {
+        Ok(table) => weapon_defs::install_local(table),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    if let Some(ref tournament) = tournament {
+        map_name = tournament.current_map().to_string();
+        sas::cvar::set_cvar("g_gametype", &(GametypeKind::Duel as u8).to_string());
+    }
+
+    if soak {
+        if soak_config.maps.is_empty() {
+            soak_config.maps.push(map_name.clone());
+        }
+        // Headless: the server is never started, so no socket is bound and
+        // stdin is left alone.
+        let mut server = DedicatedServer::new(config, soak_config.maps[0].clone());
+        let report = server.run_soak(&soak_config);
+        report.print();
+        if report.violations > 0 {
+            std::process::exit(1);
+        }
+        return;
     }
 
     println!("=================================");
@@ -1357,14 +3642,36 @@ fn main() {
     println!("Port: {}", config.server_port);
     println!("Max players: {}", config.max_players);
     println!("Map: {}", map_name);
+    println!(
+        "Gametype: {}",
+        GametypeKind::parse(&sas::cvar::get_cvar_string("g_gametype"))
+            .unwrap_or(GametypeKind::FreeForAll)
+            .name()
+    );
+    println!(
+        "Mutators: {}",
+        Mutators::parse(&sas::cvar::get_cvar_string("g_mutators"))
+            .unwrap_or_default()
+            .names()
+    );
+    println!("Weapons: {:08x}", weapon_defs::checksum());
     println!("=================================");
 
     let mut server = DedicatedServer::new(config, map_name);
+    if let Some(tournament) = tournament {
+        server.start_tournament(tournament);
+    }
 
     if let Err(e) = server.start() {
         eprintln!("Failed to start server: {}", e);
         return;
     }
 
+    if let Some(port) = metrics_port {
+        if let Err(e) = server.enable_metrics(port) {
+            eprintln!("{}", e);
+        }
+    }
+
     server.run();
 }
@@ -1,3 +1,4 @@
+use crate::log_info;
 use macroquad::prelude::*;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -229,57 +230,75 @@ impl LeadAnnouncement {
         let is_local_tied_for_lead = tied_count > 1 && local_score == top_score;
         let is_local_in_lead_group = is_local_leader || is_local_tied_for_lead;
 
-        println!(
+        log_info!(
+            Game,
             "    LeadAnnouncement::update: local_id={}, killer_id={:?}",
-            local_id, killer_id
+            local_id,
+            killer_id
         );
-        println!(
+        log_info!(
+            Game,
             "      old_leader={:?}, new_leader={:?}",
-            old_leader, new_leader
+            old_leader,
+            new_leader
         );
-        println!(
+        log_info!(
+            Game,
             "      was_local_in_lead_group={}, is_local_in_lead_group={}",
-            was_local_in_lead_group, is_local_in_lead_group
+            was_local_in_lead_group,
+            is_local_in_lead_group
         );
-        println!(
+        log_info!(
+            Game,
             "      was_local_sole_leader={}, is_local_leader={}, is_local_tied_for_lead={}",
-            was_local_sole_leader, is_local_leader, is_local_tied_for_lead
+            was_local_sole_leader,
+            is_local_leader,
+            is_local_tied_for_lead
         );
-        println!(
+        log_info!(
+            Game,
             "      top_score={}, tied_count={}, local_score={}",
-            top_score, tied_count, local_score
+            top_score,
+            tied_count,
+            local_score
         );
 
         let announcement = if let Some(killer) = killer_id {
             if killer == local_id {
                 if !was_local_in_lead_group && is_local_leader {
-                    println!("      -> Case: local killer became sole leader from behind");
+                    log_info!(
+                        Game,
+                        "      -> Case: local killer became sole leader from behind"
+                    );
                     Some("taken_the_lead")
                 } else if !was_local_in_lead_group && is_local_tied_for_lead {
-                    println!("      -> Case: local killer tied for lead");
+                    log_info!(Game, "      -> Case: local killer tied for lead");
                     Some("tied_for_the_lead")
                 } else if was_local_in_lead_group && is_local_leader && !was_local_sole_leader {
-                    println!("      -> Case: local killer became sole leader from tied");
+                    log_info!(
+                        Game,
+                        "      -> Case: local killer became sole leader from tied"
+                    );
                     Some("taken_the_lead")
                 } else if was_local_in_lead_group && !is_local_in_lead_group {
-                    println!("      -> Case: local lost lead group (suicide)");
+                    log_info!(Game, "      -> Case: local lost lead group (suicide)");
                     Some("lost_the_lead")
                 } else if was_local_sole_leader && is_local_tied_for_lead {
-                    println!("      -> Case: local sole leader now tied (suicide)");
+                    log_info!(Game, "      -> Case: local sole leader now tied (suicide)");
                     Some("tied_for_the_lead")
                 } else {
-                    println!("      -> Case: local killer no announcement");
+                    log_info!(Game, "      -> Case: local killer no announcement");
                     None
                 }
             } else {
                 if was_local_in_lead_group && !is_local_in_lead_group {
-                    println!("      -> Case: local lost lead group");
+                    log_info!(Game, "      -> Case: local lost lead group");
                     Some("lost_the_lead")
                 } else if was_local_sole_leader && is_local_tied_for_lead {
-                    println!("      -> Case: local sole leader now tied by other");
+                    log_info!(Game, "      -> Case: local sole leader now tied by other");
                     Some("tied_for_the_lead")
                 } else {
-                    println!("      -> Case: other killer no announcement");
+                    log_info!(Game, "      -> Case: other killer no announcement");
                     None
                 }
             }
@@ -357,6 +376,7 @@ pub struct GameResults {
     pub winner_weapon: Option<super::weapon::Weapon>,
     pub scores: Vec<(u16, String, i32, u32, u32)>,
     pub player_models: Vec<(u16, String, usize, usize)>,
+    pub headline: Option<String>,
 }
 
 impl GameResults {
@@ -368,10 +388,19 @@ impl GameResults {
             winner_weapon: None,
             scores: Vec::new(),
             player_models: Vec::new(),
+            headline: None,
         }
     }
 
-    pub fn trigger(&mut self, players: &[super::player::Player], current_time: f32) {
+    pub fn trigger(
+        &mut self,
+        players: &[super::player::Player],
+        current_time: f32,
+        gametype: &dyn super::gametype::Gametype,
+        team_scores: &super::gametype::TeamScores,
+    ) {
+        use super::gametype::{other_team, team_name, MatchWinner, ScoreEntry};
+
         self.show = true;
         self.start_time = current_time;
 
@@ -390,6 +419,29 @@ impl GameResults {
 
         player_scores.sort_by(|a, b| b.2.cmp(&a.2));
 
+        let entries = ScoreEntry::from_players(players);
+        let winner = gametype.winner(&entries, team_scores);
+
+        self.headline = match winner {
+            MatchWinner::Team(team) => {
+                player_scores.sort_by_key(|s| {
+                    players.iter().find(|p| p.id == s.0).map(|p| p.team) != Some(team)
+                });
+                Some(format!(
+                    "{} TEAM WINS {} - {}",
+                    team_name(team).to_uppercase(),
+                    team_scores.get(team),
+                    team_scores.get(other_team(team))
+                ))
+            }
+            MatchWinner::Draw if gametype.is_team_game() => Some(format!(
+                "TEAMS TIED {} - {}",
+                team_scores.red, team_scores.blue
+            )),
+            MatchWinner::Draw => Some("DRAW".to_string()),
+            MatchWinner::Player(_) => None,
+        };
+
         self.winner_id = player_scores.first().map(|s| s.0);
         self.winner_weapon = self.winner_id.and_then(|id| {
             players
@@ -442,6 +494,18 @@ impl GameResults {
             Color::new(1.0, 0.8, 0.2, alpha),
         );
 
+        if let Some(headline) = &self.headline {
+            let headline_size = 32.0;
+            let headline_w = crate::render::measure_q3_banner_string(headline, headline_size);
+            crate::render::draw_q3_banner_string(
+                headline,
+                screen_w / 2.0 - headline_w / 2.0,
+                screen_h * 0.15 + title_size,
+                headline_size,
+                Color::new(1.0, 1.0, 1.0, alpha),
+            );
+        }
+
         let podium_y = screen_h * 0.65;
         let model_scale = screen_h * 0.7 / 120.0;
 
@@ -553,6 +617,7 @@ impl GameResults {
                         0.0,
                         false,
                         is_winner,
+                        false,
                         0.0,
                     );
                 }
@@ -1,7 +1,193 @@
+use super::gametype::{Gametype, TEAM_FREE};
+use super::mutators::Mutators;
+use crate::cvar;
+
+/// Percentage of each hit that armor soaks up, rounded up as in Q3.
+pub const ARMOR_PROTECTION: i32 = 66;
+const KNOCKBACK_MASS: f32 = 200.0;
+
 pub struct CombatEvent {
     pub attacker_id: u16,
     pub target_id: u16,
     pub damage: i32,
+    /// Damage the attacker owes themselves under mirror friendly fire.
+    pub mirrored: i32,
+}
+
+/// What a teammate's hit does, picked by `g_friendlyfire`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FriendlyFire {
+    #[default]
+    Off,
+    On,
+    /// The teammate is spared and the attacker takes the hit.
+    Mirror,
+    /// Only the share armor would have soaked is taken.
+    ArmorOnly,
+}
+
+impl FriendlyFire {
+    pub fn from_value(value: i32) -> Self {
+        match value {
+            1 => FriendlyFire::On,
+            2 => FriendlyFire::Mirror,
+            3 => FriendlyFire::ArmorOnly,
+            _ => FriendlyFire::Off,
+        }
+    }
+}
+
+/// Everything besides health and armor that decides how much a hit hurts.
+/// Built once per frame so the local game and the server agree.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DamageRules {
+    pub friendly_fire: FriendlyFire,
+    pub self_damage: bool,
+    pub knockback: f32,
+    pub mutators: Mutators,
+}
+
+impl Default for DamageRules {
+    fn default() -> Self {
+        Self {
+            friendly_fire: FriendlyFire::Off,
+            self_damage: true,
+            knockback: 1600.0,
+            mutators: Mutators::default(),
+        }
+    }
+}
+
+impl DamageRules {
+    pub fn from_cvars(mutators: Mutators) -> Self {
+        Self {
+            friendly_fire: FriendlyFire::from_value(cvar::get_cvar_integer("g_friendlyfire")),
+            self_damage: cvar::get_cvar_bool("g_selfdamage"),
+            knockback: cvar::get_cvar_float("g_knockback").max(0.0),
+            mutators,
+        }
+    }
+
+    /// Whether teammates' hits reach each other at all.
+    pub fn hits_teammates(&self) -> bool {
+        self.friendly_fire != FriendlyFire::Off
+    }
+
+    /// Push given to a player caught by `damage` points of splash.
+    pub fn knockback_scale(&self, damage: f32) -> f32 {
+        self.knockback * damage.clamp(0.0, 200.0) / KNOCKBACK_MASS
+    }
+}
+
+/// Q3's linear splash falloff from the center of the blast.
+pub fn splash_damage(damage: i32, radius: f32, dist: f32) -> f32 {
+    if dist >= radius {
+        return 0.0;
+    }
+    (damage as f32 * (1.0 - dist / radius)).max(0.0)
+}
+
+/// What a blast does to something `dist` away: the splash damage and the
+/// push away from the center.
+pub struct SplashHit {
+    pub damage: f32,
+    pub push_x: f32,
+    pub push_y: f32,
+}
+
+pub fn splash_hit(
+    origin: (f32, f32),
+    pos: (f32, f32),
+    damage: i32,
+    radius: f32,
+    rules: &DamageRules,
+) -> Option<SplashHit> {
+    let dx = pos.0 - origin.0;
+    let dy = pos.1 - origin.1;
+    let dist = (dx * dx + dy * dy).sqrt();
+    if dist >= radius {
+        return None;
+    }
+    let damage = splash_damage(damage, radius, dist);
+    let (push_x, push_y) = if dist > 0.1 {
+        let scale = rules.knockback_scale(damage);
+        (dx / dist * scale, dy / dist * scale)
+    } else {
+        (0.0, 0.0)
+    };
+    Some(SplashHit {
+        damage,
+        push_x,
+        push_y,
+    })
+}
+
+/// The parts of a player the damage rules look at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Combatant {
+    pub id: u16,
+    pub team: u8,
+    pub frozen: bool,
+}
+
+impl Combatant {
+    /// Anyone not in the player list, such as the world.
+    pub fn outsider(id: u16) -> Self {
+        Self {
+            id,
+            team: TEAM_FREE,
+            frozen: false,
+        }
+    }
+}
+
+/// Players the gametype would shield from each other's fire.
+pub fn teammates(gametype: &dyn Gametype, attacker: Combatant, target: Combatant) -> bool {
+    attacker.id != target.id && !gametype.allows_damage(attacker.team, target.team, false)
+}
+
+/// Whether `attacker` may hurt `target` at all. Nothing lands between rounds
+/// or on frozen players, and teammates are left to friendly fire.
+pub fn damage_allowed(
+    gametype: &dyn Gametype,
+    attacker: Combatant,
+    target: Combatant,
+    between_rounds: bool,
+    rules: &DamageRules,
+) -> bool {
+    if between_rounds || target.frozen {
+        return false;
+    }
+    if teammates(gametype, attacker, target) {
+        return rules.hits_teammates();
+    }
+    gametype.allows_damage(attacker.team, target.team, attacker.id == target.id)
+}
+
+/// Ids in `players` that `attacker_id` cannot hurt, and ids of its teammates.
+pub fn damage_groups(
+    gametype: &dyn Gametype,
+    attacker_id: u16,
+    players: &[Combatant],
+    between_rounds: bool,
+    rules: &DamageRules,
+) -> (Vec<u16>, Vec<u16>) {
+    let attacker = players
+        .iter()
+        .copied()
+        .find(|p| p.id == attacker_id)
+        .unwrap_or(Combatant::outsider(attacker_id));
+    let protected = players
+        .iter()
+        .filter(|p| !damage_allowed(gametype, attacker, **p, between_rounds, rules))
+        .map(|p| p.id)
+        .collect();
+    let teammates = players
+        .iter()
+        .filter(|p| teammates(gametype, attacker, **p))
+        .map(|p| p.id)
+        .collect();
+    (protected, teammates)
 }
 
 pub struct KillEvent {
@@ -15,12 +201,38 @@ pub struct CombatResult {
     pub respawn_events: Vec<u16>,
 }
 
+/// Applies `damage` after the rules and mutators, with armor soaking two
+/// thirds of it. Hurting yourself costs half. The event carries the health
+/// actually taken and whatever is mirrored back to the attacker.
 pub fn apply_damage(
     target_health: &mut i32,
+    target_armor: &mut i32,
     damage: i32,
     attacker_id: u16,
     target_id: u16,
+    teammates: bool,
+    rules: &DamageRules,
 ) -> (bool, CombatEvent) {
+    let is_self = attacker_id == target_id;
+    let mut damage = rules.mutators.modify_damage(damage, is_self).max(0);
+    let mut mirrored = 0;
+    let mut armor_only = false;
+    if is_self {
+        damage = if rules.self_damage { damage / 2 } else { 0 };
+    } else if teammates {
+        match rules.friendly_fire {
+            FriendlyFire::On => {}
+            FriendlyFire::Off => damage = 0,
+            FriendlyFire::Mirror => mirrored = std::mem::take(&mut damage),
+            FriendlyFire::ArmorOnly => armor_only = true,
+        }
+    }
+
+    let absorbed = ((damage * ARMOR_PROTECTION + 99) / 100)
+        .min(*target_armor)
+        .max(0);
+    *target_armor -= absorbed;
+    let damage = if armor_only { 0 } else { damage - absorbed };
     *target_health -= damage;
 
     let died = *target_health <= 0;
@@ -31,6 +243,7 @@ pub fn apply_damage(
             attacker_id,
             target_id,
             damage,
+            mirrored,
         },
     )
 }
@@ -50,3 +263,129 @@ pub fn respawn_player(
     *vel_x = 0.0;
     *vel_y = 0.0;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::gametype;
+
+    fn hit(
+        armor: i32,
+        damage: i32,
+        attacker: u16,
+        teammates: bool,
+        rules: &DamageRules,
+    ) -> (i32, i32, i32) {
+        let (mut health, mut armor) = (100, armor);
+        let (_, event) = apply_damage(
+            &mut health,
+            &mut armor,
+            damage,
+            attacker,
+            2,
+            teammates,
+            rules,
+        );
+        (health, armor, event.mirrored)
+    }
+
+    #[test]
+    fn test_armor_absorbs_two_thirds() {
+        let rules = DamageRules::default();
+        assert_eq!(hit(100, 50, 1, false, &rules), (83, 67, 0));
+        assert_eq!(hit(100, 100, 1, false, &rules), (66, 34, 0));
+        // Whatever armor can't cover goes to health.
+        assert_eq!(hit(10, 60, 1, false, &rules), (50, 0, 0));
+        assert_eq!(hit(0, 60, 1, false, &rules), (40, 0, 0));
+    }
+
+    #[test]
+    fn test_self_damage() {
+        let mut rules = DamageRules::default();
+        assert_eq!(hit(0, 100, 2, false, &rules), (50, 0, 0));
+        rules.self_damage = false;
+        assert_eq!(hit(0, 100, 2, false, &rules), (100, 0, 0));
+    }
+
+    #[test]
+    fn test_friendly_fire_modes() {
+        let mut rules = DamageRules::default();
+        assert_eq!(hit(50, 60, 1, true, &rules), (100, 50, 0));
+        assert!(!rules.hits_teammates());
+
+        rules.friendly_fire = FriendlyFire::On;
+        assert_eq!(hit(50, 60, 1, true, &rules), (80, 10, 0));
+
+        rules.friendly_fire = FriendlyFire::Mirror;
+        assert_eq!(hit(50, 60, 1, true, &rules), (100, 50, 60));
+
+        rules.friendly_fire = FriendlyFire::ArmorOnly;
+        assert_eq!(hit(50, 60, 1, true, &rules), (100, 10, 0));
+        // Enemies are never affected by the friendly fire setting.
+        assert_eq!(hit(50, 60, 1, false, &rules), (80, 10, 0));
+
+        assert_eq!(FriendlyFire::from_value(2), FriendlyFire::Mirror);
+        assert_eq!(FriendlyFire::from_value(7), FriendlyFire::Off);
+    }
+
+    #[test]
+    fn test_splash_and_knockback() {
+        assert_eq!(splash_damage(100, 120.0, 0.0), 100.0);
+        assert_eq!(splash_damage(100, 120.0, 60.0), 50.0);
+        assert_eq!(splash_damage(100, 120.0, 130.0), 0.0);
+
+        let rules = DamageRules {
+            knockback: 1000.0,
+            ..DamageRules::default()
+        };
+        assert_eq!(rules.knockback_scale(100.0), 500.0);
+        assert_eq!(rules.knockback_scale(400.0), 1000.0);
+
+        let hit = splash_hit((0.0, 0.0), (60.0, 0.0), 100, 120.0, &rules).unwrap();
+        assert_eq!(hit.damage, 50.0);
+        assert_eq!((hit.push_x, hit.push_y), (250.0, 0.0));
+        assert!(splash_hit((0.0, 0.0), (0.0, 130.0), 100, 120.0, &rules).is_none());
+    }
+
+    #[test]
+    fn test_damage_groups_follow_teams_and_rules() {
+        let tdm = gametype::create(gametype::GametypeKind::TeamDeathmatch);
+        let players = [
+            Combatant {
+                id: 1,
+                team: gametype::TEAM_RED,
+                frozen: false,
+            },
+            Combatant {
+                id: 2,
+                team: gametype::TEAM_RED,
+                frozen: false,
+            },
+            Combatant {
+                id: 3,
+                team: gametype::TEAM_BLUE,
+                frozen: false,
+            },
+            Combatant {
+                id: 4,
+                team: gametype::TEAM_BLUE,
+                frozen: true,
+            },
+        ];
+
+        let rules = DamageRules::default();
+        let (protected, teammates) = damage_groups(&*tdm, 1, &players, false, &rules);
+        assert_eq!(protected, vec![2, 4]);
+        assert_eq!(teammates, vec![2]);
+
+        let rules = DamageRules {
+            friendly_fire: FriendlyFire::On,
+            ..DamageRules::default()
+        };
+        let (protected, _) = damage_groups(&*tdm, 1, &players, false, &rules);
+        assert_eq!(protected, vec![4]);
+
+        let (protected, _) = damage_groups(&*tdm, 1, &players, true, &rules);
+        assert_eq!(protected, vec![1, 2, 3, 4]);
+    }
+}
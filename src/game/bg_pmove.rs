@@ -1,6 +1,8 @@
 use super::collision;
 use super::constants::*;
-use super::map::Map;
+use super::grapple::{GRAPPLE_MAX_SPEED, GRAPPLE_ORIGIN_Y, GRAPPLE_SLOW_RADIUS};
+use super::map::{Liquid, Map};
+use super::physics::PhysicsProfile;
 
 #[derive(Clone, Debug)]
 pub struct PmoveState {
@@ -9,6 +11,10 @@ pub struct PmoveState {
     pub vel_x: f32,
     pub vel_y: f32,
     pub was_in_air: bool,
+    /// Seconds left in which another jump counts as a double jump.
+    pub jump_time: f32,
+    /// Downward speed built up since the player last stopped rising, uncapped.
+    pub fall_speed: f32,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -17,6 +23,10 @@ pub struct PmoveCmd {
     pub jump: bool,
     pub crouch: bool,
     pub haste_active: bool,
+    /// Jump thrusts up and crouch down instead of jumping and falling.
+    pub flight_active: bool,
+    /// Anchor of an attached grappling hook.
+    pub grapple: Option<(f32, f32)>,
 }
 
 #[derive(Clone, Debug)]
@@ -26,14 +36,28 @@ pub struct PmoveResult {
     pub new_vel_x: f32,
     pub new_vel_y: f32,
     pub new_was_in_air: bool,
+    pub new_jump_time: f32,
+    pub new_fall_speed: f32,
     pub jumped: bool,
     pub landed: bool,
     pub had_impulse: bool,
     pub impulse_type: String,
     pub hit_jumppad: bool,
+    /// Unscaled damage for how hard the player just hit the ground.
+    pub fall_damage: i32,
+    /// Depth and kind of liquid at the new position, see
+    /// [`collision::water_level`].
+    pub water_level: u8,
+    pub liquid: Option<Liquid>,
 }
 
-pub fn pmove(state: &PmoveState, cmd: &PmoveCmd, dt: f32, map: &Map) -> PmoveResult {
+pub fn pmove(
+    state: &PmoveState,
+    cmd: &PmoveCmd,
+    dt: f32,
+    map: &Map,
+    physics: &PhysicsProfile,
+) -> PmoveResult {
     const MAX_DT: f32 = 0.05;
 
     let x = state.x;
@@ -43,23 +67,36 @@ pub fn pmove(state: &PmoveState, cmd: &PmoveCmd, dt: f32, map: &Map) -> PmoveRes
     let was_in_air = state.was_in_air;
 
     let on_ground = collision::check_on_ground(x, y, map);
+    let (water_level, _) = collision::water_level(x, y, map);
+    let swimming = water_level >= 2 && !cmd.flight_active;
 
     let dt_clamped = dt.min(MAX_DT);
     let dt_norm = dt_clamped * 60.0;
 
     let base_max_speed = if cmd.crouch {
-        MAX_SPEED_GROUND * CROUCH_SPEED_MULT
+        physics.max_speed_ground * CROUCH_SPEED_MULT
     } else {
-        MAX_SPEED_GROUND
+        physics.max_speed_ground
     };
     let max_speed = if cmd.haste_active {
         base_max_speed * HASTE_SPEED_MULT
     } else {
         base_max_speed
     };
+    let max_speed = if swimming {
+        max_speed * SWIM_SPEED_MULT
+    } else {
+        max_speed
+    };
 
-    let accel = if on_ground { GROUND_ACCEL } else { AIR_ACCEL };
-    let change_dir_accel = accel * 2.3;
+    let (accel, change_dir_accel) = if on_ground {
+        (physics.ground_accel, physics.ground_accel * 2.3)
+    } else {
+        (
+            physics.air_accel,
+            physics.air_accel * 2.3 * physics.air_control,
+        )
+    };
 
     if cmd.move_right < -0.01 {
         if vel_x > 0.0 {
@@ -84,31 +121,77 @@ pub fn pmove(state: &PmoveState, cmd: &PmoveCmd, dt: f32, map: &Map) -> PmoveRes
     }
 
     let mut jumped = false;
-    if cmd.jump && on_ground && vel_y >= -0.5 {
-        let jump_force = if cmd.haste_active {
-            JUMP_FORCE * HASTE_JUMP_MULT
+    let mut jump_time = (state.jump_time - dt_clamped).max(0.0);
+    if cmd.flight_active {
+        if cmd.jump {
+            vel_y = (vel_y - FLIGHT_ACCEL * dt_norm).max(-FLIGHT_MAX_SPEED);
+        } else if cmd.crouch {
+            vel_y = (vel_y + FLIGHT_ACCEL * dt_norm).min(FLIGHT_MAX_SPEED);
         } else {
-            JUMP_FORCE
-        };
-        vel_y = jump_force;
-        jumped = true;
-    }
+            vel_y /= 1.0 + (0.1 * dt_norm);
+        }
+    } else if swimming {
+        if cmd.jump {
+            vel_y = (vel_y - SWIM_ACCEL * dt_norm).max(-SWIM_MAX_SPEED);
+        } else if cmd.crouch {
+            vel_y = (vel_y + SWIM_ACCEL * dt_norm).min(SWIM_MAX_SPEED);
+        }
+        vel_y += physics.gravity * physics.gravity_scale * SWIM_GRAVITY_SCALE * dt_norm;
+        vel_x /= 1.0 + (WATER_DRAG * dt_norm);
+        vel_y /= 1.0 + (WATER_DRAG * dt_norm);
+    } else {
+        let rising = physics.ramp_jump && vel_y < 0.0;
+        if cmd.jump && on_ground && (vel_y >= -0.5 || rising) {
+            let mut jump_force = if cmd.haste_active {
+                physics.jump_force * HASTE_JUMP_MULT
+            } else {
+                physics.jump_force
+            };
+            if physics.double_jump && state.jump_time > 0.0 {
+                jump_force += DOUBLE_JUMP_BOOST;
+            }
+            if rising {
+                jump_force += vel_y;
+            }
+            vel_y = jump_force;
+            jumped = true;
+            jump_time = DOUBLE_JUMP_WINDOW;
+        }
 
-    vel_y += GRAVITY * dt_norm;
+        vel_y += physics.gravity * physics.gravity_scale * dt_norm;
+    }
 
-    if vel_y > -1.0 && vel_y < 0.0 {
-        vel_y /= 1.0 + (0.11 * dt_norm);
+    if let Some((anchor_x, anchor_y)) = cmd.grapple {
+        let dx = anchor_x - x;
+        let dy = anchor_y - (y + GRAPPLE_ORIGIN_Y);
+        let dist = (dx * dx + dy * dy).sqrt();
+        if dist > 1.0 {
+            let pull = physics.grapple_pull * (dist / GRAPPLE_SLOW_RADIUS).min(1.0) * dt_norm;
+            vel_x += dx / dist * pull;
+            vel_y += dy / dist * pull;
+            let speed = (vel_x * vel_x + vel_y * vel_y).sqrt();
+            if speed > GRAPPLE_MAX_SPEED {
+                vel_x *= GRAPPLE_MAX_SPEED / speed;
+                vel_y *= GRAPPLE_MAX_SPEED / speed;
+            }
+        }
     }
-    if vel_y > 0.0 && vel_y < 5.0 {
-        vel_y *= 1.0 + (0.1 * dt_norm);
+
+    if !swimming {
+        if vel_y > -1.0 && vel_y < 0.0 {
+            vel_y /= 1.0 + (0.11 * dt_norm);
+        }
+        if vel_y > 0.0 && vel_y < 5.0 {
+            vel_y *= 1.0 + (0.1 * dt_norm);
+        }
     }
 
     if cmd.move_right.abs() < 0.01 {
         if vel_x.abs() > 0.01 {
             if on_ground {
-                vel_x /= 1.0 + (0.14 * dt_norm);
+                vel_x /= 1.0 + (physics.ground_friction * dt_norm);
             } else {
-                vel_x /= 1.0 + (0.025 * dt_norm);
+                vel_x /= 1.0 + (physics.air_friction * dt_norm);
             }
             if vel_x.abs() < 0.01 {
                 vel_x = 0.0;
@@ -116,19 +199,29 @@ pub fn pmove(state: &PmoveState, cmd: &PmoveCmd, dt: f32, map: &Map) -> PmoveRes
         }
     }
 
-    if vel_y > MAX_FALL_SPEED {
-        vel_y = MAX_FALL_SPEED;
+    // Fall speed keeps building past max_fall_speed, so a longer drop,
+    // stronger gravity or a downward jump pad all land harder, as in Q3's
+    // PM_CrashLand. Liquid breaks a fall.
+    let fall_speed = if vel_y > 0.0 && !cmd.flight_active && water_level == 0 {
+        (state.fall_speed + physics.gravity * physics.gravity_scale * dt_norm).max(vel_y)
+    } else {
+        0.0
+    };
+
+    if vel_y > physics.max_fall_speed {
+        vel_y = physics.max_fall_speed;
     }
 
     if vel_y < -15.0 {
         vel_y = -15.0;
     }
 
-    if vel_x.abs() > MAX_SPEED_AIR {
-        vel_x = vel_x.signum() * MAX_SPEED_AIR;
+    if vel_x.abs() > physics.max_speed_air {
+        vel_x = vel_x.signum() * physics.max_speed_air;
     }
 
-    let mut coll = collision::move_with_collision(x, y, vel_x, vel_y, cmd.crouch, dt_norm, map);
+    let mut coll =
+        collision::move_with_collision((x, y), (vel_x, vel_y), cmd.crouch, dt_norm, map, physics);
 
     let mut had_impulse = false;
     let mut impulse_type = String::new();
@@ -148,6 +241,19 @@ pub fn pmove(state: &PmoveState, cmd: &PmoveCmd, dt: f32, map: &Map) -> PmoveRes
     }
 
     let landed = coll.on_ground && !was_in_air && vel_y > 2.0;
+    let fall_damage = if coll.on_ground && was_in_air && !hit_jumppad {
+        if fall_speed >= FALL_SPEED_FAR {
+            FALL_DAMAGE_FAR
+        } else if fall_speed >= FALL_SPEED_MEDIUM {
+            FALL_DAMAGE_MEDIUM
+        } else {
+            0
+        }
+    } else {
+        0
+    };
+
+    let (new_water_level, liquid) = collision::water_level(coll.new_x, coll.new_y, map);
 
     PmoveResult {
         new_x: coll.new_x,
@@ -155,10 +261,103 @@ pub fn pmove(state: &PmoveState, cmd: &PmoveCmd, dt: f32, map: &Map) -> PmoveRes
         new_vel_x: coll.new_vel_x,
         new_vel_y: coll.new_vel_y,
         new_was_in_air: !coll.on_ground,
+        new_jump_time: jump_time,
+        new_fall_speed: if coll.on_ground { 0.0 } else { fall_speed },
         jumped,
         landed,
         had_impulse,
         impulse_type,
         hit_jumppad,
+        fall_damage,
+        water_level: new_water_level,
+        liquid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floor_map() -> Map {
+        let mut map = Map::new("test");
+        for x in 0..map.width {
+            for y in 0..map.height {
+                map.tiles[x][y].solid = y >= 20;
+            }
+        }
+        map.jumppads.clear();
+        map
+    }
+
+    fn cmd(move_right: f32, jump: bool) -> PmoveCmd {
+        PmoveCmd {
+            move_right,
+            jump,
+            crouch: false,
+            haste_active: false,
+            flight_active: false,
+            grapple: None,
+        }
+    }
+
+    fn step(state: &PmoveState, cmd: &PmoveCmd, map: &Map, physics: &PhysicsProfile) -> PmoveState {
+        let result = pmove(state, cmd, 1.0 / 60.0, map, physics);
+        PmoveState {
+            x: result.new_x,
+            y: result.new_y,
+            vel_x: result.new_vel_x,
+            vel_y: result.new_vel_y,
+            was_in_air: result.new_was_in_air,
+            jump_time: result.new_jump_time,
+            fall_speed: result.new_fall_speed,
+        }
+    }
+
+    #[test]
+    fn test_falling_damage_on_landing() {
+        let mut map = floor_map();
+        for column in &mut map.tiles {
+            column.resize(80, column[0].clone());
+            for (y, tile) in column.iter_mut().enumerate() {
+                tile.solid = y >= 70;
+            }
+        }
+        map.height = 80;
+        let floor_y = 70.0 * 16.0 - 24.0;
+
+        let landing_damage = |height: f32, vel_y: f32, physics: &PhysicsProfile| {
+            let mut state = PmoveState {
+                x: 600.0,
+                y: floor_y - height,
+                vel_x: 0.0,
+                vel_y,
+                was_in_air: true,
+                jump_time: 0.0,
+                fall_speed: 0.0,
+            };
+            for _ in 0..600 {
+                let result = pmove(&state, &cmd(0.0, false), 1.0 / 60.0, &map, physics);
+                if !result.new_was_in_air {
+                    assert_eq!(result.new_fall_speed, 0.0);
+                    return result.fall_damage;
+                }
+                state = step(&state, &cmd(0.0, false), &map, physics);
+            }
+            panic!("never landed");
+        };
+        let vq3 = PhysicsProfile::vq3();
+        // A jump's worth of drop lands at full fall speed but doesn't hurt.
+        assert_eq!(landing_damage(80.0, 0.0, &vq3), 0);
+        assert_eq!(landing_damage(360.0, 0.0, &vq3), FALL_DAMAGE_MEDIUM);
+        assert_eq!(landing_damage(520.0, 0.0, &vq3), FALL_DAMAGE_FAR);
+
+        // The same drop hurts under heavier gravity or when thrown downwards.
+        let heavy = PhysicsProfile {
+            gravity_scale: 2.0,
+            ..vq3
+        };
+        assert_eq!(landing_damage(240.0, 0.0, &vq3), 0);
+        assert_eq!(landing_damage(240.0, 0.0, &heavy), FALL_DAMAGE_MEDIUM);
+        assert_eq!(landing_damage(80.0, 12.0, &vq3), FALL_DAMAGE_FAR);
     }
 }
@@ -1,8 +1,11 @@
 use crate::compat_rand::*;
+use crate::game::freeze::THAW_RADIUS;
 use crate::game::map::{ItemType, Map};
 use crate::game::nav_graph::NavGraph;
 use crate::game::player::Player;
 use crate::game::weapon::Weapon;
+use crate::logging::{self, LogLevel};
+use crate::{log_debug, log_trace};
 
 #[derive(Clone, Debug)]
 pub struct BotAI {
@@ -86,10 +89,17 @@ impl BotAI {
 
         self.last_position = (bot.x, bot.y);
 
-        if self.think_time % 60 == 0 {
-            // println!("[Bot {}] stuck={} search_timer={} evade_timer={} rocket_timer={} move_dir={:.1}",
-            //     bot.id, self.stuck_counter, self.search_timer, self.evade_timer,
-            //     self.rocket_jump_timer, self.move_direction);
+        if self.think_time.is_multiple_of(60) {
+            log_debug!(
+                Bot,
+                "[Bot {}] stuck={} search_timer={} evade_timer={} rocket_timer={} move_dir={:.1}",
+                bot.id,
+                self.stuck_counter,
+                self.search_timer,
+                self.evade_timer,
+                self.rocket_jump_timer,
+                self.move_direction
+            );
         }
 
         if self.path_update_timer > 0 {
@@ -100,7 +110,8 @@ impl BotAI {
         let mut closest_dist = 10000.0;
 
         for player in players {
-            if player.id != bot.id && !player.dead {
+            let teammate = bot.team != 0 && player.team == bot.team;
+            if player.id != bot.id && !player.dead && !teammate {
                 let dx = player.x - bot.x;
                 let dy = player.y - bot.y;
                 let dist = (dx * dx + dy * dy).sqrt();
@@ -155,6 +166,14 @@ impl BotAI {
             }
         }
 
+        if let Some((teammate_x, teammate_y, dist)) = Self::closest_frozen_teammate(bot, players) {
+            let enemy_in_the_way = closest_enemy.is_some_and(|(_, d)| d < dist.min(300.0));
+            if !enemy_in_the_way {
+                self.go_thaw(bot, (teammate_x, teammate_y), dist, map, nav_graph);
+                return;
+            }
+        }
+
         let needs_health = bot.health < 50;
         let needs_armor = bot.armor < 50;
 
@@ -164,9 +183,17 @@ impl BotAI {
         let has_rail = bot.has_weapon[6] && bot.ammo[6] > 0;
         let has_plasma = bot.has_weapon[7] && bot.ammo[7] > 0;
         let has_bfg = bot.has_weapon[8] && bot.ammo[8] > 0;
-
-        let has_good_weapon =
-            has_shotgun || has_grenade || has_rocket || has_rail || has_plasma || has_bfg;
+        let has_nailgun = bot.has_weapon[9] && bot.ammo[9] > 0;
+        let has_chaingun = bot.has_weapon[11] && bot.ammo[11] > 0;
+
+        let has_good_weapon = has_shotgun
+            || has_grenade
+            || has_rocket
+            || has_rail
+            || has_plasma
+            || has_bfg
+            || has_nailgun
+            || has_chaingun;
         let needs_weapon = !has_good_weapon;
 
         let mut best_item: Option<(f32, f32, f32)> = None;
@@ -186,10 +213,14 @@ impl BotAI {
                     ItemType::RocketLauncher | ItemType::Railgun | ItemType::LightningGun => {
                         (needs_weapon, 5)
                     }
-                    ItemType::Plasmagun | ItemType::Shotgun | ItemType::GrenadeLauncher => {
-                        (needs_weapon, 4)
-                    }
+                    ItemType::Plasmagun
+                    | ItemType::Shotgun
+                    | ItemType::GrenadeLauncher
+                    | ItemType::Nailgun
+                    | ItemType::Chaingun => (needs_weapon, 4),
+                    ItemType::ProxLauncher => (needs_weapon, 3),
                     ItemType::Quad | ItemType::Haste | ItemType::Regen => (true, 6),
+                    ItemType::Medkit | ItemType::Teleporter => (bot.holdable.is_none(), 1),
                     _ => (false, 0),
                 };
 
@@ -234,9 +265,9 @@ impl BotAI {
                 {
                     if self.use_nav_graph(bot, (enemy.x, enemy.y), nav_graph, map) {
                         found_navigation_aid = true;
-                        // if self.think_time % 120 == 0 {
-                        //     println!("[Bot {}] Using nav graph to reach enemy", bot.id);
-                        // }
+                        if self.think_time.is_multiple_of(120) {
+                            log_debug!(Bot, "[Bot {}] Using nav graph to reach enemy", bot.id);
+                        }
                     }
                 }
             }
@@ -250,7 +281,12 @@ impl BotAI {
                     self.search_timer = 100;
                     self.want_jump = true;
                     self.stuck_counter = 0;
-                    // println!("[Bot {}] STUCK! Reversing direction to {:.1}", bot.id, self.search_direction);
+                    log_trace!(
+                        Bot,
+                        "Bot {} stuck, reversing direction to {:.1}",
+                        bot.id,
+                        self.search_direction
+                    );
                 }
                 self.move_direction = self.search_direction;
                 found_navigation_aid = true;
@@ -265,9 +301,15 @@ impl BotAI {
                     if dist_to_pad < 300.0 {
                         self.move_direction = dx_to_pad.signum();
                         found_navigation_aid = true;
-                        // if self.think_time % 60 == 0 {
-                        //     println!("[Bot {}] Enemy above, moving to jumppad at ({:.0},{:.0})", bot.id, pad.x, pad.y);
-                        // }
+                        if self.think_time.is_multiple_of(60) {
+                            log_debug!(
+                                Bot,
+                                "[Bot {}] Enemy above, moving to jumppad at ({:.0},{:.0})",
+                                bot.id,
+                                pad.x,
+                                pad.y
+                            );
+                        }
                         break;
                     }
                 }
@@ -287,9 +329,13 @@ impl BotAI {
                             if dist_after < dist - 50.0 {
                                 self.move_direction = dx_to_tele.signum();
                                 found_navigation_aid = true;
-                                // if self.think_time % 60 == 0 {
-                                //     println!("[Bot {}] Enemy above, moving to teleporter", bot.id);
-                                // }
+                                if self.think_time.is_multiple_of(60) {
+                                    log_debug!(
+                                        Bot,
+                                        "[Bot {}] Enemy above, moving to teleporter",
+                                        bot.id
+                                    );
+                                }
                                 break;
                             }
                         }
@@ -310,9 +356,13 @@ impl BotAI {
                         if gen_range_i32(0, 100) < 60 {
                             self.rocket_jump_timer = 200;
                             found_navigation_aid = true;
-                            // if self.think_time % 60 == 0 {
-                            //     println!("[Bot {}] Enemy directly above, rocket jumping!", bot.id);
-                            // }
+                            if self.think_time.is_multiple_of(60) {
+                                log_debug!(
+                                    Bot,
+                                    "[Bot {}] Enemy directly above, rocket jumping!",
+                                    bot.id
+                                );
+                            }
                         }
                     }
                 }
@@ -321,9 +371,14 @@ impl BotAI {
                     if self.search_timer == 0 {
                         self.search_direction = if gen_range_i32(0, 2) == 0 { 1.0 } else { -1.0 };
                         self.search_timer = 150;
-                        // if self.think_time % 60 == 0 {
-                        //     println!("[Bot {}] Enemy above, searching for way up, dir={:.1}", bot.id, self.search_direction);
-                        // }
+                        if self.think_time.is_multiple_of(60) {
+                            log_debug!(
+                                Bot,
+                                "[Bot {}] Enemy above, searching for way up, dir={:.1}",
+                                bot.id,
+                                self.search_direction
+                            );
+                        }
                     }
                     self.move_direction = self.search_direction;
                     self.want_jump = true;
@@ -345,9 +400,13 @@ impl BotAI {
                         if dist_after < dist - 50.0 {
                             self.move_direction = dx_to_tele.signum();
                             found_navigation_aid = true;
-                            // if self.think_time % 60 == 0 {
-                            //     println!("[Bot {}] Enemy below, moving to teleporter", bot.id);
-                            // }
+                            if self.think_time.is_multiple_of(60) {
+                                log_debug!(
+                                    Bot,
+                                    "[Bot {}] Enemy below, moving to teleporter",
+                                    bot.id
+                                );
+                            }
                             break;
                         }
                     }
@@ -357,9 +416,14 @@ impl BotAI {
                     if self.search_timer == 0 {
                         self.search_direction = if gen_range_i32(0, 2) == 0 { 1.0 } else { -1.0 };
                         self.search_timer = 150;
-                        // if self.think_time % 60 == 0 {
-                        //     println!("[Bot {}] Enemy below, searching for way down, dir={:.1}", bot.id, self.search_direction);
-                        // }
+                        if self.think_time.is_multiple_of(60) {
+                            log_debug!(
+                                Bot,
+                                "[Bot {}] Enemy below, searching for way down, dir={:.1}",
+                                bot.id,
+                                self.search_direction
+                            );
+                        }
                     }
                     self.move_direction = self.search_direction;
                     found_navigation_aid = true;
@@ -370,9 +434,14 @@ impl BotAI {
                 self.move_direction = self.evade_direction;
             } else if self.search_timer > 0 && !found_navigation_aid {
                 self.move_direction = self.search_direction;
-                // if self.think_time % 60 == 0 {
-                //     println!("[Bot {}] Following search path, timer={}", bot.id, self.search_timer);
-                // }
+                if self.think_time.is_multiple_of(60) {
+                    log_debug!(
+                        Bot,
+                        "[Bot {}] Following search path, timer={}",
+                        bot.id,
+                        self.search_timer
+                    );
+                }
             } else if !found_navigation_aid {
                 if let Some((item_x, item_y, item_dist)) = best_item {
                     let must_get_item = (needs_health && bot.health < 30)
@@ -384,32 +453,48 @@ impl BotAI {
                         self.target_item = Some((item_x, item_y));
                         let item_dx = item_x - bot.x;
                         self.move_direction = item_dx.signum();
-                        // if self.think_time % 60 == 0 {
-                        //     println!("[Bot {}] Going for item (priority={})", bot.id, best_item_priority);
-                        // }
+                        if self.think_time.is_multiple_of(60) {
+                            log_debug!(
+                                Bot,
+                                "[Bot {}] Going for item (priority={})",
+                                bot.id,
+                                best_item_priority
+                            );
+                        }
                     } else {
                         self.target_item = None;
                         self.search_timer = 0;
                         if dist > 50.0 && dx_abs > 10.0 {
                             self.move_direction = dx.signum();
-                            // if self.think_time % 60 == 0 {
-                            //     println!("[Bot {}] Moving toward enemy (normal)", bot.id);
-                            // }
+                            if self.think_time.is_multiple_of(60) {
+                                log_debug!(Bot, "[Bot {}] Moving toward enemy (normal)", bot.id);
+                            }
                         } else if dist < 100.0 && has_los {
                             self.move_direction = -dx.signum();
-                            // if self.think_time % 60 == 0 {
-                            //     println!("[Bot {}] Backing away (too close)", bot.id);
-                            // }
+                            if self.think_time.is_multiple_of(60) {
+                                log_debug!(Bot, "[Bot {}] Backing away (too close)", bot.id);
+                            }
                         } else if dx_abs <= 10.0 {
                             self.move_direction = dx.signum();
-                            // if self.think_time % 60 == 0 {
-                            //     println!("[Bot {}] Aligned horizontally, minor adjustment", bot.id);
-                            // }
+                            if self.think_time.is_multiple_of(60) {
+                                log_debug!(
+                                    Bot,
+                                    "[Bot {}] Aligned horizontally, minor adjustment",
+                                    bot.id
+                                );
+                            }
                         } else {
                             self.move_direction = 0.0;
-                            // if self.think_time % 60 == 0 {
-                            // println!("[Bot {}] STANDING STILL - dist={:.0} dx_abs={:.0} dy={:.0}", bot.id, dist, dx_abs, dy.abs());
-                            // }
+                            if self.think_time.is_multiple_of(60) {
+                                log_debug!(
+                                    Bot,
+                                    "[Bot {}] STANDING STILL - dist={:.0} dx_abs={:.0} dy={:.0}",
+                                    bot.id,
+                                    dist,
+                                    dx_abs,
+                                    dy.abs()
+                                );
+                            }
                         }
                     }
                 } else {
@@ -482,10 +567,18 @@ impl BotAI {
                     Some(Weapon::Railgun)
                 } else if dist < 200.0 && bot.has_weapon[2] && bot.ammo[2] > 0 {
                     Some(Weapon::Shotgun)
+                } else if dist < 200.0 && bot.has_weapon[9] && bot.ammo[9] > 0 {
+                    Some(Weapon::Nailgun)
                 } else if dist < 150.0 && bot.has_weapon[7] && bot.ammo[7] > 0 {
                     Some(Weapon::Plasmagun)
+                } else if dist < 300.0 && bot.has_weapon[11] && bot.ammo[11] > 0 {
+                    Some(Weapon::Chaingun)
                 } else if bot.has_weapon[3] && bot.ammo[3] > 0 {
                     Some(Weapon::GrenadeLauncher)
+                } else if bot.has_weapon[11] && bot.ammo[11] > 0 {
+                    Some(Weapon::Chaingun)
+                } else if bot.has_weapon[10] && bot.ammo[10] > 0 {
+                    Some(Weapon::ProxLauncher)
                 } else if bot.has_weapon[1] && bot.ammo[1] > 0 {
                     Some(Weapon::MachineGun)
                 } else if bot.ammo[bot.weapon as usize] > 0 || bot.weapon as u8 == 0 {
@@ -538,6 +631,49 @@ impl BotAI {
         }
     }
 
+    fn closest_frozen_teammate(bot: &Player, players: &[Player]) -> Option<(f32, f32, f32)> {
+        if bot.team == 0 {
+            return None;
+        }
+        players
+            .iter()
+            .filter(|p| p.id != bot.id && p.frozen && p.team == bot.team)
+            .map(|p| {
+                let dist = ((p.x - bot.x).powi(2) + (p.y - bot.y).powi(2)).sqrt();
+                (p.x, p.y, dist)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
+    /// Walks over to a frozen teammate and stands still next to them.
+    fn go_thaw(
+        &mut self,
+        bot: &Player,
+        target: (f32, f32),
+        dist: f32,
+        map: &Map,
+        nav_graph: Option<&NavGraph>,
+    ) {
+        self.target_player = None;
+        self.want_shoot = false;
+
+        if dist < THAW_RADIUS * 0.5 {
+            self.move_direction = 0.0;
+            self.want_jump = false;
+            return;
+        }
+
+        let needs_path = (target.1 - bot.y).abs() > 40.0 || self.stuck_counter > 20;
+        let navigating = match nav_graph {
+            Some(nav_graph) if needs_path => self.use_nav_graph(bot, target, nav_graph, map),
+            _ => false,
+        };
+        if !navigating {
+            self.move_direction = (target.0 - bot.x).signum();
+            self.want_jump = self.stuck_counter > 20;
+        }
+    }
+
     fn find_reachable_node(x: f32, y: f32, nav_graph: &NavGraph, _map: &Map) -> Option<usize> {
         let feet_y = y + 24.0;
 
@@ -589,9 +725,17 @@ impl BotAI {
             let start_node = Self::find_reachable_node(bot.x, bot.y, nav_graph, map);
 
             if let Some(start) = start_node {
-                // let start_node_info = &nav_graph.nodes[start];
-                // println!("[Bot {}] Bot at ({:.0},{:.0}), nearest reachable node {} at ({:.0},{:.0})",
-                // bot.id, bot.x, bot.y, start, start_node_info.x, start_node_info.y);
+                let start_node_info = &nav_graph.nodes[start];
+                log_debug!(
+                    Bot,
+                    "[Bot {}] Bot at ({:.0},{:.0}), nearest reachable node {} at ({:.0},{:.0})",
+                    bot.id,
+                    bot.x,
+                    bot.y,
+                    start,
+                    start_node_info.x,
+                    start_node_info.y
+                );
 
                 let mut candidates: Vec<(usize, f32)> = nav_graph
                     .nodes
@@ -610,33 +754,64 @@ impl BotAI {
                 for (goal, _dist) in candidates.iter().take(10) {
                     if let Some(path) = nav_graph.find_path(start, *goal) {
                         self.current_path = path;
-                        let _goal_node = &nav_graph.nodes[*goal];
-                        // println!("[Bot {}] Found path: {} waypoints from node {} ({:.0},{:.0}) to node {} ({:.0},{:.0}), target=({:.0},{:.0})",
-                        //     bot.id, self.current_path.len(),
-                        //     start, nav_graph.nodes[start].x, nav_graph.nodes[start].y,
-                        //     goal, goal_node.x, goal_node.y,
-                        //     target.0, target.1);
-
-                        // for i in 0..self.current_path.len().min(5) {
-                        //     let node = &nav_graph.nodes[self.current_path[i]];
-                        //     let edge_info = if i + 1 < self.current_path.len() {
-                        //         nav_graph.edges.iter()
-                        //             .find(|e| e.from == self.current_path[i] && e.to == self.current_path[i+1])
-                        //             .map(|e| format!("{:?}", e.edge_type))
-                        //             .unwrap_or("?".to_string())
-                        //     } else {
-                        //         "END".to_string()
-                        //     };
-                        //     println!("  [{}] node {} at ({:.0},{:.0}) -> {}", i, self.current_path[i], node.x, node.y, edge_info);
-                        // }
+                        let goal_node = &nav_graph.nodes[*goal];
+                        log_debug!(
+                            Bot,
+                            "[Bot {}] Found path: {} waypoints from node {} ({:.0},{:.0}) to node {} ({:.0},{:.0}), target=({:.0},{:.0})",
+                            bot.id,
+                            self.current_path.len(),
+                            start,
+                            start_node_info.x,
+                            start_node_info.y,
+                            goal,
+                            goal_node.x,
+                            goal_node.y,
+                            target.0,
+                            target.1
+                        );
+
+                        if logging::enabled(LogLevel::Debug) {
+                            for (i, &node_id) in self.current_path.iter().take(5).enumerate() {
+                                let node = &nav_graph.nodes[node_id];
+                                let edge_info = match self.current_path.get(i + 1) {
+                                    Some(&next_id) => nav_graph
+                                        .edges
+                                        .iter()
+                                        .find(|e| e.from == node_id && e.to == next_id)
+                                        .map(|e| format!("{:?}", e.edge_type))
+                                        .unwrap_or("?".to_string()),
+                                    None => "END".to_string(),
+                                };
+                                log_debug!(
+                                    Bot,
+                                    "  [{}] node {} at ({:.0},{:.0}) -> {}",
+                                    i,
+                                    node_id,
+                                    node.x,
+                                    node.y,
+                                    edge_info
+                                );
+                            }
+                        }
 
                         return true;
                     }
                 }
 
-                // println!("[Bot {}] No path found from node {} to any of 10 nearest nodes to target", bot.id, start);
+                log_trace!(
+                    Bot,
+                    "Bot {} found no path from node {} to target",
+                    bot.id,
+                    start
+                );
             } else {
-                // println!("[Bot {}] Could not find start node near ({:.0},{:.0})", bot.id, bot.x, bot.y);
+                log_trace!(
+                    Bot,
+                    "Bot {} has no start node near ({:.0},{:.0})",
+                    bot.id,
+                    bot.x,
+                    bot.y
+                );
             }
             return false;
         }
@@ -656,13 +831,23 @@ impl BotAI {
             let dx_abs = dx.abs();
             let dist = (dx * dx + dy * dy).sqrt();
 
-            if self.think_time % 120 == 0 {
-                let _edge_type = edge_to_next
+            if self.think_time.is_multiple_of(120) {
+                let edge_type = edge_to_next
                     .map(|e| format!("{:?}", e.edge_type))
                     .unwrap_or("Unknown".to_string());
-                // println!("[Bot {}] Path: {}/{} waypoints, next at ({:.0},{:.0}), type={}, dist={:.0}, bot at ({:.0},{:.0})",
-                //     bot.id, self.current_path.len() - 1, self.current_path.len(),
-                //     next_node.x, next_node.y, edge_type, dist, bot.x, bot.y);
+                log_debug!(
+                    Bot,
+                    "[Bot {}] Path: {}/{} waypoints, next at ({:.0},{:.0}), type={}, dist={:.0}, bot at ({:.0},{:.0})",
+                    bot.id,
+                    self.current_path.len() - 1,
+                    self.current_path.len(),
+                    next_node.x,
+                    next_node.y,
+                    edge_type,
+                    dist,
+                    bot.x,
+                    bot.y
+                );
             }
 
             let on_ground = map.is_solid(((bot.x) / 32.0) as i32, ((bot.y + 24.0) / 16.0) as i32);
@@ -691,15 +876,25 @@ impl BotAI {
                     let new_next = &nav_graph.nodes[self.current_path[1]];
                     let new_dx = new_next.x - bot.x;
                     self.move_direction = new_dx.signum();
-                    // println!("[Bot {}] Reached waypoint! {} remaining, next at ({:.0}, {:.0})",
-                    //     bot.id, self.current_path.len() - 1, new_next.x, new_next.y);
+                    log_debug!(
+                        Bot,
+                        "[Bot {}] Reached waypoint! {} remaining, next at ({:.0}, {:.0})",
+                        bot.id,
+                        self.current_path.len() - 1,
+                        new_next.x,
+                        new_next.y
+                    );
                 } else {
-                    // println!("[Bot {}] Reached final waypoint!", bot.id);
+                    log_debug!(Bot, "[Bot {}] Reached final waypoint!", bot.id);
                     self.current_path.clear();
                 }
             } else {
                 if self.stuck_counter > 30 && dist > 100.0 {
-                    // println!("[Bot {}] Stuck while following path, skipping waypoint", bot.id);
+                    log_debug!(
+                        Bot,
+                        "[Bot {}] Stuck while following path, skipping waypoint",
+                        bot.id
+                    );
                     self.current_path.remove(0);
                     self.stuck_counter = 0;
                     if self.current_path.len() <= 1 {
@@ -718,9 +913,14 @@ impl BotAI {
                         | super::nav_graph::NavEdgeType::JumpGap => {
                             if !is_next_jumppad && dy <= -4.0 {
                                 self.want_jump = true;
-                            } else if self.think_time % 120 == 0 && dy <= -4.0 {
-                                // println!("[Bot {}] NOT jumping: is_next_jumppad={}, next_node_type={:?}",
-                                //     bot.id, is_next_jumppad, next_node.node_type);
+                            } else if self.think_time.is_multiple_of(120) && dy <= -4.0 {
+                                log_debug!(
+                                    Bot,
+                                    "[Bot {}] NOT jumping: is_next_jumppad={}, next_node_type={:?}",
+                                    bot.id,
+                                    is_next_jumppad,
+                                    next_node.node_type
+                                );
                             }
                         }
                         super::nav_graph::NavEdgeType::JumpDown => {
@@ -761,7 +961,12 @@ impl BotAI {
 
             return true;
         } else if !self.current_path.is_empty() {
-            // println!("[Bot {}] Path has only {} nodes, not following", bot.id, self.current_path.len());
+            log_debug!(
+                Bot,
+                "[Bot {}] Path has only {} nodes, not following",
+                bot.id,
+                self.current_path.len()
+            );
         }
 
         false
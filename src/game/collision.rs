@@ -1,5 +1,6 @@
 use super::constants::*;
-use super::map::Map;
+use super::map::{Liquid, Map};
+use super::physics::PhysicsProfile;
 use macroquad::prelude::Vec2;
 
 pub struct CollisionResult {
@@ -21,14 +22,40 @@ pub fn check_on_ground(x: f32, y: f32, map: &Map) -> bool {
         || (map.is_solid(check_x_right, check_y_feet) && !map.is_solid(check_x_right, check_y_body))
 }
 
+/// Whether solid tiles fill the space even a crouched player needs, so they
+/// can't move at all.
+pub fn is_crushed(x: f32, y: f32, map: &Map) -> bool {
+    let check_x = (x / 32.0).floor() as i32;
+    let check_y_body = ((y + 8.0) / 16.0).floor() as i32;
+    let check_y_head = ((y - 8.0) / 16.0).floor() as i32;
+    map.is_solid(check_x, check_y_body) && map.is_solid(check_x, check_y_head)
+}
+
+/// How deep a player is in liquid, like Q3's `waterlevel`: 0 dry, 1 feet,
+/// 2 waist, 3 head under. The liquid is whatever is at their feet.
+pub fn water_level(x: f32, y: f32, map: &Map) -> (u8, Option<Liquid>) {
+    let check_x = (x / 32.0).floor() as i32;
+    let row = |offset: f32| ((y + offset) / 16.0).floor() as i32;
+    let Some(liquid) = map.liquid_at(check_x, row(8.0)) else {
+        return (0, None);
+    };
+    let level = if map.liquid_at(check_x, row(-24.0)).is_none() {
+        1
+    } else if map.liquid_at(check_x, row(-56.0)).is_none() {
+        2
+    } else {
+        3
+    };
+    (level, Some(liquid))
+}
+
 pub fn move_with_collision(
-    x: f32,
-    y: f32,
-    vel_x: f32,
-    vel_y: f32,
+    (x, y): (f32, f32),
+    (vel_x, vel_y): (f32, f32),
     crouch: bool,
     dt_norm: f32,
     map: &Map,
+    physics: &PhysicsProfile,
 ) -> CollisionResult {
     let hitbox_height = if crouch {
         PLAYER_HITBOX_HEIGHT_CROUCH
@@ -82,9 +109,11 @@ pub fn move_with_collision(
                 && !map.is_solid(step_tile_x, step_tile_y_head);
 
             if can_step_up {
-                let step_height = 16.0;
                 new_x = target_x;
-                new_y = y - step_height;
+                new_y = y - physics.step_height;
+                if physics.ramp_jump {
+                    new_vel_y = new_vel_y.min(-RAMP_STEP_LIFT);
+                }
             } else {
                 new_vel_x = 0.0;
             }
@@ -19,16 +19,31 @@ pub const DAMAGE_RAIL: i32 = 100;
 pub const DAMAGE_PLASMA: i32 = 20;
 pub const DAMAGE_BFG: i32 = 200;
 pub const DAMAGE_GAUNTLET: i32 = 50;
+pub const DAMAGE_NAIL: i32 = 20;
+pub const DAMAGE_PROX: i32 = 100;
+pub const DAMAGE_CHAINGUN: i32 = 7;
 
 pub const GRENADE_BOUNCE_WALL: f32 = 0.4;
 pub const GRENADE_BOUNCE_FLOOR: f32 = 0.4;
 pub const GRENADE_SLOWDOWN: f32 = 1.27;
 pub const GRENADE_FUSE_SECS: f32 = 2.5;
 
+pub const NAILGUN_NAILS: usize = 15;
+pub const NAILGUN_SPREAD: f32 = 0.12;
+
+pub const PROX_ARM_SECS: f32 = 2.0;
+pub const PROX_LIFETIME_SECS: f32 = 20.0;
+pub const PROX_TRIGGER_RADIUS: f32 = 96.0;
+
+/// Chaingun refire between a standing start and a fully spun barrel.
+pub const CHAINGUN_REFIRE_SLOW: f32 = 0.12;
+pub const CHAINGUN_REFIRE_FAST: f32 = 0.03;
+
 pub const ITEM_RESPAWN_HEALTH: u32 = 35 * 60;
 pub const ITEM_RESPAWN_ARMOR: u32 = 25 * 60;
 pub const ITEM_RESPAWN_WEAPON: u32 = 5 * 60;
 pub const ITEM_RESPAWN_POWERUP: u32 = 120 * 60;
+pub const ITEM_RESPAWN_HOLDABLE: u32 = 60 * 60;
 
 pub const POWERUP_DURATION_QUAD: u16 = 30 * 60;
 pub const POWERUP_DURATION_HASTE: u16 = 30 * 60;
@@ -45,6 +60,35 @@ pub const CROUCH_SPEED_MULT: f32 = 0.6;
 
 pub const HASTE_SPEED_MULT: f32 = 1.3;
 pub const HASTE_JUMP_MULT: f32 = 1.2;
+pub const HASTE_FIRE_MULT: f32 = 1.3;
+
+pub const REGEN_INTERVAL: u16 = 60;
+
+pub const FLIGHT_ACCEL: f32 = 0.6;
+pub const FLIGHT_MAX_SPEED: f32 = 6.0;
+
+/// A jump within this many seconds of the last one gets the double jump boost.
+pub const DOUBLE_JUMP_WINDOW: f32 = 0.4;
+pub const DOUBLE_JUMP_BOOST: f32 = -1.0;
+/// Upward speed a ramp jump profile gives for stepping up a tile.
+pub const RAMP_STEP_LIFT: f32 = 1.5;
+
+/// Landing this fast hurts, like Q3's medium and far falls. The speed is the
+/// one built up under gravity, not capped at the profile's max fall speed.
+pub const FALL_SPEED_MEDIUM: f32 = 8.5;
+pub const FALL_SPEED_FAR: f32 = 10.0;
+pub const FALL_DAMAGE_MEDIUM: i32 = 5;
+pub const FALL_DAMAGE_FAR: i32 = 10;
+/// Swimming in a liquid up to the waist: gravity and run speed are scaled
+/// down, jump and crouch swim up and down, and drag slows every move.
+pub const SWIM_GRAVITY_SCALE: f32 = 0.2;
+pub const SWIM_SPEED_MULT: f32 = 0.6;
+pub const SWIM_ACCEL: f32 = 0.3;
+pub const SWIM_MAX_SPEED: f32 = 3.0;
+pub const WATER_DRAG: f32 = 0.06;
+
+/// Enough to gib through any armor, for crushing and suicide.
+pub const WORLD_KILL_DAMAGE: i32 = 100_000;
 
 pub const STARTING_HEALTH: i32 = 125;
 
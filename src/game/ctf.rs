@@ -0,0 +1,451 @@
+use super::gametype::{other_team, team_name, TeamScores, TEAM_BLUE, TEAM_FREE, TEAM_RED};
+use super::map::FlagBase;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const FLAG_RETURN_TIME: f32 = 30.0;
+pub const FLAG_TOUCH_RADIUS: f32 = 32.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FlagStatus {
+    AtBase,
+    Carried(u16),
+    Dropped,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Flag {
+    pub team: u8,
+    pub base_x: f32,
+    pub base_y: f32,
+    pub x: f32,
+    pub y: f32,
+    pub status: FlagStatus,
+    pub return_timer: f32,
+}
+
+impl Flag {
+    pub fn new(team: u8, base_x: f32, base_y: f32) -> Self {
+        Self {
+            team,
+            base_x,
+            base_y,
+            x: base_x,
+            y: base_y,
+            status: FlagStatus::AtBase,
+            return_timer: 0.0,
+        }
+    }
+
+    pub fn carrier(&self) -> Option<u16> {
+        match self.status {
+            FlagStatus::Carried(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    fn return_to_base(&mut self) {
+        self.x = self.base_x;
+        self.y = self.base_y;
+        self.status = FlagStatus::AtBase;
+        self.return_timer = 0.0;
+    }
+
+    fn drop_at(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+        self.status = FlagStatus::Dropped;
+        self.return_timer = FLAG_RETURN_TIME;
+    }
+
+    fn touches(&self, x: f32, y: f32) -> bool {
+        self.carrier().is_none()
+            && (self.x - x).powi(2) + (self.y - y).powi(2) < FLAG_TOUCH_RADIUS * FLAG_TOUCH_RADIUS
+    }
+}
+
+/// Flag events carry the team of the flag involved, not of the player.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FlagEvent {
+    Taken { team: u8, player_id: u16 },
+    Dropped { team: u8, player_id: u16 },
+    Returned { team: u8, player_id: Option<u16> },
+    Captured { team: u8, player_id: u16 },
+}
+
+impl FlagEvent {
+    pub fn message(&self, name_of: impl Fn(u16) -> String) -> String {
+        match *self {
+            FlagEvent::Taken { team, player_id } => {
+                format!("{} got the {} flag!", name_of(player_id), team_name(team))
+            }
+            FlagEvent::Dropped { team, player_id } => {
+                format!("{} lost the {} flag!", name_of(player_id), team_name(team))
+            }
+            FlagEvent::Returned {
+                team,
+                player_id: Some(id),
+            } => format!("{} returned the {} flag!", name_of(id), team_name(team)),
+            FlagEvent::Returned {
+                team,
+                player_id: None,
+            } => format!("The {} flag has returned!", team_name(team)),
+            FlagEvent::Captured { team, player_id } => {
+                format!(
+                    "{} captured the {} flag!",
+                    name_of(player_id),
+                    team_name(team)
+                )
+            }
+        }
+    }
+
+    /// Announcer sound as heard by the local player.
+    pub fn announcement(&self, local_id: Option<u16>, local_team: u8) -> Option<&'static str> {
+        match *self {
+            FlagEvent::Taken { player_id, .. } if Some(player_id) == local_id => {
+                Some("you_have_flag")
+            }
+            FlagEvent::Taken { team, .. } if team == local_team => Some("enemy_has_flag"),
+            FlagEvent::Taken { .. } if local_team != TEAM_FREE => Some("team_has_flag"),
+            FlagEvent::Taken { .. } | FlagEvent::Dropped { .. } => None,
+            FlagEvent::Returned { team, .. } => Some(if team == TEAM_RED {
+                "red_returned"
+            } else {
+                "blue_returned"
+            }),
+            FlagEvent::Captured { team, .. } => Some(if other_team(team) == TEAM_RED {
+                "red_scores"
+            } else {
+                "blue_scores"
+            }),
+        }
+    }
+}
+
+pub struct CtfPlayer {
+    pub id: u16,
+    pub team: u8,
+    pub x: f32,
+    pub y: f32,
+    pub alive: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CtfState {
+    pub flags: Vec<Flag>,
+    pub captures: TeamScores,
+}
+
+impl CtfState {
+    /// Uses the first base of each team; maps without both bases are unplayable.
+    pub fn new(bases: &[FlagBase]) -> Self {
+        let flags = [TEAM_RED, TEAM_BLUE]
+            .iter()
+            .filter_map(|&team| {
+                bases
+                    .iter()
+                    .find(|b| b.team == team)
+                    .map(|b| Flag::new(team, b.x, b.y))
+            })
+            .collect();
+        Self {
+            flags,
+            captures: TeamScores::default(),
+        }
+    }
+
+    pub fn is_playable(&self) -> bool {
+        self.flags.len() == 2
+    }
+
+    pub fn flag(&self, team: u8) -> Option<&Flag> {
+        self.flags.iter().find(|f| f.team == team)
+    }
+
+    pub fn carried_by(&self, player_id: u16) -> Option<u8> {
+        self.flags
+            .iter()
+            .find(|f| f.carrier() == Some(player_id))
+            .map(|f| f.team)
+    }
+
+    pub fn reset(&mut self) {
+        for flag in &mut self.flags {
+            flag.return_to_base();
+        }
+        self.captures = TeamScores::default();
+    }
+
+    pub fn drop_flag(&mut self, player_id: u16, x: f32, y: f32) -> Option<FlagEvent> {
+        let flag = self
+            .flags
+            .iter_mut()
+            .find(|f| f.carrier() == Some(player_id))?;
+        flag.drop_at(x, y);
+        Some(FlagEvent::Dropped {
+            team: flag.team,
+            player_id,
+        })
+    }
+
+    pub fn update(&mut self, dt: f32, players: &[CtfPlayer]) -> Vec<FlagEvent> {
+        let mut events = Vec::new();
+
+        for flag in &mut self.flags {
+            match flag.status {
+                FlagStatus::Carried(id) => match players.iter().find(|p| p.id == id) {
+                    Some(p) if p.alive => {
+                        flag.x = p.x;
+                        flag.y = p.y;
+                    }
+                    carrier => {
+                        let (x, y) = carrier.map_or((flag.x, flag.y), |p| (p.x, p.y));
+                        flag.drop_at(x, y);
+                        events.push(FlagEvent::Dropped {
+                            team: flag.team,
+                            player_id: id,
+                        });
+                    }
+                },
+                FlagStatus::Dropped => {
+                    flag.return_timer -= dt;
+                    if flag.return_timer <= 0.0 {
+                        flag.return_to_base();
+                        events.push(FlagEvent::Returned {
+                            team: flag.team,
+                            player_id: None,
+                        });
+                    }
+                }
+                FlagStatus::AtBase => {}
+            }
+        }
+
+        for p in players.iter().filter(|p| p.alive && p.team != TEAM_FREE) {
+            for i in 0..self.flags.len() {
+                if !self.flags[i].touches(p.x, p.y) {
+                    continue;
+                }
+
+                if self.flags[i].team != p.team {
+                    let flag = &mut self.flags[i];
+                    flag.status = FlagStatus::Carried(p.id);
+                    flag.x = p.x;
+                    flag.y = p.y;
+                    events.push(FlagEvent::Taken {
+                        team: flag.team,
+                        player_id: p.id,
+                    });
+                } else if self.flags[i].status == FlagStatus::Dropped {
+                    self.flags[i].return_to_base();
+                    events.push(FlagEvent::Returned {
+                        team: p.team,
+                        player_id: Some(p.id),
+                    });
+                } else if let Some(enemy) =
+                    self.flags.iter_mut().find(|f| f.carrier() == Some(p.id))
+                {
+                    enemy.return_to_base();
+                    self.captures.add(p.team, 1);
+                    events.push(FlagEvent::Captured {
+                        team: enemy.team,
+                        player_id: p.id,
+                    });
+                }
+            }
+        }
+
+        events
+    }
+
+    pub fn render(&self, camera_x: f32, camera_y: f32, players: &[super::player::Player]) {
+        for flag in &self.flags {
+            let base_x = flag.base_x - camera_x;
+            let base_y = flag.base_y - camera_y;
+            draw_rectangle(
+                base_x - 16.0,
+                base_y + 14.0,
+                32.0,
+                6.0,
+                Color::from_rgba(60, 60, 70, 255),
+            );
+            draw_rectangle(
+                base_x - 12.0,
+                base_y + 14.0,
+                24.0,
+                2.0,
+                team_color(flag.team),
+            );
+
+            let (x, y) = match flag.carrier() {
+                Some(id) => match players.iter().find(|p| p.id == id) {
+                    Some(p) => (p.x - 10.0, p.y - 20.0),
+                    None => continue,
+                },
+                None => (flag.x, flag.y),
+            };
+            draw_flag(x - camera_x, y - camera_y, flag.team, 1.0);
+        }
+    }
+}
+
+pub fn team_color(team: u8) -> Color {
+    match team {
+        TEAM_RED => Color::from_rgba(230, 40, 40, 255),
+        TEAM_BLUE => Color::from_rgba(40, 90, 240, 255),
+        _ => Color::from_rgba(200, 200, 200, 255),
+    }
+}
+
+pub fn draw_flag(x: f32, y: f32, team: u8, scale: f32) {
+    let wave = (get_time() as f32 * 6.0 + x * 0.05).sin() * 2.0 * scale;
+    let top = y - 20.0 * scale;
+    draw_line(x, y + 14.0 * scale, x, top, 2.0 * scale, LIGHTGRAY);
+    draw_triangle(
+        vec2(x + 1.0, top),
+        vec2(x + 18.0 * scale, top + 6.0 * scale + wave),
+        vec2(x + 1.0, top + 12.0 * scale),
+        team_color(team),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(id: u16, team: u8, x: f32) -> CtfPlayer {
+        CtfPlayer {
+            id,
+            team,
+            x,
+            y: 0.0,
+            alive: true,
+        }
+    }
+
+    fn state() -> CtfState {
+        CtfState::new(&[
+            FlagBase {
+                x: 0.0,
+                y: 0.0,
+                team: TEAM_RED,
+            },
+            FlagBase {
+                x: 1000.0,
+                y: 0.0,
+                team: TEAM_BLUE,
+            },
+        ])
+    }
+
+    #[test]
+    fn test_take_and_capture() {
+        let mut ctf = state();
+        assert!(ctf.is_playable());
+
+        let events = ctf.update(0.1, &[player(1, TEAM_RED, 1000.0)]);
+        assert_eq!(
+            events,
+            vec![FlagEvent::Taken {
+                team: TEAM_BLUE,
+                player_id: 1
+            }]
+        );
+        assert_eq!(ctf.carried_by(1), Some(TEAM_BLUE));
+
+        assert!(ctf.update(0.1, &[player(1, TEAM_RED, 500.0)]).is_empty());
+        assert_eq!(ctf.flag(TEAM_BLUE).unwrap().x, 500.0);
+
+        let events = ctf.update(0.1, &[player(1, TEAM_RED, 10.0)]);
+        assert_eq!(
+            events,
+            vec![FlagEvent::Captured {
+                team: TEAM_BLUE,
+                player_id: 1
+            }]
+        );
+        assert_eq!(ctf.captures.red, 1);
+        assert_eq!(ctf.flag(TEAM_BLUE).unwrap().status, FlagStatus::AtBase);
+    }
+
+    #[test]
+    fn test_no_capture_while_own_flag_away() {
+        let mut ctf = state();
+        ctf.update(
+            0.1,
+            &[player(1, TEAM_RED, 1000.0), player(2, TEAM_BLUE, 0.0)],
+        );
+        assert_eq!(ctf.carried_by(2), Some(TEAM_RED));
+
+        let events = ctf.update(
+            0.1,
+            &[player(1, TEAM_RED, 0.0), player(2, TEAM_BLUE, 500.0)],
+        );
+        assert!(events.is_empty());
+        assert_eq!(ctf.captures, TeamScores::default());
+    }
+
+    #[test]
+    fn test_drop_return_and_timer() {
+        let mut ctf = state();
+        ctf.update(0.1, &[player(1, TEAM_RED, 1000.0)]);
+
+        let mut dead = player(1, TEAM_RED, 600.0);
+        dead.alive = false;
+        let events = ctf.update(0.1, &[dead]);
+        assert_eq!(
+            events,
+            vec![FlagEvent::Dropped {
+                team: TEAM_BLUE,
+                player_id: 1
+            }]
+        );
+        assert_eq!(ctf.flag(TEAM_BLUE).unwrap().status, FlagStatus::Dropped);
+
+        let events = ctf.update(0.1, &[player(2, TEAM_BLUE, 610.0)]);
+        assert_eq!(
+            events,
+            vec![FlagEvent::Returned {
+                team: TEAM_BLUE,
+                player_id: Some(2)
+            }]
+        );
+
+        ctf.update(0.1, &[player(1, TEAM_RED, 1000.0)]);
+        ctf.drop_flag(1, 300.0, 0.0);
+        assert!(ctf.update(FLAG_RETURN_TIME - 1.0, &[]).is_empty());
+        assert_eq!(
+            ctf.update(1.5, &[]),
+            vec![FlagEvent::Returned {
+                team: TEAM_BLUE,
+                player_id: None
+            }]
+        );
+        assert_eq!(ctf.flag(TEAM_BLUE).unwrap().x, 1000.0);
+    }
+
+    #[test]
+    fn test_announcements() {
+        let taken = FlagEvent::Taken {
+            team: TEAM_BLUE,
+            player_id: 1,
+        };
+        assert_eq!(taken.announcement(Some(1), TEAM_RED), Some("you_have_flag"));
+        assert_eq!(taken.announcement(Some(3), TEAM_RED), Some("team_has_flag"));
+        assert_eq!(
+            taken.announcement(Some(2), TEAM_BLUE),
+            Some("enemy_has_flag")
+        );
+
+        let captured = FlagEvent::Captured {
+            team: TEAM_BLUE,
+            player_id: 1,
+        };
+        assert_eq!(captured.announcement(None, TEAM_BLUE), Some("red_scores"));
+        assert_eq!(
+            captured.message(|id| format!("P{}", id)),
+            "P1 captured the Blue flag!"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// Extra players spectate and nobody rotates in.
+    Off,
+    /// The loser goes to the back of the queue and the next challenger plays.
+    WinnerStays,
+    /// The loser drops to spectators and has to queue again.
+    LoserLeaves,
+}
+
+impl Rotation {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Rotation::WinnerStays,
+            2 => Rotation::LoserLeaves,
+            _ => Rotation::Off,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rotation::Off => "off",
+            Rotation::WinnerStays => "winner stays",
+            Rotation::LoserLeaves => "loser leaves",
+        }
+    }
+}
+
+/// Spectators waiting for a slot in a player-limited gametype, first come
+/// first served.
+#[derive(Clone, Debug)]
+pub struct DuelQueue {
+    pub rotation: Rotation,
+    waiting: Vec<u16>,
+}
+
+impl Default for DuelQueue {
+    fn default() -> Self {
+        Self::new(Rotation::WinnerStays)
+    }
+}
+
+impl DuelQueue {
+    pub fn new(rotation: Rotation) -> Self {
+        Self {
+            rotation,
+            waiting: Vec::new(),
+        }
+    }
+
+    /// Reads `g_duelqueue`: 0 off, 1 winner stays, 2 loser leaves.
+    pub fn from_cvar() -> Self {
+        let value = crate::cvar::get_cvar_integer("g_duelqueue").clamp(0, 2) as u8;
+        Self::new(Rotation::from_u8(value))
+    }
+
+    pub fn waiting(&self) -> &[u16] {
+        &self.waiting
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waiting.is_empty()
+    }
+
+    /// 1-based place in line, None when not queued.
+    pub fn position(&self, player_id: u16) -> Option<usize> {
+        self.waiting
+            .iter()
+            .position(|&id| id == player_id)
+            .map(|idx| idx + 1)
+    }
+
+    /// Queues the player at the back and returns their place in line.
+    pub fn join(&mut self, player_id: u16) -> usize {
+        if let Some(position) = self.position(player_id) {
+            return position;
+        }
+        self.waiting.push(player_id);
+        self.waiting.len()
+    }
+
+    pub fn leave(&mut self, player_id: u16) -> bool {
+        let before = self.waiting.len();
+        self.waiting.retain(|&id| id != player_id);
+        self.waiting.len() != before
+    }
+
+    /// Takes the next player in line for an open slot.
+    pub fn pop_next(&mut self) -> Option<u16> {
+        if self.waiting.is_empty() {
+            None
+        } else {
+            Some(self.waiting.remove(0))
+        }
+    }
+
+    /// Applies the rotation after `loser` lost; returns the challenger who
+    /// takes their slot, or None when the loser keeps playing.
+    pub fn finish_match(&mut self, loser: u16) -> Option<u16> {
+        if self.rotation == Rotation::Off || self.waiting.is_empty() {
+            return None;
+        }
+        let challenger = self.pop_next();
+        if self.rotation == Rotation::WinnerStays {
+            self.join(loser);
+        }
+        challenger
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_winner_stays_requeues_loser() {
+        let mut queue = DuelQueue::new(Rotation::WinnerStays);
+        assert_eq!(queue.finish_match(2), None);
+        assert_eq!(queue.join(3), 1);
+        assert_eq!(queue.join(4), 2);
+        assert_eq!(queue.join(3), 1);
+
+        assert_eq!(queue.finish_match(2), Some(3));
+        assert_eq!(queue.waiting(), &[4, 2]);
+        assert_eq!(queue.finish_match(1), Some(4));
+        assert_eq!(queue.waiting(), &[2, 1]);
+    }
+
+    #[test]
+    fn test_loser_leaves_and_off() {
+        let mut queue = DuelQueue::new(Rotation::LoserLeaves);
+        queue.join(3);
+        assert_eq!(queue.finish_match(2), Some(3));
+        assert!(queue.is_empty());
+        assert_eq!(queue.position(2), None);
+
+        let mut queue = DuelQueue::new(Rotation::Off);
+        queue.join(3);
+        assert_eq!(queue.finish_match(2), None);
+        assert!(queue.leave(3));
+        assert!(!queue.leave(3));
+    }
+}
@@ -0,0 +1,118 @@
+use super::gametype::TEAM_FREE;
+
+pub const THAW_TIME: f32 = 3.0;
+pub const THAW_RADIUS: f32 = 48.0;
+/// Progress lost per second while nobody is standing next to a frozen player.
+pub const THAW_DECAY: f32 = 0.25;
+
+pub struct FreezePlayer {
+    pub id: u16,
+    pub team: u8,
+    pub x: f32,
+    pub y: f32,
+    pub frozen: bool,
+    pub alive: bool,
+    pub thaw_progress: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThawEvent {
+    pub player_id: u16,
+    pub thawed_by: u16,
+}
+
+fn in_thaw_range(a: &FreezePlayer, b: &FreezePlayer) -> bool {
+    (a.x - b.x).powi(2) + (a.y - b.y).powi(2) < THAW_RADIUS * THAW_RADIUS
+}
+
+/// Advances thaw progress in place and reports the players that thawed out.
+pub fn update_thaw(dt: f32, players: &mut [FreezePlayer]) -> Vec<ThawEvent> {
+    let mut events = Vec::new();
+
+    for i in 0..players.len() {
+        if !players[i].frozen {
+            continue;
+        }
+
+        let target = &players[i];
+        let thawer = players.iter().find(|p| {
+            p.alive
+                && p.id != target.id
+                && p.team == target.team
+                && p.team != TEAM_FREE
+                && in_thaw_range(p, target)
+        });
+
+        match thawer.map(|p| p.id) {
+            Some(thawed_by) => {
+                players[i].thaw_progress += dt / THAW_TIME;
+                if players[i].thaw_progress >= 1.0 {
+                    players[i].thaw_progress = 0.0;
+                    players[i].frozen = false;
+                    events.push(ThawEvent {
+                        player_id: players[i].id,
+                        thawed_by,
+                    });
+                }
+            }
+            None => {
+                players[i].thaw_progress = (players[i].thaw_progress - dt * THAW_DECAY).max(0.0);
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::gametype::{TEAM_BLUE, TEAM_RED};
+
+    fn player(id: u16, team: u8, x: f32, frozen: bool) -> FreezePlayer {
+        FreezePlayer {
+            id,
+            team,
+            x,
+            y: 0.0,
+            frozen,
+            alive: !frozen,
+            thaw_progress: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_teammate_thaws_over_time() {
+        let mut players = vec![
+            player(1, TEAM_RED, 0.0, true),
+            player(2, TEAM_RED, 20.0, false),
+        ];
+
+        assert!(update_thaw(1.0, &mut players).is_empty());
+        assert!((players[0].thaw_progress - 1.0 / THAW_TIME).abs() < 0.001);
+
+        let events = update_thaw(THAW_TIME, &mut players);
+        assert_eq!(
+            events,
+            vec![ThawEvent {
+                player_id: 1,
+                thawed_by: 2
+            }]
+        );
+        assert!(!players[0].frozen);
+    }
+
+    #[test]
+    fn test_enemies_and_distance_do_not_thaw() {
+        let mut players = vec![
+            player(1, TEAM_RED, 0.0, true),
+            player(2, TEAM_BLUE, 10.0, false),
+            player(3, TEAM_RED, 500.0, false),
+        ];
+        players[0].thaw_progress = 0.5;
+
+        assert!(update_thaw(1.0, &mut players).is_empty());
+        assert!((players[0].thaw_progress - (0.5 - THAW_DECAY)).abs() < 0.001);
+        assert!(players[0].frozen);
+    }
+}
@@ -0,0 +1,840 @@
+use super::map::SpawnPoint;
+use super::weapon::{Weapon, WEAPON_COUNT};
+
+pub const TEAM_FREE: u8 = 0;
+pub const TEAM_RED: u8 = 1;
+pub const TEAM_BLUE: u8 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GametypeKind {
+    FreeForAll = 0,
+    Duel = 1,
+    TeamDeathmatch = 3,
+    CaptureTheFlag = 4,
+    ClanArena = 5,
+    FreezeTag = 6,
+}
+
+impl GametypeKind {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::FreeForAll),
+            1 => Some(Self::Duel),
+            3 => Some(Self::TeamDeathmatch),
+            4 => Some(Self::CaptureTheFlag),
+            5 => Some(Self::ClanArena),
+            6 => Some(Self::FreezeTag),
+            _ => None,
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "ffa" | "dm" => Some(Self::FreeForAll),
+            "duel" | "1v1" | "tourney" => Some(Self::Duel),
+            "tdm" | "team" => Some(Self::TeamDeathmatch),
+            "ctf" => Some(Self::CaptureTheFlag),
+            "ca" | "clanarena" => Some(Self::ClanArena),
+            "ft" | "freeze" | "freezetag" => Some(Self::FreezeTag),
+            other => other.parse::<u8>().ok().and_then(Self::from_u8),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FreeForAll => "Free For All",
+            Self::Duel => "Duel",
+            Self::TeamDeathmatch => "Team Deathmatch",
+            Self::CaptureTheFlag => "Capture the Flag",
+            Self::ClanArena => "Clan Arena",
+            Self::FreezeTag => "Freeze Tag",
+        }
+    }
+
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Self::FreeForAll => "ffa",
+            Self::Duel => "duel",
+            Self::TeamDeathmatch => "tdm",
+            Self::CaptureTheFlag => "ctf",
+            Self::ClanArena => "ca",
+            Self::FreezeTag => "ft",
+        }
+    }
+}
+
+pub fn team_name(team: u8) -> &'static str {
+    match team {
+        TEAM_RED => "Red",
+        TEAM_BLUE => "Blue",
+        _ => "Free",
+    }
+}
+
+pub fn other_team(team: u8) -> u8 {
+    match team {
+        TEAM_RED => TEAM_BLUE,
+        TEAM_BLUE => TEAM_RED,
+        _ => TEAM_FREE,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreEntry {
+    pub id: u16,
+    pub team: u8,
+    pub frags: i32,
+}
+
+impl ScoreEntry {
+    pub fn from_players(players: &[super::player::Player]) -> Vec<Self> {
+        players
+            .iter()
+            .map(|p| Self {
+                id: p.id,
+                team: p.team,
+                frags: p.frags,
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TeamScores {
+    pub red: i32,
+    pub blue: i32,
+}
+
+impl TeamScores {
+    pub fn from_entries(entries: &[ScoreEntry]) -> Self {
+        let mut scores = Self::default();
+        for entry in entries {
+            scores.add(entry.team, entry.frags);
+        }
+        scores
+    }
+
+    pub fn get(&self, team: u8) -> i32 {
+        match team {
+            TEAM_RED => self.red,
+            TEAM_BLUE => self.blue,
+            _ => 0,
+        }
+    }
+
+    pub fn add(&mut self, team: u8, delta: i32) {
+        match team {
+            TEAM_RED => self.red += delta,
+            TEAM_BLUE => self.blue += delta,
+            _ => {}
+        }
+    }
+
+    pub fn leader(&self) -> Option<u8> {
+        if self.red > self.blue {
+            Some(TEAM_RED)
+        } else if self.blue > self.red {
+            Some(TEAM_BLUE)
+        } else {
+            None
+        }
+    }
+}
+
+/// Health, armor and ammo handed out on every spawn, replacing the default kit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Loadout {
+    pub health: i32,
+    pub armor: i32,
+    pub ammo: [u8; WEAPON_COUNT],
+    pub gauntlet: bool,
+    pub weapon: Weapon,
+    pub quad: bool,
+}
+
+impl Loadout {
+    /// The kit `Player::spawn` hands out when the gametype has no loadout.
+    pub fn standard() -> Self {
+        Self {
+            health: super::constants::STARTING_HEALTH,
+            armor: 0,
+            ammo: [0, 100, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            gauntlet: true,
+            weapon: Weapon::MachineGun,
+            quad: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MatchWinner {
+    Player(u16),
+    Team(u8),
+    Draw,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScoreboardLayout {
+    FreeForAll,
+    Duel,
+    Teams,
+}
+
+pub trait Gametype: Send {
+    fn kind(&self) -> GametypeKind;
+
+    fn is_team_game(&self) -> bool {
+        false
+    }
+
+    fn max_players(&self) -> Option<usize> {
+        None
+    }
+
+    /// Team score comes from flag captures instead of frags.
+    fn uses_flags(&self) -> bool {
+        false
+    }
+
+    /// Rounds end when a team is wiped out; dead players wait for the next round.
+    fn is_round_based(&self) -> bool {
+        false
+    }
+
+    /// Fragged players freeze in place until a teammate thaws them.
+    fn freezes_on_death(&self) -> bool {
+        false
+    }
+
+    fn items_enabled(&self) -> bool {
+        true
+    }
+
+    fn spawn_loadout(&self) -> Option<Loadout> {
+        None
+    }
+
+    fn time_limit(&self) -> f32 {
+        600.0
+    }
+
+    fn pick_team(&self, _players: &[ScoreEntry]) -> u8 {
+        TEAM_FREE
+    }
+
+    /// Frag delta for the killer, or for the victim on a suicide.
+    fn kill_score(&self, killer: &ScoreEntry, victim: &ScoreEntry) -> i32 {
+        if killer.id == victim.id {
+            -1
+        } else {
+            1
+        }
+    }
+
+    fn allows_damage(&self, _attacker_team: u8, _target_team: u8, _is_self: bool) -> bool {
+        true
+    }
+
+    fn select_spawn(&self, spawn_points: &[SpawnPoint], query: &SpawnQuery) -> Option<usize> {
+        pick_spawn(spawn_points, (0..spawn_points.len()).collect(), query)
+    }
+
+    fn is_match_over(&self, match_time: f32, time_limit: f32) -> bool {
+        time_limit > 0.0 && match_time >= time_limit
+    }
+
+    fn winner(&self, players: &[ScoreEntry], _team_scores: &TeamScores) -> MatchWinner {
+        top_player(players)
+    }
+
+    fn scoreboard_layout(&self) -> ScoreboardLayout {
+        ScoreboardLayout::FreeForAll
+    }
+
+    /// Team rule sets announce team lead changes instead of the personal lead.
+    fn team_lead_announcement(
+        &self,
+        _before: &TeamScores,
+        _after: &TeamScores,
+    ) -> Option<&'static str> {
+        None
+    }
+}
+
+fn top_player(players: &[ScoreEntry]) -> MatchWinner {
+    let Some(top) = players.iter().map(|p| p.frags).max() else {
+        return MatchWinner::Draw;
+    };
+    let mut leaders = players.iter().filter(|p| p.frags == top);
+    match (leaders.next(), leaders.next()) {
+        (Some(leader), None) => MatchWinner::Player(leader.id),
+        _ => MatchWinner::Draw,
+    }
+}
+
+/// Who a spawn point is being picked for.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpawnQuery<'a> {
+    pub team: u8,
+    /// Living enemies to keep away from.
+    pub enemies: &'a [(f32, f32)],
+    /// Everyone alive; spawning on top of them is a telefrag.
+    pub occupied: &'a [(f32, f32)],
+    /// Where the player died, so they don't reappear on the same spot.
+    pub avoid: Option<(f32, f32)>,
+    pub roll: usize,
+}
+
+/// Within this distance of the death spot a spawn counts as the same one.
+const SPAWN_AVOID_RADIUS: f32 = 64.0;
+
+/// Whether a player standing at `a` and one at `b` overlap.
+pub fn bodies_overlap(a: (f32, f32), b: (f32, f32)) -> bool {
+    use super::constants::{PLAYER_HITBOX_HEIGHT, PLAYER_HITBOX_WIDTH};
+    (a.0 - b.0).abs() < PLAYER_HITBOX_WIDTH && (a.1 - b.1).abs() < PLAYER_HITBOX_HEIGHT
+}
+
+/// Q3's pick: drop spawns that would telefrag or that the player just died
+/// at (unless nothing else is left), then roll among the half farthest from
+/// living enemies.
+fn pick_spawn(
+    spawn_points: &[SpawnPoint],
+    mut candidates: Vec<usize>,
+    query: &SpawnQuery,
+) -> Option<usize> {
+    let pos = |idx: usize| (spawn_points[idx].x, spawn_points[idx].y);
+    let keep_if_any = |candidates: &mut Vec<usize>, keep: &dyn Fn(usize) -> bool| {
+        if candidates.iter().any(|&idx| keep(idx)) {
+            candidates.retain(|&idx| keep(idx));
+        }
+    };
+    keep_if_any(&mut candidates, &|idx| {
+        !query.occupied.iter().any(|&p| bodies_overlap(pos(idx), p))
+    });
+    if let Some((ax, ay)) = query.avoid {
+        keep_if_any(&mut candidates, &|idx| {
+            let (x, y) = pos(idx);
+            (x - ax).powi(2) + (y - ay).powi(2) > SPAWN_AVOID_RADIUS * SPAWN_AVOID_RADIUS
+        });
+    }
+    if candidates.is_empty() {
+        return None;
+    }
+    if query.enemies.is_empty() {
+        return Some(candidates[query.roll % candidates.len()]);
+    }
+
+    let nearest_enemy = |idx: usize| {
+        let (x, y) = pos(idx);
+        query
+            .enemies
+            .iter()
+            .map(|(ex, ey)| (x - ex).powi(2) + (y - ey).powi(2))
+            .fold(f32::MAX, f32::min)
+    };
+    candidates.sort_by(|&a, &b| nearest_enemy(b).total_cmp(&nearest_enemy(a)));
+    let farthest = candidates.len().div_ceil(2);
+    Some(candidates[query.roll % farthest])
+}
+
+pub struct FreeForAll;
+
+impl Gametype for FreeForAll {
+    fn kind(&self) -> GametypeKind {
+        GametypeKind::FreeForAll
+    }
+}
+
+pub struct Duel;
+
+impl Gametype for Duel {
+    fn kind(&self) -> GametypeKind {
+        GametypeKind::Duel
+    }
+
+    fn max_players(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn scoreboard_layout(&self) -> ScoreboardLayout {
+        ScoreboardLayout::Duel
+    }
+}
+
+/// Teammates are shielded from each other here; whether their hits land
+/// anyway is up to `DamageRules::friendly_fire`.
+#[derive(Default)]
+pub struct TeamDeathmatch;
+
+impl Gametype for TeamDeathmatch {
+    fn kind(&self) -> GametypeKind {
+        GametypeKind::TeamDeathmatch
+    }
+
+    fn is_team_game(&self) -> bool {
+        true
+    }
+
+    fn pick_team(&self, players: &[ScoreEntry]) -> u8 {
+        let red = players.iter().filter(|p| p.team == TEAM_RED).count();
+        let blue = players.iter().filter(|p| p.team == TEAM_BLUE).count();
+        if blue < red {
+            TEAM_BLUE
+        } else {
+            TEAM_RED
+        }
+    }
+
+    fn kill_score(&self, killer: &ScoreEntry, victim: &ScoreEntry) -> i32 {
+        if killer.id == victim.id || (killer.team != TEAM_FREE && killer.team == victim.team) {
+            -1
+        } else {
+            1
+        }
+    }
+
+    fn allows_damage(&self, attacker_team: u8, target_team: u8, is_self: bool) -> bool {
+        is_self || attacker_team == TEAM_FREE || attacker_team != target_team
+    }
+
+    fn select_spawn(&self, spawn_points: &[SpawnPoint], query: &SpawnQuery) -> Option<usize> {
+        let team_spawns: Vec<usize> = (0..spawn_points.len())
+            .filter(|&i| spawn_points[i].team == query.team)
+            .collect();
+        if team_spawns.is_empty() {
+            return pick_spawn(spawn_points, (0..spawn_points.len()).collect(), query);
+        }
+        pick_spawn(spawn_points, team_spawns, query)
+    }
+
+    fn winner(&self, _players: &[ScoreEntry], team_scores: &TeamScores) -> MatchWinner {
+        match team_scores.leader() {
+            Some(team) => MatchWinner::Team(team),
+            None => MatchWinner::Draw,
+        }
+    }
+
+    fn scoreboard_layout(&self) -> ScoreboardLayout {
+        ScoreboardLayout::Teams
+    }
+
+    fn team_lead_announcement(
+        &self,
+        before: &TeamScores,
+        after: &TeamScores,
+    ) -> Option<&'static str> {
+        if before.leader() == after.leader() {
+            return None;
+        }
+        Some(match after.leader() {
+            Some(TEAM_RED) => "red_leads",
+            Some(_) => "blue_leads",
+            None => "teams_tied",
+        })
+    }
+}
+
+/// Team rules as in team deathmatch, but scored by flag captures.
+#[derive(Default)]
+pub struct CaptureTheFlag {
+    pub teams: TeamDeathmatch,
+}
+
+impl Gametype for CaptureTheFlag {
+    fn kind(&self) -> GametypeKind {
+        GametypeKind::CaptureTheFlag
+    }
+
+    fn is_team_game(&self) -> bool {
+        true
+    }
+
+    fn uses_flags(&self) -> bool {
+        true
+    }
+
+    fn pick_team(&self, players: &[ScoreEntry]) -> u8 {
+        self.teams.pick_team(players)
+    }
+
+    fn kill_score(&self, killer: &ScoreEntry, victim: &ScoreEntry) -> i32 {
+        self.teams.kill_score(killer, victim)
+    }
+
+    fn allows_damage(&self, attacker_team: u8, target_team: u8, is_self: bool) -> bool {
+        self.teams
+            .allows_damage(attacker_team, target_team, is_self)
+    }
+
+    fn select_spawn(&self, spawn_points: &[SpawnPoint], query: &SpawnQuery) -> Option<usize> {
+        self.teams.select_spawn(spawn_points, query)
+    }
+
+    fn winner(&self, players: &[ScoreEntry], team_scores: &TeamScores) -> MatchWinner {
+        self.teams.winner(players, team_scores)
+    }
+
+    fn scoreboard_layout(&self) -> ScoreboardLayout {
+        ScoreboardLayout::Teams
+    }
+}
+
+/// Round-based team elimination: full stack on spawn, no items, no self damage.
+#[derive(Default)]
+pub struct ClanArena {
+    pub teams: TeamDeathmatch,
+}
+
+impl Gametype for ClanArena {
+    fn kind(&self) -> GametypeKind {
+        GametypeKind::ClanArena
+    }
+
+    fn is_team_game(&self) -> bool {
+        true
+    }
+
+    fn is_round_based(&self) -> bool {
+        true
+    }
+
+    fn items_enabled(&self) -> bool {
+        false
+    }
+
+    fn spawn_loadout(&self) -> Option<Loadout> {
+        Some(Loadout {
+            health: 200,
+            armor: 100,
+            ammo: [0, 100, 25, 25, 25, 150, 25, 100, 0, 0, 0, 0],
+            gauntlet: true,
+            weapon: Weapon::RocketLauncher,
+            quad: false,
+        })
+    }
+
+    fn time_limit(&self) -> f32 {
+        0.0
+    }
+
+    fn pick_team(&self, players: &[ScoreEntry]) -> u8 {
+        self.teams.pick_team(players)
+    }
+
+    fn kill_score(&self, killer: &ScoreEntry, victim: &ScoreEntry) -> i32 {
+        self.teams.kill_score(killer, victim)
+    }
+
+    fn allows_damage(&self, attacker_team: u8, target_team: u8, is_self: bool) -> bool {
+        !is_self
+            && self
+                .teams
+                .allows_damage(attacker_team, target_team, is_self)
+    }
+
+    fn select_spawn(&self, spawn_points: &[SpawnPoint], query: &SpawnQuery) -> Option<usize> {
+        self.teams.select_spawn(spawn_points, query)
+    }
+
+    fn winner(&self, players: &[ScoreEntry], team_scores: &TeamScores) -> MatchWinner {
+        self.teams.winner(players, team_scores)
+    }
+
+    fn scoreboard_layout(&self) -> ScoreboardLayout {
+        ScoreboardLayout::Teams
+    }
+}
+
+/// Team rounds where frags freeze instead of kill; a round ends when one team is all frozen.
+#[derive(Default)]
+pub struct FreezeTag {
+    pub teams: TeamDeathmatch,
+}
+
+impl Gametype for FreezeTag {
+    fn kind(&self) -> GametypeKind {
+        GametypeKind::FreezeTag
+    }
+
+    fn is_team_game(&self) -> bool {
+        true
+    }
+
+    fn is_round_based(&self) -> bool {
+        true
+    }
+
+    fn freezes_on_death(&self) -> bool {
+        true
+    }
+
+    fn time_limit(&self) -> f32 {
+        0.0
+    }
+
+    fn pick_team(&self, players: &[ScoreEntry]) -> u8 {
+        self.teams.pick_team(players)
+    }
+
+    fn kill_score(&self, killer: &ScoreEntry, victim: &ScoreEntry) -> i32 {
+        self.teams.kill_score(killer, victim)
+    }
+
+    fn allows_damage(&self, attacker_team: u8, target_team: u8, is_self: bool) -> bool {
+        self.teams
+            .allows_damage(attacker_team, target_team, is_self)
+    }
+
+    fn select_spawn(&self, spawn_points: &[SpawnPoint], query: &SpawnQuery) -> Option<usize> {
+        self.teams.select_spawn(spawn_points, query)
+    }
+
+    fn winner(&self, players: &[ScoreEntry], team_scores: &TeamScores) -> MatchWinner {
+        self.teams.winner(players, team_scores)
+    }
+
+    fn scoreboard_layout(&self) -> ScoreboardLayout {
+        ScoreboardLayout::Teams
+    }
+}
+
+pub fn create(kind: GametypeKind) -> Box<dyn Gametype> {
+    match kind {
+        GametypeKind::FreeForAll => Box::new(FreeForAll),
+        GametypeKind::Duel => Box::new(Duel),
+        GametypeKind::TeamDeathmatch => Box::new(TeamDeathmatch),
+        GametypeKind::CaptureTheFlag => Box::new(CaptureTheFlag::default()),
+        GametypeKind::ClanArena => Box::new(ClanArena::default()),
+        GametypeKind::FreezeTag => Box::new(FreezeTag::default()),
+    }
+}
+
+/// Reads `g_gametype`, falling back to free-for-all for unknown values.
+pub fn from_cvar() -> Box<dyn Gametype> {
+    let value = crate::cvar::get_cvar_string("g_gametype");
+    create(GametypeKind::parse(&value).unwrap_or(GametypeKind::FreeForAll))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u16, team: u8, frags: i32) -> ScoreEntry {
+        ScoreEntry { id, team, frags }
+    }
+
+    fn spawn(x: f32, team: u8) -> SpawnPoint {
+        SpawnPoint { x, y: 0.0, team }
+    }
+
+    fn query(team: u8, enemies: &[(f32, f32)], roll: usize) -> SpawnQuery<'_> {
+        SpawnQuery {
+            team,
+            enemies,
+            roll,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_kind() {
+        assert_eq!(
+            GametypeKind::parse("tdm"),
+            Some(GametypeKind::TeamDeathmatch)
+        );
+        assert_eq!(GametypeKind::parse("1"), Some(GametypeKind::Duel));
+        assert_eq!(GametypeKind::parse("0"), Some(GametypeKind::FreeForAll));
+        assert_eq!(GametypeKind::parse("2"), None);
+        assert_eq!(GametypeKind::from_u8(3), Some(GametypeKind::TeamDeathmatch));
+        assert_eq!(
+            GametypeKind::parse("ctf"),
+            Some(GametypeKind::CaptureTheFlag)
+        );
+        assert!(create(GametypeKind::CaptureTheFlag).uses_flags());
+        assert_eq!(GametypeKind::parse("ca"), Some(GametypeKind::ClanArena));
+    }
+
+    #[test]
+    fn test_clan_arena_rules() {
+        let ca = create(GametypeKind::ClanArena);
+        assert!(ca.is_team_game());
+        assert!(ca.is_round_based());
+        assert!(!ca.items_enabled());
+        assert!(!ca.allows_damage(TEAM_RED, TEAM_RED, true));
+        assert!(!ca.allows_damage(TEAM_RED, TEAM_RED, false));
+        assert!(ca.allows_damage(TEAM_RED, TEAM_BLUE, false));
+
+        let loadout = ca.spawn_loadout().unwrap();
+        assert_eq!(loadout.health, 200);
+        assert!(loadout.ammo[1..8].iter().all(|&a| a > 0));
+        assert!(create(GametypeKind::TeamDeathmatch)
+            .spawn_loadout()
+            .is_none());
+
+        let ft = create(GametypeKind::FreezeTag);
+        assert_eq!(GametypeKind::parse("freeze"), Some(GametypeKind::FreezeTag));
+        assert!(ft.is_round_based() && ft.freezes_on_death());
+        assert!(!ca.freezes_on_death());
+    }
+
+    #[test]
+    fn test_ffa_scoring_and_winner() {
+        let ffa = create(GametypeKind::FreeForAll);
+        let a = entry(1, TEAM_FREE, 0);
+        let b = entry(2, TEAM_FREE, 0);
+        assert_eq!(ffa.kill_score(&a, &b), 1);
+        assert_eq!(ffa.kill_score(&a, &a), -1);
+        assert!(ffa.allows_damage(TEAM_FREE, TEAM_FREE, false));
+
+        let scores = TeamScores::default();
+        assert_eq!(
+            ffa.winner(&[entry(1, 0, 5), entry(2, 0, 7)], &scores),
+            MatchWinner::Player(2)
+        );
+        assert_eq!(
+            ffa.winner(&[entry(1, 0, 7), entry(2, 0, 7)], &scores),
+            MatchWinner::Draw
+        );
+    }
+
+    #[test]
+    fn test_tdm_teams_and_team_damage() {
+        let tdm = create(GametypeKind::TeamDeathmatch);
+        let players = vec![
+            entry(1, TEAM_RED, 0),
+            entry(2, TEAM_BLUE, 0),
+            entry(3, TEAM_RED, 0),
+        ];
+        assert_eq!(tdm.pick_team(&players), TEAM_BLUE);
+        assert_eq!(tdm.pick_team(&players[..2]), TEAM_RED);
+
+        assert_eq!(tdm.kill_score(&players[0], &players[1]), 1);
+        assert_eq!(tdm.kill_score(&players[0], &players[2]), -1);
+        assert!(!tdm.allows_damage(TEAM_RED, TEAM_RED, false));
+        assert!(tdm.allows_damage(TEAM_RED, TEAM_RED, true));
+        assert!(tdm.allows_damage(TEAM_RED, TEAM_BLUE, false));
+    }
+
+    #[test]
+    fn test_tdm_winner_and_announcements() {
+        let tdm = create(GametypeKind::TeamDeathmatch);
+        let tied = TeamScores { red: 3, blue: 3 };
+        let red = TeamScores { red: 4, blue: 3 };
+        assert_eq!(tdm.winner(&[], &red), MatchWinner::Team(TEAM_RED));
+        assert_eq!(tdm.winner(&[], &tied), MatchWinner::Draw);
+        assert_eq!(tdm.team_lead_announcement(&tied, &red), Some("red_leads"));
+        assert_eq!(tdm.team_lead_announcement(&red, &tied), Some("teams_tied"));
+        assert_eq!(tdm.team_lead_announcement(&red, &red), None);
+
+        let entries = [
+            entry(1, TEAM_RED, 4),
+            entry(2, TEAM_BLUE, 2),
+            entry(3, TEAM_BLUE, 3),
+        ];
+        assert_eq!(
+            TeamScores::from_entries(&entries),
+            TeamScores { red: 4, blue: 5 }
+        );
+    }
+
+    #[test]
+    fn test_spawn_selection() {
+        let spawns = vec![
+            spawn(0.0, TEAM_RED),
+            spawn(500.0, TEAM_BLUE),
+            spawn(1000.0, 0),
+        ];
+
+        let duel = create(GametypeKind::Duel);
+        assert_eq!(duel.max_players(), Some(2));
+        assert_eq!(
+            duel.select_spawn(&spawns, &query(TEAM_FREE, &[(100.0, 0.0)], 0)),
+            Some(2)
+        );
+        assert_eq!(
+            duel.select_spawn(&spawns, &query(TEAM_FREE, &[(900.0, 0.0)], 0)),
+            Some(0)
+        );
+
+        let tdm = create(GametypeKind::TeamDeathmatch);
+        assert_eq!(
+            tdm.select_spawn(&spawns, &query(TEAM_BLUE, &[(500.0, 0.0)], 7)),
+            Some(1)
+        );
+
+        let ffa = create(GametypeKind::FreeForAll);
+        assert_eq!(
+            ffa.select_spawn(&spawns, &query(TEAM_FREE, &[], 4)),
+            Some(1)
+        );
+        assert_eq!(ffa.select_spawn(&[], &query(TEAM_FREE, &[], 4)), None);
+    }
+
+    #[test]
+    fn test_spawn_selection_eight_players() {
+        // Eight spawns 200 apart, alternating red and blue.
+        let spawns: Vec<SpawnPoint> = (0..8)
+            .map(|i| {
+                spawn(
+                    i as f32 * 200.0,
+                    if i % 2 == 0 { TEAM_RED } else { TEAM_BLUE },
+                )
+            })
+            .collect();
+        let picks = |gametype: &dyn Gametype, query: SpawnQuery| -> Vec<usize> {
+            (0..16)
+                .filter_map(|roll| gametype.select_spawn(&spawns, &SpawnQuery { roll, ..query }))
+                .collect()
+        };
+        let ffa = create(GametypeKind::FreeForAll);
+
+        // Enemies camp the left end: only the far half is ever picked, all of it.
+        let enemies = [(0.0, 0.0), (100.0, 0.0), (250.0, 0.0)];
+        let mut far = picks(ffa.as_ref(), query(TEAM_FREE, &enemies, 0));
+        far.sort();
+        far.dedup();
+        assert_eq!(far, vec![4, 5, 6, 7]);
+
+        // Seven players stand on seven spawns: the free one is the only choice,
+        // even though it's right next to an enemy.
+        let others: Vec<(f32, f32)> = (0..8)
+            .filter(|&i| i != 1)
+            .map(|i| (i as f32 * 200.0, 8.0))
+            .collect();
+        let crowded = SpawnQuery {
+            occupied: &others,
+            ..query(TEAM_FREE, &others, 0)
+        };
+        assert!(picks(ffa.as_ref(), crowded).iter().all(|&idx| idx == 1));
+
+        // Never straight back onto the spawn we died at.
+        let died_here = SpawnQuery {
+            avoid: Some((600.0, 8.0)),
+            ..query(TEAM_FREE, &[], 0)
+        };
+        let picked = picks(ffa.as_ref(), died_here);
+        assert!(!picked.contains(&3));
+        assert_eq!(picked.len(), 16);
+
+        // Team modes keep to their own side's spawns.
+        let tdm = create(GametypeKind::TeamDeathmatch);
+        assert!(picks(tdm.as_ref(), query(TEAM_BLUE, &enemies, 0))
+            .iter()
+            .all(|&idx| spawns[idx].team == TEAM_BLUE));
+        let ctf = create(GametypeKind::CaptureTheFlag);
+        assert!(picks(ctf.as_ref(), query(TEAM_RED, &[], 0))
+            .iter()
+            .all(|&idx| spawns[idx].team == TEAM_RED));
+
+        // With every spawn taken someone has to be telefragged.
+        let everyone: Vec<(f32, f32)> = (0..8).map(|i| (i as f32 * 200.0, 8.0)).collect();
+        let full = SpawnQuery {
+            occupied: &everyone,
+            ..query(TEAM_FREE, &everyone, 0)
+        };
+        let idx = ffa.select_spawn(&spawns, &full).unwrap();
+        let spawn = (spawns[idx].x, spawns[idx].y);
+        assert!(everyone.iter().any(|&p| bodies_overlap(spawn, p)));
+        assert!(!bodies_overlap((0.0, 8.0), (200.0, 8.0)));
+    }
+}
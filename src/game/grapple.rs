@@ -0,0 +1,203 @@
+use super::map::Map;
+use serde::{Deserialize, Serialize};
+
+/// Hook travel per 60 Hz frame.
+pub const GRAPPLE_SPEED: f32 = 24.0;
+/// The hook is lost if it flies further than this without catching.
+pub const GRAPPLE_MAX_LENGTH: f32 = 640.0;
+/// The cable leaves the player this far above the origin, level with the guns.
+pub const GRAPPLE_ORIGIN_Y: f32 = -24.0;
+/// Within this distance of the anchor the pull eases off so the player hangs
+/// instead of bouncing around the hook.
+pub const GRAPPLE_SLOW_RADIUS: f32 = 48.0;
+pub const GRAPPLE_MAX_SPEED: f32 = 10.0;
+
+const STEP: f32 = 4.0;
+
+/// Offhand grappling hook. Part of the replicated player state so clients can
+/// predict the pull and draw the cable.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Grapple {
+    #[default]
+    Idle,
+    Flying {
+        x: f32,
+        y: f32,
+        vel_x: f32,
+        vel_y: f32,
+    },
+    Attached {
+        x: f32,
+        y: f32,
+    },
+    /// Missed; nothing more happens until the button is let go.
+    Retracted,
+}
+
+/// The pull set by g_grapple and g_grapplepull, 0 when the hook is off.
+pub fn pull_from_cvars() -> f32 {
+    if crate::cvar::get_cvar_bool("g_grapple") {
+        crate::cvar::get_cvar_float("g_grapplepull").max(0.0)
+    } else {
+        0.0
+    }
+}
+
+impl Grapple {
+    /// Where the hook end is while it is out.
+    pub fn hook_pos(&self) -> Option<(f32, f32)> {
+        match *self {
+            Grapple::Flying { x, y, .. } | Grapple::Attached { x, y } => Some((x, y)),
+            _ => None,
+        }
+    }
+
+    /// The point pmove pulls towards.
+    pub fn anchor(&self) -> Option<(f32, f32)> {
+        match *self {
+            Grapple::Attached { x, y } => Some((x, y)),
+            _ => None,
+        }
+    }
+
+    /// Runs the hook for one command. Holding the button fires it from idle and
+    /// keeps it out; letting go releases it. Callers pass `held` as false while
+    /// the profile's `grapple_pull` is 0.
+    pub fn update(
+        &mut self,
+        held: bool,
+        angle: f32,
+        owner_x: f32,
+        owner_y: f32,
+        dt: f32,
+        map: &Map,
+    ) {
+        if !held {
+            *self = Grapple::Idle;
+            return;
+        }
+
+        let origin_y = owner_y + GRAPPLE_ORIGIN_Y;
+        if *self == Grapple::Idle {
+            *self = Grapple::Flying {
+                x: owner_x,
+                y: origin_y,
+                vel_x: angle.cos() * GRAPPLE_SPEED,
+                vel_y: angle.sin() * GRAPPLE_SPEED,
+            };
+        }
+
+        let Grapple::Flying { x, y, vel_x, vel_y } = *self else {
+            return;
+        };
+        let dt_norm = dt.min(0.05) * 60.0;
+        let travel = (vel_x * vel_x + vel_y * vel_y).sqrt() * dt_norm;
+        let steps = (travel / STEP).ceil().max(1.0) as usize;
+        let (step_x, step_y) = (
+            vel_x * dt_norm / steps as f32,
+            vel_y * dt_norm / steps as f32,
+        );
+
+        let (mut hx, mut hy) = (x, y);
+        for _ in 0..steps {
+            let (nx, ny) = (hx + step_x, hy + step_y);
+            if map.is_solid((nx / 32.0).floor() as i32, (ny / 16.0).floor() as i32) {
+                *self = Grapple::Attached { x: hx, y: hy };
+                return;
+            }
+            hx = nx;
+            hy = ny;
+        }
+
+        let (dx, dy) = (hx - owner_x, hy - origin_y);
+        *self = if (dx * dx + dy * dy).sqrt() > GRAPPLE_MAX_LENGTH {
+            Grapple::Retracted
+        } else {
+            Grapple::Flying {
+                x: hx,
+                y: hy,
+                vel_x,
+                vel_y,
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bg_pmove::{pmove, PmoveCmd, PmoveState};
+    use crate::game::physics::PhysicsProfile;
+
+    fn walled_map() -> Map {
+        let mut map = Map::new("test");
+        for column in map.tiles.iter_mut() {
+            for tile in column.iter_mut() {
+                tile.solid = false;
+            }
+        }
+        for y in 0..map.height {
+            map.tiles[20][y].solid = true;
+        }
+        map
+    }
+
+    #[test]
+    fn test_hook_attaches_and_pulls() {
+        let map = walled_map();
+        let (px, py) = (100.0, 200.0 - GRAPPLE_ORIGIN_Y);
+
+        let mut hook = Grapple::default();
+        for _ in 0..60 {
+            hook.update(true, 0.0, px, py, 1.0 / 60.0, &map);
+            if hook.anchor().is_some() {
+                break;
+            }
+        }
+        let (ax, ay) = hook.anchor().expect("hook should catch the wall");
+        assert!((ax - 640.0).abs() <= STEP && (ay - 200.0).abs() < 0.01);
+
+        let state = PmoveState {
+            x: px,
+            y: py,
+            vel_x: 0.0,
+            vel_y: 0.0,
+            was_in_air: true,
+            jump_time: 0.0,
+            fall_speed: 0.0,
+        };
+        let cmd = PmoveCmd {
+            move_right: 0.0,
+            jump: false,
+            crouch: false,
+            haste_active: false,
+            flight_active: false,
+            grapple: hook.anchor(),
+        };
+        let physics = PhysicsProfile {
+            grapple_pull: 0.5,
+            ..PhysicsProfile::default()
+        };
+        let result = pmove(&state, &cmd, 1.0 / 60.0, &map, &physics);
+        assert!(result.new_vel_x > 0.4, "pulled towards the wall");
+
+        let unhooked = pmove(&state, &cmd, 1.0 / 60.0, &map, &PhysicsProfile::default());
+        assert_eq!(unhooked.new_vel_x, 0.0, "no pull with the hook off");
+
+        hook.update(false, 0.0, px, py, 1.0 / 60.0, &map);
+        assert_eq!(hook, Grapple::Idle);
+    }
+
+    #[test]
+    fn test_missed_hook_waits_for_release() {
+        let map = walled_map();
+        let mut hook = Grapple::default();
+        for _ in 0..120 {
+            hook.update(true, 0.0, 700.0, 300.0, 1.0 / 60.0, &map);
+        }
+        assert_eq!(hook, Grapple::Retracted);
+        hook.update(false, 0.0, 700.0, 300.0, 1.0 / 60.0, &map);
+        hook.update(true, 0.0, 700.0, 300.0, 1.0 / 60.0, &map);
+        assert!(matches!(hook, Grapple::Flying { .. }));
+    }
+}
@@ -24,6 +24,9 @@ pub enum ItemModelType {
     WeaponRailgun,
     WeaponPlasmagun,
     WeaponBFG,
+    WeaponNailgun,
+    WeaponProxLauncher,
+    WeaponChaingun,
 
     AmmoShells,
     AmmoBullets,
@@ -83,12 +86,17 @@ impl ItemModelType {
             ItemType::Railgun => Some(ItemModelType::WeaponRailgun),
             ItemType::Plasmagun => Some(ItemModelType::WeaponPlasmagun),
             ItemType::BFG => Some(ItemModelType::WeaponBFG),
+            ItemType::Nailgun => Some(ItemModelType::WeaponNailgun),
+            ItemType::ProxLauncher => Some(ItemModelType::WeaponProxLauncher),
+            ItemType::Chaingun => Some(ItemModelType::WeaponChaingun),
             ItemType::Quad => Some(ItemModelType::PowerupQuad),
             ItemType::Regen => Some(ItemModelType::PowerupRegen),
             ItemType::Battle => Some(ItemModelType::PowerupBattleSuit),
             ItemType::Flight => Some(ItemModelType::PowerupFlight),
             ItemType::Haste => Some(ItemModelType::PowerupHaste),
             ItemType::Invis => Some(ItemModelType::PowerupInvis),
+            ItemType::Medkit => Some(ItemModelType::HoldableMedkit),
+            ItemType::Teleporter => Some(ItemModelType::HoldableTeleporter),
         }
     }
 
@@ -120,6 +128,11 @@ impl ItemModelType {
             ItemModelType::WeaponRailgun => "q3-resources/models/weapons2/railgun/railgun.md3",
             ItemModelType::WeaponPlasmagun => "q3-resources/models/weapons2/plasma/plasma.md3",
             ItemModelType::WeaponBFG => "q3-resources/models/weapons2/bfg/bfg.md3",
+            ItemModelType::WeaponNailgun => "q3-resources/models/weapons/nailgun/nailgun.md3",
+            ItemModelType::WeaponProxLauncher => {
+                "q3-resources/models/weapons/proxmine/proxmine.md3"
+            }
+            ItemModelType::WeaponChaingun => "q3-resources/models/weapons/vulcan/vulcan.md3",
 
             ItemModelType::AmmoShells => "q3-resources/models/powerups/ammo/shotgunam.md3",
             ItemModelType::AmmoBullets => "q3-resources/models/powerups/ammo/machinegunam.md3",
@@ -213,6 +226,9 @@ impl ItemModelType {
                 "models/weapons2/bfg/f_bfg.png",
                 "models/weapons2/bfg/f_bfg2.png",
             ]),
+            ItemModelType::WeaponNailgun => Some(vec!["models/weapons/nailgun/nailgun.png"]),
+            ItemModelType::WeaponProxLauncher => Some(vec!["models/weapons/proxmine/proxmine.png"]),
+            ItemModelType::WeaponChaingun => Some(vec!["models/weapons/vulcan/vulcan.png"]),
             _ => None,
         }
     }
@@ -428,6 +444,9 @@ impl ItemModel {
                 | ItemModelType::WeaponRailgun
                 | ItemModelType::WeaponPlasmagun
                 | ItemModelType::WeaponBFG
+                | ItemModelType::WeaponNailgun
+                | ItemModelType::WeaponProxLauncher
+                | ItemModelType::WeaponChaingun
         );
 
         let mut color = base_color;
@@ -523,6 +542,9 @@ impl ItemModel {
                 | ItemModelType::WeaponRailgun
                 | ItemModelType::WeaponPlasmagun
                 | ItemModelType::WeaponBFG
+                | ItemModelType::WeaponNailgun
+                | ItemModelType::WeaponProxLauncher
+                | ItemModelType::WeaponChaingun
         );
 
         let mut color = base_color;
@@ -91,6 +91,29 @@ impl LightningBeam {
         }
     }
 
+    /// A straight, steady beam for the grappling hook cable.
+    pub fn cable(
+        start_x: f32,
+        start_y: f32,
+        end_x: f32,
+        end_y: f32,
+        color: Color,
+        material: Material,
+    ) -> Self {
+        Self {
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            segments: vec![(start_x, start_y), (end_x, end_y)],
+            life: 0.0,
+            max_life: LIGHTNING_BEAM_DURATION,
+            color,
+            player_id: 0,
+            material,
+        }
+    }
+
     pub fn update(&mut self, dt: f32) -> bool {
         self.life += dt;
         self.life < self.max_life
@@ -432,6 +455,21 @@ impl LightningEffects {
         }
     }
 
+    pub fn render_cable(
+        &self,
+        start: (f32, f32),
+        end: (f32, f32),
+        color: Color,
+        camera_x: f32,
+        camera_y: f32,
+    ) {
+        let Some(material) = &self.material else {
+            return;
+        };
+        LightningBeam::cable(start.0, start.1, end.0, end.1, color, material.clone())
+            .render(camera_x, camera_y);
+    }
+
     pub fn create_impact_particles(&self) -> Vec<Particle> {
         let mut all_particles = Vec::new();
 
@@ -0,0 +1,342 @@
+use super::map::{Liquid, Map};
+use super::means_of_death::MeansOfDeath;
+use crate::audio::events::AudioEvent;
+use macroquad::prelude::*;
+use std::sync::OnceLock;
+
+/// Seconds of air a player has once their head goes under, as in Q3.
+pub const AIR_SUPPLY: f32 = 12.0;
+/// Seconds between hurts from drowning, slime or lava.
+const PAIN_INTERVAL: f32 = 1.0;
+const DROWN_DAMAGE_START: i32 = 2;
+const DROWN_DAMAGE_MAX: i32 = 15;
+/// Per point of water level, so wading hurts less than diving in.
+const SLIME_DAMAGE: i32 = 10;
+const LAVA_DAMAGE: i32 = 30;
+
+static LIQUID_MATERIAL: OnceLock<Material> = OnceLock::new();
+
+/// Air left and the pain clock for a player in liquid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Breath {
+    pub air: f32,
+    drown_damage: i32,
+    pain_timer: f32,
+}
+
+impl Default for Breath {
+    fn default() -> Self {
+        Self {
+            air: AIR_SUPPLY,
+            drown_damage: DROWN_DAMAGE_START,
+            pain_timer: 0.0,
+        }
+    }
+}
+
+impl Breath {
+    /// Advances `dt` seconds at `water_level` of `liquid` and returns the
+    /// world damage due now, if any. Each drowning hit is worse than the last
+    /// until the player comes up for air.
+    pub fn update(
+        &mut self,
+        water_level: u8,
+        liquid: Option<Liquid>,
+        dt: f32,
+    ) -> Option<(i32, MeansOfDeath)> {
+        if water_level < 3 {
+            self.air = AIR_SUPPLY;
+            self.drown_damage = DROWN_DAMAGE_START;
+        } else {
+            self.air = (self.air - dt).max(0.0);
+        }
+        self.pain_timer = (self.pain_timer - dt).max(0.0);
+        if self.pain_timer > 0.0 || water_level == 0 {
+            return None;
+        }
+
+        let level = water_level as i32;
+        let hurt = match liquid {
+            Some(Liquid::Lava) => (LAVA_DAMAGE * level, MeansOfDeath::Lava),
+            Some(Liquid::Slime) => (SLIME_DAMAGE * level, MeansOfDeath::Slime),
+            _ if self.air <= 0.0 => {
+                self.drown_damage = (self.drown_damage + 2).min(DROWN_DAMAGE_MAX);
+                (self.drown_damage, MeansOfDeath::Drowned)
+            }
+            _ => return None,
+        };
+        self.pain_timer = PAIN_INTERVAL;
+        Some(hurt)
+    }
+}
+
+/// Sounds for a player whose water level went from `old` to `new`.
+pub fn splash_events(old: u8, new: u8, x: f32) -> Vec<AudioEvent> {
+    let mut events = Vec::new();
+    if old == 0 && new > 0 {
+        events.push(AudioEvent::SplashIn { x });
+    } else if old > 0 && new == 0 {
+        events.push(AudioEvent::SplashOut { x });
+    }
+    if old < 3 && new == 3 {
+        events.push(AudioEvent::Underwater { x });
+    }
+    events
+}
+
+/// Draws every visible liquid tile over the players, so whoever is in it
+/// looks submerged.
+pub fn render_liquids(map: &Map, camera_x: f32, camera_y: f32) {
+    let start_x = ((camera_x / 32.0).floor() as i32).max(0);
+    let end_x = (((camera_x + screen_width()) / 32.0).ceil() as i32).min(map.width as i32);
+    let start_y = ((camera_y / 16.0).floor() as i32).max(0);
+    let end_y = (((camera_y + screen_height()) / 16.0).ceil() as i32).min(map.height as i32);
+
+    let material = get_liquid_material();
+    let time = get_time() as f32;
+
+    for y in start_y..end_y {
+        let mut x = start_x;
+        while x < end_x {
+            let Some(liquid) = map.liquid_at(x, y) else {
+                x += 1;
+                continue;
+            };
+            // Runs of the same liquid share one quad; the top row of a pool
+            // gets the rippling surface.
+            let surface = map.liquid_at(x, y - 1).is_none();
+            let run_start = x;
+            while x < end_x
+                && map.liquid_at(x, y) == Some(liquid)
+                && map.liquid_at(x, y - 1).is_none() == surface
+            {
+                x += 1;
+            }
+
+            let world_x = run_start as f32 * 32.0;
+            let world_y = y as f32 * 16.0;
+            let width = (x - run_start) as f32 * 32.0;
+
+            material.set_uniform("iTime", time);
+            material.set_uniform("worldOrigin", (world_x, world_y));
+            material.set_uniform("size", (width, 16.0f32));
+            material.set_uniform("surface", if surface { 1.0f32 } else { 0.0 });
+            gl_use_material(material);
+            draw_rectangle(
+                world_x - camera_x,
+                world_y - camera_y,
+                width,
+                16.0,
+                liquid_color(liquid),
+            );
+            gl_use_default_material();
+        }
+    }
+}
+
+fn liquid_color(liquid: Liquid) -> Color {
+    match liquid {
+        Liquid::Water => Color::from_rgba(40, 90, 170, 110),
+        Liquid::Slime => Color::from_rgba(90, 170, 40, 140),
+        Liquid::Lava => Color::from_rgba(230, 80, 20, 200),
+    }
+}
+
+fn get_liquid_material() -> &'static Material {
+    LIQUID_MATERIAL.get_or_init(|| {
+        let vertex_shader = r#"#version 100
+        attribute vec3 position;
+        attribute vec2 texcoord;
+        attribute vec4 color0;
+
+        varying lowp vec2 uv;
+        varying lowp vec4 color;
+
+        uniform mat4 Model;
+        uniform mat4 Projection;
+
+        void main() {
+            gl_Position = Projection * Model * vec4(position, 1.0);
+            color = color0 / 255.0;
+            uv = texcoord;
+        }"#;
+
+        let fragment_shader = r#"#version 100
+        precision mediump float;
+
+        varying lowp vec2 uv;
+        varying lowp vec4 color;
+
+        uniform float iTime;
+        uniform vec2 worldOrigin;
+        uniform vec2 size;
+        uniform float surface;
+
+        void main() {
+            vec2 p = worldOrigin + uv * size;
+
+            float ripple = sin(p.x * 0.08 + iTime * 2.0) * 0.5
+                + sin(p.x * 0.031 - iTime * 1.3 + p.y * 0.05) * 0.5;
+            float caustic = sin(p.x * 0.13 + sin(p.y * 0.11 + iTime) * 2.0)
+                * sin(p.y * 0.17 - iTime * 0.7);
+
+            vec3 rgb = color.rgb * (0.9 + 0.15 * caustic);
+            float alpha = color.a;
+
+            if (surface > 0.5) {
+                float line = uv.y * size.y - (2.0 + ripple * 2.0);
+                if (line < 0.0) {
+                    discard;
+                }
+                float crest = 1.0 - smoothstep(0.0, 3.0, line);
+                rgb = mix(rgb, vec3(1.0), crest * 0.45);
+                alpha = mix(alpha, 0.9, crest);
+            }
+
+            gl_FragColor = vec4(rgb, alpha);
+        }"#;
+
+        load_material(
+            ShaderSource::Glsl {
+                vertex: vertex_shader,
+                fragment: fragment_shader,
+            },
+            MaterialParams {
+                uniforms: vec![
+                    UniformDesc::new("iTime", UniformType::Float1),
+                    UniformDesc::new("worldOrigin", UniformType::Float2),
+                    UniformDesc::new("size", UniformType::Float2),
+                    UniformDesc::new("surface", UniformType::Float1),
+                ],
+                pipeline_params: PipelineParams {
+                    color_blend: Some(miniquad::BlendState::new(
+                        miniquad::Equation::Add,
+                        miniquad::BlendFactor::Value(miniquad::BlendValue::SourceAlpha),
+                        miniquad::BlendFactor::OneMinusValue(miniquad::BlendValue::SourceAlpha),
+                    )),
+                    depth_test: miniquad::Comparison::Always,
+                    depth_write: false,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bg_pmove::{pmove, PmoveCmd, PmoveState};
+    use crate::game::physics::PhysicsProfile;
+
+    #[test]
+    fn test_drowning() {
+        let mut breath = Breath::default();
+        assert_eq!(breath.update(2, Some(Liquid::Water), 5.0), None);
+        assert_eq!(breath.air, AIR_SUPPLY);
+
+        for _ in 0..11 {
+            assert_eq!(breath.update(3, Some(Liquid::Water), 1.0), None);
+        }
+        assert_eq!(
+            breath.update(3, Some(Liquid::Water), 1.0),
+            Some((4, MeansOfDeath::Drowned))
+        );
+        assert_eq!(breath.update(3, Some(Liquid::Water), 0.5), None);
+        assert_eq!(
+            breath.update(3, Some(Liquid::Water), 0.5),
+            Some((6, MeansOfDeath::Drowned))
+        );
+
+        // Surfacing refills the air and resets how bad drowning gets.
+        breath.update(1, Some(Liquid::Water), 1.0);
+        assert_eq!(breath.air, AIR_SUPPLY);
+        assert_eq!(breath.drown_damage, DROWN_DAMAGE_START);
+    }
+
+    #[test]
+    fn test_slime_and_lava() {
+        let mut breath = Breath::default();
+        assert_eq!(
+            breath.update(1, Some(Liquid::Slime), 0.016),
+            Some((10, MeansOfDeath::Slime))
+        );
+        assert_eq!(breath.update(1, Some(Liquid::Slime), 0.5), None);
+        assert_eq!(
+            breath.update(3, Some(Liquid::Lava), 0.5),
+            Some((90, MeansOfDeath::Lava))
+        );
+        assert_eq!(breath.update(0, None, 5.0), None);
+    }
+
+    fn floor_map() -> Map {
+        let mut map = Map::new("test");
+        for x in 0..map.width {
+            for y in 0..map.height {
+                map.tiles[x][y].solid = y >= 20;
+            }
+        }
+        map.jumppads.clear();
+        map
+    }
+
+    fn cmd(move_right: f32, jump: bool) -> PmoveCmd {
+        PmoveCmd {
+            move_right,
+            jump,
+            crouch: false,
+            haste_active: false,
+            flight_active: false,
+            grapple: None,
+        }
+    }
+
+    fn step(state: &PmoveState, cmd: &PmoveCmd, map: &Map, physics: &PhysicsProfile) -> PmoveState {
+        let result = pmove(state, cmd, 1.0 / 60.0, map, physics);
+        PmoveState {
+            x: result.new_x,
+            y: result.new_y,
+            vel_x: result.new_vel_x,
+            vel_y: result.new_vel_y,
+            was_in_air: result.new_was_in_air,
+            jump_time: result.new_jump_time,
+            fall_speed: result.new_fall_speed,
+        }
+    }
+
+    #[test]
+    fn test_swimming_sinks_slowly_and_jump_swims_up() {
+        let mut map = floor_map();
+        for column in &mut map.tiles {
+            for tile in &mut column[5..20] {
+                tile.liquid = Some(Liquid::Water);
+            }
+        }
+        let submerged = PmoveState {
+            x: 600.0,
+            y: 12.0 * 16.0,
+            vel_x: 0.0,
+            vel_y: 0.0,
+            was_in_air: true,
+            jump_time: 0.0,
+            fall_speed: 0.0,
+        };
+        let physics = PhysicsProfile::vq3();
+        let result = pmove(&submerged, &cmd(0.0, false), 1.0 / 60.0, &map, &physics);
+        assert_eq!(result.water_level, 3);
+        assert_eq!(result.liquid, Some(Liquid::Water));
+
+        let mut sinking = submerged.clone();
+        let mut swimming = submerged.clone();
+        for _ in 0..30 {
+            sinking = step(&sinking, &cmd(0.0, false), &map, &physics);
+            swimming = step(&swimming, &cmd(0.0, true), &map, &physics);
+        }
+        let dry = step(&submerged, &cmd(0.0, false), &floor_map(), &physics);
+        assert!(sinking.vel_y > 0.0 && sinking.vel_y < dry.vel_y * 30.0);
+        assert!(swimming.y < submerged.y);
+        assert_eq!(sinking.fall_speed, 0.0);
+    }
+}
@@ -13,6 +13,8 @@ pub struct Map {
     pub lights: Vec<LightSource>,
     #[serde(default)]
     pub background_elements: Vec<BackgroundElement>,
+    #[serde(default)]
+    pub flags: Vec<FlagBase>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -41,6 +43,17 @@ pub struct Tile {
     pub glow_texture: Option<String>,
     #[serde(default)]
     pub blend_alpha: f32,
+    #[serde(default)]
+    pub liquid: Option<Liquid>,
+}
+
+/// What fills a non-solid tile that players can swim in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Liquid {
+    Water,
+    Slime,
+    Lava,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -50,6 +63,13 @@ pub struct SpawnPoint {
     pub team: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FlagBase {
+    pub x: f32,
+    pub y: f32,
+    pub team: u8,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Item {
     pub x: f32,
@@ -213,12 +233,17 @@ pub enum ItemType {
     Railgun,
     Plasmagun,
     BFG,
+    Nailgun,
+    ProxLauncher,
+    Chaingun,
     Quad,
     Regen,
     Battle,
     Flight,
     Haste,
     Invis,
+    Medkit,
+    Teleporter,
 }
 
 impl Map {
@@ -268,6 +293,7 @@ impl Map {
                     detail_texture: None,
                     glow_texture: None,
                     blend_alpha: 1.0,
+                    liquid: None,
                 };
                 height
             ];
@@ -428,6 +454,7 @@ impl Map {
             teleporters: vec![],
             lights: vec![],
             background_elements: vec![],
+            flags: vec![],
         }
     }
 
@@ -443,6 +470,7 @@ impl Map {
                     detail_texture: None,
                     glow_texture: None,
                     blend_alpha: 1.0,
+                    liquid: None,
                 };
                 height
             ];
@@ -1000,6 +1028,7 @@ impl Map {
             ],
             lights: vec![],
             background_elements: vec![],
+            flags: vec![],
         }
     }
 
@@ -1011,6 +1040,25 @@ impl Map {
         self.tiles[tile_x as usize][tile_y as usize].solid
     }
 
+    pub fn liquid_at(&self, tile_x: i32, tile_y: i32) -> Option<Liquid> {
+        if tile_x < 0 || tile_y < 0 || tile_x >= self.width as i32 || tile_y >= self.height as i32 {
+            return None;
+        }
+        let tile = &self.tiles[tile_x as usize][tile_y as usize];
+        tile.liquid.filter(|_| !tile.solid)
+    }
+
+    /// Puts every placed item back and removes dropped ones, as at map start.
+    pub fn reset_items(&mut self) {
+        self.items.retain(|item| !item.dropped);
+        for item in &mut self.items {
+            item.active = true;
+            item.respawn_time = 0;
+            item.vel_x = 0.0;
+            item.vel_y = 0.0;
+        }
+    }
+
     pub fn map_width(&self) -> usize {
         self.width
     }
@@ -1,6 +1,7 @@
 use super::file_loader;
 use super::map::{
-    BackgroundElement, Item, ItemType, JumpPad, LightSource, Map, SpawnPoint, Teleporter, Tile,
+    BackgroundElement, FlagBase, Item, ItemType, JumpPad, LightSource, Liquid, Map, SpawnPoint,
+    Teleporter, Tile,
 };
 use serde::{Deserialize, Serialize};
 use std::fs::File;
@@ -21,6 +22,8 @@ pub struct MapFile {
     pub lights: Vec<LightData>,
     #[serde(default)]
     pub background_elements: Option<Vec<BackgroundElement>>,
+    #[serde(default)]
+    pub flags: Vec<FlagBaseData>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -35,6 +38,8 @@ pub struct TileData {
     pub x_end: usize,
     pub solid: bool,
     pub texture_id: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquid: Option<Liquid>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -44,6 +49,13 @@ pub struct SpawnPointData {
     pub team: u8,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FlagBaseData {
+    pub tile_x: f32,
+    pub tile_y: f32,
+    pub team: u8,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ItemData {
     pub tile_x: f32,
@@ -123,6 +135,7 @@ impl MapFile {
                     detail_texture: None,
                     glow_texture: None,
                     blend_alpha: 1.0,
+                    liquid: None,
                 };
                 self.height
             ];
@@ -143,6 +156,7 @@ impl MapFile {
                         detail_texture: None,
                         glow_texture: None,
                         blend_alpha: 1.0,
+                        liquid: tile_data.liquid,
                     };
                 }
             }
@@ -158,6 +172,16 @@ impl MapFile {
             })
             .collect();
 
+        let flags = self
+            .flags
+            .iter()
+            .map(|f| FlagBase {
+                x: f.tile_x * self.tile_width,
+                y: f.tile_y * self.tile_height,
+                team: f.team,
+            })
+            .collect();
+
         let items = self
             .items
             .iter()
@@ -175,12 +199,17 @@ impl MapFile {
                     "Railgun" => ItemType::Railgun,
                     "Plasmagun" => ItemType::Plasmagun,
                     "BFG" => ItemType::BFG,
+                    "Nailgun" => ItemType::Nailgun,
+                    "ProxLauncher" => ItemType::ProxLauncher,
+                    "Chaingun" => ItemType::Chaingun,
                     "Quad" => ItemType::Quad,
                     "Regen" => ItemType::Regen,
                     "Battle" => ItemType::Battle,
                     "Flight" => ItemType::Flight,
                     "Haste" => ItemType::Haste,
                     "Invis" => ItemType::Invis,
+                    "Medkit" => ItemType::Medkit,
+                    "Teleporter" => ItemType::Teleporter,
                     _ => return None,
                 };
                 Some(Item {
@@ -253,6 +282,7 @@ impl MapFile {
             teleporters,
             lights,
             background_elements: self.background_elements.clone().unwrap_or_default(),
+            flags,
         }
     }
 
@@ -266,14 +296,16 @@ impl MapFile {
 
             while x < map.width {
                 let tile = &map.tiles[x][y];
-                if tile.solid || tile.texture_id != 0 {
+                if tile.solid || tile.texture_id != 0 || tile.liquid.is_some() {
                     let x_start = x;
                     let texture_id = tile.texture_id;
                     let solid = tile.solid;
+                    let liquid = tile.liquid;
 
                     while x < map.width
                         && map.tiles[x][y].solid == solid
                         && map.tiles[x][y].texture_id == texture_id
+                        && map.tiles[x][y].liquid == liquid
                     {
                         x += 1;
                     }
@@ -283,6 +315,7 @@ impl MapFile {
                         x_end: x - 1,
                         solid,
                         texture_id,
+                        liquid,
                     });
                 } else {
                     x += 1;
@@ -307,6 +340,16 @@ impl MapFile {
             })
             .collect();
 
+        let flags = map
+            .flags
+            .iter()
+            .map(|f| FlagBaseData {
+                tile_x: f.x / tile_width,
+                tile_y: f.y / tile_height,
+                team: f.team,
+            })
+            .collect();
+
         let items = map
             .items
             .iter()
@@ -324,12 +367,17 @@ impl MapFile {
                     ItemType::Railgun => "Railgun",
                     ItemType::Plasmagun => "Plasmagun",
                     ItemType::BFG => "BFG",
+                    ItemType::Nailgun => "Nailgun",
+                    ItemType::ProxLauncher => "ProxLauncher",
+                    ItemType::Chaingun => "Chaingun",
                     ItemType::Quad => "Quad",
                     ItemType::Regen => "Regen",
                     ItemType::Battle => "Battle",
                     ItemType::Flight => "Flight",
                     ItemType::Haste => "Haste",
                     ItemType::Invis => "Invis",
+                    ItemType::Medkit => "Medkit",
+                    ItemType::Teleporter => "Teleporter",
                 };
                 ItemData {
                     tile_x: item.x / tile_width,
@@ -392,6 +440,7 @@ impl MapFile {
             teleporters,
             lights,
             background_elements: Some(map.background_elements.clone()),
+            flags,
         }
     }
 }
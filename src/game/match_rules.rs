@@ -0,0 +1,366 @@
+use super::gametype::{Gametype, MatchWinner, ScoreEntry, TeamScores};
+use serde::{Deserialize, Serialize};
+
+/// How long the scoreboard stays up before the next match starts.
+pub const INTERMISSION_TIME: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EndReason {
+    TimeLimit,
+    FragLimit,
+    CaptureLimit,
+    MercyLimit,
+    RoundLimit,
+}
+
+impl EndReason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EndReason::TimeLimit => "Timelimit hit",
+            EndReason::FragLimit => "Fraglimit hit",
+            EndReason::CaptureLimit => "Capturelimit hit",
+            EndReason::MercyLimit => "Mercy limit hit",
+            EndReason::RoundLimit => "Roundlimit hit",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LimitEvent {
+    /// The leader is this many frags (or captures) away from the limit.
+    ScoresLeft(u32),
+    /// Time ran out on a tie and another period was added.
+    Overtime {
+        period: u32,
+        length: f32,
+    },
+    /// Time ran out on a tie with overtime off; the next score wins.
+    SuddenDeath,
+    MatchEnded(EndReason),
+}
+
+impl LimitEvent {
+    pub fn message(&self) -> Option<String> {
+        match *self {
+            LimitEvent::ScoresLeft(_) => None,
+            LimitEvent::Overtime { period, length } => Some(format!(
+                "Overtime {}! {} seconds added",
+                period, length as u32
+            )),
+            LimitEvent::SuddenDeath => Some("Sudden death! Next score wins".to_string()),
+            LimitEvent::MatchEnded(reason) => Some(reason.name().to_string()),
+        }
+    }
+
+    pub fn announcement(&self) -> Option<&'static str> {
+        match *self {
+            LimitEvent::ScoresLeft(3) => Some("3_frags"),
+            LimitEvent::ScoresLeft(2) => Some("2_frags"),
+            LimitEvent::ScoresLeft(1) => Some("1_frag"),
+            LimitEvent::SuddenDeath => Some("sudden_death"),
+            _ => None,
+        }
+    }
+}
+
+/// Score and time limits plus the overtime state of the current match.
+/// Round-based gametypes end on `g_roundlimit` instead and only use the clock.
+#[derive(Clone, Debug, Default)]
+pub struct MatchRules {
+    /// Frags (team frags in team modes) that win the match, 0 for no limit.
+    pub fraglimit: i32,
+    pub capturelimit: i32,
+    /// Team lead that ends the match early, 0 for no limit.
+    pub mercylimit: i32,
+    /// Length of an overtime period; 0 goes to sudden death instead.
+    pub overtime_length: f32,
+    pub overtime: u32,
+    pub sudden_death: bool,
+    pub ended: Option<EndReason>,
+    /// Lowest scores-left count already announced.
+    announced_left: Option<u32>,
+}
+
+impl MatchRules {
+    pub fn new(fraglimit: i32, capturelimit: i32, mercylimit: i32, overtime_length: f32) -> Self {
+        Self {
+            fraglimit,
+            capturelimit,
+            mercylimit,
+            overtime_length,
+            ..Self::default()
+        }
+    }
+
+    /// Reads `fraglimit`, `capturelimit`, `mercylimit` and `g_overtime`.
+    pub fn from_cvar() -> Self {
+        Self::new(
+            crate::cvar::get_cvar_integer("fraglimit").max(0),
+            crate::cvar::get_cvar_integer("capturelimit").max(0),
+            crate::cvar::get_cvar_integer("mercylimit").max(0),
+            crate::cvar::get_cvar_float("g_overtime").max(0.0),
+        )
+    }
+
+    /// Clears overtime and the end state, keeping the limits.
+    pub fn reset(&mut self) {
+        self.overtime = 0;
+        self.sudden_death = false;
+        self.ended = None;
+        self.announced_left = None;
+    }
+
+    /// The gametype's time limit stretched by the overtime periods played.
+    pub fn time_limit(&self, base: f32) -> f32 {
+        if base <= 0.0 {
+            return base;
+        }
+        base + self.overtime as f32 * self.overtime_length
+    }
+
+    pub fn status_text(&self) -> Option<String> {
+        if self.sudden_death {
+            Some("SUDDEN DEATH".to_string())
+        } else if self.overtime > 1 {
+            Some(format!("OVERTIME {}", self.overtime))
+        } else if self.overtime == 1 {
+            Some("OVERTIME".to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Checks every limit against the current scores; ends the match at most once.
+    pub fn update(
+        &mut self,
+        match_time: f32,
+        base_time_limit: f32,
+        gametype: &dyn Gametype,
+        players: &[ScoreEntry],
+        team_scores: &TeamScores,
+    ) -> Vec<LimitEvent> {
+        let mut events = Vec::new();
+        if self.ended.is_some() {
+            return events;
+        }
+        let tied = gametype.winner(players, team_scores) == MatchWinner::Draw;
+
+        if !gametype.is_round_based() {
+            let (limit, reason) = if gametype.uses_flags() {
+                (self.capturelimit, EndReason::CaptureLimit)
+            } else {
+                (self.fraglimit, EndReason::FragLimit)
+            };
+            let leader = if gametype.is_team_game() {
+                team_scores.red.max(team_scores.blue)
+            } else {
+                players.iter().map(|p| p.frags).max().unwrap_or(0)
+            };
+
+            if limit > 0 {
+                if leader >= limit && !tied {
+                    return self.end(events, reason);
+                }
+                let left = (limit - leader).max(1) as u32;
+                if left <= 3 && self.announced_left.is_none_or(|prev| left < prev) {
+                    self.announced_left = Some(left);
+                    events.push(LimitEvent::ScoresLeft(left));
+                }
+            }
+
+            if gametype.is_team_game()
+                && self.mercylimit > 0
+                && (team_scores.red - team_scores.blue).abs() >= self.mercylimit
+            {
+                return self.end(events, EndReason::MercyLimit);
+            }
+        }
+
+        if self.sudden_death {
+            if !tied {
+                return self.end(events, EndReason::TimeLimit);
+            }
+            return events;
+        }
+
+        let time_limit = self.time_limit(base_time_limit);
+        if gametype.is_match_over(match_time, time_limit) {
+            if !tied {
+                return self.end(events, EndReason::TimeLimit);
+            }
+            if self.overtime_length > 0.0 {
+                self.overtime += 1;
+                events.push(LimitEvent::Overtime {
+                    period: self.overtime,
+                    length: self.overtime_length,
+                });
+            } else {
+                self.sudden_death = true;
+                events.push(LimitEvent::SuddenDeath);
+            }
+        }
+        events
+    }
+
+    /// Marks the match over from outside, e.g. when the round limit is hit.
+    pub fn finish(&mut self, reason: EndReason) -> Option<LimitEvent> {
+        if self.ended.is_some() {
+            return None;
+        }
+        self.ended = Some(reason);
+        Some(LimitEvent::MatchEnded(reason))
+    }
+
+    fn end(&mut self, mut events: Vec<LimitEvent>, reason: EndReason) -> Vec<LimitEvent> {
+        events.extend(self.finish(reason));
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::gametype::{self, GametypeKind, TEAM_BLUE, TEAM_FREE, TEAM_RED};
+
+    fn entry(id: u16, team: u8, frags: i32) -> ScoreEntry {
+        ScoreEntry { id, team, frags }
+    }
+
+    #[test]
+    fn test_fraglimit_counts_down_and_ends() {
+        let ffa = gametype::create(GametypeKind::FreeForAll);
+        let mut rules = MatchRules::new(10, 0, 0, 0.0);
+        let teams = TeamScores::default();
+
+        let events = rules.update(1.0, 600.0, ffa.as_ref(), &[entry(1, TEAM_FREE, 7)], &teams);
+        assert_eq!(events, vec![LimitEvent::ScoresLeft(3)]);
+        assert_eq!(events[0].announcement(), Some("3_frags"));
+        let players = [entry(1, TEAM_FREE, 8), entry(2, TEAM_FREE, 2)];
+        assert_eq!(
+            rules.update(2.0, 600.0, ffa.as_ref(), &players, &teams),
+            vec![LimitEvent::ScoresLeft(2)]
+        );
+        assert!(rules
+            .update(3.0, 600.0, ffa.as_ref(), &players, &teams)
+            .is_empty());
+
+        let players = [entry(1, TEAM_FREE, 10), entry(2, TEAM_FREE, 2)];
+        assert_eq!(
+            rules.update(4.0, 600.0, ffa.as_ref(), &players, &teams),
+            vec![LimitEvent::MatchEnded(EndReason::FragLimit)]
+        );
+        assert!(rules
+            .update(5.0, 600.0, ffa.as_ref(), &players, &teams)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_tie_goes_to_overtime_periods() {
+        let duel = gametype::create(GametypeKind::Duel);
+        let mut rules = MatchRules::new(0, 0, 0, 120.0);
+        let teams = TeamScores::default();
+        let tied = [entry(1, TEAM_FREE, 5), entry(2, TEAM_FREE, 5)];
+
+        assert_eq!(
+            rules.update(600.0, 600.0, duel.as_ref(), &tied, &teams),
+            vec![LimitEvent::Overtime {
+                period: 1,
+                length: 120.0
+            }]
+        );
+        assert_eq!(rules.time_limit(600.0), 720.0);
+        assert!(rules
+            .update(700.0, 600.0, duel.as_ref(), &tied, &teams)
+            .is_empty());
+        rules.update(720.0, 600.0, duel.as_ref(), &tied, &teams);
+        assert_eq!(rules.status_text().as_deref(), Some("OVERTIME 2"));
+
+        let ahead = [entry(1, TEAM_FREE, 6), entry(2, TEAM_FREE, 5)];
+        assert!(rules
+            .update(800.0, 600.0, duel.as_ref(), &ahead, &teams)
+            .is_empty());
+        assert_eq!(
+            rules.update(840.0, 600.0, duel.as_ref(), &ahead, &teams),
+            vec![LimitEvent::MatchEnded(EndReason::TimeLimit)]
+        );
+    }
+
+    #[test]
+    fn test_sudden_death_ends_on_next_score() {
+        let tdm = gametype::create(GametypeKind::TeamDeathmatch);
+        let mut rules = MatchRules::new(0, 0, 0, 0.0);
+        let players = [entry(1, TEAM_RED, 3), entry(2, TEAM_BLUE, 3)];
+        let tied = TeamScores { red: 3, blue: 3 };
+
+        let events = rules.update(600.0, 600.0, tdm.as_ref(), &players, &tied);
+        assert_eq!(events, vec![LimitEvent::SuddenDeath]);
+        assert_eq!(events[0].announcement(), Some("sudden_death"));
+        assert!(rules
+            .update(900.0, 600.0, tdm.as_ref(), &players, &tied)
+            .is_empty());
+
+        let ahead = TeamScores { red: 3, blue: 4 };
+        assert_eq!(
+            rules.update(901.0, 600.0, tdm.as_ref(), &players, &ahead),
+            vec![LimitEvent::MatchEnded(EndReason::TimeLimit)]
+        );
+        rules.reset();
+        assert!(!rules.sudden_death);
+        assert_eq!(rules.ended, None);
+    }
+
+    #[test]
+    fn test_capture_and_mercy_limits() {
+        let ctf = gametype::create(GametypeKind::CaptureTheFlag);
+        let mut rules = MatchRules::new(5, 3, 0, 0.0);
+        let players = [entry(1, TEAM_RED, 20), entry(2, TEAM_BLUE, 0)];
+        assert_eq!(
+            rules.update(
+                10.0,
+                600.0,
+                ctf.as_ref(),
+                &players,
+                &TeamScores { red: 2, blue: 0 }
+            ),
+            vec![LimitEvent::ScoresLeft(1)]
+        );
+        assert_eq!(
+            rules.update(
+                11.0,
+                600.0,
+                ctf.as_ref(),
+                &players,
+                &TeamScores { red: 3, blue: 0 }
+            ),
+            vec![LimitEvent::MatchEnded(EndReason::CaptureLimit)]
+        );
+
+        let tdm = gametype::create(GametypeKind::TeamDeathmatch);
+        let mut rules = MatchRules::new(0, 0, 15, 0.0);
+        assert!(rules
+            .update(
+                10.0,
+                600.0,
+                tdm.as_ref(),
+                &players,
+                &TeamScores { red: 14, blue: 0 }
+            )
+            .is_empty());
+        assert_eq!(
+            rules.update(
+                11.0,
+                600.0,
+                tdm.as_ref(),
+                &players,
+                &TeamScores { red: 15, blue: 0 }
+            ),
+            vec![LimitEvent::MatchEnded(EndReason::MercyLimit)]
+        );
+
+        let ffa = gametype::create(GametypeKind::FreeForAll);
+        let mut rules = MatchRules::new(0, 0, 1, 0.0);
+        assert!(rules
+            .update(10.0, 600.0, ffa.as_ref(), &players, &TeamScores::default())
+            .is_empty());
+    }
+}
@@ -0,0 +1,401 @@
+use super::map::ItemType;
+use super::means_of_death::MeansOfDeath;
+use super::weapon::Weapon;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WeaponStats {
+    pub shots: u32,
+    pub hits: u32,
+    pub damage: i32,
+    pub kills: u32,
+    pub deaths: u32,
+}
+
+impl WeaponStats {
+    /// Hit percentage, 0 when the weapon was never fired.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            (self.hits.min(self.shots) as f32 / self.shots as f32) * 100.0
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub id: u16,
+    pub name: String,
+    pub team: u8,
+    pub frags: i32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub suicides: u32,
+    pub damage_given: i32,
+    pub damage_taken: i32,
+    /// Keyed by weapon name.
+    pub weapons: BTreeMap<String, WeaponStats>,
+    /// Deaths keyed by what caused them, weapons and world alike.
+    #[serde(default)]
+    pub causes_of_death: BTreeMap<String, u32>,
+    /// Pickup counts keyed by item name.
+    pub items: BTreeMap<String, u32>,
+    /// Seconds spent holding each powerup.
+    pub powerup_time: BTreeMap<String, f32>,
+    pub spree: u32,
+    pub longest_spree: u32,
+}
+
+impl PlayerStats {
+    fn weapon(&mut self, weapon: Weapon) -> &mut WeaponStats {
+        self.weapons.entry(weapon.name().to_string()).or_default()
+    }
+
+    pub fn shots(&self) -> u32 {
+        self.weapons.values().map(|w| w.shots).sum()
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        let shots = self.shots();
+        if shots == 0 {
+            return 0.0;
+        }
+        let hits: u32 = self.weapons.values().map(|w| w.hits.min(w.shots)).sum();
+        (hits as f32 / shots as f32) * 100.0
+    }
+
+    /// Weapon with the most kills, ties going to damage.
+    pub fn best_weapon(&self) -> Option<&str> {
+        self.weapons
+            .iter()
+            .filter(|(_, w)| w.kills > 0 || w.damage > 0)
+            .max_by_key(|(_, w)| (w.kills, w.damage))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Per-player numbers for one match. Fed from the damage, pickup and
+/// shooting paths on whichever side runs the simulation.
+#[derive(Clone, Debug, Default)]
+pub struct MatchStats {
+    players: BTreeMap<u16, PlayerStats>,
+    /// Attacker and weapon pairs already credited with a hit this frame, so
+    /// shotgun pellets and splash on several players count once per shot.
+    frame_hits: HashSet<(u16, u8)>,
+}
+
+#[derive(Serialize)]
+struct MatchReport<'a> {
+    map: &'a str,
+    gametype: &'a str,
+    duration: f32,
+    ended_at: u64,
+    players: Vec<&'a PlayerStats>,
+}
+
+impl MatchStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.players.clear();
+        self.frame_hits.clear();
+    }
+
+    pub fn players(&self) -> impl Iterator<Item = &PlayerStats> {
+        self.players.values()
+    }
+
+    pub fn get(&self, player_id: u16) -> Option<&PlayerStats> {
+        self.players.get(&player_id)
+    }
+
+    /// Replaces everything with a server-sent copy.
+    pub fn set_players(&mut self, players: Vec<PlayerStats>) {
+        self.players = players.into_iter().map(|p| (p.id, p)).collect();
+    }
+
+    pub fn to_vec(&self) -> Vec<PlayerStats> {
+        self.players.values().cloned().collect()
+    }
+
+    fn player(&mut self, player_id: u16) -> &mut PlayerStats {
+        self.players
+            .entry(player_id)
+            .or_insert_with(|| PlayerStats {
+                id: player_id,
+                ..Default::default()
+            })
+    }
+
+    /// Call once per simulation frame.
+    pub fn end_frame(&mut self) {
+        self.frame_hits.clear();
+    }
+
+    pub fn record_shot(&mut self, player_id: u16, weapon: Weapon) {
+        self.player(player_id).weapon(weapon).shots += 1;
+    }
+
+    /// `dealt` is the health actually removed; self-damage only counts as
+    /// taken.
+    pub fn record_damage(&mut self, attacker_id: u16, target_id: u16, weapon: Weapon, dealt: i32) {
+        self.player(target_id).damage_taken += dealt;
+        if attacker_id == target_id {
+            return;
+        }
+        let first_hit = self.frame_hits.insert((attacker_id, weapon as u8));
+        let attacker = self.player(attacker_id);
+        attacker.damage_given += dealt;
+        let stats = attacker.weapon(weapon);
+        stats.damage += dealt;
+        if first_hit {
+            stats.hits += 1;
+        }
+    }
+
+    pub fn record_kill(&mut self, killer_id: u16, victim_id: u16, weapon: Weapon) {
+        self.record_death(killer_id, victim_id, MeansOfDeath::Weapon(weapon));
+    }
+
+    /// World deaths count as suicides whoever `killer_id` is.
+    pub fn record_death(&mut self, killer_id: u16, victim_id: u16, means: MeansOfDeath) {
+        let victim = self.player(victim_id);
+        victim.deaths += 1;
+        victim.spree = 0;
+        *victim
+            .causes_of_death
+            .entry(means.name().to_string())
+            .or_default() += 1;
+        if let MeansOfDeath::Weapon(weapon) = means {
+            victim.weapon(weapon).deaths += 1;
+        }
+        if killer_id == victim_id || means.is_world() {
+            victim.suicides += 1;
+            return;
+        }
+        let killer = self.player(killer_id);
+        killer.kills += 1;
+        killer.spree += 1;
+        killer.longest_spree = killer.longest_spree.max(killer.spree);
+        if let MeansOfDeath::Weapon(weapon) = means {
+            killer.weapon(weapon).kills += 1;
+        }
+    }
+
+    pub fn record_pickup(&mut self, player_id: u16, item: ItemType) {
+        *self
+            .player(player_id)
+            .items
+            .entry(format!("{:?}", item))
+            .or_default() += 1;
+    }
+
+    pub fn add_powerup_time(&mut self, player_id: u16, powerup: &str, dt: f32) {
+        *self
+            .player(player_id)
+            .powerup_time
+            .entry(powerup.to_string())
+            .or_default() += dt;
+    }
+
+    /// Copies names, teams and final scores in before the match is reported.
+    pub fn set_player_info(&mut self, player_id: u16, name: &str, team: u8, frags: i32) {
+        let player = self.player(player_id);
+        player.name = name.to_string();
+        player.team = team;
+        player.frags = frags;
+    }
+
+    pub fn to_json(&self, map: &str, gametype: &str, duration: f32) -> Result<String, String> {
+        let ended_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut players: Vec<&PlayerStats> = self.players.values().collect();
+        players.sort_by_key(|p| std::cmp::Reverse(p.frags));
+        let report = MatchReport {
+            map,
+            gametype,
+            duration,
+            ended_at,
+            players,
+        };
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+    }
+
+    /// Writes `<dir>/match_<unix time>_<map>.json` and returns the path.
+    pub fn write_json(
+        &self,
+        dir: &str,
+        map: &str,
+        gametype: &str,
+        duration: f32,
+    ) -> Result<String, String> {
+        let json = self.to_json(map, gametype, duration)?;
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir, e))?;
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = std::path::Path::new(dir).join(format!("match_{}_{}.json", stamp, map));
+        std::fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path.display().to_string())
+    }
+
+    /// End-of-match table: one row per player, best fraggers first.
+    pub fn draw(&self, alpha: f32) {
+        let screen_w = screen_width();
+        let screen_h = screen_height();
+
+        draw_rectangle(
+            0.0,
+            0.0,
+            screen_w,
+            screen_h,
+            Color::new(0.0, 0.0, 0.0, 0.9 * alpha),
+        );
+
+        let title = "MATCH STATS";
+        let title_size = 48.0;
+        let title_w = crate::render::measure_q3_banner_string(title, title_size);
+        crate::render::draw_q3_banner_string(
+            title,
+            screen_w / 2.0 - title_w / 2.0,
+            screen_h * 0.1,
+            title_size,
+            Color::new(1.0, 0.8, 0.2, alpha),
+        );
+
+        let columns = [
+            ("NAME", 0.0),
+            ("FRAGS", 220.0),
+            ("K/D", 300.0),
+            ("ACC", 380.0),
+            ("DMG+", 460.0),
+            ("DMG-", 540.0),
+            ("SPREE", 620.0),
+            ("ITEMS", 700.0),
+            ("BEST WEAPON", 780.0),
+        ];
+        let table_w = 940.0;
+        let left = (screen_w - table_w).max(0.0) / 2.0;
+        let mut y = screen_h * 0.1 + title_size + 30.0;
+        let header_color = Color::new(0.7, 0.7, 0.7, alpha);
+        for (label, x) in columns {
+            draw_text(label, left + x, y, 20.0, header_color);
+        }
+        y += 8.0;
+        draw_line(left, y, left + table_w, y, 1.0, header_color);
+        y += 24.0;
+
+        let mut players: Vec<&PlayerStats> = self.players.values().collect();
+        players.sort_by_key(|p| std::cmp::Reverse(p.frags));
+        let color = Color::new(0.95, 0.95, 0.95, alpha);
+        for player in players {
+            let items: u32 = player.items.values().sum();
+            let cells = [
+                player.name.clone(),
+                player.frags.to_string(),
+                format!("{}/{}", player.kills, player.deaths),
+                format!("{:.0}%", player.accuracy()),
+                player.damage_given.to_string(),
+                player.damage_taken.to_string(),
+                player.longest_spree.to_string(),
+                items.to_string(),
+                player.best_weapon().unwrap_or("-").to_string(),
+            ];
+            for (cell, (_, x)) in cells.iter().zip(columns.iter()) {
+                draw_text(cell, left + x, y, 22.0, color);
+            }
+            y += 26.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hits_count_once_per_frame() {
+        let mut stats = MatchStats::new();
+        stats.record_shot(1, Weapon::Shotgun);
+        stats.record_damage(1, 2, Weapon::Shotgun, 10);
+        stats.record_damage(1, 2, Weapon::Shotgun, 10);
+        stats.record_damage(1, 1, Weapon::Shotgun, 5);
+        stats.end_frame();
+        stats.record_shot(1, Weapon::Shotgun);
+        stats.end_frame();
+
+        let shooter = stats.get(1).unwrap();
+        let shotgun = &shooter.weapons["Shotgun"];
+        assert_eq!((shotgun.shots, shotgun.hits, shotgun.damage), (2, 1, 20));
+        assert_eq!(shotgun.accuracy(), 50.0);
+        assert_eq!(shooter.damage_given, 20);
+        assert_eq!(shooter.damage_taken, 5);
+        assert_eq!(stats.get(2).unwrap().damage_taken, 20);
+    }
+
+    #[test]
+    fn test_kills_sprees_and_suicides() {
+        let mut stats = MatchStats::new();
+        stats.record_kill(1, 2, Weapon::Railgun);
+        stats.record_kill(1, 3, Weapon::RocketLauncher);
+        stats.record_kill(2, 1, Weapon::Railgun);
+        stats.record_kill(1, 2, Weapon::Railgun);
+        stats.record_kill(2, 2, Weapon::RocketLauncher);
+
+        let one = stats.get(1).unwrap();
+        assert_eq!(
+            (one.kills, one.deaths, one.spree, one.longest_spree),
+            (3, 1, 1, 2)
+        );
+        assert_eq!(one.weapons["Railgun"].kills, 2);
+        assert_eq!(one.best_weapon(), Some("Railgun"));
+
+        let two = stats.get(2).unwrap();
+        assert_eq!((two.kills, two.deaths, two.suicides), (1, 3, 1));
+        assert_eq!(two.weapons["Railgun"].deaths, 2);
+    }
+
+    #[test]
+    fn test_causes_of_death() {
+        let mut stats = MatchStats::new();
+        stats.record_death(1, 2, MeansOfDeath::Telefrag);
+        stats.record_death(2, 2, MeansOfDeath::Falling);
+        stats.record_death(2, 2, MeansOfDeath::Falling);
+        stats.record_death(1, 2, MeansOfDeath::Lava);
+        stats.record_kill(1, 2, Weapon::Shotgun);
+
+        let one = stats.get(1).unwrap();
+        assert_eq!((one.kills, one.spree), (2, 2));
+        assert_eq!(one.weapons.len(), 1);
+        assert_eq!(one.weapons["Shotgun"].kills, 1);
+
+        let two = stats.get(2).unwrap();
+        assert_eq!((two.deaths, two.suicides), (5, 3));
+        assert_eq!(two.causes_of_death["Falling"], 2);
+        assert_eq!(two.causes_of_death["Lava"], 1);
+        assert_eq!(two.causes_of_death["Telefrag"], 1);
+        assert_eq!(two.causes_of_death["Shotgun"], 1);
+    }
+
+    #[test]
+    fn test_json_report() {
+        let mut stats = MatchStats::new();
+        stats.record_pickup(1, ItemType::Quad);
+        stats.add_powerup_time(1, "quad", 1.5);
+        stats.set_player_info(1, "Visor", 0, 4);
+        let json = stats.to_json("q3dm17", "Free For All", 300.0).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["map"], "q3dm17");
+        assert_eq!(value["players"][0]["name"], "Visor");
+        assert_eq!(value["players"][0]["items"]["Quad"], 1);
+        assert_eq!(value["players"][0]["powerup_time"]["quad"], 1.5);
+    }
+}
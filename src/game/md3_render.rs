@@ -59,6 +59,7 @@ enum ShaderType {
     Envmap,
     AlphaTest,
     DiffuseSpecular,
+    Frozen,
 }
 
 pub struct MD3OrientationBatchItem {
@@ -404,6 +405,30 @@ impl MD3OrientationBatch {
         });
     }
 
+    fn add_frozen_mesh_with_orientation(
+        &mut self,
+        mesh: &Mesh,
+        frame_idx: usize,
+        orientation: &Orientation,
+        screen_x: f32,
+        screen_y: f32,
+        texture: Option<&Texture2D>,
+    ) {
+        self.items.push(MD3OrientationBatchItem {
+            mesh: mesh as *const Mesh,
+            frame_idx,
+            orientation: *orientation,
+            screen_x,
+            screen_y,
+            color: WHITE,
+            texture: texture.cloned(),
+            texture_path: None,
+            shader_textures: None,
+            shader_type: ShaderType::Frozen,
+            use_quad_shader: false,
+        });
+    }
+
     pub fn flush(&mut self, lighting_context: Option<&LightingContext>) {
         if self.items.is_empty() {
             return;
@@ -617,6 +642,14 @@ impl MD3OrientationBatch {
                 count_shader!("md3_quad_damage");
                 gl_use_default_material();
             }
+            ShaderType::Frozen => {
+                let material = super::model_shader::get_frozen_shader_material();
+                gl_use_material(material);
+                material.set_uniform("time", get_time() as f32);
+                draw_mesh(&mesh_data);
+                count_shader!("md3_frozen");
+                gl_use_default_material();
+            }
             ShaderType::Fire => {
                 if let Some(shader_tex) = items
                     .first()
@@ -1541,6 +1574,7 @@ pub fn render_md3_mesh_with_orientation(
             shader_textures,
             lighting_context,
             false,
+            false,
         );
     }
 }
@@ -1585,6 +1619,47 @@ pub fn render_md3_mesh_with_orientation_quad(
             shader_textures,
             lighting_context,
             true,
+            false,
+        );
+    }
+}
+
+/// Draws the Freeze Tag ice shell over a mesh that has already been rendered.
+/// The shell shader ignores vertex color, so none is taken.
+pub fn render_md3_mesh_with_orientation_frozen(
+    mesh: &Mesh,
+    frame_idx: usize,
+    orientation: &Orientation,
+    screen_x: f32,
+    screen_y: f32,
+    texture: Option<&Texture2D>,
+    lighting_context: Option<&LightingContext>,
+) {
+    if BATCH_ENABLED.with(|enabled| *enabled.borrow()) {
+        GLOBAL_ORIENTATION_BATCH.with(|batch| {
+            batch.borrow_mut().add_frozen_mesh_with_orientation(
+                mesh,
+                frame_idx,
+                orientation,
+                screen_x,
+                screen_y,
+                texture,
+            );
+        });
+    } else {
+        render_md3_mesh_with_orientation_internal(
+            mesh,
+            frame_idx,
+            orientation,
+            screen_x,
+            screen_y,
+            WHITE,
+            texture,
+            None,
+            None,
+            lighting_context,
+            false,
+            true,
         );
     }
 }
@@ -1601,6 +1676,7 @@ fn render_md3_mesh_with_orientation_internal(
     shader_textures: Option<&[Texture2D]>,
     lighting_context: Option<&LightingContext>,
     use_quad_shader: bool,
+    frozen: bool,
 ) {
     if mesh.vertices.len() <= frame_idx {
         return;
@@ -1727,6 +1803,15 @@ fn render_md3_mesh_with_orientation_internal(
             gl_use_default_material();
             return;
         }
+        if frozen {
+            let material = super::model_shader::get_frozen_shader_material();
+            gl_use_material(material);
+            material.set_uniform("time", get_time() as f32);
+            draw_mesh(&mesh_data);
+            count_shader!("md3_frozen");
+            gl_use_default_material();
+            return;
+        }
         let mut shader_applied = false;
         if let Some(path) = texture_path {
             let path_lower = path.to_lowercase();
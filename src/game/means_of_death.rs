@@ -0,0 +1,111 @@
+use super::weapon::Weapon;
+use serde::{Deserialize, Serialize};
+
+/// Killer id for deaths nobody else caused.
+pub const WORLD_ID: u16 = u16::MAX;
+
+/// What killed a player, like Q3's `MOD_*` values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MeansOfDeath {
+    Weapon(Weapon),
+    Telefrag,
+    Suicide,
+    Falling,
+    Crushed,
+    Lava,
+    Slime,
+    Drowned,
+}
+
+impl MeansOfDeath {
+    pub fn name(self) -> &'static str {
+        match self {
+            MeansOfDeath::Weapon(weapon) => weapon.name(),
+            MeansOfDeath::Telefrag => "Telefrag",
+            MeansOfDeath::Suicide => "Suicide",
+            MeansOfDeath::Falling => "Falling",
+            MeansOfDeath::Crushed => "Crushed",
+            MeansOfDeath::Lava => "Lava",
+            MeansOfDeath::Slime => "Slime",
+            MeansOfDeath::Drowned => "Drowned",
+        }
+    }
+
+    /// No other player takes the credit; the victim loses a frag instead.
+    pub fn is_world(self) -> bool {
+        !matches!(self, MeansOfDeath::Weapon(_) | MeansOfDeath::Telefrag)
+    }
+
+    /// `killer` is `None` when the victim has only themselves or the world to
+    /// blame.
+    pub fn obituary(self, killer: Option<&str>, victim: &str) -> String {
+        let Some(killer) = killer.filter(|_| !self.is_world()) else {
+            let how = match self {
+                MeansOfDeath::Suicide => "suicides",
+                MeansOfDeath::Falling => "cratered",
+                MeansOfDeath::Crushed => "was squished",
+                MeansOfDeath::Lava => "does a back flip into the lava",
+                MeansOfDeath::Slime => "melted",
+                MeansOfDeath::Drowned => "sank like a rock",
+                MeansOfDeath::Weapon(Weapon::GrenadeLauncher) => "tripped on their own grenade",
+                MeansOfDeath::Weapon(Weapon::RocketLauncher) => "blew themselves up",
+                MeansOfDeath::Weapon(Weapon::Plasmagun) => "melted themselves",
+                MeansOfDeath::Weapon(Weapon::BFG) => "should have used a smaller gun",
+                MeansOfDeath::Weapon(Weapon::ProxLauncher) => "found their prox mine",
+                _ => "killed themselves",
+            };
+            return format!("{} {}", victim, how);
+        };
+        let Self::Weapon(weapon) = self else {
+            return format!("{} tried to invade {}'s personal space", victim, killer);
+        };
+        match weapon {
+            Weapon::Gauntlet => format!("{} was pummeled by {}", victim, killer),
+            Weapon::MachineGun => format!("{} was machinegunned by {}", victim, killer),
+            Weapon::Shotgun => format!("{} was gunned down by {}", victim, killer),
+            Weapon::GrenadeLauncher => format!("{} ate {}'s grenade", victim, killer),
+            Weapon::RocketLauncher => format!("{} ate {}'s rocket", victim, killer),
+            Weapon::Lightning => format!("{} was electrocuted by {}", victim, killer),
+            Weapon::Railgun => format!("{} was railed by {}", victim, killer),
+            Weapon::Plasmagun => format!("{} was melted by {}'s plasmagun", victim, killer),
+            Weapon::BFG => format!("{} was blasted by {}'s BFG", victim, killer),
+            Weapon::Nailgun => format!("{} was nailed by {}", victim, killer),
+            Weapon::ProxLauncher => format!("{} was too close to {}'s Prox Mine", victim, killer),
+            Weapon::Chaingun => format!("{} got lead poisoning from {}'s Chaingun", victim, killer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_obituaries() {
+        let rail = MeansOfDeath::Weapon(Weapon::Railgun);
+        assert_eq!(
+            rail.obituary(Some("Sarge"), "Doom"),
+            "Doom was railed by Sarge"
+        );
+        assert_eq!(
+            MeansOfDeath::Weapon(Weapon::RocketLauncher).obituary(None, "Doom"),
+            "Doom blew themselves up"
+        );
+        assert_eq!(
+            MeansOfDeath::Telefrag.obituary(Some("Sarge"), "Doom"),
+            "Doom tried to invade Sarge's personal space"
+        );
+        // World deaths never credit anyone, even if a killer is passed along.
+        assert_eq!(
+            MeansOfDeath::Falling.obituary(Some("Sarge"), "Doom"),
+            "Doom cratered"
+        );
+        assert_eq!(
+            MeansOfDeath::Lava.obituary(None, "Doom"),
+            "Doom does a back flip into the lava"
+        );
+        assert!(MeansOfDeath::Drowned.is_world());
+        assert!(!MeansOfDeath::Telefrag.is_world());
+        assert!(!rail.is_world());
+    }
+}
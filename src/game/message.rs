@@ -1,3 +1,4 @@
+use super::means_of_death::MeansOfDeath;
 use macroquad::prelude::*;
 
 #[derive(Clone, Debug)]
@@ -16,21 +17,8 @@ impl GameMessage {
         }
     }
 
-    pub fn kill_message(killer: &str, victim: &str, weapon: u8) -> Self {
-        let weapon_text = match weapon {
-            0 => "pummeled",
-            1 => "machinegunned",
-            2 => "gunned down",
-            3 => "shredded by shrapnel from",
-            4 => "was blasted by",
-            5 => "electrocuted by",
-            6 => "railed by",
-            7 => "melted by plasmagun of",
-            8 => "blasted by BFG of",
-            _ => "killed by",
-        };
-
-        let text = format!("{} was {} {}", victim, weapon_text, killer);
+    pub fn obituary(killer: Option<&str>, victim: &str, means: MeansOfDeath) -> Self {
+        let text = means.obituary(killer, victim);
         Self::new(text, Color::from_rgba(255, 255, 100, 255))
     }
 
@@ -1,3 +1,4 @@
+use crate::{log_debug, log_info, log_trace, log_warn};
 pub mod animation;
 pub mod award;
 pub mod award_shader;
@@ -7,31 +8,44 @@ pub mod bg_pmove;
 pub mod bot_ai;
 pub mod collision;
 pub mod constants;
+pub mod ctf;
 pub mod damage_number;
 pub mod deferred_renderer;
 pub mod defrag;
+pub mod duel_queue;
 pub mod file_loader;
+pub mod freeze;
+pub mod gametype;
 pub mod gib;
+pub mod grapple;
 pub mod hitscan;
 pub mod item_model;
 pub mod light;
 pub mod light_grid;
 pub mod lightmap;
 pub mod lightning;
+pub mod liquid;
 pub mod liquid_blood;
 pub mod map;
 pub mod map_loader;
+pub mod match_rules;
+pub mod match_stats;
 pub mod md3;
 pub mod md3_anim;
 pub mod md3_pose;
 pub mod md3_render;
+pub mod means_of_death;
 pub mod message;
 pub mod model_cache;
 pub mod model_shader;
+pub mod mutators;
 pub mod muzzle;
 pub mod nav_graph;
 pub mod nav_graph_generator;
 pub mod particle;
+pub mod pause;
+pub mod physics;
+pub mod pickup;
 pub mod player;
 pub mod player_model;
 pub mod procedural_tiles;
@@ -39,6 +53,7 @@ pub mod projectile;
 pub mod projectile_model_cache;
 pub mod q3_shader_parser;
 pub mod railgun;
+pub mod round;
 pub mod shader;
 pub mod skin_loader;
 pub mod smoke;
@@ -50,15 +65,20 @@ pub mod tile_rendering;
 pub mod tile_shader;
 pub mod tile_shader_materials;
 pub mod tile_textures;
+pub mod tournament;
 pub mod trail;
 pub mod usercmd;
+pub mod warmup;
 pub mod weapon;
+pub mod weapon_defs;
 pub mod weapon_hit_effect;
 pub mod weapon_model_cache;
 
 use crate::audio::events::AudioEventQueue;
 use crate::network::{NetHud, NetworkClient, NetworkConfig};
+use bg_combat::DamageRules;
 use macroquad::prelude::*;
+use means_of_death::{MeansOfDeath, WORLD_ID};
 
 pub struct Corpse {
     pub player: player::Player,
@@ -84,10 +104,23 @@ pub struct GameState {
     pub pending_hits: Vec<(usize, i32, f32, f32, u16)>,
     pub messages: Vec<message::GameMessage>,
     pub map: map::Map,
+    /// Items as the map placed them, before mutators filtered them.
+    map_items: Vec<map::Item>,
+    pub map_name: String,
     pub time: f64,
     pub frame: u64,
     pub match_time: f32,
     pub time_limit: f32,
+    pub gametype: Box<dyn gametype::Gametype>,
+    pub mutators: mutators::Mutators,
+    pub physics: physics::PhysicsProfile,
+    pub ctf: ctf::CtfState,
+    pub round: round::RoundState,
+    pub warmup: warmup::WarmupState,
+    pub pause: pause::PauseState,
+    pub match_rules: match_rules::MatchRules,
+    pub match_stats: match_stats::MatchStats,
+    pub show_match_stats: bool,
     pub model_cache: model_cache::ModelCache,
     pub item_model_cache: item_model::ItemModelCache,
     pub weapon_model_cache: weapon_model_cache::WeaponModelCache,
@@ -157,10 +190,565 @@ impl GameState {
 
     pub fn end_match(&mut self) {
         if !self.game_results.show {
-            self.game_results.trigger(&self.players, self.match_time);
+            let team_scores = self.team_scores();
+            self.game_results.trigger(
+                &self.players,
+                self.match_time,
+                self.gametype.as_ref(),
+                &team_scores,
+            );
+            if !self.is_multiplayer {
+                self.write_match_stats();
+            }
+        }
+    }
+
+    /// Saves the local match to `g_statsdir`; servers write their own.
+    fn write_match_stats(&mut self) {
+        for player in &self.players {
+            self.match_stats
+                .set_player_info(player.id, &player.name, player.team, player.frags);
+        }
+        let dir = crate::cvar::get_cvar_string("g_statsdir");
+        if dir.is_empty() {
+            return;
+        }
+        match self.match_stats.write_json(
+            &dir,
+            &self.map_name,
+            self.gametype.kind().short_name(),
+            self.match_time,
+        ) {
+            Ok(path) => log_info!(Game, "Match stats written to {}", path),
+            Err(e) => log_warn!(Game, "Failed to write match stats: {}", e),
+        }
+    }
+
+    pub fn team_scores(&self) -> gametype::TeamScores {
+        if self.gametype.uses_flags() {
+            return self.ctf.captures;
+        }
+        if self.gametype.is_round_based() {
+            return self.round.wins;
+        }
+        gametype::TeamScores::from_entries(&gametype::ScoreEntry::from_players(&self.players))
+    }
+
+    fn update_flags(&mut self, dt: f32) {
+        let players: Vec<ctf::CtfPlayer> = self
+            .players
+            .iter()
+            .map(|p| ctf::CtfPlayer {
+                id: p.id,
+                team: p.team,
+                x: p.x,
+                y: p.y,
+                alive: !p.dead,
+            })
+            .collect();
+        let events = self.ctf.update(dt, &players);
+        self.handle_flag_events(&events);
+    }
+
+    fn handle_flag_events(&mut self, events: &[ctf::FlagEvent]) {
+        let local_player = if self.is_multiplayer {
+            let id = self.network_client.as_ref().and_then(|c| c.player_id());
+            self.players.iter().find(|p| Some(p.id) == id)
+        } else {
+            self.players.first()
+        };
+        let local_id = local_player.map(|p| p.id);
+        let local_team = local_player.map_or(gametype::TEAM_FREE, |p| p.team);
+
+        for event in events {
+            let text = event.message(|id| {
+                self.players
+                    .iter()
+                    .find(|p| p.id == id)
+                    .map_or_else(|| format!("Player{}", id), |p| p.name.clone())
+            });
+            log_info!(Game, "{}", text);
+
+            let team = match *event {
+                ctf::FlagEvent::Taken { team, .. }
+                | ctf::FlagEvent::Dropped { team, .. }
+                | ctf::FlagEvent::Returned { team, .. }
+                | ctf::FlagEvent::Captured { team, .. } => team,
+            };
+            self.messages
+                .push(message::GameMessage::new(text, ctf::team_color(team)));
+
+            if let Some(announcement) = event.announcement(local_id, local_team) {
+                self.audio_events
+                    .push(crate::audio::events::AudioEvent::CtfAnnouncement {
+                        announcement: announcement.to_string(),
+                    });
+            }
+        }
+    }
+
+    fn update_rounds(&mut self, dt: f32) {
+        let players: Vec<round::RoundPlayer> = self
+            .players
+            .iter()
+            .map(|p| round::RoundPlayer {
+                team: p.team,
+                standing: !p.dead,
+            })
+            .collect();
+        let events = self.round.update(dt, &players);
+        self.handle_round_events(&events);
+    }
+
+    fn handle_round_events(&mut self, events: &[round::RoundEvent]) {
+        for event in events {
+            if let round::RoundEvent::NewRound(_) = event {
+                if !self.is_multiplayer {
+                    self.respawn_for_round();
+                }
+            }
+
+            if let Some(text) = event.message() {
+                log_info!(Game, "{}", text);
+                let team = match *event {
+                    round::RoundEvent::Won { team, .. } | round::RoundEvent::MatchWon(team) => {
+                        team.unwrap_or(gametype::TEAM_FREE)
+                    }
+                    _ => gametype::TEAM_FREE,
+                };
+                self.messages
+                    .push(message::GameMessage::new(text, ctf::team_color(team)));
+            }
+
+            if let Some(announcement) = event.announcement() {
+                self.audio_events
+                    .push(crate::audio::events::AudioEvent::RoundAnnouncement {
+                        announcement: announcement.to_string(),
+                    });
+            }
+
+            if let round::RoundEvent::MatchWon(_) = event {
+                self.end_match();
+            }
+        }
+    }
+
+    fn handle_warmup_events(&mut self, events: &[warmup::WarmupEvent]) {
+        for event in events {
+            if let Some(text) = event.message() {
+                log_info!(Game, "{}", text);
+                self.messages.push(message::GameMessage::new(text, YELLOW));
+            }
+            if let Some(announcement) = event.announcement() {
+                self.audio_events
+                    .push(crate::audio::events::AudioEvent::RoundAnnouncement {
+                        announcement: announcement.to_string(),
+                    });
+            }
+            if *event == warmup::WarmupEvent::Started {
+                self.match_time = 0.0;
+                self.match_rules.reset();
+                self.match_stats.reset();
+                self.show_match_stats = false;
+                self.map.reset_items();
+                self.projectiles.clear();
+                self.game_results.show = false;
+                self.lead_announcements.reset();
+                self.time_announcements = award::TimeAnnouncement::new();
+                self.time_announcements.announced_fight = true;
+            }
         }
     }
 
+    fn handle_limit_events(&mut self, events: &[match_rules::LimitEvent]) {
+        for event in events {
+            if let Some(text) = event.message() {
+                log_info!(Game, "{}", text);
+                self.messages.push(message::GameMessage::new(text, YELLOW));
+            }
+            if let Some(announcement) = event.announcement() {
+                self.audio_events
+                    .push(crate::audio::events::AudioEvent::TimeAnnouncement {
+                        announcement: announcement.to_string(),
+                    });
+            }
+            if let match_rules::LimitEvent::MatchEnded(_) = event {
+                self.end_match();
+            }
+        }
+    }
+
+    /// The server has frozen the match; nothing simulates or predicts.
+    pub fn is_paused(&self) -> bool {
+        self.is_multiplayer && self.pause.is_paused()
+    }
+
+    /// Asks the server to call a timeout, or to end ours.
+    pub fn request_pause(&mut self, pause: bool) -> Result<(), String> {
+        if !self.is_multiplayer {
+            return Err("timeouts only apply on a server".to_string());
+        }
+        self.send_chat(if pause { "timeout" } else { "timein" }.to_string())
+    }
+
+    /// Asks the server to toggle our ready state during warmup.
+    pub fn toggle_ready(&mut self) -> Result<(), String> {
+        if !self.is_multiplayer {
+            return Err("ready only applies on a server".to_string());
+        }
+        if self.warmup.is_live() {
+            return Err("the match is already live".to_string());
+        }
+        self.send_chat("ready".to_string())
+    }
+
+    /// Turns fresh deaths into ice blocks and, when running locally, thaws
+    /// players that have a teammate standing next to them.
+    fn update_freeze(&mut self, dt: f32) {
+        let mut newly_frozen = Vec::new();
+        for player in &mut self.players {
+            if player.dead && !player.frozen {
+                player.freeze();
+                newly_frozen.push(player.id);
+            }
+        }
+        self.corpses
+            .retain(|c| !newly_frozen.contains(&c.player.id));
+
+        if self.is_multiplayer {
+            return;
+        }
+
+        let mut freeze_players: Vec<freeze::FreezePlayer> = self
+            .players
+            .iter()
+            .map(|p| freeze::FreezePlayer {
+                id: p.id,
+                team: p.team,
+                x: p.x,
+                y: p.y,
+                frozen: p.frozen,
+                alive: !p.dead,
+                thaw_progress: p.thaw_progress,
+            })
+            .collect();
+        let events = freeze::update_thaw(dt, &mut freeze_players);
+        for (player, fp) in self.players.iter_mut().zip(&freeze_players) {
+            player.thaw_progress = fp.thaw_progress;
+        }
+
+        for event in events {
+            let name_of = |id: u16| {
+                self.players
+                    .iter()
+                    .find(|p| p.id == id)
+                    .map_or_else(|| format!("Player{}", id), |p| p.name.clone())
+            };
+            let text = format!(
+                "{} was thawed by {}",
+                name_of(event.player_id),
+                name_of(event.thawed_by)
+            );
+            log_info!(Game, "{}", text);
+
+            if let Some(player) = self.players.iter_mut().find(|p| p.id == event.player_id) {
+                player.thaw();
+                self.messages.push(message::GameMessage::new(
+                    text,
+                    ctf::team_color(player.team),
+                ));
+                self.audio_events
+                    .push(crate::audio::events::AudioEvent::ItemPickup { x: player.x });
+            }
+        }
+    }
+
+    /// The gametype's loadout with the active mutators layered on top.
+    pub fn spawn_loadout(&self) -> Option<gametype::Loadout> {
+        self.mutators.spawn_loadout(self.gametype.spawn_loadout())
+    }
+
+    /// Fires the player's holdable. The teleporter drops them on a spawn point
+    /// chosen the same way as a respawn.
+    pub fn use_holdable(&mut self, player_idx: usize) {
+        use crate::audio::events::AudioEvent;
+        let Some(player) = self.players.get_mut(player_idx) else {
+            return;
+        };
+        if player.dead {
+            return;
+        }
+        match pickup::use_holdable(player) {
+            Some(pickup::Holdable::Medkit) => {
+                let x = player.x;
+                self.audio_events.push(AudioEvent::ItemPickup { x });
+            }
+            Some(pickup::Holdable::Teleporter) => {
+                let (id, team) = (player.id, player.team);
+                let others: Vec<&player::Player> = self
+                    .players
+                    .iter()
+                    .filter(|p| p.id != id && !p.dead)
+                    .collect();
+                let enemies: Vec<(f32, f32)> = others
+                    .iter()
+                    .filter(|p| p.team == gametype::TEAM_FREE || p.team != team)
+                    .map(|p| (p.x, p.y))
+                    .collect();
+                let occupied: Vec<(f32, f32)> = others.iter().map(|p| (p.x, p.y)).collect();
+                let query = gametype::SpawnQuery {
+                    team,
+                    enemies: &enemies,
+                    occupied: &occupied,
+                    avoid: None,
+                    roll: rand::rand() as usize,
+                };
+                let Some(spawn_idx) = self.gametype.select_spawn(&self.map.spawn_points, &query)
+                else {
+                    return;
+                };
+                let spawn = &self.map.spawn_points[spawn_idx];
+                let player = &mut self.players[player_idx];
+                self.audio_events
+                    .push(AudioEvent::TeleportIn { x: player.x });
+                player.x = spawn.x;
+                player.y = spawn.y;
+                player.vel_x = 0.0;
+                player.vel_y = 0.0;
+                self.audio_events
+                    .push(AudioEvent::TeleportOut { x: spawn.x });
+                self.telefrag(player_idx);
+            }
+            None => {}
+        }
+    }
+
+    /// Puts every player back on a spawn point with the round loadout.
+    fn respawn_for_round(&mut self) {
+        let spawn_points = self.map.spawn_points.clone();
+        let loadout = self.spawn_loadout();
+        let mut taken = Vec::new();
+        for player in &mut self.players {
+            let query = gametype::SpawnQuery {
+                team: player.team,
+                occupied: &taken,
+                roll: rand::rand() as usize,
+                ..Default::default()
+            };
+            let Some(spawn_idx) = self.gametype.select_spawn(&spawn_points, &query) else {
+                continue;
+            };
+            let spawn = &spawn_points[spawn_idx];
+            player.spawn(spawn.x, spawn.y, &self.map);
+            taken.push((player.x, player.y));
+            if let Some(loadout) = &loadout {
+                player.apply_loadout(loadout);
+            }
+        }
+        self.projectiles.clear();
+        self.map.items.retain(|item| !item.dropped);
+    }
+
+    /// Alive teammate to follow while eliminated in a round-based game.
+    pub fn spectate_target(&self, player_id: u16) -> Option<&player::Player> {
+        if !self.gametype.is_round_based() {
+            return None;
+        }
+        let team = self.players.iter().find(|p| p.id == player_id)?.team;
+        self.players
+            .iter()
+            .find(|p| p.id != player_id && p.team == team && !p.dead)
+    }
+
+    /// Gibs everyone the player at `player_idx` has just appeared on top of.
+    fn telefrag(&mut self, player_idx: usize) {
+        if self.is_multiplayer {
+            return;
+        }
+        let (killer_id, killer_name) = (
+            self.players[player_idx].id,
+            self.players[player_idx].name.clone(),
+        );
+        let position = (self.players[player_idx].x, self.players[player_idx].y);
+        for idx in 0..self.players.len() {
+            let victim = &mut self.players[idx];
+            if idx == player_idx
+                || victim.dead
+                || !gametype::bodies_overlap(position, (victim.x, victim.y))
+            {
+                continue;
+            }
+            victim.telefrag();
+            let (victim_id, victim_name, x, y) =
+                (victim.id, victim.name.clone(), victim.x, victim.y);
+            self.audio_events
+                .push(crate::audio::events::AudioEvent::PlayerGib { x });
+            self.gibs.extend(gib::spawn_gibs(x, y));
+            self.match_stats
+                .record_death(killer_id, victim_id, MeansOfDeath::Telefrag);
+            self.apply_kill_score(killer_id, victim_id);
+            self.messages.push(message::GameMessage::obituary(
+                Some(&killer_name),
+                &victim_name,
+                MeansOfDeath::Telefrag,
+            ));
+        }
+    }
+
+    /// Hurts a player with something other than a weapon. Deaths cost the
+    /// victim a frag.
+    fn world_damage(
+        &mut self,
+        player_idx: usize,
+        damage: i32,
+        means: MeansOfDeath,
+        rules: &DamageRules,
+    ) {
+        let player = &mut self.players[player_idx];
+        if player.dead || damage <= 0 {
+            return;
+        }
+        let (died, gibbed, _) = player.take_damage(damage, WORLD_ID, false, rules);
+        let (id, name, x, y) = (player.id, player.name.clone(), player.x, player.y);
+        if !died {
+            self.audio_events
+                .push(crate::audio::events::AudioEvent::PlayerPain {
+                    health: player.health,
+                    x,
+                    model: player.model.clone(),
+                });
+            return;
+        }
+        if gibbed {
+            self.audio_events
+                .push(crate::audio::events::AudioEvent::PlayerGib { x });
+            self.gibs.extend(gib::spawn_gibs(x, y));
+        } else {
+            self.audio_events
+                .push(crate::audio::events::AudioEvent::PlayerDeath {
+                    x,
+                    model: player.model.clone(),
+                });
+            self.corpses.push(Corpse {
+                player: player.clone(),
+                lifetime: 10.0,
+            });
+        }
+        self.match_stats.record_death(id, id, means);
+        self.apply_kill_score(id, id);
+        self.messages
+            .push(message::GameMessage::obituary(None, &name, means));
+    }
+
+    /// Landing damage, players squeezed into solid tiles, drowning and
+    /// burning in slime or lava.
+    fn apply_world_damage(&mut self, dt: f32, rules: &DamageRules) {
+        let scale = crate::cvar::get_cvar_float("g_fallingdamage").max(0.0);
+        for idx in 0..self.players.len() {
+            let player = &mut self.players[idx];
+            let fall_damage = std::mem::take(&mut player.fall_damage);
+            if player.dead {
+                continue;
+            }
+            let liquid_damage = player.breath.update(player.water_level, player.liquid, dt);
+            let x = player.x;
+            if collision::is_crushed(player.x, player.y, &self.map) {
+                self.world_damage(
+                    idx,
+                    constants::WORLD_KILL_DAMAGE,
+                    MeansOfDeath::Crushed,
+                    rules,
+                );
+                continue;
+            } else if fall_damage > 0 {
+                let damage = (fall_damage as f32 * scale).round() as i32;
+                self.world_damage(idx, damage, MeansOfDeath::Falling, rules);
+            }
+            if let Some((damage, means)) = liquid_damage {
+                if means == MeansOfDeath::Drowned {
+                    self.audio_events
+                        .push(crate::audio::events::AudioEvent::Drowning { x });
+                }
+                self.world_damage(idx, damage, means, rules);
+            }
+        }
+    }
+
+    /// Kills our own player, or asks the server to.
+    pub fn suicide(&mut self) -> Result<(), String> {
+        if self.is_multiplayer {
+            return self.send_chat("kill".to_string());
+        }
+        match self.players.first() {
+            None => return Err("no player to kill".to_string()),
+            Some(player) if player.dead => return Err("already dead".to_string()),
+            Some(_) => {}
+        }
+        let rules = DamageRules::from_cvars(self.mutators);
+        self.world_damage(
+            0,
+            constants::WORLD_KILL_DAMAGE,
+            MeansOfDeath::Suicide,
+            &rules,
+        );
+        Ok(())
+    }
+
+    fn apply_kill_score(&mut self, killer_id: u16, victim_id: u16) {
+        let entries = gametype::ScoreEntry::from_players(&self.players);
+        let killer = entries.iter().find(|e| e.id == killer_id);
+        let victim = entries.iter().find(|e| e.id == victim_id);
+        if let (Some(killer), Some(victim)) = (killer, victim) {
+            let delta = self.gametype.kill_score(killer, victim);
+            if let Some(player) = self.players.iter_mut().find(|p| p.id == killer_id) {
+                player.frags += delta;
+            }
+        }
+    }
+
+    fn combatant(&self, id: u16) -> bg_combat::Combatant {
+        self.players
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.combatant())
+            .unwrap_or(bg_combat::Combatant::outsider(id))
+    }
+
+    fn between_rounds(&self) -> bool {
+        self.gametype.is_round_based() && !self.round.is_active()
+    }
+
+    fn teammates(&self, attacker_id: u16, target_id: u16) -> bool {
+        bg_combat::teammates(
+            &*self.gametype,
+            self.combatant(attacker_id),
+            self.combatant(target_id),
+        )
+    }
+
+    fn damage_allowed(&self, attacker_id: u16, target_id: u16, rules: &DamageRules) -> bool {
+        bg_combat::damage_allowed(
+            &*self.gametype,
+            self.combatant(attacker_id),
+            self.combatant(target_id),
+            self.between_rounds(),
+            rules,
+        )
+    }
+
+    /// Players `attacker_id` cannot hurt, and its teammates.
+    fn damage_groups(&self, attacker_id: u16, rules: &DamageRules) -> (Vec<u16>, Vec<u16>) {
+        let combatants: Vec<bg_combat::Combatant> =
+            self.players.iter().map(|p| p.combatant()).collect();
+        bg_combat::damage_groups(
+            &*self.gametype,
+            attacker_id,
+            &combatants,
+            self.between_rounds(),
+            rules,
+        )
+    }
+
     pub fn add_damage_number(
         &mut self,
         player_id: u32,
@@ -250,6 +838,17 @@ impl GameState {
         }
         self.network_client = None;
         self.is_multiplayer = false;
+        weapon_defs::restore_local();
+        self.mutators = mutators::Mutators::from_cvar();
+        self.physics = physics::PhysicsProfile::from_cvars();
+        self.mutators.apply_physics(&mut self.physics);
+        self.filter_map_items();
+    }
+
+    /// Rebuilds the map's items from its original list for the current mutators.
+    fn filter_map_items(&mut self) {
+        self.map.items = self.map_items.clone();
+        self.mutators.filter_items(&mut self.map.items);
     }
 
     pub fn send_chat(&mut self, message: String) -> Result<(), String> {
@@ -291,9 +890,9 @@ impl GameState {
                 | NetMessage::Heartbeat
                 | NetMessage::Acknowledgement { .. }
         ) {
-            println!(
-                "[{:.3}] [CLIENT] Received: {:?}",
-                macroquad::prelude::get_time(),
+            log_debug!(
+                Net,
+                "Received: {:?}",
                 match &msg {
                     NetMessage::PlayerDied {
                         player_id,
@@ -318,25 +917,151 @@ impl GameState {
                 accepted, reason, ..
             } => {
                 if accepted {
-                    println!("Connected to server");
+                    log_info!(Net, "Connected to server");
                 } else {
-                    println!("Connection rejected: {}", reason);
+                    log_info!(Net, "Connection rejected: {}", reason);
                     self.disconnect_from_server();
                 }
             }
+            NetMessage::ServerInfo {
+                gametype,
+                mutators,
+                weapons_checksum,
+                weapons,
+                physics,
+                ..
+            } => {
+                self.physics = physics;
+                match weapons.map(weapon_defs::WeaponTable::checked) {
+                    Some(Ok(table)) if table.checksum() != weapon_defs::checksum() => {
+                        log_info!(Game, "Using the server's weapon table");
+                        weapon_defs::install(table);
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        log_warn!(Net, "Server sent a bad weapon table: {}", e);
+                        self.disconnect_from_server();
+                        return;
+                    }
+                    None if weapons_checksum != weapon_defs::checksum() => log_warn!(
+                        Game,
+                        "Weapon table differs from the server's ({:08x}, server {:08x})",
+                        weapon_defs::checksum(),
+                        weapons_checksum
+                    ),
+                    None => {}
+                }
+                match gametype::GametypeKind::from_u8(gametype) {
+                    Some(kind) => {
+                        log_info!(Game, "Server gametype: {}", kind.name());
+                        self.gametype = gametype::create(kind);
+                        self.time_limit = self.gametype.time_limit();
+                    }
+                    None => log_warn!(Game, "Unknown server gametype {}", gametype),
+                }
+                let mutators = mutators::Mutators::from_bits(mutators);
+                if mutators != self.mutators {
+                    log_info!(Game, "Server mutators: {}", mutators.names());
+                    self.mutators = mutators;
+                    self.filter_map_items();
+                }
+            }
+            NetMessage::FlagStatus {
+                flags,
+                red_captures,
+                blue_captures,
+                events,
+            } => {
+                self.ctf.flags = flags;
+                self.ctf.captures = gametype::TeamScores {
+                    red: red_captures,
+                    blue: blue_captures,
+                };
+                self.handle_flag_events(&events);
+            }
+            NetMessage::RoundStatus {
+                phase,
+                round,
+                red_wins,
+                blue_wins,
+                events,
+            } => {
+                self.round.phase = phase;
+                self.round.round = round;
+                self.round.wins = gametype::TeamScores {
+                    red: red_wins,
+                    blue: blue_wins,
+                };
+                self.handle_round_events(&events);
+            }
+            NetMessage::MatchStatus {
+                phase,
+                ready,
+                min_ready,
+                events,
+            } => {
+                self.warmup.phase = phase;
+                self.warmup.ready = ready;
+                self.warmup.min_ready = min_ready as usize;
+                self.handle_warmup_events(&events);
+            }
+            NetMessage::LimitStatus {
+                match_time,
+                overtime,
+                sudden_death,
+                ended,
+                events,
+            } => {
+                if ended.is_none() && self.match_rules.ended.is_some() {
+                    self.game_results.show = false;
+                    self.match_stats.reset();
+                    self.show_match_stats = false;
+                    self.lead_announcements.reset();
+                    self.time_announcements = award::TimeAnnouncement::new();
+                }
+                self.match_time = match_time;
+                self.match_rules.overtime = overtime;
+                self.match_rules.sudden_death = sudden_death;
+                self.match_rules.ended = ended;
+                self.handle_limit_events(&events);
+            }
+            NetMessage::MatchStats { players } => {
+                self.match_stats.set_players(players);
+            }
+            NetMessage::PauseStatus { phase, events } => {
+                self.pause.phase = phase;
+                for event in &events {
+                    if let Some(announcement) = event.announcement() {
+                        self.audio_events.push(
+                            crate::audio::events::AudioEvent::RoundAnnouncement {
+                                announcement: announcement.to_string(),
+                            },
+                        );
+                    }
+                }
+            }
             NetMessage::MapChange { map_name } => {
-                println!("Server changing map to: {}", map_name);
+                log_info!(Net, "Server changing map to: {}", map_name);
+                self.map_name = map_name.clone();
                 self.map = map::Map::load_from_file(&map_name).unwrap_or_else(|e| {
-                    eprintln!("Failed to load map '{}': {}", map_name, e);
+                    log_warn!(Game, "Failed to load map '{}': {}", map_name, e);
                     map::Map::new(&map_name)
                 });
+                self.map_items = self.map.items.clone();
+                self.filter_map_items();
+                self.ctf = ctf::CtfState::new(&self.map.flags);
+                self.round.reset();
                 self.players.clear();
                 self.projectiles.clear();
                 self.particles.clear();
                 self.gibs.clear();
                 self.smokes.clear();
                 self.lights.clear();
-                println!("Map loaded: {} spawn points", self.map.spawn_points.len());
+                log_info!(
+                    Game,
+                    "Map loaded: {} spawn points",
+                    self.map.spawn_points.len()
+                );
             }
             NetMessage::PlayerRespawn {
                 player_id: _player_id,
@@ -354,7 +1079,20 @@ impl GameState {
                             player.model = "sarge".to_string();
                             player.should_interpolate = false;
                             self.players.push(player);
-                            println!("Local player spawned at ({}, {})", position.0, position.1);
+                            log_info!(
+                                Game,
+                                "Local player spawned at ({}, {})",
+                                position.0,
+                                position.1
+                            );
+                        } else if let Some(player) = self
+                            .players
+                            .iter_mut()
+                            .find(|p| p.id == _player_id && p.frozen)
+                        {
+                            player.x = position.0;
+                            player.y = position.1;
+                            player.thaw();
                         }
                     } else {
                         if let Some(player) = self.players.iter_mut().find(|p| p.id == _player_id) {
@@ -364,11 +1102,16 @@ impl GameState {
                             player.prev_y = position.1;
                             player.dead = false;
                             player.gibbed = false;
+                            player.frozen = false;
+                            player.thaw_progress = 0.0;
                             player.health = 100;
                             player.should_interpolate = false;
-                            println!(
+                            log_info!(
+                                Game,
                                 "Remote player {} respawned at ({}, {})",
-                                _player_id, position.0, position.1
+                                _player_id,
+                                position.0,
+                                position.1
                             );
                         } else {
                             let mut player = player::Player::new(
@@ -381,9 +1124,12 @@ impl GameState {
                             player.model = "visor".to_string();
                             player.should_interpolate = false;
                             self.players.push(player);
-                            println!(
+                            log_info!(
+                                Game,
                                 "Remote player {} spawned at ({}, {})",
-                                _player_id, position.0, position.1
+                                _player_id,
+                                position.0,
+                                position.1
                             );
                         }
                     }
@@ -437,9 +1183,9 @@ impl GameState {
                         player.vel_x = knockback_x;
                         player.vel_y = knockback_y;
 
-                        println!(
-                            "[{:.3}] [CLIENT] Knockback applied: ({:.2},{:.2}) -> ({:.2},{:.2})",
-                            macroquad::prelude::get_time(),
+                        log_debug!(
+                            Game,
+                            "Knockback applied: ({:.2},{:.2}) -> ({:.2},{:.2})",
                             vel_before.0,
                             vel_before.1,
                             player.vel_x,
@@ -498,7 +1244,7 @@ impl GameState {
                 }
             }
             NetMessage::Chat { message, .. } => {
-                println!("[Chat] {}", message);
+                log_info!(Game, "{}", message);
             }
             NetMessage::PlayerDied {
                 player_id,
@@ -506,18 +1252,42 @@ impl GameState {
                 gibbed,
                 position,
                 velocity,
+                means,
             } => {
-                println!(
-                    "[{:.3}] Player {} was killed by {} (gibbed: {})",
-                    macroquad::prelude::get_time(),
+                log_info!(
+                    Game,
+                    "Player {} was killed by {} (gibbed: {})",
                     player_id,
                     killer_id,
                     gibbed
                 );
 
+                let name_of = |id: u16| {
+                    self.players
+                        .iter()
+                        .find(|p| p.id == id)
+                        .map(|p| p.name.clone())
+                };
+                if let Some(victim_name) = name_of(player_id) {
+                    let killer_name = name_of(killer_id).filter(|_| killer_id != player_id);
+                    self.messages.push(message::GameMessage::obituary(
+                        killer_name.as_deref(),
+                        &victim_name,
+                        means,
+                    ));
+                }
+
                 if let Some(player) = self.players.iter_mut().find(|p| p.id == player_id) {
-                    println!("[{:.3}] [CLIENT] Setting player {} dead=true, gibbed={}, pos=({:.1},{:.1}), vel=({:.2},{:.2})", 
-                        macroquad::prelude::get_time(), player_id, gibbed, position.0, position.1, velocity.0, velocity.1);
+                    log_debug!(
+                        Game,
+                        "Setting player {} dead=true, gibbed={}, pos=({:.1},{:.1}), vel=({:.2},{:.2})",
+                        player_id,
+                        gibbed,
+                        position.0,
+                        position.1,
+                        velocity.0,
+                        velocity.1
+                    );
 
                     if !gibbed {
                         let corpse_player = player.clone();
@@ -566,9 +1336,9 @@ impl GameState {
                 player_id,
                 position,
             } => {
-                println!(
-                    "[{:.3}] Player {} was gibbed at ({:.1}, {:.1})",
-                    macroquad::prelude::get_time(),
+                log_info!(
+                    Game,
+                    "Player {} was gibbed at ({:.1}, {:.1})",
                     player_id,
                     position.0,
                     position.1
@@ -594,18 +1364,13 @@ impl GameState {
                 }
             }
             NetMessage::Disconnect { player_id, reason } => {
-                println!(
-                    "[{:.3}] Player {} disconnected: {}",
-                    macroquad::prelude::get_time(),
-                    player_id,
-                    reason
-                );
+                log_info!(Game, "Player {} disconnected: {}", player_id, reason);
 
                 self.players.retain(|p| p.id != player_id);
 
-                println!(
-                    "[{:.3}] Removed player {}, {} players remain",
-                    macroquad::prelude::get_time(),
+                log_info!(
+                    Game,
+                    "Removed player {}, {} players remain",
                     player_id,
                     self.players.len()
                 );
@@ -632,7 +1397,7 @@ impl GameState {
                         let error = (error_x * error_x + error_y * error_y).sqrt();
 
                         if error > 50.0 {
-                            println!("[CLIENT] Large error {:.1}px, snapping to server", error);
+                            log_debug!(Net, "Large error {:.1}px, snapping to server", error);
                             player.x = net_player.position.0;
                             player.y = net_player.position.1;
                             player.vel_x = net_player.velocity.0;
@@ -643,7 +1408,10 @@ impl GameState {
                         player.armor = net_player.armor;
                         player.frags = net_player.frags;
                         player.deaths = net_player.deaths;
-                        player.powerups.quad = net_player.powerup_quad;
+                        player.team = net_player.team;
+                        player.powerups = net_player.powerups;
+                        player.holdable = net_player.holdable;
+                        player.thaw_progress = net_player.thaw_progress;
                     }
                     continue;
                 }
@@ -653,6 +1421,10 @@ impl GameState {
                     .iter_mut()
                     .find(|p| p.id == net_player.player_id)
                 {
+                    player.thaw_progress = net_player.thaw_progress;
+                    if net_player.is_frozen && !player.frozen {
+                        player.freeze();
+                    }
                     if player.dead || net_player.is_dead {
                         continue;
                     }
@@ -662,7 +1434,10 @@ impl GameState {
                     player.weapon = unsafe { std::mem::transmute(net_player.weapon) };
                     player.frags = net_player.frags;
                     player.deaths = net_player.deaths;
-                    player.powerups.quad = net_player.powerup_quad;
+                    player.team = net_player.team;
+                    player.powerups = net_player.powerups;
+                    player.holdable = net_player.holdable;
+                    player.grapple = net_player.grapple;
                     player.crouch = net_player.is_crouching;
                     player.refire = if net_player.is_attacking { 0.1 } else { 0.0 };
                     player.should_interpolate = true;
@@ -682,8 +1457,10 @@ impl GameState {
                     player.angle = net_player.angle;
                     player.health = net_player.health;
                     player.armor = net_player.armor;
+                    player.team = net_player.team;
                     player.was_in_air = !net_player.on_ground;
                     player.crouch = net_player.is_crouching;
+                    player.grapple = net_player.grapple;
                     player.model = "visor".to_string();
                     player.should_interpolate = false;
                     self.players.push(player);
@@ -699,8 +1476,9 @@ impl GameState {
         static mut LAST_SYNC_CALL: f64 = 0.0;
         unsafe {
             if macroquad::prelude::get_time() - LAST_SYNC_CALL > 2.0 {
-                println!(
-                    "[SYNC] sync_projectiles called with {} network projectiles, have {} local",
+                log_debug!(
+                    Net,
+                    "sync_projectiles called with {} network projectiles, have {} local",
                     network_projectiles.len(),
                     self.projectiles.len()
                 );
@@ -746,8 +1524,13 @@ impl GameState {
                 static mut LAST_CREATE_PRINT: f64 = 0.0;
                 unsafe {
                     if macroquad::prelude::get_time() - LAST_CREATE_PRINT > 1.0 {
-                        println!("[SYNC] Creating NEW projectile {} from snapshot (owner={} weapon={:?})", 
-                            proj.id, net_proj.owner_id, weapon);
+                        log_debug!(
+                            Net,
+                            "Creating NEW projectile {} from snapshot (owner={} weapon={:?})",
+                            proj.id,
+                            net_proj.owner_id,
+                            weapon
+                        );
                         LAST_CREATE_PRINT = macroquad::prelude::get_time();
                     }
                 }
@@ -766,7 +1549,7 @@ impl GameState {
                 static mut LAST_REMOVE_PRINT: f64 = 0.0;
                 unsafe {
                     if macroquad::prelude::get_time() - LAST_REMOVE_PRINT > 1.0 {
-                        println!("[SYNC] Removing projectile {} (not in snapshot)", p.id);
+                        log_debug!(Net, "Removing projectile {} (not in snapshot)", p.id);
                         LAST_REMOVE_PRINT = macroquad::prelude::get_time();
                     }
                 }
@@ -779,8 +1562,9 @@ impl GameState {
             static mut LAST_SYNC_PRINT: f64 = 0.0;
             unsafe {
                 if macroquad::prelude::get_time() - LAST_SYNC_PRINT > 1.0 {
-                    println!(
-                        "[SYNC] Projectiles: {} -> {} (removed {})",
+                    log_debug!(
+                        Net,
+                        "Projectiles: {} -> {} (removed {})",
                         before_count,
                         after_count,
                         before_count - after_count
@@ -793,6 +1577,13 @@ impl GameState {
 
     pub fn new(map_name: &str) -> Self {
         let teleports = Vec::new();
+        let gametype = gametype::from_cvar();
+        let mutators = mutators::Mutators::from_cvar();
+        let mut physics = physics::PhysicsProfile::from_cvars();
+        mutators.apply_physics(&mut physics);
+        let mut map = map::Map::new(map_name);
+        let map_items = map.items.clone();
+        mutators.filter_items(&mut map.items);
 
         Self {
             players: Vec::new(),
@@ -812,7 +1603,16 @@ impl GameState {
             debug_rays: Vec::new(),
             pending_hits: Vec::new(),
             messages: Vec::new(),
-            map: map::Map::new(map_name),
+            ctf: ctf::CtfState::new(&map.flags),
+            round: round::RoundState::from_cvar(),
+            warmup: warmup::WarmupState::live(),
+            pause: pause::PauseState::default(),
+            match_rules: match_rules::MatchRules::from_cvar(),
+            match_stats: match_stats::MatchStats::new(),
+            show_match_stats: false,
+            map_name: map_name.to_string(),
+            map,
+            map_items,
             time: 0.0,
             frame: 0,
             match_time: 0.0,
@@ -821,7 +1621,10 @@ impl GameState {
             debug_test_yaw: 0.0,
             debug_test_pitch: 0.0,
             debug_test_roll: 0.0,
-            time_limit: 600.0,
+            time_limit: gametype.time_limit(),
+            gametype,
+            mutators,
+            physics,
             model_cache: model_cache::ModelCache::new(),
             item_model_cache: item_model::ItemModelCache::new(),
             weapon_model_cache: weapon_model_cache::WeaponModelCache::new(),
@@ -869,6 +1672,13 @@ impl GameState {
         let teleports = Vec::new();
         let mut award_icon_cache = award::AwardIconCache::new();
         award_icon_cache.load().await;
+        let gametype = gametype::from_cvar();
+        let mutators = mutators::Mutators::from_cvar();
+        let mut physics = physics::PhysicsProfile::from_cvars();
+        mutators.apply_physics(&mut physics);
+        let mut map = map::Map::new_async(map_name).await;
+        let map_items = map.items.clone();
+        mutators.filter_items(&mut map.items);
 
         Self {
             players: Vec::new(),
@@ -892,7 +1702,16 @@ impl GameState {
             debug_rays: Vec::new(),
             pending_hits: Vec::new(),
             messages: Vec::new(),
-            map: map::Map::new_async(map_name).await,
+            ctf: ctf::CtfState::new(&map.flags),
+            round: round::RoundState::from_cvar(),
+            warmup: warmup::WarmupState::live(),
+            pause: pause::PauseState::default(),
+            match_rules: match_rules::MatchRules::from_cvar(),
+            match_stats: match_stats::MatchStats::new(),
+            show_match_stats: false,
+            map_name: map_name.to_string(),
+            map,
+            map_items,
             time: 0.0,
             frame: 0,
             match_time: 0.0,
@@ -901,7 +1720,10 @@ impl GameState {
             debug_test_yaw: 0.0,
             debug_test_pitch: 0.0,
             debug_test_roll: 0.0,
-            time_limit: 600.0,
+            time_limit: gametype.time_limit(),
+            gametype,
+            mutators,
+            physics,
             model_cache: model_cache::ModelCache::new(),
             item_model_cache: item_model::ItemModelCache::new(),
             weapon_model_cache: weapon_model_cache::WeaponModelCache::new(),
@@ -952,8 +1774,9 @@ impl GameState {
         {
             if let Ok(json) = std::fs::read_to_string(&path) {
                 if let Ok(mode) = serde_json::from_str::<defrag::DefragMode>(&json) {
-                    println!(
-                        "[Defrag] Loaded defrag mode: {} checkpoints",
+                    log_info!(
+                        Game,
+                        "Loaded defrag mode: {} checkpoints",
                         mode.checkpoints.len()
                     );
                     return Some(mode);
@@ -969,8 +1792,9 @@ impl GameState {
         let path = format!("maps/{}_navgraph.json", map_name);
         if let Ok(json) = std::fs::read_to_string(&path) {
             if let Ok(graph) = serde_json::from_str::<nav_graph::NavGraph>(&json) {
-                println!(
-                    "[Nav] Loaded navigation graph: {} nodes, {} edges",
+                log_info!(
+                    Bot,
+                    "Loaded navigation graph: {} nodes, {} edges",
                     graph.nodes.len(),
                     graph.edges.len()
                 );
@@ -978,15 +1802,16 @@ impl GameState {
             }
         }
 
-        println!("[Nav] No navigation graph found, generating...");
+        log_warn!(Bot, "No navigation graph found, generating...");
         if let Ok(map) = map::Map::load_from_file(map_name) {
             let generator = nav_graph_generator::NavGraphGenerator::new(map);
             let graph = generator.generate();
 
             let json = serde_json::to_string_pretty(&graph).unwrap();
             let _ = std::fs::write(&path, json);
-            println!(
-                "[Nav] Generated and saved: {} nodes, {} edges",
+            log_info!(
+                Bot,
+                "Generated and saved navigation graph: {} nodes, {} edges",
                 graph.nodes.len(),
                 graph.edges.len()
             );
@@ -994,7 +1819,7 @@ impl GameState {
             return Some(graph);
         }
 
-        println!("[Nav] Failed to generate navigation graph");
+        log_warn!(Bot, "Failed to generate navigation graph");
         None
     }
 
@@ -1021,9 +1846,9 @@ impl GameState {
                             client.interpolate_player(player.id, interp_time).is_some();
 
                         if self.frame % 60 == 0 && player.dead {
-                            println!(
-                                "[{:.3}] [DEATH DEBUG] p{} dead={} gibbed={} can_interp={}",
-                                macroquad::prelude::get_time(),
+                            log_debug!(
+                                Game,
+                                "Death p{} dead={} gibbed={} can_interp={}",
                                 player.id,
                                 player.dead,
                                 player.gibbed,
@@ -1035,8 +1860,15 @@ impl GameState {
                             use crate::game::bg_pmove::{pmove, PmoveCmd, PmoveState};
 
                             if self.frame % 60 == 0 {
-                                println!("[{:.3}] [CORPSE PHYSICS] p{} dead, applying gravity at ({:.1},{:.1}) vel=({:.2},{:.2})", 
-                                    macroquad::prelude::get_time(), player.id, player.x, player.y, player.vel_x, player.vel_y);
+                                log_trace!(
+                                    Game,
+                                    "Corpse p{} dead, applying gravity at ({:.1},{:.1}) vel=({:.2},{:.2})",
+                                    player.id,
+                                    player.x,
+                                    player.y,
+                                    player.vel_x,
+                                    player.vel_y
+                                );
                             }
 
                             let state = PmoveState {
@@ -1045,6 +1877,8 @@ impl GameState {
                                 vel_x: player.vel_x,
                                 vel_y: player.vel_y,
                                 was_in_air: true,
+                                jump_time: 0.0,
+                                fall_speed: 0.0,
                             };
 
                             let cmd = PmoveCmd {
@@ -1052,9 +1886,11 @@ impl GameState {
                                 jump: false,
                                 crouch: false,
                                 haste_active: false,
+                                flight_active: false,
+                                grapple: None,
                             };
 
-                            let result = pmove(&state, &cmd, dt, &self.map);
+                            let result = pmove(&state, &cmd, dt, &self.map, &self.physics);
                             player.x = result.new_x;
                             player.y = result.new_y;
                             player.vel_x = result.new_vel_x;
@@ -1065,9 +1901,9 @@ impl GameState {
                             client.interpolate_player(player.id, interp_time)
                         {
                             if debug_interp && self.frame % 60 == 0 {
-                                println!(
-                                    "[{:.3}] [INTERP] p{} pos=({:.1},{:.1}) vel=({:.2},{:.2})",
-                                    macroquad::prelude::get_time(),
+                                log_trace!(
+                                    Net,
+                                    "Interp p{} pos=({:.1},{:.1}) vel=({:.2},{:.2})",
                                     player.id,
                                     interpolated.position.0,
                                     interpolated.position.1,
@@ -1087,11 +1923,7 @@ impl GameState {
                             player.was_in_air = !interpolated.on_ground;
                         } else {
                             if debug_interp && self.frame % 60 == 0 {
-                                println!(
-                                    "[{:.3}] [INTERP] p{} - NO DATA",
-                                    macroquad::prelude::get_time(),
-                                    player.id
-                                );
+                                log_trace!(Net, "Interp p{} - NO DATA", player.id);
                             }
                             player.cx = player.x;
                             player.cy = player.y;
@@ -1111,7 +1943,9 @@ impl GameState {
 
         self.time += dt as f64;
         self.frame += 1;
-        self.match_time += dt;
+        if self.warmup.is_live() {
+            self.match_time += dt;
+        }
 
         if let Some(ref mut story) = self.story_mode {
             let (new_enemies, _should_change_level) =
@@ -1144,6 +1978,10 @@ impl GameState {
                 }
             }
 
+            if player.frozen {
+                continue;
+            }
+
             if let Some(model) = self.model_cache.get_or_load(&player.model) {
                 let prev_on_ground = !player.was_in_air;
                 let on_ground = !player.was_in_air;
@@ -1249,6 +2087,8 @@ impl GameState {
                     vel_x: corpse.player.vel_x,
                     vel_y: corpse.player.vel_y,
                     was_in_air: true,
+                    jump_time: 0.0,
+                    fall_speed: 0.0,
                 };
 
                 let cmd = PmoveCmd {
@@ -1256,9 +2096,11 @@ impl GameState {
                     jump: false,
                     crouch: false,
                     haste_active: false,
+                    flight_active: false,
+                    grapple: None,
                 };
 
-                let result = pmove(&state, &cmd, dt, &self.map);
+                let result = pmove(&state, &cmd, dt, &self.map, &self.physics);
                 corpse.player.x = result.new_x;
                 corpse.player.y = result.new_y;
                 corpse.player.vel_x = result.new_vel_x;
@@ -1360,220 +2202,67 @@ impl GameState {
             }
         }
 
+        if !self.is_multiplayer {
+            for player in &self.players {
+                for powerup in player.powerups.active() {
+                    self.match_stats.add_powerup_time(player.id, powerup, dt);
+                }
+            }
+        }
+
+        pickup::tick_respawns(&mut self.map.items);
+
+        let items_enabled = self.gametype.items_enabled();
         for player in &mut self.players {
-            if player.dead {
+            if player.dead || !items_enabled {
                 continue;
             }
 
             for item in &mut self.map.items {
-                if !item.active {
-                    if item.respawn_time > 0 {
-                        item.respawn_time -= 1;
-                    } else {
-                        item.active = true;
-                    }
+                if !item.active || !pickup::in_reach(player.x, player.y, item) {
                     continue;
                 }
+                if !pickup::touch(player, item.item_type) {
+                    continue;
+                }
+                item.active = false;
+                item.respawn_time = pickup::respawn_time(item.item_type);
+                self.match_stats.record_pickup(player.id, item.item_type);
+                if item.dropped {
+                    log_debug!(
+                        Game,
+                        "Player {} picked up DROPPED {:?} at ({:.1},{:.1})",
+                        player.id,
+                        item.item_type,
+                        item.x,
+                        item.y
+                    );
+                }
 
-                let dx = player.x - item.x;
-                let dy = player.y - item.y;
-                if (dx * dx + dy * dy).sqrt() < 24.0 {
-                    use map::ItemType::*;
-                    match item.item_type {
-                        Health25 => {
-                            if player.health < 100 {
-                                player.health = (player.health + 25).min(100);
-                                item.active = false;
-                                item.respawn_time = self::constants::ITEM_RESPAWN_HEALTH;
-                                self.audio_events.push(
-                                    crate::audio::events::AudioEvent::ItemPickup { x: item.x },
-                                );
-                            }
-                        }
-                        Health50 => {
-                            if player.health < 100 {
-                                player.health = (player.health + 50).min(100);
-                                item.active = false;
-                                item.respawn_time = self::constants::ITEM_RESPAWN_HEALTH;
-                                self.audio_events.push(
-                                    crate::audio::events::AudioEvent::ItemPickup { x: item.x },
-                                );
-                            }
-                        }
-                        Health100 => {
-                            if player.health < 200 {
-                                player.health = (player.health + 100).min(200);
-                                item.active = false;
-                                item.respawn_time = self::constants::ITEM_RESPAWN_HEALTH;
-                                self.audio_events.push(
-                                    crate::audio::events::AudioEvent::ItemPickup { x: item.x },
-                                );
-                            }
-                        }
-                        Armor50 => {
-                            if player.armor < 100 {
-                                player.armor = (player.armor + 50).min(100);
-                                item.active = false;
-                                item.respawn_time = self::constants::ITEM_RESPAWN_ARMOR;
-                                self.audio_events.push(
-                                    crate::audio::events::AudioEvent::ArmorPickup { x: item.x },
-                                );
-                            }
-                        }
-                        Armor100 => {
-                            if player.armor < 200 {
-                                player.armor = (player.armor + 100).min(200);
-                                item.active = false;
-                                item.respawn_time = self::constants::ITEM_RESPAWN_ARMOR;
-                                self.audio_events.push(
-                                    crate::audio::events::AudioEvent::ArmorPickup { x: item.x },
-                                );
-                            }
-                        }
-                        RocketLauncher => {
-                            if item.dropped {
-                                println!("[ITEM PICKUP] Player {} picked up DROPPED RocketLauncher at ({:.1},{:.1})", 
-                                    player.id, item.x, item.y);
-                            }
-                            player.has_weapon[4] = true;
-                            player.ammo[4] = (player.ammo[4] + 10).min(100);
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_WEAPON;
-                            self.audio_events
-                                .push(crate::audio::events::AudioEvent::WeaponPickup { x: item.x });
-                        }
-                        LightningGun => {
-                            if item.dropped {
-                                println!("[ITEM PICKUP] Player {} picked up DROPPED LightningGun at ({:.1},{:.1})", 
-                                    player.id, item.x, item.y);
-                            }
-                            player.has_weapon[5] = true;
-                            player.ammo[5] = (player.ammo[5].saturating_add(100)).min(200);
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_WEAPON;
-                            self.audio_events
-                                .push(crate::audio::events::AudioEvent::WeaponPickup { x: item.x });
-                        }
-                        Railgun => {
-                            if item.dropped {
-                                println!("[ITEM PICKUP] Player {} picked up DROPPED Railgun at ({:.1},{:.1})", 
-                                    player.id, item.x, item.y);
-                            }
-                            player.has_weapon[6] = true;
-                            player.ammo[6] = (player.ammo[6] + 10).min(100);
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_WEAPON;
-                            self.audio_events
-                                .push(crate::audio::events::AudioEvent::WeaponPickup { x: item.x });
-                        }
-                        Plasmagun => {
-                            if item.dropped {
-                                println!("[ITEM PICKUP] Player {} picked up DROPPED Plasmagun at ({:.1},{:.1})", 
-                                    player.id, item.x, item.y);
-                            }
-                            player.has_weapon[7] = true;
-                            player.ammo[7] = (player.ammo[7] + 50).min(200);
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_WEAPON;
-                            self.audio_events
-                                .push(crate::audio::events::AudioEvent::WeaponPickup { x: item.x });
-                        }
-                        Quad => {
-                            player.powerups.quad = self::constants::POWERUP_DURATION_QUAD;
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_POWERUP;
-                            self.audio_events.push(
-                                crate::audio::events::AudioEvent::PowerupPickup { x: item.x },
-                            );
-                            self.audio_events
-                                .push(crate::audio::events::AudioEvent::QuadDamage);
-                        }
-                        Regen => {
-                            player.powerups.regen = self::constants::POWERUP_DURATION_REGEN;
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_POWERUP;
-                            self.audio_events.push(
-                                crate::audio::events::AudioEvent::PowerupPickup { x: item.x },
-                            );
-                        }
-                        Battle => {
-                            player.powerups.battle = self::constants::POWERUP_DURATION_BATTLE;
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_POWERUP;
-                            self.audio_events.push(
-                                crate::audio::events::AudioEvent::PowerupPickup { x: item.x },
-                            );
-                        }
-                        Flight => {
-                            player.powerups.flight = self::constants::POWERUP_DURATION_FLIGHT;
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_POWERUP;
-                            self.audio_events.push(
-                                crate::audio::events::AudioEvent::PowerupPickup { x: item.x },
-                            );
-                        }
-                        Haste => {
-                            player.powerups.haste = self::constants::POWERUP_DURATION_HASTE;
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_POWERUP;
-                            self.audio_events.push(
-                                crate::audio::events::AudioEvent::PowerupPickup { x: item.x },
-                            );
-                        }
-                        Invis => {
-                            player.powerups.invis = self::constants::POWERUP_DURATION_INVIS;
-                            item.active = false;
-                            item.respawn_time = self::constants::ITEM_RESPAWN_POWERUP;
-                            self.audio_events.push(
-                                crate::audio::events::AudioEvent::PowerupPickup { x: item.x },
-                            );
-                        }
-                        Shotgun => {
-                            if item.dropped {
-                                println!("[ITEM PICKUP] Player {} picked up DROPPED Shotgun at ({:.1},{:.1})", 
-                                    player.id, item.x, item.y);
-                            }
-                            player.has_weapon[2] = true;
-                            player.ammo[2] = (player.ammo[2] + 10).min(100);
-                            item.active = false;
-                            item.respawn_time = 300;
-                            self.audio_events
-                                .push(crate::audio::events::AudioEvent::WeaponPickup { x: item.x });
-                        }
-                        GrenadeLauncher => {
-                            if item.dropped {
-                                println!("[ITEM PICKUP] Player {} picked up DROPPED GrenadeLauncher at ({:.1},{:.1})", 
-                                    player.id, item.x, item.y);
-                            }
-                            player.has_weapon[3] = true;
-                            player.ammo[3] = (player.ammo[3] + 10).min(100);
-                            item.active = false;
-                            item.respawn_time = 300;
-                            self.audio_events
-                                .push(crate::audio::events::AudioEvent::WeaponPickup { x: item.x });
-                        }
-                        BFG => {
-                            if item.dropped {
-                                println!("[ITEM PICKUP] Player {} picked up DROPPED BFG at ({:.1},{:.1})", 
-                                    player.id, item.x, item.y);
-                            }
-                            player.has_weapon[8] = true;
-                            player.ammo[8] = (player.ammo[8] + 15).min(200);
-                            item.active = false;
-                            item.respawn_time = 600;
-                            self.audio_events
-                                .push(crate::audio::events::AudioEvent::WeaponPickup { x: item.x });
-                        }
+                use crate::audio::events::AudioEvent;
+                let x = item.x;
+                self.audio_events.push(match pickup::kind(item.item_type) {
+                    pickup::PickupKind::Health => AudioEvent::ItemPickup { x },
+                    pickup::PickupKind::Armor => AudioEvent::ArmorPickup { x },
+                    pickup::PickupKind::Weapon => AudioEvent::WeaponPickup { x },
+                    pickup::PickupKind::Powerup | pickup::PickupKind::Holdable => {
+                        AudioEvent::PowerupPickup { x }
                     }
+                });
+                if item.item_type == map::ItemType::Quad {
+                    self.audio_events.push(AudioEvent::QuadDamage);
                 }
             }
         }
 
         self.map.items.retain(|item| {
             if item.dropped && !item.active {
-                println!(
-                    "[ITEM CLEANUP] Removing dropped {:?} at ({:.1},{:.1})",
-                    item.item_type, item.x, item.y
+                log_debug!(
+                    Game,
+                    "Removing dropped {:?} at ({:.1},{:.1})",
+                    item.item_type,
+                    item.x,
+                    item.y
                 );
                 false
             } else {
@@ -1593,6 +2282,9 @@ impl GameState {
         self.item_model_cache.update_all(dt);
         self.railgun_effects.update(dt);
         self.lightning_effects.update(dt);
+        if self.players.iter().any(|p| p.grapple.hook_pos().is_some()) {
+            self.lightning_effects.init_material();
+        }
         self.weapon_hit_effects.retain_mut(|e| e.update());
         self.damage_numbers.retain_mut(|d| d.update());
 
@@ -1686,6 +2378,7 @@ impl GameState {
                         | weapon::Weapon::GrenadeLauncher
                         | weapon::Weapon::Plasmagun
                         | weapon::Weapon::BFG
+                        | weapon::Weapon::ProxLauncher
                 );
 
                 if has_explosion {
@@ -1703,6 +2396,12 @@ impl GameState {
             alive
         });
 
+        let mutators = self.mutators;
+        let rules = DamageRules::from_cvars(mutators);
+        let mut vampire_heals: Vec<(u16, i32)> = Vec::new();
+        let mut mirror_hits: Vec<(u16, i32, weapon::Weapon)> = Vec::new();
+        let mut stat_damage: Vec<(u16, u16, weapon::Weapon, i32)> = Vec::new();
+
         if !self.is_multiplayer {
             let projectiles_to_check = self.projectiles.clone();
             let mut projectiles_to_remove = Vec::new();
@@ -1713,8 +2412,13 @@ impl GameState {
                     let mut hit_player_id = None;
                     let mut weapon_to_drop = None;
 
+                    let (protected, teammates) = self.damage_groups(proj.owner_id, &rules);
+
                     for player in &mut self.players {
-                        if player.id != proj.owner_id && !player.gibbed {
+                        if player.id != proj.owner_id
+                            && !player.gibbed
+                            && !protected.contains(&player.id)
+                        {
                             let hitbox_height = if player.dead {
                                 constants::PLAYER_HITBOX_HEIGHT_CROUCH
                             } else if player.crouch {
@@ -1764,7 +2468,21 @@ impl GameState {
                                     (player.x, player.y)
                                 };
 
-                                let (died, gibbed) = player.take_damage(proj.damage);
+                                let (died, gibbed, hit) = player.take_damage(
+                                    proj.damage,
+                                    proj.owner_id,
+                                    teammates.contains(&player.id),
+                                    &rules,
+                                );
+                                let dealt = hit.damage;
+                                vampire_heals.push((proj.owner_id, dealt));
+                                mirror_hits.push((proj.owner_id, hit.mirrored, proj.weapon_type));
+                                stat_damage.push((
+                                    proj.owner_id,
+                                    player.id,
+                                    proj.weapon_type,
+                                    dealt,
+                                ));
 
                                 self.weapon_hit_effects.push(
                                     weapon_hit_effect::WeaponHitEffect::new_blood(proj.x, proj.y),
@@ -1891,6 +2609,7 @@ impl GameState {
                                 | weapon::Weapon::GrenadeLauncher
                                 | weapon::Weapon::Plasmagun
                                 | weapon::Weapon::BFG
+                                | weapon::Weapon::ProxLauncher
                         );
 
                         if has_explosion {
@@ -1922,13 +2641,19 @@ impl GameState {
                             | weapon::Weapon::GrenadeLauncher
                             | weapon::Weapon::Plasmagun
                             | weapon::Weapon::BFG
+                            | weapon::Weapon::ProxLauncher
                     )
                 {
                     let mut corpse_to_create = None;
                     let mut weapon_to_drop = None;
 
+                    let (protected, teammates) = self.damage_groups(proj.owner_id, &rules);
+
                     for player in &mut self.players {
-                        if player.id != proj.owner_id && !player.gibbed {
+                        if player.id != proj.owner_id
+                            && !player.gibbed
+                            && !protected.contains(&player.id)
+                        {
                             let hitbox_height = if player.dead {
                                 constants::PLAYER_HITBOX_HEIGHT_CROUCH
                             } else if player.crouch {
@@ -1949,8 +2674,14 @@ impl GameState {
                                 }) {
                                     p.active = false;
 
-                                    println!("[{:.3}] [COLLISION] Non-explosive projectile [{}] {:?} direct hit player {} for {} damage", 
-                                        macroquad::prelude::get_time(), p.id, proj.weapon_type, player.id, proj.damage);
+                                    log_debug!(
+                                        Game,
+                                        "Non-explosive projectile [{}] {:?} direct hit player {} for {} damage",
+                                        p.id,
+                                        proj.weapon_type,
+                                        player.id,
+                                        proj.damage
+                                    );
 
                                     let was_alive = !player.dead;
 
@@ -1987,7 +2718,25 @@ impl GameState {
                                         (player.x, player.y)
                                     };
 
-                                    let (died, gibbed) = player.take_damage(proj.damage);
+                                    let (died, gibbed, hit) = player.take_damage(
+                                        proj.damage,
+                                        proj.owner_id,
+                                        teammates.contains(&player.id),
+                                        &rules,
+                                    );
+                                    let dealt = hit.damage;
+                                    vampire_heals.push((proj.owner_id, dealt));
+                                    mirror_hits.push((
+                                        proj.owner_id,
+                                        hit.mirrored,
+                                        proj.weapon_type,
+                                    ));
+                                    stat_damage.push((
+                                        proj.owner_id,
+                                        player.id,
+                                        proj.weapon_type,
+                                        dealt,
+                                    ));
 
                                     self.weapon_hit_effects.push(
                                         weapon_hit_effect::WeaponHitEffect::new_blood(
@@ -2045,9 +2794,12 @@ impl GameState {
                                                 },
                                             );
                                         }
-                                        println!(
-                                            "[PROJECTILE KILL] owner={} victim={} weapon={:?}",
-                                            proj.owner_id, player.id, proj.weapon_type
+                                        log_debug!(
+                                            Game,
+                                            "Projectile kill owner={} victim={} weapon={:?}",
+                                            proj.owner_id,
+                                            player.id,
+                                            proj.weapon_type
                                         );
                                         kills.push((
                                             proj.owner_id,
@@ -2114,6 +2866,7 @@ impl GameState {
                             | weapon::Weapon::GrenadeLauncher
                             | weapon::Weapon::Plasmagun
                             | weapon::Weapon::BFG
+                            | weapon::Weapon::ProxLauncher
                     )
                 {
                     let mut corpse_hit_idx = None;
@@ -2178,6 +2931,7 @@ impl GameState {
                             | weapon::Weapon::GrenadeLauncher
                             | weapon::Weapon::Plasmagun
                             | weapon::Weapon::BFG
+                            | weapon::Weapon::ProxLauncher
                     )
                 {
                     let mut corpse_hit_idx = None;
@@ -2207,8 +2961,10 @@ impl GameState {
                                 let x = p.x;
                                 let y = p.y;
 
-                                println!("[{:.3}] [CORPSE HIT] Explosive projectile hit corpse, adding explosion", 
-                                    macroquad::prelude::get_time());
+                                log_debug!(
+                                    Game,
+                                    "Explosive projectile hit corpse, adding explosion"
+                                );
                                 exploded_projectiles.push((
                                     x,
                                     y,
@@ -2229,11 +2985,27 @@ impl GameState {
 
         let hits = self.pending_hits.drain(..).collect::<Vec<_>>();
         for (idx, damage, hit_x, hit_y, owner_id) in hits {
+            let target_id = self.players[idx].id;
+            if !self.damage_allowed(owner_id, target_id, &rules) {
+                continue;
+            }
+            let teammates = self.teammates(owner_id, target_id);
             let player_x = self.players[idx].x;
             let player_y = self.players[idx].y;
             let player_weapon = self.players[idx].weapon;
             let was_alive = !self.players[idx].dead;
-            let (died, gibbed) = self.players[idx].take_damage(damage);
+            let (died, gibbed, hit) =
+                self.players[idx].take_damage(damage, owner_id, teammates, &rules);
+            let dealt = hit.damage;
+            vampire_heals.push((owner_id, dealt));
+            let owner_weapon = self
+                .players
+                .iter()
+                .find(|p| p.id == owner_id)
+                .map(|p| p.weapon)
+                .unwrap_or(weapon::Weapon::MachineGun);
+            mirror_hits.push((owner_id, hit.mirrored, owner_weapon));
+            stat_damage.push((owner_id, self.players[idx].id, owner_weapon, dealt));
 
             if was_alive {
                 for _ in 0..3 {
@@ -2323,15 +3095,11 @@ impl GameState {
                 self.gibs
                     .extend(gib::spawn_gibs(player_x, self.players[idx].y));
                 if was_alive {
-                    if owner_id == self.players[idx].id {
-                        if let Some(player) = self.players.iter_mut().find(|p| p.id == owner_id) {
-                            player.frags -= 1;
-                        }
-                    } else {
-                        if let Some(killer) = self.players.iter_mut().find(|p| p.id == owner_id) {
-                            killer.frags += 1;
-                        }
-                    }
+                    self.match_stats
+                        .record_kill(owner_id, self.players[idx].id, owner_weapon);
+                    self.match_stats
+                        .record_kill(owner_id, self.players[idx].id, owner_weapon);
+                    self.apply_kill_score(owner_id, self.players[idx].id);
 
                     let victim_was_airborne = self.players[idx].was_in_air;
                     self.check_and_award(
@@ -2348,10 +3116,10 @@ impl GameState {
                         .map(|p| p.name.clone())
                         .unwrap_or("Unknown".to_string());
                     let victim_name = self.players[idx].name.clone();
-                    self.messages.push(message::GameMessage::kill_message(
-                        &killer_name,
+                    self.messages.push(message::GameMessage::obituary(
+                        Some(&killer_name),
                         &victim_name,
-                        owner_id as u8,
+                        MeansOfDeath::Weapon(owner_weapon),
                     ));
                 }
             } else if died && was_alive {
@@ -2383,15 +3151,7 @@ impl GameState {
                         spin_roll,
                     });
                 }
-                if owner_id == self.players[idx].id {
-                    if let Some(player) = self.players.iter_mut().find(|p| p.id == owner_id) {
-                        player.frags -= 1;
-                    }
-                } else {
-                    if let Some(killer) = self.players.iter_mut().find(|p| p.id == owner_id) {
-                        killer.frags += 1;
-                    }
-                }
+                self.apply_kill_score(owner_id, self.players[idx].id);
 
                 let victim_was_airborne = self.players[idx].was_in_air;
                 self.check_and_award(
@@ -2409,10 +3169,10 @@ impl GameState {
                     .unwrap_or("Unknown".to_string());
                 let victim_name = self.players[idx].name.clone();
                 let victim_model = self.players[idx].model.clone();
-                self.messages.push(message::GameMessage::kill_message(
-                    &killer_name,
+                self.messages.push(message::GameMessage::obituary(
+                    Some(&killer_name),
                     &victim_name,
-                    owner_id as u8,
+                    MeansOfDeath::Weapon(owner_weapon),
                 ));
 
                 self.audio_events
@@ -2478,29 +3238,21 @@ impl GameState {
             let mut new_corpses = Vec::new();
             let mut weapons_to_drop = Vec::new();
 
+            let (protected, teammates) = self.damage_groups(owner_id, &rules);
+
             for player in &mut self.players {
-                if Some(player.id) == direct_hit_player_id {
+                if Some(player.id) == direct_hit_player_id || player.frozen {
                     continue;
                 }
 
-                let dx = player.x - x;
-                let dy = player.y - y;
-                let dist = (dx * dx + dy * dy).sqrt();
-
-                if dist < radius {
-                    let damage_points = (damage as f32 - 0.5 * dist).max(0.0);
-
-                    let mass = 200.0;
-                    let g_knockback = 1200.0;
-                    let knockback = damage_points.min(200.0);
-                    let knockback_scale = g_knockback * knockback / mass;
-
-                    if dist > 0.1 {
-                        let dir_x = dx / dist;
-                        let dir_y = dy / dist;
+                if let Some(hit) =
+                    bg_combat::splash_hit((x, y), (player.x, player.y), damage, radius, &rules)
+                {
+                    let damage_points = hit.damage;
 
-                        player.vel_x += dir_x * knockback_scale;
-                        player.vel_y += dir_y * knockback_scale;
+                    if hit.push_x != 0.0 || hit.push_y != 0.0 {
+                        player.vel_x += hit.push_x;
+                        player.vel_y += hit.push_y;
 
                         // 20% chance to flip if knocked up significantly
                         if player.vel_y < -4.0 && crate::compat_rand::gen_range_f32(0.0, 1.0) < 0.2
@@ -2511,7 +3263,7 @@ impl GameState {
                         }
                     }
 
-                    if !self.is_multiplayer {
+                    if !self.is_multiplayer && !protected.contains(&player.id) {
                         if player.id != owner_id {
                             let actual_damage = damage_points as i32;
                             let was_alive = !player.dead;
@@ -2546,7 +3298,16 @@ impl GameState {
                                 (player.x, player.y)
                             };
 
-                            let (died, gibbed) = player.take_damage(actual_damage);
+                            let (died, gibbed, hit) = player.take_damage(
+                                actual_damage,
+                                owner_id,
+                                teammates.contains(&player.id),
+                                &rules,
+                            );
+                            let dealt = hit.damage;
+                            vampire_heals.push((owner_id, dealt));
+                            mirror_hits.push((owner_id, hit.mirrored, weapon));
+                            stat_damage.push((owner_id, player.id, weapon, dealt));
 
                             if was_alive && actual_damage > 5 {
                                 let gib_count = (actual_damage / 10).min(5);
@@ -2600,7 +3361,7 @@ impl GameState {
                                 kills.push((owner_id, player.id, false, weapon));
                             }
                         } else if owner_id as u16 == player.id {
-                            let self_damage = (damage_points * 0.5) as i32;
+                            let self_damage = damage_points as i32;
                             let was_alive = !player.dead;
 
                             let weapon_drop_pos = if was_alive {
@@ -2633,10 +3394,13 @@ impl GameState {
                                 (player.x, player.y)
                             };
 
-                            let (died, gibbed) = player.take_damage(self_damage);
+                            let (died, gibbed, hit) =
+                                player.take_damage(self_damage, player.id, false, &rules);
+                            let dealt = hit.damage;
+                            stat_damage.push((player.id, player.id, weapon, dealt));
 
-                            if was_alive && self_damage > 5 {
-                                let gib_count = (self_damage / 10).min(5);
+                            if was_alive && dealt > 5 {
+                                let gib_count = (dealt / 10).min(5);
                                 for _ in 0..gib_count {
                                     let gib_type = match crate::compat_rand::gen_range_usize(0, 3) {
                                         0 => gib::GibType::Intestine,
@@ -2659,7 +3423,7 @@ impl GameState {
                                     player.id,
                                     player.x,
                                     player.y,
-                                    self_damage,
+                                    dealt,
                                     player.health,
                                     player.armor,
                                 ));
@@ -2732,29 +3496,14 @@ impl GameState {
 
             let mut corpses_to_gib = Vec::new();
             for (idx, corpse) in self.corpses.iter_mut().enumerate() {
-                let dx = corpse.player.x - x;
-                let dy = corpse.player.y - y;
-                let dist = (dx * dx + dy * dy).sqrt();
-
-                if dist < radius {
-                    let damage_points = (damage as f32 - 0.5 * dist).max(0.0);
-
-                    if damage_points > 20.0 {
-                        corpses_to_gib.push((idx, corpse.player.x, corpse.player.y));
+                let pos = (corpse.player.x, corpse.player.y);
+                if let Some(hit) = bg_combat::splash_hit((x, y), pos, damage, radius, &rules) {
+                    if hit.damage > 20.0 {
+                        corpses_to_gib.push((idx, pos.0, pos.1));
                     }
 
-                    let mass = 200.0;
-                    let g_knockback = 1200.0;
-                    let knockback = damage_points.min(200.0);
-                    let knockback_scale = g_knockback * knockback / mass;
-
-                    if dist > 0.1 {
-                        let dir_x = dx / dist;
-                        let dir_y = dy / dist;
-
-                        corpse.player.vel_x += dir_x * knockback_scale;
-                        corpse.player.vel_y += dir_y * knockback_scale;
-                    }
+                    corpse.player.vel_x += hit.push_x;
+                    corpse.player.vel_y += hit.push_y;
                 }
             }
 
@@ -2811,28 +3560,52 @@ impl GameState {
             let was_local_in_lead_group = old_leader == local_player_id
                 || (old_leader.is_none() && local_score_before == top_score_before);
 
-            if killer_id == victim_id {
-                if let Some(player) = self.players.iter_mut().find(|p| p.id == killer_id) {
-                    player.frags -= 1;
-                }
-            } else {
-                if let Some(killer) = self.players.iter_mut().find(|p| p.id == killer_id) {
-                    killer.frags += 1;
-                }
+            let team_scores_before = self.team_scores();
+            self.match_stats.record_kill(killer_id, victim_id, weapon);
+            self.apply_kill_score(killer_id, victim_id);
+            if !self.is_multiplayer {
+                let name_of = |id: u16| {
+                    self.players
+                        .iter()
+                        .find(|p| p.id == id)
+                        .map(|p| p.name.clone())
+                        .unwrap_or("Unknown".to_string())
+                };
+                let killer = (killer_id != victim_id).then(|| name_of(killer_id));
+                let obituary = message::GameMessage::obituary(
+                    killer.as_deref(),
+                    &name_of(victim_id),
+                    MeansOfDeath::Weapon(weapon),
+                );
+                self.messages.push(obituary);
             }
 
             let scores_after: Vec<_> = self.players.iter().map(|p| (p.id, p.frags)).collect();
 
-            println!(
+            log_debug!(
+                Game,
                 "Kill: killer={}, victim={}, old_leader={:?}",
-                killer_id, victim_id, old_leader
+                killer_id,
+                victim_id,
+                old_leader
             );
-            println!("  Scores before: {:?}", scores_before);
-            println!("  Scores after: {:?}", scores_after);
+            log_debug!(Game, "  Scores before: {:?}", scores_before);
+            log_debug!(Game, "  Scores after: {:?}", scores_after);
 
             self.check_and_award(killer_id, victim_id, was_airborne, weapon);
 
-            if let Some(local_id) = local_player_id {
+            if self.gametype.is_team_game() {
+                let team_scores_after = self.team_scores();
+                if let Some(announcement) = self
+                    .gametype
+                    .team_lead_announcement(&team_scores_before, &team_scores_after)
+                {
+                    self.audio_events
+                        .push(crate::audio::events::AudioEvent::LeadChange {
+                            announcement: announcement.to_string(),
+                        });
+                }
+            } else if let Some(local_id) = local_player_id {
                 if killer_id == local_id || was_local_in_lead_group {
                     if let Some(announcement) = self.lead_announcements.update(
                         &self.players,
@@ -2841,7 +3614,7 @@ impl GameState {
                         old_leader,
                         was_local_in_lead_group,
                     ) {
-                        println!("  -> Announcement: {}", announcement);
+                        log_debug!(Game, "  -> Announcement: {}", announcement);
                         self.audio_events
                             .push(crate::audio::events::AudioEvent::LeadChange {
                                 announcement: announcement.to_string(),
@@ -2857,23 +3630,113 @@ impl GameState {
             }
         }
 
+        for (attacker_id, target_id, weapon, dealt) in stat_damage {
+            self.match_stats
+                .record_damage(attacker_id, target_id, weapon, dealt);
+        }
+        self.match_stats.end_frame();
+
+        for (attacker_id, dealt) in vampire_heals {
+            if let Some(attacker) = self
+                .players
+                .iter_mut()
+                .find(|p| p.id == attacker_id && !p.dead)
+            {
+                attacker.health = mutators.vampire_heal(attacker.health, dealt);
+            }
+        }
+
+        for (attacker_id, mirrored, weapon) in mirror_hits {
+            if let Some(idx) = self.players.iter().position(|p| p.id == attacker_id) {
+                self.world_damage(idx, mirrored, MeansOfDeath::Weapon(weapon), &rules);
+            }
+        }
+
+        if !self.is_multiplayer {
+            self.apply_world_damage(dt, &rules);
+        }
+
+        if self.gametype.freezes_on_death() {
+            self.update_freeze(dt);
+        }
+
         let spawn_points = self.map.spawn_points.clone();
+        let mut positions: Vec<_> = self
+            .players
+            .iter()
+            .filter(|p| !p.dead)
+            .map(|p| (p.id, p.team, p.x, p.y))
+            .collect();
+        let mut respawned = Vec::new();
+        let round_based = self.gametype.is_round_based();
+        let loadout = self.spawn_loadout();
         for player in &mut self.players {
-            if player.dead {
+            if player.dead && !round_based {
                 player.respawn_timer -= dt;
                 if player.respawn_timer <= 0.0 {
-                    let spawn_idx = if spawn_points.len() > 1 {
-                        rand::gen_range(0, spawn_points.len())
-                    } else {
-                        0
+                    let enemies: Vec<(f32, f32)> = positions
+                        .iter()
+                        .filter(|(id, team, _, _)| {
+                            *id != player.id
+                                && (*team == gametype::TEAM_FREE || *team != player.team)
+                        })
+                        .map(|(_, _, x, y)| (*x, *y))
+                        .collect();
+                    let occupied: Vec<(f32, f32)> =
+                        positions.iter().map(|(_, _, x, y)| (*x, *y)).collect();
+                    let query = gametype::SpawnQuery {
+                        team: player.team,
+                        enemies: &enemies,
+                        occupied: &occupied,
+                        avoid: Some((player.x, player.y)),
+                        roll: rand::rand() as usize,
+                    };
+                    let Some(spawn_idx) = self.gametype.select_spawn(&spawn_points, &query) else {
+                        continue;
                     };
                     let spawn = &spawn_points[spawn_idx];
                     player.spawn(spawn.x, spawn.y, &self.map);
+                    positions.push((player.id, player.team, player.x, player.y));
+                    respawned.push(player.id);
+                    if let Some(loadout) = &loadout {
+                        player.apply_loadout(loadout);
+                    }
                     self.audio_events
                         .push(crate::audio::events::AudioEvent::ItemPickup { x: spawn.x });
                 }
             }
         }
+        for id in respawned {
+            if let Some(idx) = self.players.iter().position(|p| p.id == id) {
+                self.telefrag(idx);
+            }
+        }
+
+        if self.gametype.uses_flags() {
+            if self.is_multiplayer {
+                for flag in &mut self.ctf.flags {
+                    if flag.status == ctf::FlagStatus::Dropped {
+                        flag.return_timer = (flag.return_timer - dt).max(0.0);
+                    }
+                }
+            } else {
+                self.update_flags(dt);
+            }
+        }
+
+        if round_based {
+            if self.is_multiplayer {
+                self.round.phase = match self.round.phase {
+                    round::RoundPhase::Countdown(t) => {
+                        round::RoundPhase::Countdown((t - dt).max(0.0))
+                    }
+                    round::RoundPhase::Ended(t) => round::RoundPhase::Ended((t - dt).max(0.0)),
+                    phase => phase,
+                };
+            } else {
+                self.update_rounds(dt);
+            }
+        }
 
         if let Some(defrag) = &mut self.defrag_mode {
             if let Some(player) = self.players.first() {
@@ -2881,20 +3744,20 @@ impl GameState {
                 for event in events {
                     match event {
                         defrag::DefragEvent::RunStarted => {
-                            println!("[Defrag] Run started!");
+                            log_info!(Game, "Run started!");
                         }
                         defrag::DefragEvent::CheckpointReached(idx) => {
-                            println!("[Defrag] Checkpoint {} reached!", idx + 1);
+                            log_info!(Game, "Checkpoint {} reached!", idx + 1);
                             self.audio_events
                                 .push(crate::audio::events::AudioEvent::ItemPickup { x: player.x });
                         }
                         defrag::DefragEvent::RunFinished(time) => {
-                            println!("[Defrag] Run finished in {:.3}s", time);
+                            log_info!(Game, "Run finished in {:.3}s", time);
                             self.audio_events
                                 .push(crate::audio::events::AudioEvent::TeleportIn { x: player.x });
                         }
                         defrag::DefragEvent::NewRecord(time) => {
-                            println!("[Defrag] NEW RECORD: {:.3}s!", time);
+                            log_info!(Game, "NEW RECORD: {:.3}s!", time);
                             self.audio_events
                                 .push(crate::audio::events::AudioEvent::TeleportIn { x: player.x });
                         }
@@ -2908,9 +3771,11 @@ impl GameState {
             !award.is_expired()
         });
 
+        let time_limit = self.match_rules.time_limit(self.time_limit);
         if let Some(announcement) = self
             .time_announcements
-            .update(self.match_time, self.time_limit)
+            .update(self.match_time, time_limit)
+            .filter(|_| !round_based)
         {
             self.audio_events
                 .push(crate::audio::events::AudioEvent::TimeAnnouncement {
@@ -2918,8 +3783,15 @@ impl GameState {
                 });
         }
 
-        if self.match_time >= self.time_limit && !self.game_results.show {
-            self.game_results.trigger(&self.players, self.match_time);
+        if self.warmup.is_live() && !self.is_multiplayer {
+            let events = self.match_rules.update(
+                self.match_time,
+                self.time_limit,
+                self.gametype.as_ref(),
+                &gametype::ScoreEntry::from_players(&self.players),
+                &self.team_scores(),
+            );
+            self.handle_limit_events(&events);
         }
     }
 
@@ -3100,8 +3972,11 @@ impl GameState {
                                 weapon_model,
                                 model_yaw_offset,
                                 BLACK,
-                                if matches!(player.weapon, crate::game::weapon::Weapon::MachineGun)
-                                {
+                                if matches!(
+                                    player.weapon,
+                                    crate::game::weapon::Weapon::MachineGun
+                                        | crate::game::weapon::Weapon::Chaingun
+                                ) {
                                     player.barrel_spin_angle
                                 } else {
                                     0.0
@@ -3162,6 +4037,10 @@ impl GameState {
             for jumppad in &self.map.jumppads {
                 jumppad.render(camera_x, camera_y);
             }
+
+            if self.gametype.uses_flags() {
+                self.ctf.render(camera_x, camera_y, &self.players);
+            }
         }
 
         {
@@ -3170,8 +4049,9 @@ impl GameState {
             let screen_h = screen_height();
             let item_margin = 100.0;
 
+            let items_enabled = self.gametype.items_enabled();
             for item in &self.map.items {
-                if item.active {
+                if item.active && items_enabled {
                     let screen_x = item.x - camera_x;
                     let screen_y = item.y - camera_y;
 
@@ -3449,13 +4329,14 @@ impl GameState {
                         0.0,
                         false,
                         false,
+                        false,
                         0.0,
                     );
                 }
             }
 
             for player in &self.players {
-                if player.gibbed || player.dead {
+                if player.gibbed || (player.dead && !player.frozen) {
                     continue;
                 }
                 let screen_x = player.x - camera_x;
@@ -3470,7 +4351,9 @@ impl GameState {
                 }
 
                 if let Some(model) = self.model_cache.get_mut(&player.model) {
-                    let color = if player.dead {
+                    let color = if player.frozen {
+                        Color::from_rgba(170, 210, 255, 255)
+                    } else if player.dead {
                         Color::from_rgba(255, 255, 255, 150)
                     } else if player.powerups.quad > 0 {
                         Color::from_rgba(150, 150, 255, 255)
@@ -3556,13 +4439,37 @@ impl GameState {
                         somersault_angle,
                         player.somersault_axis_y,
                         has_quad_damage,
-                        if matches!(player.weapon, crate::game::weapon::Weapon::MachineGun) {
+                        player.frozen,
+                        if matches!(
+                            player.weapon,
+                            crate::game::weapon::Weapon::MachineGun
+                                | crate::game::weapon::Weapon::Chaingun
+                        ) {
                             player.barrel_spin_angle
                         } else {
                             0.0
                         },
                     );
 
+                    if player.frozen && player.thaw_progress > 0.0 {
+                        let bar_w = 40.0;
+                        let bar_y = screen_y - 70.0;
+                        draw_rectangle(
+                            screen_x - bar_w / 2.0,
+                            bar_y,
+                            bar_w,
+                            4.0,
+                            Color::from_rgba(0, 0, 0, 160),
+                        );
+                        draw_rectangle(
+                            screen_x - bar_w / 2.0,
+                            bar_y,
+                            bar_w * player.thaw_progress.min(1.0),
+                            4.0,
+                            Color::from_rgba(120, 200, 255, 230),
+                        );
+                    }
+
                     if !player.dead {
                         let base_y = screen_y - 50.0;
                         let name_y = base_y - 10.0;
@@ -3628,6 +4535,11 @@ impl GameState {
             md3_render::disable_batching();
         }
 
+        {
+            let _scope = crate::profiler::scope("render_liquids");
+            liquid::render_liquids(&self.map, camera_x, camera_y);
+        }
+
         {
             let _scope = crate::profiler::scope("render_debug_hitboxes");
             if self.debug_hitboxes {
@@ -3635,6 +4547,18 @@ impl GameState {
             }
         }
 
+        for player in &self.players {
+            if let Some(hook) = player.grapple.hook_pos() {
+                self.lightning_effects.render_cable(
+                    (player.x, player.y + grapple::GRAPPLE_ORIGIN_Y),
+                    hook,
+                    lightning::get_player_lightning_color(player.id),
+                    camera_x,
+                    camera_y,
+                );
+            }
+        }
+
         {
             let _scope = crate::profiler::scope("render_effects");
 
@@ -3949,6 +4873,25 @@ impl GameState {
             camera_x,
             camera_y,
         );
+
+        if self.game_results.show {
+            if self.show_match_stats {
+                self.match_stats.draw(1.0);
+            }
+            let hint = if self.show_match_stats {
+                "ENTER: results"
+            } else {
+                "ENTER: match stats"
+            };
+            let hint_w = measure_text(hint, None, 20, 1.0).width;
+            draw_text(
+                hint,
+                screen_width() / 2.0 - hint_w / 2.0,
+                screen_height() - 30.0,
+                20.0,
+                Color::new(0.8, 0.8, 0.8, 1.0),
+            );
+        }
     }
 
     pub fn render_messages(&self) {
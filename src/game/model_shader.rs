@@ -9,6 +9,7 @@ static MODEL_DIFFUSE_SPECULAR_MATERIAL: OnceLock<Material> = OnceLock::new();
 static MODEL_ALPHA_TEST_MATERIAL: OnceLock<Material> = OnceLock::new();
 static MODEL_ENVMAP_MATERIAL: OnceLock<Material> = OnceLock::new();
 static MODEL_FIRE_MATERIAL: OnceLock<Material> = OnceLock::new();
+static MODEL_FROZEN_MATERIAL: OnceLock<Material> = OnceLock::new();
 
 fn get_model_diffuse_specular_material() -> &'static Material {
     MODEL_DIFFUSE_SPECULAR_MATERIAL.get_or_init(|| {
@@ -267,6 +268,83 @@ fn get_model_fire_material() -> &'static Material {
     })
 }
 
+/// Ice shell drawn over frozen players in Freeze Tag.
+fn get_model_frozen_material() -> &'static Material {
+    MODEL_FROZEN_MATERIAL.get_or_init(|| {
+        let vertex_shader = r#"#version 100
+        attribute vec3 position;
+        attribute vec2 texcoord;
+        attribute vec4 color0;
+        attribute vec4 normal;
+
+        varying lowp vec2 uv;
+        varying lowp vec3 vNormal;
+        varying mediump vec3 vPos;
+
+        uniform mat4 Model;
+        uniform mat4 Projection;
+
+        void main() {
+            vec3 n = normalize(normal.xyz);
+            gl_Position = Projection * Model * vec4(position + n * 1.5, 1.0);
+            uv = texcoord;
+            vNormal = n;
+            vPos = position;
+        }"#;
+
+        let fragment_shader = r#"#version 100
+        precision lowp float;
+
+        varying lowp vec2 uv;
+        varying lowp vec3 vNormal;
+        varying mediump vec3 vPos;
+
+        uniform sampler2D Texture;
+        uniform mediump float time;
+
+        float hash(vec2 p) {
+            return fract(sin(dot(p, vec2(127.1, 311.7))) * 43758.5453);
+        }
+
+        void main() {
+            vec4 baseColor = texture2D(Texture, uv);
+            float luma = dot(baseColor.rgb, vec3(0.299, 0.587, 0.114));
+
+            float fresnel = 1.0 - abs(dot(normalize(vNormal), vec3(0.0, 0.0, 1.0)));
+            fresnel = pow(fresnel, 1.2);
+
+            float frost = hash(floor(vPos.xy * 0.5));
+            float glint = smoothstep(0.97, 1.0, hash(floor(vPos.xy * 0.25) + floor(time * 2.0)));
+
+            vec3 ice = vec3(0.55, 0.8, 1.0) * (0.5 + luma * 0.6);
+            vec3 rim = vec3(0.85, 0.95, 1.0);
+            vec3 final = mix(ice, rim, fresnel) + frost * 0.08 + glint * 0.6;
+
+            gl_FragColor = vec4(final, 0.55 + fresnel * 0.35);
+        }"#;
+
+        load_material(
+            ShaderSource::Glsl {
+                vertex: vertex_shader,
+                fragment: fragment_shader,
+            },
+            MaterialParams {
+                uniforms: vec![UniformDesc::new("time", UniformType::Float1)],
+                pipeline_params: PipelineParams {
+                    color_blend: Some(miniquad::BlendState::new(
+                        miniquad::Equation::Add,
+                        miniquad::BlendFactor::Value(miniquad::BlendValue::SourceAlpha),
+                        miniquad::BlendFactor::OneMinusValue(miniquad::BlendValue::SourceAlpha),
+                    )),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    })
+}
+
 pub fn get_fire_shader_material() -> &'static Material {
     get_model_fire_material()
 }
@@ -283,6 +361,10 @@ pub fn get_diffuse_specular_material() -> &'static Material {
     get_model_diffuse_specular_material()
 }
 
+pub fn get_frozen_shader_material() -> &'static Material {
+    get_model_frozen_material()
+}
+
 pub struct ModelShaderManager {
     shader_parser: Option<Q3ShaderParser>,
     loaded_textures: HashMap<String, Texture2D>,
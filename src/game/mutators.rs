@@ -0,0 +1,294 @@
+use super::gametype::Loadout;
+use super::map::{Item, ItemType};
+use super::physics::PhysicsProfile;
+use super::weapon::{Weapon, WEAPON_COUNT};
+
+pub const LOW_GRAVITY_SCALE: f32 = 0.4;
+pub const INSTAGIB_DAMAGE: i32 = 1000;
+pub const VAMPIRE_MAX_HEALTH: i32 = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutator {
+    Instagib = 0,
+    RocketsOnly = 1,
+    NoSelfDamage = 2,
+    LowGravity = 3,
+    Vampire = 4,
+    QuadForAll = 5,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 6] = [
+        Mutator::Instagib,
+        Mutator::RocketsOnly,
+        Mutator::NoSelfDamage,
+        Mutator::LowGravity,
+        Mutator::Vampire,
+        Mutator::QuadForAll,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "instagib" | "insta" => Some(Self::Instagib),
+            "rockets" | "rocketsonly" | "rockets_only" => Some(Self::RocketsOnly),
+            "noselfdamage" | "noself" | "no_self_damage" => Some(Self::NoSelfDamage),
+            "lowgrav" | "lowgravity" | "low_gravity" => Some(Self::LowGravity),
+            "vampire" => Some(Self::Vampire),
+            "quad" | "quadforall" | "quad_for_all" => Some(Self::QuadForAll),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Instagib => "instagib",
+            Self::RocketsOnly => "rockets",
+            Self::NoSelfDamage => "noselfdamage",
+            Self::LowGravity => "lowgrav",
+            Self::Vampire => "vampire",
+            Self::QuadForAll => "quad",
+        }
+    }
+}
+
+/// The set of mutators layered over the current gametype.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Mutators {
+    bits: u8,
+}
+
+impl Mutators {
+    /// Comma or space separated mutator names; empty or "none" clears them.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut mutators = Self::default();
+        for name in value.split([',', ' ']).filter(|s| !s.trim().is_empty()) {
+            if name.trim().eq_ignore_ascii_case("none") {
+                continue;
+            }
+            let mutator = Mutator::parse(name).ok_or_else(|| {
+                let known: Vec<&str> = Mutator::ALL.iter().map(|m| m.name()).collect();
+                format!("Unknown mutator '{}' (known: {})", name, known.join(", "))
+            })?;
+            mutators.insert(mutator);
+        }
+        Ok(mutators)
+    }
+
+    pub fn from_cvar() -> Self {
+        let value = crate::cvar::get_cvar_string("g_mutators");
+        Self::parse(&value).unwrap_or_else(|e| {
+            crate::log_warn!(Game, "g_mutators: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            bits: bits & ((1 << Mutator::ALL.len()) - 1),
+        }
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    pub fn insert(&mut self, mutator: Mutator) {
+        self.bits |= 1 << mutator as u8;
+    }
+
+    pub fn contains(&self, mutator: Mutator) -> bool {
+        self.bits & (1 << mutator as u8) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn names(&self) -> String {
+        let names: Vec<&str> = Mutator::ALL
+            .iter()
+            .filter(|m| self.contains(**m))
+            .map(|m| m.name())
+            .collect();
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(",")
+        }
+    }
+
+    pub fn allows_item(&self, item_type: ItemType) -> bool {
+        if self.contains(Mutator::Instagib) {
+            return false;
+        }
+        if self.contains(Mutator::RocketsOnly) {
+            return !matches!(
+                item_type,
+                ItemType::Shotgun
+                    | ItemType::GrenadeLauncher
+                    | ItemType::LightningGun
+                    | ItemType::Railgun
+                    | ItemType::Plasmagun
+                    | ItemType::BFG
+            );
+        }
+        true
+    }
+
+    /// Drops the map items the active mutators take out of play.
+    pub fn filter_items(&self, items: &mut Vec<Item>) {
+        items.retain(|item| self.allows_item(item.item_type));
+    }
+
+    /// Layers the mutators over the gametype's loadout (or the default kit).
+    pub fn spawn_loadout(&self, base: Option<Loadout>) -> Option<Loadout> {
+        if self.contains(Mutator::Instagib) {
+            let mut ammo = [0; WEAPON_COUNT];
+            ammo[Weapon::Railgun as usize] = 255;
+            return Some(Loadout {
+                health: base.map_or(super::constants::STARTING_HEALTH, |l| l.health),
+                armor: 0,
+                ammo,
+                gauntlet: false,
+                weapon: Weapon::Railgun,
+                quad: false,
+            });
+        }
+        if !self.contains(Mutator::RocketsOnly) && !self.contains(Mutator::QuadForAll) {
+            return base;
+        }
+
+        let mut loadout = base.unwrap_or_else(Loadout::standard);
+        if self.contains(Mutator::RocketsOnly) {
+            let rockets = loadout.ammo[Weapon::RocketLauncher as usize].max(50);
+            loadout.ammo = [0; WEAPON_COUNT];
+            loadout.ammo[Weapon::RocketLauncher as usize] = rockets;
+            loadout.gauntlet = true;
+            loadout.weapon = Weapon::RocketLauncher;
+        }
+        if self.contains(Mutator::QuadForAll) {
+            loadout.quad = true;
+        }
+        Some(loadout)
+    }
+
+    pub fn modify_damage(&self, damage: i32, is_self: bool) -> i32 {
+        if is_self {
+            if self.contains(Mutator::Instagib) || self.contains(Mutator::NoSelfDamage) {
+                return 0;
+            }
+            return damage;
+        }
+        if self.contains(Mutator::Instagib) && damage > 0 {
+            return INSTAGIB_DAMAGE;
+        }
+        damage
+    }
+
+    /// Attacker health after a hit that took `dealt` health from someone else.
+    pub fn vampire_heal(&self, health: i32, dealt: i32) -> i32 {
+        if !self.contains(Mutator::Vampire) || dealt <= 0 || health <= 0 {
+            return health;
+        }
+        (health + dealt / 2).min(VAMPIRE_MAX_HEALTH.max(health))
+    }
+
+    pub fn gravity_scale(&self) -> f32 {
+        if self.contains(Mutator::LowGravity) {
+            LOW_GRAVITY_SCALE
+        } else {
+            1.0
+        }
+    }
+
+    /// Applies the movement changes to `physics`; call whenever the set or
+    /// the profile changes.
+    pub fn apply_physics(&self, physics: &mut PhysicsProfile) {
+        physics.gravity_scale = self.gravity_scale();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(item_type: ItemType) -> Item {
+        Item {
+            x: 0.0,
+            y: 0.0,
+            item_type,
+            respawn_time: 0,
+            active: true,
+            vel_x: 0.0,
+            vel_y: 0.0,
+            dropped: false,
+            yaw: 0.0,
+            spin_yaw: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+            spin_pitch: 0.0,
+            spin_roll: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_parse_and_bits() {
+        let m = Mutators::parse("instagib, lowgrav").unwrap();
+        assert!(m.contains(Mutator::Instagib));
+        assert!(m.contains(Mutator::LowGravity));
+        assert!(!m.contains(Mutator::Vampire));
+        assert_eq!(Mutators::from_bits(m.bits()), m);
+        assert_eq!(m.names(), "instagib,lowgrav");
+        assert!(Mutators::parse("none").unwrap().is_empty());
+        assert!(Mutators::parse("").unwrap().is_empty());
+        assert!(Mutators::parse("instagib,moon").is_err());
+
+        let mut physics = PhysicsProfile::default();
+        m.apply_physics(&mut physics);
+        assert_eq!(physics.gravity_scale, LOW_GRAVITY_SCALE);
+        Mutators::default().apply_physics(&mut physics);
+        assert_eq!(physics.gravity_scale, 1.0);
+    }
+
+    #[test]
+    fn test_instagib() {
+        let m = Mutators::parse("instagib").unwrap();
+        let mut items = vec![item(ItemType::Health25), item(ItemType::Railgun)];
+        m.filter_items(&mut items);
+        assert!(items.is_empty());
+
+        let loadout = m.spawn_loadout(None).unwrap();
+        assert_eq!(loadout.weapon, Weapon::Railgun);
+        assert!(!loadout.gauntlet);
+        assert_eq!(loadout.ammo.iter().filter(|&&a| a > 0).count(), 1);
+
+        assert_eq!(m.modify_damage(100, false), INSTAGIB_DAMAGE);
+        assert_eq!(m.modify_damage(100, true), 0);
+    }
+
+    #[test]
+    fn test_composed_mutators() {
+        let m = Mutators::parse("rockets quad vampire noselfdamage").unwrap();
+        let mut items = vec![
+            item(ItemType::RocketLauncher),
+            item(ItemType::Railgun),
+            item(ItemType::Armor50),
+        ];
+        m.filter_items(&mut items);
+        assert_eq!(items.len(), 2);
+
+        let base = Loadout::standard();
+        let loadout = m.spawn_loadout(Some(base)).unwrap();
+        assert_eq!(loadout.weapon, Weapon::RocketLauncher);
+        assert_eq!(loadout.ammo[Weapon::MachineGun as usize], 0);
+        assert!(loadout.quad);
+        assert_eq!(loadout.health, base.health);
+
+        assert_eq!(m.modify_damage(80, false), 80);
+        assert_eq!(m.modify_damage(80, true), 0);
+        assert_eq!(m.vampire_heal(100, 80), 140);
+        assert_eq!(m.vampire_heal(190, 80), VAMPIRE_MAX_HEALTH);
+        assert_eq!(m.gravity_scale(), 1.0);
+        assert_eq!(Mutators::default().spawn_loadout(None), None);
+    }
+}
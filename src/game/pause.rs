@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+
+pub const UNPAUSE_COUNTDOWN: f32 = 5.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PausePhase {
+    Running,
+    /// `by` is None for an admin pause, which has no time cap.
+    Paused {
+        by: Option<u16>,
+        remaining: Option<f32>,
+    },
+    Unpausing(f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PauseEvent {
+    Paused {
+        by: Option<u16>,
+    },
+    /// The timeout ran out before anyone called unpause.
+    Expired,
+    Unpausing,
+    Countdown(u32),
+    Resumed,
+}
+
+impl PauseEvent {
+    pub fn announcement(&self) -> Option<&'static str> {
+        match *self {
+            PauseEvent::Countdown(3) => Some("three"),
+            PauseEvent::Countdown(2) => Some("two"),
+            PauseEvent::Countdown(1) => Some("one"),
+            PauseEvent::Resumed => Some("fight"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PauseState {
+    pub phase: PausePhase,
+    /// Timeouts each player may call per match.
+    pub max_timeouts: u32,
+    /// Longest a single timeout lasts before the game resumes on its own.
+    pub max_duration: f32,
+    used: Vec<(u16, u32)>,
+}
+
+impl Default for PauseState {
+    fn default() -> Self {
+        Self::new(0, 0.0)
+    }
+}
+
+impl PauseState {
+    pub fn new(max_timeouts: u32, max_duration: f32) -> Self {
+        Self {
+            phase: PausePhase::Running,
+            max_timeouts,
+            max_duration,
+            used: Vec::new(),
+        }
+    }
+
+    /// Reads `g_timeouts` and `g_timeoutlen`.
+    pub fn from_cvar() -> Self {
+        Self::new(
+            crate::cvar::get_cvar_integer("g_timeouts").max(0) as u32,
+            crate::cvar::get_cvar_float("g_timeoutlen").max(1.0),
+        )
+    }
+
+    /// Resumes play and gives everyone their timeouts back.
+    pub fn reset(&mut self) {
+        self.phase = PausePhase::Running;
+        self.used.clear();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.phase != PausePhase::Running
+    }
+
+    pub fn timeouts_left(&self, player_id: u16) -> u32 {
+        let used = self
+            .used
+            .iter()
+            .find(|(id, _)| *id == player_id)
+            .map_or(0, |(_, n)| *n);
+        self.max_timeouts.saturating_sub(used)
+    }
+
+    pub fn call_timeout(&mut self, player_id: u16) -> Result<PauseEvent, String> {
+        if self.is_paused() {
+            return Err("The game is already paused".to_string());
+        }
+        if self.timeouts_left(player_id) == 0 {
+            return Err("No timeouts left".to_string());
+        }
+        match self.used.iter_mut().find(|(id, _)| *id == player_id) {
+            Some((_, n)) => *n += 1,
+            None => self.used.push((player_id, 1)),
+        }
+        self.phase = PausePhase::Paused {
+            by: Some(player_id),
+            remaining: Some(self.max_duration),
+        };
+        Ok(PauseEvent::Paused {
+            by: Some(player_id),
+        })
+    }
+
+    pub fn admin_pause(&mut self) -> Result<PauseEvent, String> {
+        if self.is_paused() {
+            return Err("The game is already paused".to_string());
+        }
+        self.phase = PausePhase::Paused {
+            by: None,
+            remaining: None,
+        };
+        Ok(PauseEvent::Paused { by: None })
+    }
+
+    /// `player_id` None is the server admin, who may lift any pause. Players
+    /// can only end their own timeout.
+    pub fn unpause(&mut self, player_id: Option<u16>) -> Result<PauseEvent, String> {
+        let PausePhase::Paused { by, .. } = self.phase else {
+            return Err("The game is not paused".to_string());
+        };
+        if player_id.is_some() && by != player_id {
+            return Err("Only the player who called the timeout can unpause".to_string());
+        }
+        self.phase = PausePhase::Unpausing(UNPAUSE_COUNTDOWN);
+        Ok(PauseEvent::Unpausing)
+    }
+
+    pub fn update(&mut self, dt: f32) -> Vec<PauseEvent> {
+        let mut events = Vec::new();
+        match self.phase {
+            PausePhase::Running => {}
+            PausePhase::Paused {
+                by,
+                remaining: Some(t),
+            } => {
+                let next = t - dt;
+                if next <= 0.0 {
+                    self.phase = PausePhase::Unpausing(UNPAUSE_COUNTDOWN);
+                    events.push(PauseEvent::Expired);
+                    events.push(PauseEvent::Unpausing);
+                } else {
+                    self.phase = PausePhase::Paused {
+                        by,
+                        remaining: Some(next),
+                    };
+                }
+            }
+            PausePhase::Paused {
+                remaining: None, ..
+            } => {}
+            PausePhase::Unpausing(t) => {
+                let next = t - dt;
+                if next <= 0.0 {
+                    self.phase = PausePhase::Running;
+                    events.push(PauseEvent::Resumed);
+                } else {
+                    let secs = next.ceil() as u32;
+                    if secs < t.ceil() as u32 {
+                        events.push(PauseEvent::Countdown(secs));
+                    }
+                    self.phase = PausePhase::Unpausing(next);
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(state: &mut PauseState, secs: f32) -> Vec<PauseEvent> {
+        let mut events = Vec::new();
+        for _ in 0..(secs * 10.0) as usize {
+            events.extend(state.update(0.1));
+        }
+        events
+    }
+
+    #[test]
+    fn test_timeouts_are_limited_per_player() {
+        let mut state = PauseState::new(1, 30.0);
+        assert_eq!(
+            state.call_timeout(1),
+            Ok(PauseEvent::Paused { by: Some(1) })
+        );
+        assert!(state.call_timeout(2).is_err());
+        assert!(state.unpause(Some(2)).is_err());
+        assert_eq!(state.unpause(Some(1)), Ok(PauseEvent::Unpausing));
+        run(&mut state, 6.0);
+        assert!(!state.is_paused());
+
+        assert_eq!(state.timeouts_left(1), 0);
+        assert!(state.call_timeout(1).is_err());
+        assert!(state.call_timeout(2).is_ok());
+
+        state.reset();
+        assert_eq!(state.timeouts_left(1), 1);
+    }
+
+    #[test]
+    fn test_timeout_expires_into_countdown() {
+        let mut state = PauseState::new(2, 5.0);
+        state.call_timeout(1).unwrap();
+        let events = run(&mut state, 5.5);
+        assert_eq!(events, vec![PauseEvent::Expired, PauseEvent::Unpausing]);
+
+        let events = run(&mut state, 5.5);
+        assert!(events.contains(&PauseEvent::Countdown(4)));
+        let announced: Vec<_> = events.iter().filter_map(|e| e.announcement()).collect();
+        assert_eq!(announced, vec!["three", "two", "one", "fight"]);
+        assert_eq!(state.phase, PausePhase::Running);
+    }
+
+    #[test]
+    fn test_admin_pause_has_no_cap() {
+        let mut state = PauseState::new(0, 5.0);
+        assert!(state.call_timeout(1).is_err());
+        state.admin_pause().unwrap();
+        assert!(run(&mut state, 60.0).is_empty());
+        assert!(state.unpause(Some(1)).is_err());
+        assert!(state.unpause(None).is_ok());
+    }
+}
@@ -0,0 +1,182 @@
+use super::constants::*;
+use super::grapple;
+use crate::cvar;
+use serde::{Deserialize, Serialize};
+
+/// Movement tuning shared by the server and client prediction. The server
+/// picks one with `pm_physics` and sends it in the server info.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsProfile {
+    pub gravity: f32,
+    /// Multiplier on `gravity`, lowered by the low gravity mutator.
+    pub gravity_scale: f32,
+    pub ground_accel: f32,
+    pub air_accel: f32,
+    pub max_speed_ground: f32,
+    pub max_speed_air: f32,
+    pub max_fall_speed: f32,
+    pub jump_force: f32,
+    pub ground_friction: f32,
+    pub air_friction: f32,
+    pub step_height: f32,
+    /// How hard you can turn around in the air, 1 being as hard as on the ground.
+    pub air_control: f32,
+    /// A second jump soon after the first goes higher.
+    pub double_jump: bool,
+    /// Stepping up while running lifts you, so a jump off stairs carries further.
+    pub ramp_jump: bool,
+    /// Grappling hook pull per 60 Hz frame, 0 when the hook is off.
+    pub grapple_pull: f32,
+}
+
+impl Default for PhysicsProfile {
+    fn default() -> Self {
+        Self::vq3()
+    }
+}
+
+impl PhysicsProfile {
+    pub fn vq3() -> Self {
+        Self {
+            gravity: GRAVITY,
+            gravity_scale: 1.0,
+            ground_accel: GROUND_ACCEL,
+            air_accel: AIR_ACCEL,
+            max_speed_ground: MAX_SPEED_GROUND,
+            max_speed_air: MAX_SPEED_AIR,
+            max_fall_speed: MAX_FALL_SPEED,
+            jump_force: JUMP_FORCE,
+            ground_friction: 0.14,
+            air_friction: 0.025,
+            step_height: 16.0,
+            air_control: 1.0,
+            double_jump: false,
+            ramp_jump: false,
+            grapple_pull: 0.0,
+        }
+    }
+
+    pub fn cpm() -> Self {
+        Self {
+            air_accel: 0.45,
+            max_speed_air: 7.5,
+            air_friction: 0.01,
+            air_control: 2.5,
+            double_jump: true,
+            ramp_jump: true,
+            ..Self::vq3()
+        }
+    }
+
+    /// The vq3 profile with the `pm_*` tuning cvars applied.
+    pub fn custom() -> Self {
+        Self {
+            gravity: cvar::get_cvar_float("pm_gravity"),
+            ground_accel: cvar::get_cvar_float("pm_accel"),
+            air_accel: cvar::get_cvar_float("pm_airaccel"),
+            max_speed_ground: cvar::get_cvar_float("pm_maxspeed"),
+            max_speed_air: cvar::get_cvar_float("pm_airmaxspeed"),
+            jump_force: -cvar::get_cvar_float("pm_jumpforce").abs(),
+            air_control: cvar::get_cvar_float("pm_aircontrol"),
+            double_jump: cvar::get_cvar_bool("pm_doublejump"),
+            ramp_jump: cvar::get_cvar_bool("pm_rampjump"),
+            ..Self::vq3()
+        }
+    }
+
+    pub fn from_cvars() -> Self {
+        let profile = match cvar::get_cvar_string("pm_physics").to_lowercase().as_str() {
+            "cpm" => Self::cpm(),
+            "custom" => Self::custom(),
+            _ => Self::vq3(),
+        };
+        Self {
+            grapple_pull: grapple::pull_from_cvars(),
+            ..profile
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bg_pmove::{pmove, PmoveCmd, PmoveState};
+    use crate::game::map::Map;
+
+    fn floor_map() -> Map {
+        let mut map = Map::new("test");
+        for x in 0..map.width {
+            for y in 0..map.height {
+                map.tiles[x][y].solid = y >= 20;
+            }
+        }
+        map.jumppads.clear();
+        map
+    }
+
+    fn cmd(move_right: f32, jump: bool) -> PmoveCmd {
+        PmoveCmd {
+            move_right,
+            jump,
+            crouch: false,
+            haste_active: false,
+            flight_active: false,
+            grapple: None,
+        }
+    }
+
+    fn step(state: &PmoveState, cmd: &PmoveCmd, map: &Map, physics: &PhysicsProfile) -> PmoveState {
+        let result = pmove(state, cmd, 1.0 / 60.0, map, physics);
+        PmoveState {
+            x: result.new_x,
+            y: result.new_y,
+            vel_x: result.new_vel_x,
+            vel_y: result.new_vel_y,
+            was_in_air: result.new_was_in_air,
+            jump_time: result.new_jump_time,
+            fall_speed: result.new_fall_speed,
+        }
+    }
+
+    #[test]
+    fn test_cpm_turns_around_faster_in_the_air() {
+        let map = floor_map();
+        let airborne = PmoveState {
+            x: 600.0,
+            y: 100.0,
+            vel_x: 5.0,
+            vel_y: 0.0,
+            was_in_air: true,
+            jump_time: 0.0,
+            fall_speed: 0.0,
+        };
+        let vq3 = step(&airborne, &cmd(-1.0, false), &map, &PhysicsProfile::vq3());
+        let cpm = step(&airborne, &cmd(-1.0, false), &map, &PhysicsProfile::cpm());
+        assert!(cpm.vel_x < vq3.vel_x);
+    }
+
+    #[test]
+    fn test_double_jump_goes_higher() {
+        let map = floor_map();
+        let standing = PmoveState {
+            x: 600.0,
+            y: 20.0 * 16.0 - 24.0,
+            vel_x: 0.0,
+            vel_y: 0.0,
+            was_in_air: false,
+            jump_time: 0.0,
+            fall_speed: 0.0,
+        };
+        let first = step(&standing, &cmd(0.0, true), &map, &PhysicsProfile::cpm());
+        assert!(first.jump_time > 0.0);
+
+        let again = PmoveState {
+            jump_time: first.jump_time,
+            ..standing.clone()
+        };
+        let cpm = step(&again, &cmd(0.0, true), &map, &PhysicsProfile::cpm());
+        let vq3 = step(&again, &cmd(0.0, true), &map, &PhysicsProfile::vq3());
+        assert!(cpm.vel_y < first.vel_y);
+        assert_eq!(vq3.vel_y, first.vel_y);
+    }
+}
@@ -0,0 +1,361 @@
+use super::constants::*;
+use super::map::{Item, ItemType};
+use super::weapon::Weapon;
+use serde::{Deserialize, Serialize};
+
+/// Frames left on each powerup, counted down once per simulation tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerUps {
+    pub quad: u16,
+    pub regen: u16,
+    pub battle: u16,
+    pub flight: u16,
+    pub haste: u16,
+    pub invis: u16,
+}
+
+impl PowerUps {
+    /// Names of the powerups currently running.
+    pub fn active(&self) -> impl Iterator<Item = &'static str> {
+        [
+            ("quad", self.quad),
+            ("regen", self.regen),
+            ("battle", self.battle),
+            ("flight", self.flight),
+            ("haste", self.haste),
+            ("invis", self.invis),
+        ]
+        .into_iter()
+        .filter(|(_, left)| *left > 0)
+        .map(|(name, _)| name)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Counts everything down one tick. Regeneration heals once a second:
+    /// 15 up to 100 health, then 5 up to 200.
+    pub fn tick(&mut self, health: &mut i32) {
+        self.quad = self.quad.saturating_sub(1);
+        self.battle = self.battle.saturating_sub(1);
+        self.flight = self.flight.saturating_sub(1);
+        self.haste = self.haste.saturating_sub(1);
+        self.invis = self.invis.saturating_sub(1);
+        if self.regen > 0 {
+            self.regen -= 1;
+            if self.regen.is_multiple_of(REGEN_INTERVAL) && *health > 0 {
+                if *health < 100 {
+                    *health = (*health + 15).min(100);
+                } else if *health < 200 {
+                    *health = (*health + 5).min(200);
+                }
+            }
+        }
+    }
+
+    /// Battle Suit halves incoming damage.
+    pub fn absorb(&self, damage: i32) -> i32 {
+        if self.battle > 0 {
+            damage / 2
+        } else {
+            damage
+        }
+    }
+
+    /// Haste speeds up weapon fire along with movement.
+    pub fn fire_rate(&self) -> f32 {
+        if self.haste > 0 {
+            HASTE_FIRE_MULT
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Items carried in the single holdable slot until the use button fires them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Holdable {
+    Medkit,
+    Teleporter,
+}
+
+impl Holdable {
+    pub fn item_type(self) -> ItemType {
+        match self {
+            Holdable::Medkit => ItemType::Medkit,
+            Holdable::Teleporter => ItemType::Teleporter,
+        }
+    }
+}
+
+pub const PICKUP_RADIUS: f32 = 24.0;
+/// The medkit heals to max health plus 25 and is kept when already there.
+pub const MEDKIT_HEALTH: i32 = 125;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickupKind {
+    Health,
+    Armor,
+    Weapon,
+    Powerup,
+    Holdable,
+}
+
+/// The player fields an item can change. Implemented by the local player and
+/// the dedicated server's player so both follow the same rules.
+pub trait ItemHolder {
+    fn health_mut(&mut self) -> &mut i32;
+    fn armor_mut(&mut self) -> &mut i32;
+    fn powerups_mut(&mut self) -> &mut PowerUps;
+    fn holdable_mut(&mut self) -> &mut Option<Holdable>;
+    /// Gives the weapon and adds ammo, capped at `max_ammo`.
+    fn give_weapon(&mut self, weapon: Weapon, ammo: u16, max_ammo: u16);
+}
+
+pub fn kind(item: ItemType) -> PickupKind {
+    use ItemType::*;
+    match item {
+        Health25 | Health50 | Health100 => PickupKind::Health,
+        Armor50 | Armor100 => PickupKind::Armor,
+        Shotgun | GrenadeLauncher | RocketLauncher | LightningGun | Railgun | Plasmagun | BFG
+        | Nailgun | ProxLauncher | Chaingun => PickupKind::Weapon,
+        Quad | Regen | Battle | Flight | Haste | Invis => PickupKind::Powerup,
+        Medkit | Teleporter => PickupKind::Holdable,
+    }
+}
+
+/// Frames until a taken item comes back.
+pub fn respawn_time(item: ItemType) -> u32 {
+    match kind(item) {
+        PickupKind::Health => ITEM_RESPAWN_HEALTH,
+        PickupKind::Armor => ITEM_RESPAWN_ARMOR,
+        PickupKind::Weapon => ITEM_RESPAWN_WEAPON,
+        PickupKind::Powerup => ITEM_RESPAWN_POWERUP,
+        PickupKind::Holdable => ITEM_RESPAWN_HOLDABLE,
+    }
+}
+
+/// Weapon, ammo given and ammo cap for a weapon item.
+pub fn weapon_grant(item: ItemType) -> Option<(Weapon, u16, u16)> {
+    use ItemType::*;
+    match item {
+        Shotgun => Some((Weapon::Shotgun, 10, 100)),
+        GrenadeLauncher => Some((Weapon::GrenadeLauncher, 10, 100)),
+        RocketLauncher => Some((Weapon::RocketLauncher, 10, 100)),
+        LightningGun => Some((Weapon::Lightning, 100, 200)),
+        Railgun => Some((Weapon::Railgun, 10, 100)),
+        Plasmagun => Some((Weapon::Plasmagun, 50, 200)),
+        BFG => Some((Weapon::BFG, 15, 200)),
+        Nailgun => Some((Weapon::Nailgun, 10, 100)),
+        ProxLauncher => Some((Weapon::ProxLauncher, 5, 20)),
+        Chaingun => Some((Weapon::Chaingun, 80, 200)),
+        _ => None,
+    }
+}
+
+pub fn in_reach(x: f32, y: f32, item: &Item) -> bool {
+    let dx = x - item.x;
+    let dy = y - item.y;
+    (dx * dx + dy * dy).sqrt() < PICKUP_RADIUS
+}
+
+/// Counts taken items down once per tick and brings them back.
+pub fn tick_respawns(items: &mut [Item]) {
+    for item in items.iter_mut().filter(|item| !item.active) {
+        if item.respawn_time > 0 {
+            item.respawn_time -= 1;
+        } else {
+            item.active = true;
+        }
+    }
+}
+
+/// Applies the item; false when the player has no use for it and it stays.
+pub fn touch(holder: &mut impl ItemHolder, item: ItemType) -> bool {
+    use ItemType::*;
+    let (amount, cap) = match item {
+        Health25 => (25, 100),
+        Health50 => (50, 100),
+        Health100 => (100, 200),
+        Armor50 => (50, 100),
+        Armor100 => (100, 200),
+        _ => (0, 0),
+    };
+    match kind(item) {
+        PickupKind::Health => {
+            let health = holder.health_mut();
+            if *health >= cap {
+                return false;
+            }
+            *health = (*health + amount).min(cap);
+        }
+        PickupKind::Armor => {
+            let armor = holder.armor_mut();
+            if *armor >= cap {
+                return false;
+            }
+            *armor = (*armor + amount).min(cap);
+        }
+        PickupKind::Weapon => {
+            if let Some((weapon, ammo, max_ammo)) = weapon_grant(item) {
+                holder.give_weapon(weapon, ammo, max_ammo);
+            }
+        }
+        PickupKind::Powerup => {
+            let powerups = holder.powerups_mut();
+            match item {
+                Quad => powerups.quad = POWERUP_DURATION_QUAD,
+                Regen => powerups.regen = POWERUP_DURATION_REGEN,
+                Battle => powerups.battle = POWERUP_DURATION_BATTLE,
+                Flight => powerups.flight = POWERUP_DURATION_FLIGHT,
+                Haste => powerups.haste = POWERUP_DURATION_HASTE,
+                _ => powerups.invis = POWERUP_DURATION_INVIS,
+            }
+        }
+        PickupKind::Holdable => {
+            let slot = holder.holdable_mut();
+            if slot.is_some() {
+                return false;
+            }
+            *slot = Some(if item == Medkit {
+                Holdable::Medkit
+            } else {
+                Holdable::Teleporter
+            });
+        }
+    }
+    true
+}
+
+/// Fires the held item and empties the slot. The medkit heals here; for the
+/// teleporter the caller moves the player to a spawn point.
+pub fn use_holdable(holder: &mut impl ItemHolder) -> Option<Holdable> {
+    let held = (*holder.holdable_mut())?;
+    if held == Holdable::Medkit {
+        let health = holder.health_mut();
+        if *health <= 0 || *health >= MEDKIT_HEALTH {
+            return None;
+        }
+        *health = MEDKIT_HEALTH;
+    }
+    *holder.holdable_mut() = None;
+    Some(held)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Holder {
+        health: i32,
+        armor: i32,
+        ammo: [u16; crate::game::weapon::WEAPON_COUNT],
+        powerups: PowerUps,
+        holdable: Option<Holdable>,
+    }
+
+    impl ItemHolder for Holder {
+        fn health_mut(&mut self) -> &mut i32 {
+            &mut self.health
+        }
+
+        fn armor_mut(&mut self) -> &mut i32 {
+            &mut self.armor
+        }
+
+        fn powerups_mut(&mut self) -> &mut PowerUps {
+            &mut self.powerups
+        }
+
+        fn holdable_mut(&mut self) -> &mut Option<Holdable> {
+            &mut self.holdable
+        }
+
+        fn give_weapon(&mut self, weapon: Weapon, ammo: u16, max_ammo: u16) {
+            let slot = &mut self.ammo[weapon as usize];
+            *slot = (*slot + ammo).min(max_ammo);
+        }
+    }
+
+    #[test]
+    fn test_health_and_armor_caps() {
+        let mut holder = Holder {
+            health: 90,
+            ..Default::default()
+        };
+        assert!(touch(&mut holder, ItemType::Health25));
+        assert_eq!(holder.health, 100);
+        assert!(!touch(&mut holder, ItemType::Health50));
+        assert!(touch(&mut holder, ItemType::Health100));
+        assert_eq!(holder.health, 200);
+
+        holder.armor = 150;
+        assert!(!touch(&mut holder, ItemType::Armor50));
+        assert!(touch(&mut holder, ItemType::Armor100));
+        assert_eq!(holder.armor, 200);
+        assert_eq!(respawn_time(ItemType::Armor100), ITEM_RESPAWN_ARMOR);
+    }
+
+    #[test]
+    fn test_weapons_and_powerups() {
+        let mut holder = Holder::default();
+        for _ in 0..12 {
+            assert!(touch(&mut holder, ItemType::RocketLauncher));
+        }
+        assert_eq!(holder.ammo[Weapon::RocketLauncher as usize], 100);
+
+        assert!(touch(&mut holder, ItemType::Haste));
+        assert!(touch(&mut holder, ItemType::Battle));
+        assert_eq!(holder.powerups.haste, POWERUP_DURATION_HASTE);
+        assert_eq!(holder.powerups.absorb(40), 20);
+        assert!(holder.powerups.fire_rate() > 1.0);
+        assert_eq!(
+            holder.powerups.active().collect::<Vec<_>>(),
+            vec!["battle", "haste"]
+        );
+        assert_eq!(respawn_time(ItemType::Haste), ITEM_RESPAWN_POWERUP);
+    }
+
+    #[test]
+    fn test_regen_ticks() {
+        let mut powerups = PowerUps {
+            regen: REGEN_INTERVAL * 3,
+            ..Default::default()
+        };
+        let mut health = 95;
+        for _ in 0..REGEN_INTERVAL {
+            powerups.tick(&mut health);
+        }
+        assert_eq!(health, 100);
+        for _ in 0..REGEN_INTERVAL * 2 {
+            powerups.tick(&mut health);
+        }
+        assert_eq!(health, 110);
+        assert_eq!(powerups.regen, 0);
+        powerups.tick(&mut health);
+        assert_eq!(health, 110);
+    }
+
+    #[test]
+    fn test_holdables() {
+        let mut holder = Holder::default();
+        assert!(touch(&mut holder, ItemType::Medkit));
+        assert!(!touch(&mut holder, ItemType::Teleporter));
+        assert_eq!(holder.holdable, Some(Holdable::Medkit));
+        assert_eq!(respawn_time(ItemType::Medkit), ITEM_RESPAWN_HOLDABLE);
+
+        holder.health = 130;
+        assert_eq!(use_holdable(&mut holder), None);
+        assert_eq!(holder.holdable, Some(Holdable::Medkit));
+        holder.health = 40;
+        assert_eq!(use_holdable(&mut holder), Some(Holdable::Medkit));
+        assert_eq!((holder.health, holder.holdable), (MEDKIT_HEALTH, None));
+        assert_eq!(use_holdable(&mut holder), None);
+
+        assert!(touch(&mut holder, ItemType::Teleporter));
+        assert_eq!(use_holdable(&mut holder), Some(Holdable::Teleporter));
+        assert_eq!(holder.holdable, None);
+    }
+}
@@ -1,8 +1,14 @@
 use super::animation::{AnimState, PlayerAnimation};
+use super::bg_combat::{CombatEvent, Combatant, DamageRules};
 use super::bot_ai::BotAI;
-use super::map::Map;
+use super::grapple::Grapple;
+use super::liquid::Breath;
+use super::map::{Liquid, Map};
+use super::physics::PhysicsProfile;
+pub use super::pickup::PowerUps;
+use super::pickup::{Holdable, ItemHolder};
 use super::sprite;
-use super::weapon::Weapon;
+use super::weapon::{Weapon, WEAPON_COUNT};
 use crate::audio::events::AudioEvent;
 use macroquad::prelude::*;
 
@@ -30,17 +36,30 @@ pub struct Player {
     pub team: u8,
     pub dead: bool,
     pub gibbed: bool,
+    /// Frozen players are also `dead`; they keep their pose until thawed.
+    pub frozen: bool,
+    pub thaw_progress: f32,
     pub is_bot: bool,
     pub crouch: bool,
     pub weapon: Weapon,
-    pub ammo: [u8; 9],
-    pub has_weapon: [bool; 9],
+    pub ammo: [u8; WEAPON_COUNT],
+    pub has_weapon: [bool; WEAPON_COUNT],
     pub refire: f32,
     pub weapon_switch_time: f32,
     pub powerups: PowerUps,
+    pub holdable: Option<Holdable>,
+    pub use_held: bool,
+    pub grapple: Grapple,
     pub animation: PlayerAnimation,
     pub bot_ai: Option<BotAI>,
     pub was_in_air: bool,
+    pub jump_time: f32,
+    pub fall_speed: f32,
+    /// Landing damage from movement, waiting for the game to apply it.
+    pub fall_damage: i32,
+    pub water_level: u8,
+    pub liquid: Option<Liquid>,
+    pub breath: Breath,
     pub respawn_timer: f32,
     pub lower_frame: usize,
     pub upper_frame: usize,
@@ -72,14 +91,31 @@ pub struct Player {
     pub weapon_raise_time: f32,
 }
 
-#[derive(Clone, Debug)]
-pub struct PowerUps {
-    pub quad: u16,
-    pub regen: u16,
-    pub battle: u16,
-    pub flight: u16,
-    pub haste: u16,
-    pub invis: u16,
+impl ItemHolder for Player {
+    fn health_mut(&mut self) -> &mut i32 {
+        &mut self.health
+    }
+
+    fn armor_mut(&mut self) -> &mut i32 {
+        &mut self.armor
+    }
+
+    fn powerups_mut(&mut self) -> &mut PowerUps {
+        &mut self.powerups
+    }
+
+    fn holdable_mut(&mut self) -> &mut Option<Holdable> {
+        &mut self.holdable
+    }
+
+    fn give_weapon(&mut self, weapon: Weapon, ammo: u16, max_ammo: u16) {
+        let idx = weapon as usize;
+        self.has_weapon[idx] = true;
+        let total = (self.ammo[idx] as u16 + ammo)
+            .min(max_ammo)
+            .min(u8::MAX as u16);
+        self.ammo[idx] = total as u8;
+    }
 }
 
 impl Player {
@@ -134,27 +170,7 @@ impl Player {
             }
         }
 
-        if self.powerups.quad > 0 {
-            self.powerups.quad = self.powerups.quad.saturating_sub(1);
-        }
-        if self.powerups.regen > 0 {
-            self.powerups.regen = self.powerups.regen.saturating_sub(1);
-            if (get_time() * 50.0) as u64 % 10 == 0 && self.health < 200 {
-                self.health += 1;
-            }
-        }
-        if self.powerups.battle > 0 {
-            self.powerups.battle = self.powerups.battle.saturating_sub(1);
-        }
-        if self.powerups.flight > 0 {
-            self.powerups.flight = self.powerups.flight.saturating_sub(1);
-        }
-        if self.powerups.haste > 0 {
-            self.powerups.haste = self.powerups.haste.saturating_sub(1);
-        }
-        if self.powerups.invis > 0 {
-            self.powerups.invis = self.powerups.invis.saturating_sub(1);
-        }
+        self.powerups.tick(&mut self.health);
 
         if self.health > 100 {
             self.hp_decay_timer += dt;
@@ -184,7 +200,7 @@ impl Player {
             }
         }
 
-        if matches!(self.weapon, Weapon::MachineGun) {
+        if matches!(self.weapon, Weapon::MachineGun | Weapon::Chaingun) {
             if self.barrel_spin_speed > 0.0 {
                 self.barrel_spin_speed -= super::constants::BARREL_SPIN_FRICTION * dt;
                 if self.barrel_spin_speed < 0.0 {
@@ -202,8 +218,22 @@ impl Player {
         }
     }
 
-    pub fn pmove(&mut self, cmd: &super::usercmd::UserCmd, dt: f32, map: &Map) -> Vec<AudioEvent> {
-        self.pmove_internal(cmd, dt, map, true)
+    /// True on the command where the use-item button goes down.
+    pub fn use_item_pressed(&mut self, cmd: &super::usercmd::UserCmd) -> bool {
+        let down = cmd.buttons & super::usercmd::BUTTON_USE_ITEM != 0;
+        let pressed = down && !self.use_held;
+        self.use_held = down;
+        pressed
+    }
+
+    pub fn pmove(
+        &mut self,
+        cmd: &super::usercmd::UserCmd,
+        dt: f32,
+        map: &Map,
+        physics: &PhysicsProfile,
+    ) -> Vec<AudioEvent> {
+        self.pmove_internal(cmd, dt, map, physics, true)
     }
 
     pub fn pmove_no_teleport(
@@ -211,8 +241,9 @@ impl Player {
         cmd: &super::usercmd::UserCmd,
         dt: f32,
         map: &Map,
+        physics: &PhysicsProfile,
     ) -> Vec<AudioEvent> {
-        self.pmove_internal(cmd, dt, map, false)
+        self.pmove_internal(cmd, dt, map, physics, false)
     }
 
     fn pmove_internal(
@@ -220,12 +251,14 @@ impl Player {
         cmd: &super::usercmd::UserCmd,
         dt: f32,
         map: &Map,
+        physics: &PhysicsProfile,
         allow_teleport: bool,
     ) -> Vec<AudioEvent> {
         let mut events = Vec::new();
 
         if self.dead {
-            self.apply_death_physics(dt, map);
+            self.grapple = Grapple::Idle;
+            self.apply_death_physics(dt, map, physics);
             return events;
         }
 
@@ -233,6 +266,14 @@ impl Player {
 
         self.angle = cmd.angles.0;
         self.crouch = (cmd.buttons & BUTTON_CROUCH) != 0;
+        self.grapple.update(
+            (cmd.buttons & BUTTON_HOOK) != 0 && physics.grapple_pull > 0.0,
+            cmd.angles.0,
+            self.x,
+            self.y,
+            dt,
+            map,
+        );
 
         let pmove_state = super::bg_pmove::PmoveState {
             x: self.x,
@@ -240,6 +281,8 @@ impl Player {
             vel_x: self.vel_x,
             vel_y: self.vel_y,
             was_in_air: self.was_in_air,
+            jump_time: self.jump_time,
+            fall_speed: self.fall_speed,
         };
 
         let pmove_cmd = super::bg_pmove::PmoveCmd {
@@ -247,9 +290,11 @@ impl Player {
             jump: (cmd.buttons & BUTTON_JUMP) != 0,
             crouch: (cmd.buttons & BUTTON_CROUCH) != 0,
             haste_active: self.powerups.haste > 0,
+            flight_active: self.powerups.flight > 0,
+            grapple: self.grapple.anchor(),
         };
 
-        let result = super::bg_pmove::pmove(&pmove_state, &pmove_cmd, dt, map);
+        let result = super::bg_pmove::pmove(&pmove_state, &pmove_cmd, dt, map, physics);
 
         if result.jumped {
             events.push(AudioEvent::PlayerJump {
@@ -277,6 +322,7 @@ impl Player {
                     self.vel_x = result.new_vel_x;
                     self.vel_y = result.new_vel_y;
                     self.was_in_air = result.new_was_in_air;
+                    self.fall_speed = 0.0;
                     self.fix_stuck_position(map);
                     events.push(AudioEvent::TeleportOut { x: self.x });
                     teleported = true;
@@ -320,6 +366,16 @@ impl Player {
         self.vel_x = result.new_vel_x;
         self.vel_y = result.new_vel_y;
         self.was_in_air = result.new_was_in_air;
+        self.jump_time = result.new_jump_time;
+        self.fall_speed = result.new_fall_speed;
+        self.fall_damage += result.fall_damage;
+        events.extend(super::liquid::splash_events(
+            self.water_level,
+            result.water_level,
+            self.x,
+        ));
+        self.water_level = result.water_level;
+        self.liquid = result.liquid;
 
         let moving = self.vel_x.abs() > 0.5;
         let shooting =
@@ -354,24 +410,30 @@ impl Player {
             team: 0,
             dead: false,
             gibbed: false,
+            frozen: false,
+            thaw_progress: 0.0,
             is_bot,
             crouch: false,
             weapon: Weapon::Gauntlet,
-            ammo: [0; 9],
-            has_weapon: [true, false, false, false, false, false, false, false, false],
+            ammo: [0; WEAPON_COUNT],
+            has_weapon: [
+                true, false, false, false, false, false, false, false, false, false, false, false,
+            ],
             refire: 0.0,
             weapon_switch_time: 0.0,
-            powerups: PowerUps {
-                quad: 0,
-                regen: 0,
-                battle: 0,
-                flight: 0,
-                haste: 0,
-                invis: 0,
-            },
+            powerups: PowerUps::default(),
+            holdable: None,
+            use_held: false,
+            grapple: Grapple::Idle,
             animation: PlayerAnimation::new(),
             bot_ai: if is_bot { Some(BotAI::new()) } else { None },
             was_in_air: false,
+            jump_time: 0.0,
+            fall_speed: 0.0,
+            fall_damage: 0,
+            water_level: 0,
+            liquid: None,
+            breath: Breath::default(),
             respawn_timer: 0.0,
             lower_frame: 0,
             upper_frame: 0,
@@ -536,27 +598,7 @@ impl Player {
         self.animation
             .update(is_on_ground, moving, shooting, self.vel_x.abs());
 
-        if self.powerups.quad > 0 {
-            self.powerups.quad -= 1;
-        }
-        if self.powerups.regen > 0 {
-            self.powerups.regen -= 1;
-            if self._frame() % 10 == 0 && self.health < 200 {
-                self.health += 1;
-            }
-        }
-        if self.powerups.battle > 0 {
-            self.powerups.battle -= 1;
-        }
-        if self.powerups.flight > 0 {
-            self.powerups.flight -= 1;
-        }
-        if self.powerups.haste > 0 {
-            self.powerups.haste -= 1;
-        }
-        if self.powerups.invis > 0 {
-            self.powerups.invis -= 1;
-        }
+        self.powerups.tick(&mut self.health);
 
         (if landed { Some(true) } else { None }, events)
     }
@@ -669,10 +711,17 @@ impl Player {
         self.y = aligned_y;
         self.vel_x = 0.0;
         self.vel_y = 0.0;
+        self.fall_speed = 0.0;
+        self.fall_damage = 0;
+        self.water_level = 0;
+        self.liquid = None;
+        self.breath = Breath::default();
         self.health = super::constants::STARTING_HEALTH;
         self.armor = 0;
         self.dead = false;
         self.gibbed = false;
+        self.frozen = false;
+        self.thaw_progress = 0.0;
         self.weapon = Weapon::MachineGun;
         self.refire = 0.0;
         self.weapon_switch_time = 0.0;
@@ -691,15 +740,14 @@ impl Player {
         self.idle_time = 0.0;
         self.idle_yaw = 0.0;
 
-        self.has_weapon = [true, true, false, false, false, false, false, false, false];
-        self.ammo = [0, 100, 0, 0, 0, 0, 0, 0, 0];
+        self.has_weapon = [
+            true, true, false, false, false, false, false, false, false, false, false, false,
+        ];
+        self.ammo = [0, 100, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
 
-        self.powerups.quad = 0;
-        self.powerups.regen = 0;
-        self.powerups.battle = 0;
-        self.powerups.flight = 0;
-        self.powerups.haste = 0;
-        self.powerups.invis = 0;
+        self.powerups.clear();
+        self.holdable = None;
+        self.grapple = Grapple::Idle;
 
         println!(
             "[Player] Before fix_stuck_position: x={}, y={}",
@@ -714,53 +762,135 @@ impl Player {
         self.barrel_spin_speed = 0.0;
     }
 
-    pub fn take_damage(&mut self, damage: i32) -> (bool, bool) {
+    pub fn freeze(&mut self) {
+        self.dead = true;
+        self.gibbed = false;
+        self.frozen = true;
+        self.thaw_progress = 0.0;
+        self.health = 0;
+        self.vel_x = 0.0;
+        self.refire = 0.0;
+    }
+
+    /// Back in play where the player was frozen, keeping weapons and ammo.
+    pub fn thaw(&mut self) {
+        self.dead = false;
+        self.frozen = false;
+        self.thaw_progress = 0.0;
+        self.health = super::constants::STARTING_HEALTH;
+        self.armor = 0;
+        self.respawn_timer = 0.0;
+    }
+
+    /// Seconds until the current weapon can fire again, shortened by Haste.
+    pub fn spin_barrel(&mut self) {
+        use super::constants::{BARREL_SPIN_ACCEL_IMPULSE, BARREL_SPIN_MAX_SPEED};
+        self.barrel_spin_speed =
+            (self.barrel_spin_speed + BARREL_SPIN_ACCEL_IMPULSE).min(BARREL_SPIN_MAX_SPEED);
+    }
+
+    pub fn refire_time(&self) -> f32 {
+        let base = if self.weapon == Weapon::Chaingun {
+            use super::constants::{BARREL_SPIN_MAX_SPEED, CHAINGUN_REFIRE_SLOW};
+            let spin = (self.barrel_spin_speed / BARREL_SPIN_MAX_SPEED).clamp(0.0, 1.0);
+            let fast = self.weapon.refire_time_seconds();
+            CHAINGUN_REFIRE_SLOW + (fast - CHAINGUN_REFIRE_SLOW) * spin
+        } else {
+            self.weapon.refire_time_seconds()
+        };
+        base / self.powerups.fire_rate()
+    }
+
+    pub fn apply_loadout(&mut self, loadout: &super::gametype::Loadout) {
+        self.health = loadout.health;
+        self.armor = loadout.armor;
+        self.ammo = loadout.ammo;
+        for (i, has) in self.has_weapon.iter_mut().enumerate() {
+            *has = (i == 0 && loadout.gauntlet) || loadout.ammo[i] > 0;
+        }
+        if self.has_weapon[loadout.weapon as usize] {
+            self.weapon = loadout.weapon;
+        }
+        if loadout.quad {
+            self.powerups.quad = u16::MAX;
+        }
+    }
+
+    pub fn combatant(&self) -> Combatant {
+        Combatant {
+            id: self.id,
+            team: self.team,
+            frozen: self.frozen,
+        }
+    }
+
+    /// Returns (died, gibbed, the hit as it landed).
+    pub fn take_damage(
+        &mut self,
+        damage: i32,
+        attacker_id: u16,
+        teammates: bool,
+        rules: &DamageRules,
+    ) -> (bool, bool, CombatEvent) {
         const GIB_HEALTH: i32 = -150;
 
+        let missed = CombatEvent {
+            attacker_id,
+            target_id: self.id,
+            damage: 0,
+            mirrored: 0,
+        };
+        if self.frozen {
+            return (false, false, missed);
+        }
+
         if self.dead {
             let was_gibbed = self.gibbed;
             self.health -= damage;
             if self.health <= GIB_HEALTH && !was_gibbed {
                 self.gibbed = true;
-                return (false, true);
+                return (false, true, missed);
             }
-            return (false, false);
+            return (false, false, missed);
         }
 
-        let actual_damage = if self.armor > 0 {
-            let absorbed = (damage as f32 * 0.66) as i32;
-            self.armor -= absorbed;
-            if self.armor < 0 {
-                self.armor = 0;
-            }
-            damage - absorbed
-        } else {
-            damage
-        };
-
-        self.health -= actual_damage;
+        let (_, event) = super::bg_combat::apply_damage(
+            &mut self.health,
+            &mut self.armor,
+            self.powerups.absorb(damage),
+            attacker_id,
+            self.id,
+            teammates,
+            rules,
+        );
 
         if self.health <= GIB_HEALTH {
             self.dead = true;
             self.gibbed = true;
             self.deaths += 1;
             self.respawn_timer = 3.0;
-            return (true, true);
+            return (true, true, event);
         } else if self.health <= 0 {
             self.health = 0;
             self.dead = true;
             self.deaths += 1;
             self.respawn_timer = 3.0;
-            return (true, false);
+            return (true, false, event);
         }
-        (false, false)
+        (false, false, event)
     }
 
-    fn _frame(&self) -> u64 {
-        (get_time() * 50.0) as u64
+    /// Gibbed by someone spawning on top of us; nothing protects against it.
+    pub fn telefrag(&mut self) {
+        self.health = -999;
+        self.dead = true;
+        self.gibbed = true;
+        self.frozen = false;
+        self.deaths += 1;
+        self.respawn_timer = 3.0;
     }
 
-    fn apply_death_physics(&mut self, dt: f32, map: &Map) {
+    fn apply_death_physics(&mut self, dt: f32, map: &Map, physics: &PhysicsProfile) {
         use super::bg_pmove::{pmove, PmoveCmd, PmoveState};
 
         let state = PmoveState {
@@ -769,6 +899,8 @@ impl Player {
             vel_x: self.vel_x,
             vel_y: self.vel_y,
             was_in_air: true,
+            jump_time: 0.0,
+            fall_speed: 0.0,
         };
 
         let cmd = PmoveCmd {
@@ -776,9 +908,11 @@ impl Player {
             jump: false,
             crouch: false,
             haste_active: false,
+            flight_active: false,
+            grapple: None,
         };
 
-        let result = pmove(&state, &cmd, dt, map);
+        let result = pmove(&state, &cmd, dt, map, physics);
 
         self.x = result.new_x;
         self.y = result.new_y;
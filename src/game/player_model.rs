@@ -4,6 +4,7 @@ use crate::game::md3_pose::{
     apply_local_rotation, attach_rotated_entity, axis_from_mat3, identity_axis, Orientation,
 };
 use crate::game::weapon::Weapon;
+use crate::{log_debug, log_info};
 use macroquad::prelude::*;
 use std::collections::HashMap;
 
@@ -42,46 +43,52 @@ impl PlayerModel {
         {
             let base_path = format!("q3-resources/models/players/{}", model_name);
 
-            println!("[MD3] Loading model: {}", model_name);
+            log_debug!(Render, "Loading model: {}", model_name);
 
             let lower = match super::md3::MD3Model::load(format!("{}/lower.md3", base_path)) {
                 Ok(model) => {
-                    println!(
-                        "[MD3] ✓ Loaded lower.md3: {} frames, {} meshes",
-                        model.header.num_bone_frames, model.header.num_meshes
+                    log_debug!(
+                        Render,
+                        "✓ Loaded lower.md3: {} frames, {} meshes",
+                        model.header.num_bone_frames,
+                        model.header.num_meshes
                     );
                     Some(model)
                 }
                 Err(e) => {
-                    println!("[MD3] ✗ Failed to load lower.md3: {}", e);
+                    log_debug!(Render, "✗ Failed to load lower.md3: {}", e);
                     None
                 }
             };
 
             let upper = match super::md3::MD3Model::load(format!("{}/upper.md3", base_path)) {
                 Ok(model) => {
-                    println!(
-                        "[MD3] ✓ Loaded upper.md3: {} frames, {} meshes",
-                        model.header.num_bone_frames, model.header.num_meshes
+                    log_debug!(
+                        Render,
+                        "✓ Loaded upper.md3: {} frames, {} meshes",
+                        model.header.num_bone_frames,
+                        model.header.num_meshes
                     );
                     Some(model)
                 }
                 Err(e) => {
-                    println!("[MD3] ✗ Failed to load upper.md3: {}", e);
+                    log_debug!(Render, "✗ Failed to load upper.md3: {}", e);
                     None
                 }
             };
 
             let head = match super::md3::MD3Model::load(format!("{}/head.md3", base_path)) {
                 Ok(model) => {
-                    println!(
-                        "[MD3] ✓ Loaded head.md3: {} frames, {} meshes",
-                        model.header.num_bone_frames, model.header.num_meshes
+                    log_debug!(
+                        Render,
+                        "✓ Loaded head.md3: {} frames, {} meshes",
+                        model.header.num_bone_frames,
+                        model.header.num_meshes
                     );
                     Some(model)
                 }
                 Err(e) => {
-                    println!("[MD3] ✗ Failed to load head.md3: {}", e);
+                    log_debug!(Render, "✗ Failed to load head.md3: {}", e);
                     None
                 }
             };
@@ -92,12 +99,12 @@ impl PlayerModel {
 
             let anim_config = AnimConfig::load(model_name).ok();
             if anim_config.is_some() {
-                println!("[MD3] ✓ Loaded animation.cfg");
+                log_debug!(Render, "✓ Loaded animation.cfg");
             } else {
-                println!("[MD3] ✗ No animation.cfg found");
+                log_debug!(Render, "✗ No animation.cfg found");
             }
 
-            println!("[MD3] Model '{}' loaded successfully!", model_name);
+            log_debug!(Render, "Model '{}' loaded successfully!", model_name);
 
             Ok(Self {
                 lower,
@@ -120,46 +127,52 @@ impl PlayerModel {
     pub async fn load_async(model_name: &str) -> Result<Self, String> {
         let base_path = format!("q3-resources/models/players/{}", model_name);
 
-        println!("[MD3] Loading model: {}", model_name);
+        log_debug!(Render, "Loading model: {}", model_name);
 
         let lower = match MD3Model::load_async(&format!("{}/lower.md3", base_path)).await {
             Ok(model) => {
-                println!(
-                    "[MD3] ✓ Loaded lower.md3: {} frames, {} meshes",
-                    model.header.num_bone_frames, model.header.num_meshes
+                log_debug!(
+                    Render,
+                    "✓ Loaded lower.md3: {} frames, {} meshes",
+                    model.header.num_bone_frames,
+                    model.header.num_meshes
                 );
                 Some(model)
             }
             Err(e) => {
-                println!("[MD3] ✗ Failed to load lower.md3: {}", e);
+                log_debug!(Render, "✗ Failed to load lower.md3: {}", e);
                 None
             }
         };
 
         let upper = match MD3Model::load_async(&format!("{}/upper.md3", base_path)).await {
             Ok(model) => {
-                println!(
-                    "[MD3] ✓ Loaded upper.md3: {} frames, {} meshes",
-                    model.header.num_bone_frames, model.header.num_meshes
+                log_debug!(
+                    Render,
+                    "✓ Loaded upper.md3: {} frames, {} meshes",
+                    model.header.num_bone_frames,
+                    model.header.num_meshes
                 );
                 Some(model)
             }
             Err(e) => {
-                println!("[MD3] ✗ Failed to load upper.md3: {}", e);
+                log_debug!(Render, "✗ Failed to load upper.md3: {}", e);
                 None
             }
         };
 
         let head = match MD3Model::load_async(&format!("{}/head.md3", base_path)).await {
             Ok(model) => {
-                println!(
-                    "[MD3] ✓ Loaded head.md3: {} frames, {} meshes",
-                    model.header.num_bone_frames, model.header.num_meshes
+                log_debug!(
+                    Render,
+                    "✓ Loaded head.md3: {} frames, {} meshes",
+                    model.header.num_bone_frames,
+                    model.header.num_meshes
                 );
                 Some(model)
             }
             Err(e) => {
-                println!("[MD3] ✗ Failed to load head.md3: {}", e);
+                log_debug!(Render, "✗ Failed to load head.md3: {}", e);
                 None
             }
         };
@@ -170,12 +183,12 @@ impl PlayerModel {
 
         let anim_config = AnimConfig::load_async(model_name).await.ok();
         if anim_config.is_some() {
-            println!("[MD3] ✓ Loaded animation.cfg");
+            log_debug!(Render, "✓ Loaded animation.cfg");
         } else {
-            println!("[MD3] ✗ No animation.cfg found");
+            log_debug!(Render, "✗ No animation.cfg found");
         }
 
-        println!("[MD3] Model '{}' loaded successfully!", model_name);
+        log_debug!(Render, "Model '{}' loaded successfully!", model_name);
 
         Ok(Self {
             lower,
@@ -200,6 +213,9 @@ impl PlayerModel {
             Weapon::Railgun => "q3-resources/models/weapons2/railgun/railgun.md3",
             Weapon::Plasmagun => "q3-resources/models/weapons2/plasma/plasma.md3",
             Weapon::BFG => "q3-resources/models/weapons2/bfg/bfg.md3",
+            Weapon::Nailgun => "q3-resources/models/weapons/nailgun/nailgun.md3",
+            Weapon::ProxLauncher => "q3-resources/models/weapons/proxmine/proxmine.md3",
+            Weapon::Chaingun => "q3-resources/models/weapons/vulcan/vulcan.md3",
         };
 
         #[cfg(target_arch = "wasm32")]
@@ -213,9 +229,11 @@ impl PlayerModel {
     }
 
     pub async fn load_textures(&mut self, model_name: &str, skin_name: &str) {
-        println!(
-            "[MD3] Loading textures for {} with skin {}",
-            model_name, skin_name
+        log_debug!(
+            Render,
+            "Loading textures for {} with skin {}",
+            model_name,
+            skin_name
         );
         self.textures.clear();
         self.texture_paths.clear();
@@ -239,7 +257,7 @@ impl PlayerModel {
                         if let Some(texture) =
                             super::skin_loader::load_texture_file(&texture_path).await
                         {
-                            println!("[Texture] ✓ Mapped {} -> {}", mesh_name, texture_path);
+                            log_info!(Render, "✓ Mapped {} -> {}", mesh_name, texture_path);
                             self.texture_paths
                                 .insert(mesh_name.clone(), texture_path.clone());
                             self.textures.insert(mesh_name.clone(), texture);
@@ -276,9 +294,11 @@ impl PlayerModel {
                                                     )
                                                     .await
                                                 {
-                                                    println!(
-                                                        "[Texture] ✓ Mapped {} -> {}",
-                                                        mesh_name, texture_path
+                                                    log_info!(
+                                                        Render,
+                                                        "✓ Mapped {} -> {}",
+                                                        mesh_name,
+                                                        texture_path
                                                     );
                                                     self.texture_paths.insert(
                                                         mesh_name.clone(),
@@ -321,7 +341,7 @@ impl PlayerModel {
                 super::skin_loader::load_texture_file("q3-resources/textures/sfx/firewalla.png")
                     .await
             {
-                println!("[Shader] ✓ Loaded fire texture for {}", mesh_name);
+                log_info!(Render, "✓ Loaded fire texture for {}", mesh_name);
                 additional_textures.push(fire_tex);
             }
         } else if shader_name.contains("xaero_a") {
@@ -329,7 +349,7 @@ impl PlayerModel {
                 super::skin_loader::load_texture_file("q3-resources/textures/effects/envmapbfg.png")
                     .await
             {
-                println!("[Shader] ✓ Loaded envmap texture for {}", mesh_name);
+                log_info!(Render, "✓ Loaded envmap texture for {}", mesh_name);
                 additional_textures.push(env_tex);
             }
         }
@@ -371,6 +391,7 @@ impl PlayerModel {
         somersault_angle: f32,
         somersault_axis_y: bool,
         has_quad_damage: bool,
+        frozen: bool,
         barrel_roll: f32,
     ) {
         let draw_gun = crate::cvar::get_cvar_integer("cg_drawGun");
@@ -504,6 +525,17 @@ impl PlayerModel {
                             lighting_context,
                         );
                     }
+                    if frozen {
+                        super::md3_render::render_md3_mesh_with_orientation_frozen(
+                            mesh,
+                            safe_frame,
+                            &lower_orientation,
+                            base_screen_x,
+                            base_screen_y,
+                            Some(tex),
+                            lighting_context,
+                        );
+                    }
                 }
             }
         }
@@ -572,6 +604,17 @@ impl PlayerModel {
                             lighting_context,
                         );
                     }
+                    if frozen {
+                        super::md3_render::render_md3_mesh_with_orientation_frozen(
+                            mesh,
+                            safe_frame,
+                            &torso_orientation,
+                            base_screen_x,
+                            base_screen_y,
+                            Some(tex),
+                            lighting_context,
+                        );
+                    }
                 }
             }
         }
@@ -625,6 +668,17 @@ impl PlayerModel {
                             lighting_context,
                         );
                     }
+                    if frozen {
+                        super::md3_render::render_md3_mesh_with_orientation_frozen(
+                            mesh,
+                            safe_frame,
+                            &head_orient,
+                            base_screen_x,
+                            base_screen_y,
+                            Some(tex),
+                            lighting_context,
+                        );
+                    }
                 }
             }
         }
@@ -668,6 +722,17 @@ impl PlayerModel {
                                 None,
                             );
                         }
+                        if frozen {
+                            super::md3_render::render_md3_mesh_with_orientation_frozen(
+                                mesh,
+                                safe_frame,
+                                &weapon_orient,
+                                base_screen_x,
+                                base_screen_y,
+                                Some(tex),
+                                None,
+                            );
+                        }
                     }
                 }
                 let mut barrel_orientation = None;
@@ -723,6 +788,17 @@ impl PlayerModel {
                                         None,
                                     );
                                 }
+                                if frozen {
+                                    super::md3_render::render_md3_mesh_with_orientation_frozen(
+                                        mesh,
+                                        safe_frame,
+                                        &barrel_orient,
+                                        base_screen_x,
+                                        base_screen_y,
+                                        Some(tex),
+                                        None,
+                                    );
+                                }
                             }
                         }
                     }
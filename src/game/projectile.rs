@@ -24,6 +24,9 @@ pub struct Projectile {
     pub last_trail_y: f32,
     pub just_bounced: bool,
     pub is_rolling: bool,
+    /// Prox mines stop on the first tile they touch and arm a little later.
+    pub stuck: bool,
+    pub stuck_secs: f32,
 }
 
 impl Projectile {
@@ -36,31 +39,13 @@ impl Projectile {
         player_vel_x: f32,
         player_vel_y: f32,
     ) -> Self {
+        let speed = weapon.projectile_speed();
         let (vel_x, vel_y) = match weapon {
-            Weapon::RocketLauncher => {
-                let speed = 15.0;
-                (angle.cos() * speed, angle.sin() * speed)
-            }
-            Weapon::GrenadeLauncher => {
-                let base_speed = 16.0;
-                let vx = angle.cos() * base_speed + player_vel_x * 0.5;
-                let mut vy = angle.sin() * base_speed + player_vel_y * 0.5;
-                vy -= 1.5;
-                (vx, vy)
-            }
-            Weapon::Plasmagun => {
-                let speed = 33.0;
-                (angle.cos() * speed, angle.sin() * speed)
-            }
-            Weapon::BFG => {
-                let speed = 33.0;
-                (angle.cos() * speed, angle.sin() * speed)
-            }
-            Weapon::Railgun => {
-                let speed = 200.0;
-                (angle.cos() * speed, angle.sin() * speed)
-            }
-            _ => (0.0, 0.0),
+            Weapon::GrenadeLauncher | Weapon::ProxLauncher => (
+                angle.cos() * speed + player_vel_x * 0.5,
+                angle.sin() * speed + player_vel_y * 0.5 - 1.5,
+            ),
+            _ => (angle.cos() * speed, angle.sin() * speed),
         };
 
         Self {
@@ -82,9 +67,42 @@ impl Projectile {
             last_trail_y: y,
             just_bounced: false,
             is_rolling: false,
+            stuck: false,
+            stuck_secs: 0.0,
         }
     }
 
+    /// One shot of the nailgun: a fan of nails spread evenly either side of the aim.
+    pub fn nailgun_spread(
+        x: f32,
+        y: f32,
+        angle: f32,
+        owner_id: u16,
+        player_vel_x: f32,
+        player_vel_y: f32,
+    ) -> Vec<Self> {
+        use super::constants::{NAILGUN_NAILS, NAILGUN_SPREAD};
+
+        (0..NAILGUN_NAILS)
+            .map(|i| {
+                let t = i as f32 / (NAILGUN_NAILS - 1) as f32 * 2.0 - 1.0;
+                Self::new(
+                    x,
+                    y,
+                    angle + t * NAILGUN_SPREAD,
+                    owner_id,
+                    Weapon::Nailgun,
+                    player_vel_x,
+                    player_vel_y,
+                )
+            })
+            .collect()
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.stuck && self.stuck_secs >= super::constants::PROX_ARM_SECS
+    }
+
     pub fn update(&mut self, dt: f32, map: &Map) -> bool {
         use super::constants::*;
 
@@ -171,6 +189,30 @@ impl Projectile {
                     2.0 * dt_60fps
                 };
             }
+        } else if matches!(self.weapon_type, Weapon::ProxLauncher) {
+            if self.stuck {
+                self.stuck_secs += dt;
+            } else {
+                self.vel_y += 0.25 * dt_60fps;
+
+                let (dx, dy) = (self.vel_x * dt_60fps, self.vel_y * dt_60fps);
+                let steps = ((dx * dx + dy * dy).sqrt() / 4.0).ceil().max(1.0) as usize;
+                for _ in 0..steps {
+                    let next_x = self.x + dx / steps as f32;
+                    let next_y = self.y + dy / steps as f32;
+                    if map.is_solid(
+                        (next_x / 32.0).floor() as i32,
+                        (next_y / 16.0).floor() as i32,
+                    ) {
+                        self.stuck = true;
+                        self.vel_x = 0.0;
+                        self.vel_y = 0.0;
+                        break;
+                    }
+                    self.x = next_x;
+                    self.y = next_y;
+                }
+            }
         } else {
             self.x += self.vel_x * dt_60fps;
             self.y += self.vel_y * dt_60fps;
@@ -187,7 +229,10 @@ impl Projectile {
         let tile_y = (self.y / 16.0) as i32;
 
         if map.is_solid(tile_x, tile_y) {
-            if !matches!(self.weapon_type, Weapon::GrenadeLauncher) {
+            if !matches!(
+                self.weapon_type,
+                Weapon::GrenadeLauncher | Weapon::ProxLauncher
+            ) {
                 self.active = false;
                 return false;
             }
@@ -203,10 +248,13 @@ impl Projectile {
             Weapon::Plasmagun => 60,
             Weapon::BFG => 300,
             Weapon::Railgun => 8,
+            Weapon::Nailgun => 180,
+            Weapon::ProxLauncher => (PROX_LIFETIME_SECS * 60.0) as u32,
             Weapon::Gauntlet => 0,
             Weapon::MachineGun => 0,
             Weapon::Shotgun => 0,
             Weapon::Lightning => 0,
+            Weapon::Chaingun => 0,
         };
 
         if self.life > max_life {
@@ -451,7 +499,46 @@ impl Projectile {
                     Color::from_rgba(200, 255, 255, 255),
                 );
             }
-            Weapon::Gauntlet | Weapon::MachineGun | Weapon::Shotgun | Weapon::Lightning => {}
+            Weapon::Nailgun => {
+                let angle = self.vel_y.atan2(self.vel_x);
+                let (tail_x, tail_y) = (screen_x - angle.cos() * 6.0, screen_y - angle.sin() * 6.0);
+                draw_line(
+                    tail_x,
+                    tail_y,
+                    screen_x,
+                    screen_y,
+                    2.0,
+                    Color::from_rgba(255, 190, 90, 220),
+                );
+                draw_circle(
+                    screen_x,
+                    screen_y,
+                    1.5,
+                    Color::from_rgba(255, 240, 200, 255),
+                );
+            }
+            Weapon::ProxLauncher => {
+                draw_circle(screen_x, screen_y, 5.0, Color::from_rgba(60, 60, 70, 255));
+                draw_circle(
+                    screen_x,
+                    screen_y,
+                    3.5,
+                    Color::from_rgba(110, 110, 125, 255),
+                );
+                let blink = if self.is_armed() {
+                    (self.life / 15).is_multiple_of(2)
+                } else {
+                    (self.life / 30).is_multiple_of(2)
+                };
+                if blink {
+                    draw_circle(screen_x, screen_y, 1.8, Color::from_rgba(255, 40, 40, 255));
+                }
+            }
+            Weapon::Gauntlet
+            | Weapon::MachineGun
+            | Weapon::Shotgun
+            | Weapon::Lightning
+            | Weapon::Chaingun => {}
         }
     }
 
@@ -466,12 +553,17 @@ impl Projectile {
             return false;
         }
 
+        if matches!(self.weapon_type, Weapon::ProxLauncher) {
+            return self.is_armed() && self.in_trigger_radius(target_x, target_y);
+        }
+
         let projectile_size = match self.weapon_type {
             Weapon::RocketLauncher => 8.0,
             Weapon::GrenadeLauncher => 10.0,
             Weapon::Plasmagun => 12.0,
             Weapon::BFG => 16.0,
             Weapon::Railgun => 6.0,
+            Weapon::Nailgun => 6.0,
             _ => 8.0,
         };
 
@@ -570,6 +662,7 @@ impl Projectile {
                     Weapon::BFG => 60,
                     Weapon::Plasmagun => 25,
                     Weapon::Railgun => 15,
+                    Weapon::ProxLauncher => 30,
                     _ => 0,
                 };
 
@@ -592,17 +685,7 @@ impl Projectile {
     }
 
     pub fn explosion_radius(&self) -> f32 {
-        match self.weapon_type {
-            Weapon::RocketLauncher => 120.0,
-            Weapon::GrenadeLauncher => 150.0,
-            Weapon::BFG => 150.0,
-            Weapon::Plasmagun => 70.0,
-            Weapon::Railgun => 0.0,
-            Weapon::Gauntlet => 0.0,
-            Weapon::MachineGun => 0.0,
-            Weapon::Shotgun => 0.0,
-            Weapon::Lightning => 0.0,
-        }
+        self.weapon_type.splash_radius()
     }
 
     pub fn should_create_trail(&self) -> bool {
@@ -643,10 +726,78 @@ impl Projectile {
             return false;
         }
 
+        if matches!(self.weapon_type, Weapon::ProxLauncher) {
+            return self.is_armed() && self.in_trigger_radius(player_x, player_y);
+        }
+
         let dx = player_x - self.x;
         let dy = player_y - self.y;
         let dist = (dx * dx + dy * dy).sqrt();
 
         dist < 20.0
     }
+
+    fn in_trigger_radius(&self, x: f32, y: f32) -> bool {
+        let dx = x - self.x;
+        let dy = y - self.y;
+        (dx * dx + dy * dy).sqrt() < super::constants::PROX_TRIGGER_RADIUS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::constants::{NAILGUN_NAILS, PROX_ARM_SECS};
+
+    fn open_map() -> Map {
+        let mut map = Map::new("test");
+        for column in map.tiles.iter_mut() {
+            for tile in column.iter_mut() {
+                tile.solid = false;
+            }
+        }
+        for x in 0..map.width {
+            map.tiles[x][20].solid = true;
+        }
+        map
+    }
+
+    #[test]
+    fn test_prox_mine_sticks_and_arms() {
+        let map = open_map();
+        let mut mine = Projectile::new(300.0, 200.0, 0.0, 1, Weapon::ProxLauncher, 0.0, 0.0);
+
+        for _ in 0..120 {
+            assert!(mine.update(1.0 / 60.0, &map));
+            if mine.stuck {
+                break;
+            }
+        }
+        assert!(mine.stuck, "mine should land on the floor");
+        assert!(mine.y < 320.0 && mine.y > 300.0);
+        assert!(
+            !mine.check_hit(mine.x + 10.0, mine.y),
+            "unarmed mines ignore players"
+        );
+
+        let landed_at = (mine.x, mine.y);
+        let frames = (PROX_ARM_SECS * 60.0) as usize + 1;
+        for _ in 0..frames {
+            mine.update(1.0 / 60.0, &map);
+        }
+        assert_eq!((mine.x, mine.y), landed_at);
+        assert!(mine.is_armed());
+        assert!(mine.check_hit(mine.x + 40.0, mine.y));
+        assert!(!mine.check_hit(mine.x + 400.0, mine.y));
+    }
+
+    #[test]
+    fn test_nailgun_spread() {
+        let nails = Projectile::nailgun_spread(0.0, 0.0, 0.0, 1, 0.0, 0.0);
+        assert_eq!(nails.len(), NAILGUN_NAILS);
+        let angles: Vec<f32> = nails.iter().map(|n| n.vel_y.atan2(n.vel_x)).collect();
+        assert!(angles.windows(2).all(|w| w[1] > w[0]));
+        assert!((angles[0] + angles[NAILGUN_NAILS - 1]).abs() < 1e-4);
+        assert!(nails.iter().all(|n| n.damage == Weapon::Nailgun.damage()));
+    }
 }
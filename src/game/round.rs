@@ -0,0 +1,277 @@
+use super::gametype::{team_name, TeamScores, TEAM_BLUE, TEAM_RED};
+use serde::{Deserialize, Serialize};
+
+pub const ROUND_COUNTDOWN: f32 = 5.0;
+pub const ROUND_END_DELAY: f32 = 3.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RoundPhase {
+    Waiting,
+    Countdown(f32),
+    Active,
+    Ended(f32),
+    MatchOver,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RoundEvent {
+    /// Everyone respawns with the loadout and the countdown begins.
+    NewRound(u32),
+    Countdown(u32),
+    Started(u32),
+    Won {
+        round: u32,
+        team: Option<u8>,
+    },
+    MatchWon(Option<u8>),
+}
+
+impl RoundEvent {
+    pub fn message(&self) -> Option<String> {
+        match *self {
+            RoundEvent::NewRound(round) => Some(format!("Round {}", round)),
+            RoundEvent::Countdown(_) => None,
+            RoundEvent::Started(_) => Some("FIGHT!".to_string()),
+            RoundEvent::Won {
+                team: Some(team), ..
+            } => Some(format!("{} team wins the round!", team_name(team))),
+            RoundEvent::Won { team: None, .. } => Some("Round draw!".to_string()),
+            RoundEvent::MatchWon(Some(team)) => {
+                Some(format!("{} team wins the match!", team_name(team)))
+            }
+            RoundEvent::MatchWon(None) => None,
+        }
+    }
+
+    pub fn announcement(&self) -> Option<&'static str> {
+        match *self {
+            RoundEvent::NewRound(_) => Some("prepare"),
+            RoundEvent::Countdown(3) => Some("three"),
+            RoundEvent::Countdown(2) => Some("two"),
+            RoundEvent::Countdown(1) => Some("one"),
+            RoundEvent::Started(_) => Some("fight"),
+            _ => None,
+        }
+    }
+}
+
+/// A player as seen by the round logic; `standing` is false once eliminated.
+pub struct RoundPlayer {
+    pub team: u8,
+    pub standing: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct RoundState {
+    pub phase: RoundPhase,
+    pub round: u32,
+    pub wins: TeamScores,
+    /// Round wins needed to take the match, 0 for no limit.
+    pub round_limit: i32,
+}
+
+impl Default for RoundState {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RoundState {
+    pub fn new(round_limit: i32) -> Self {
+        Self {
+            phase: RoundPhase::Waiting,
+            round: 0,
+            wins: TeamScores::default(),
+            round_limit,
+        }
+    }
+
+    /// Reads `g_roundlimit`.
+    pub fn from_cvar() -> Self {
+        Self::new(crate::cvar::get_cvar_integer("g_roundlimit").max(0))
+    }
+
+    pub fn reset(&mut self) {
+        self.phase = RoundPhase::Waiting;
+        self.round = 0;
+        self.wins = TeamScores::default();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.phase == RoundPhase::Active
+    }
+
+    pub fn countdown(&self) -> Option<u32> {
+        match self.phase {
+            RoundPhase::Countdown(t) => Some(t.ceil() as u32),
+            _ => None,
+        }
+    }
+
+    pub fn status_text(&self) -> String {
+        match self.phase {
+            RoundPhase::Waiting => "Waiting for both teams".to_string(),
+            RoundPhase::Countdown(t) => {
+                format!("Round {} begins in {}", self.round, t.ceil() as u32)
+            }
+            RoundPhase::Active | RoundPhase::Ended(_) => format!("Round {}", self.round),
+            RoundPhase::MatchOver => "Match over".to_string(),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32, players: &[RoundPlayer]) -> Vec<RoundEvent> {
+        let mut events = Vec::new();
+        let count = |team: u8, standing_only: bool| {
+            players
+                .iter()
+                .filter(|p| p.team == team && (p.standing || !standing_only))
+                .count()
+        };
+        let teams_present = count(TEAM_RED, false) > 0 && count(TEAM_BLUE, false) > 0;
+
+        match self.phase {
+            RoundPhase::Waiting => {
+                if teams_present {
+                    self.start_round(&mut events);
+                }
+            }
+            RoundPhase::Countdown(t) => {
+                if !teams_present {
+                    self.phase = RoundPhase::Waiting;
+                    return events;
+                }
+                let next = t - dt;
+                if next <= 0.0 {
+                    self.phase = RoundPhase::Active;
+                    events.push(RoundEvent::Started(self.round));
+                } else {
+                    let secs = next.ceil() as u32;
+                    if secs < t.ceil() as u32 && secs <= 3 {
+                        events.push(RoundEvent::Countdown(secs));
+                    }
+                    self.phase = RoundPhase::Countdown(next);
+                }
+            }
+            RoundPhase::Active => {
+                let red = count(TEAM_RED, true);
+                let blue = count(TEAM_BLUE, true);
+                if red > 0 && blue > 0 {
+                    return events;
+                }
+
+                let winner = match (red > 0, blue > 0) {
+                    (true, false) => Some(TEAM_RED),
+                    (false, true) => Some(TEAM_BLUE),
+                    _ => None,
+                };
+                if let Some(team) = winner {
+                    self.wins.add(team, 1);
+                }
+                events.push(RoundEvent::Won {
+                    round: self.round,
+                    team: winner,
+                });
+
+                let limit_hit = self.round_limit > 0
+                    && (self.wins.red >= self.round_limit || self.wins.blue >= self.round_limit);
+                if limit_hit {
+                    self.phase = RoundPhase::MatchOver;
+                    events.push(RoundEvent::MatchWon(self.wins.leader()));
+                } else {
+                    self.phase = RoundPhase::Ended(ROUND_END_DELAY);
+                }
+            }
+            RoundPhase::Ended(t) => {
+                let next = t - dt;
+                if next > 0.0 {
+                    self.phase = RoundPhase::Ended(next);
+                } else if teams_present {
+                    self.start_round(&mut events);
+                } else {
+                    self.phase = RoundPhase::Waiting;
+                }
+            }
+            RoundPhase::MatchOver => {}
+        }
+
+        events
+    }
+
+    fn start_round(&mut self, events: &mut Vec<RoundEvent>) {
+        self.round += 1;
+        self.phase = RoundPhase::Countdown(ROUND_COUNTDOWN);
+        events.push(RoundEvent::NewRound(self.round));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(team: u8, standing: bool) -> RoundPlayer {
+        RoundPlayer { team, standing }
+    }
+
+    fn run_countdown(state: &mut RoundState, players: &[RoundPlayer]) -> Vec<RoundEvent> {
+        let mut events = Vec::new();
+        for _ in 0..60 {
+            events.extend(state.update(0.1, players));
+        }
+        events
+    }
+
+    #[test]
+    fn test_waits_for_both_teams() {
+        let mut state = RoundState::new(3);
+        assert!(state.update(0.1, &[player(TEAM_RED, true)]).is_empty());
+        assert_eq!(state.phase, RoundPhase::Waiting);
+
+        let both = [player(TEAM_RED, true), player(TEAM_BLUE, true)];
+        assert_eq!(state.update(0.1, &both), vec![RoundEvent::NewRound(1)]);
+        assert_eq!(state.countdown(), Some(5));
+    }
+
+    #[test]
+    fn test_countdown_and_last_team_standing() {
+        let mut state = RoundState::new(3);
+        let both = [player(TEAM_RED, true), player(TEAM_BLUE, true)];
+        state.update(0.1, &both);
+
+        let events = run_countdown(&mut state, &both);
+        assert!(events.contains(&RoundEvent::Countdown(3)));
+        assert!(events.contains(&RoundEvent::Countdown(1)));
+        assert!(events.contains(&RoundEvent::Started(1)));
+        assert!(state.is_active());
+
+        let events = state.update(0.1, &[player(TEAM_RED, false), player(TEAM_BLUE, true)]);
+        assert_eq!(
+            events,
+            vec![RoundEvent::Won {
+                round: 1,
+                team: Some(TEAM_BLUE)
+            }]
+        );
+        assert_eq!(state.wins.blue, 1);
+        assert!(matches!(state.phase, RoundPhase::Ended(_)));
+
+        let events = run_countdown(&mut state, &both);
+        assert_eq!(events[0], RoundEvent::NewRound(2));
+    }
+
+    #[test]
+    fn test_round_limit_ends_match() {
+        let mut state = RoundState::new(1);
+        let both = [player(TEAM_RED, true), player(TEAM_BLUE, true)];
+        state.update(0.1, &both);
+        run_countdown(&mut state, &both);
+
+        let events = state.update(0.1, &[player(TEAM_RED, true), player(TEAM_BLUE, false)]);
+        assert_eq!(events.last(), Some(&RoundEvent::MatchWon(Some(TEAM_RED))));
+        assert_eq!(state.phase, RoundPhase::MatchOver);
+        assert!(run_countdown(&mut state, &both).is_empty());
+
+        state.reset();
+        assert_eq!(state.wins, TeamScores::default());
+        assert_eq!(state.phase, RoundPhase::Waiting);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::game::map::Map;
 use crate::game::player::Player;
-use crate::game::weapon::Weapon;
+use crate::game::weapon::{Weapon, WEAPON_COUNT};
 use macroquad::prelude::*;
 
 #[derive(Clone, Debug)]
@@ -10,8 +10,8 @@ pub struct EnemySpawn {
     pub model: String,
     pub health: i32,
     pub armor: i32,
-    pub weapons: [bool; 9],
-    pub ammo: [u8; 9],
+    pub weapons: [bool; WEAPON_COUNT],
+    pub ammo: [u8; WEAPON_COUNT],
     pub weapon: Weapon,
     pub spawned: bool,
     pub is_boss: bool,
@@ -88,8 +88,11 @@ impl StoryMode {
                         model: "grunt".to_string(),
                         health: 100,
                         armor: 0,
-                        weapons: [true, true, false, false, false, false, false, false, false],
-                        ammo: [255, 100, 0, 0, 0, 0, 0, 0, 0],
+                        weapons: [
+                            true, true, false, false, false, false, false, false, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 100, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
                         weapon: Weapon::MachineGun,
                         spawned: false,
                         is_boss: false,
@@ -102,8 +105,11 @@ impl StoryMode {
                         model: "grunt".to_string(),
                         health: 100,
                         armor: 0,
-                        weapons: [true, true, false, false, false, false, false, false, false],
-                        ammo: [255, 100, 0, 0, 0, 0, 0, 0, 0],
+                        weapons: [
+                            true, true, false, false, false, false, false, false, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 100, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
                         weapon: Weapon::MachineGun,
                         spawned: false,
                         is_boss: false,
@@ -116,8 +122,11 @@ impl StoryMode {
                         model: "sarge".to_string(),
                         health: 150,
                         armor: 25,
-                        weapons: [true, true, true, false, false, false, false, false, false],
-                        ammo: [255, 150, 80, 0, 0, 0, 0, 0, 0],
+                        weapons: [
+                            true, true, true, false, false, false, false, false, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 150, 80, 0, 0, 0, 0, 0, 0, 0, 0, 0],
                         weapon: Weapon::Shotgun,
                         spawned: false,
                         is_boss: false,
@@ -141,8 +150,11 @@ impl StoryMode {
                         model: "razor".to_string(),
                         health: 180,
                         armor: 50,
-                        weapons: [true, true, false, true, false, false, false, false, false],
-                        ammo: [255, 100, 0, 150, 0, 0, 0, 0, 0],
+                        weapons: [
+                            true, true, false, true, false, false, false, false, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 100, 0, 150, 0, 0, 0, 0, 0, 0, 0, 0],
                         weapon: Weapon::GrenadeLauncher,
                         spawned: false,
                         is_boss: false,
@@ -155,8 +167,11 @@ impl StoryMode {
                         model: "visor".to_string(),
                         health: 180,
                         armor: 50,
-                        weapons: [true, true, true, false, false, false, false, false, false],
-                        ammo: [255, 150, 100, 0, 0, 0, 0, 0, 0],
+                        weapons: [
+                            true, true, true, false, false, false, false, false, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 150, 100, 0, 0, 0, 0, 0, 0, 0, 0, 0],
                         weapon: Weapon::Shotgun,
                         spawned: false,
                         is_boss: false,
@@ -169,8 +184,11 @@ impl StoryMode {
                         model: "hunter".to_string(),
                         health: 200,
                         armor: 75,
-                        weapons: [true, true, false, false, true, false, false, false, false],
-                        ammo: [255, 150, 0, 0, 150, 0, 0, 0, 0],
+                        weapons: [
+                            true, true, false, false, true, false, false, false, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 150, 0, 0, 150, 0, 0, 0, 0, 0, 0, 0],
                         weapon: Weapon::RocketLauncher,
                         spawned: false,
                         is_boss: false,
@@ -183,8 +201,11 @@ impl StoryMode {
                         model: "keel".to_string(),
                         health: 200,
                         armor: 50,
-                        weapons: [true, true, true, true, false, false, false, false, false],
-                        ammo: [255, 150, 100, 150, 0, 0, 0, 0, 0],
+                        weapons: [
+                            true, true, true, true, false, false, false, false, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 150, 100, 150, 0, 0, 0, 0, 0, 0, 0, 0],
                         weapon: Weapon::GrenadeLauncher,
                         spawned: false,
                         is_boss: false,
@@ -209,8 +230,11 @@ impl StoryMode {
                         model: "bones".to_string(),
                         health: 220,
                         armor: 75,
-                        weapons: [true, true, true, true, false, false, false, false, false],
-                        ammo: [255, 200, 150, 150, 0, 0, 0, 0, 0],
+                        weapons: [
+                            true, true, true, true, false, false, false, false, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 200, 150, 150, 0, 0, 0, 0, 0, 0, 0, 0],
                         weapon: Weapon::GrenadeLauncher,
                         spawned: false,
                         is_boss: false,
@@ -223,8 +247,11 @@ impl StoryMode {
                         model: "uriel".to_string(),
                         health: 250,
                         armor: 75,
-                        weapons: [true, true, false, false, true, false, false, true, false],
-                        ammo: [255, 200, 0, 0, 150, 0, 0, 200, 0],
+                        weapons: [
+                            true, true, false, false, true, false, false, true, false, false,
+                            false, false,
+                        ],
+                        ammo: [255, 200, 0, 0, 150, 0, 0, 200, 0, 0, 0, 0],
                         weapon: Weapon::Plasmagun,
                         spawned: false,
                         is_boss: false,
@@ -237,8 +264,11 @@ impl StoryMode {
                         model: "crash".to_string(),
                         health: 250,
                         armor: 100,
-                        weapons: [true, true, true, false, true, false, true, false, false],
-                        ammo: [255, 200, 150, 0, 150, 0, 100, 0, 0],
+                        weapons: [
+                            true, true, true, false, true, false, true, false, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 200, 150, 0, 150, 0, 100, 0, 0, 0, 0, 0],
                         weapon: Weapon::Railgun,
                         spawned: false,
                         is_boss: false,
@@ -251,8 +281,11 @@ impl StoryMode {
                         model: "orbb".to_string(),
                         health: 250,
                         armor: 100,
-                        weapons: [true, true, true, true, true, false, true, false, false],
-                        ammo: [255, 200, 150, 150, 150, 0, 150, 0, 0],
+                        weapons: [
+                            true, true, true, true, true, false, true, false, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 200, 150, 150, 150, 0, 150, 0, 0, 0, 0, 0],
                         weapon: Weapon::RocketLauncher,
                         spawned: false,
                         is_boss: false,
@@ -265,8 +298,11 @@ impl StoryMode {
                         model: "doom".to_string(),
                         health: 700,
                         armor: 200,
-                        weapons: [true, true, true, true, true, false, true, true, false],
-                        ammo: [255, 255, 200, 200, 200, 0, 200, 200, 0],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 200, 200, 200, 0, 200, 200, 0, 0, 0, 0],
                         weapon: Weapon::RocketLauncher,
                         spawned: false,
                         is_boss: true,
@@ -291,8 +327,11 @@ impl StoryMode {
                         model: "slash".to_string(),
                         health: 300,
                         armor: 120,
-                        weapons: [true, true, true, true, true, false, true, false, false],
-                        ammo: [255, 255, 200, 200, 200, 0, 180, 0, 0],
+                        weapons: [
+                            true, true, true, true, true, false, true, false, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 200, 200, 200, 0, 180, 0, 0, 0, 0, 0],
                         weapon: Weapon::RocketLauncher,
                         spawned: false,
                         is_boss: false,
@@ -305,8 +344,11 @@ impl StoryMode {
                         model: "lucy".to_string(),
                         health: 300,
                         armor: 120,
-                        weapons: [true, true, true, true, true, false, true, true, false],
-                        ammo: [255, 255, 200, 200, 200, 0, 180, 200, 0],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 200, 200, 200, 0, 180, 200, 0, 0, 0, 0],
                         weapon: Weapon::Plasmagun,
                         spawned: false,
                         is_boss: false,
@@ -319,8 +361,11 @@ impl StoryMode {
                         model: "anarki".to_string(),
                         health: 300,
                         armor: 120,
-                        weapons: [true, true, true, true, true, false, true, false, false],
-                        ammo: [255, 255, 200, 200, 200, 0, 180, 0, 0],
+                        weapons: [
+                            true, true, true, true, true, false, true, false, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 200, 200, 200, 0, 180, 0, 0, 0, 0, 0],
                         weapon: Weapon::GrenadeLauncher,
                         spawned: false,
                         is_boss: false,
@@ -333,8 +378,11 @@ impl StoryMode {
                         model: "biker".to_string(),
                         health: 350,
                         armor: 150,
-                        weapons: [true, true, true, true, true, false, true, true, false],
-                        ammo: [255, 255, 200, 200, 200, 0, 200, 200, 0],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 200, 200, 200, 0, 200, 200, 0, 0, 0, 0],
                         weapon: Weapon::Railgun,
                         spawned: false,
                         is_boss: false,
@@ -347,8 +395,11 @@ impl StoryMode {
                         model: "xaero".to_string(),
                         health: 800,
                         armor: 280,
-                        weapons: [true, true, true, true, true, false, true, true, false],
-                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 0],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 0, 0, 0, 0],
                         weapon: Weapon::Railgun,
                         spawned: false,
                         is_boss: true,
@@ -361,8 +412,11 @@ impl StoryMode {
                         model: "major".to_string(),
                         health: 800,
                         armor: 280,
-                        weapons: [true, true, true, true, true, false, true, true, false],
-                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 0],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, false, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 0, 0, 0, 0],
                         weapon: Weapon::RocketLauncher,
                         spawned: false,
                         is_boss: true,
@@ -387,8 +441,11 @@ impl StoryMode {
                         model: "sorlag".to_string(),
                         health: 900,
                         armor: 350,
-                        weapons: [true, true, true, true, true, false, true, true, true],
-                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 220],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, true, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 220, 0, 0, 0],
                         weapon: Weapon::BFG,
                         spawned: false,
                         is_boss: true,
@@ -401,8 +458,11 @@ impl StoryMode {
                         model: "klesk".to_string(),
                         health: 900,
                         armor: 350,
-                        weapons: [true, true, true, true, true, false, true, true, true],
-                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 220],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, true, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 220, 0, 0, 0],
                         weapon: Weapon::Plasmagun,
                         spawned: false,
                         is_boss: true,
@@ -415,8 +475,11 @@ impl StoryMode {
                         model: "mynx".to_string(),
                         health: 900,
                         armor: 350,
-                        weapons: [true, true, true, true, true, false, true, true, true],
-                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 220],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, true, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 220, 0, 0, 0],
                         weapon: Weapon::Railgun,
                         spawned: false,
                         is_boss: true,
@@ -429,8 +492,11 @@ impl StoryMode {
                         model: "ranger".to_string(),
                         health: 1000,
                         armor: 400,
-                        weapons: [true, true, true, true, true, false, true, true, true],
-                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 255],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, true, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 255, 0, 0, 0],
                         weapon: Weapon::RocketLauncher,
                         spawned: false,
                         is_boss: true,
@@ -443,8 +509,11 @@ impl StoryMode {
                         model: "tankjr".to_string(),
                         health: 2500,
                         armor: 600,
-                        weapons: [true, true, true, true, true, false, true, true, true],
-                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 255],
+                        weapons: [
+                            true, true, true, true, true, false, true, true, true, false, false,
+                            false,
+                        ],
+                        ammo: [255, 255, 255, 255, 255, 0, 255, 255, 255, 0, 0, 0],
                         weapon: Weapon::BFG,
                         spawned: false,
                         is_boss: true,
@@ -0,0 +1,531 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_RESULTS_FILE: &str = "tournament_results.json";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BracketFormat {
+    Single,
+    Double,
+}
+
+fn default_format() -> BracketFormat {
+    BracketFormat::Single
+}
+
+fn default_results_file() -> String {
+    DEFAULT_RESULTS_FILE.to_string()
+}
+
+/// A bracket as written in the tournament config file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TournamentConfig {
+    pub name: String,
+    #[serde(default = "default_format")]
+    pub format: BracketFormat,
+    /// Entrants in seed order, matched against player names.
+    pub players: Vec<String>,
+    /// Played in rotation, one map per match.
+    pub maps: Vec<String>,
+    #[serde(default = "default_results_file")]
+    pub results_file: String,
+}
+
+impl TournamentConfig {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let config: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&json).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.players.len() < 2 {
+            return Err("a bracket needs at least two players".to_string());
+        }
+        if self.maps.is_empty() {
+            return Err("a bracket needs at least one map".to_string());
+        }
+        for (i, name) in self.players.iter().enumerate() {
+            if name.trim().is_empty() {
+                return Err(format!("player {} has no name", i + 1));
+            }
+            if self.players[..i]
+                .iter()
+                .any(|other| other.eq_ignore_ascii_case(name))
+            {
+                return Err(format!("player '{}' is listed twice", name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Winners,
+    Losers,
+    GrandFinal,
+}
+
+/// Where a match side comes from: a seed, or the winner or loser of an
+/// earlier match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Slot {
+    Seed(usize),
+    Winner(usize),
+    Loser(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Entrant {
+    Player(usize),
+    Bye,
+    /// Decided by a match that hasn't been played yet.
+    Pending,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MatchResult {
+    pub map: String,
+    pub scores: [i32; 2],
+    /// 0 or 1, the side that won.
+    pub winner: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct BracketMatch {
+    pub stage: Stage,
+    pub round: u32,
+    slots: [Slot; 2],
+    pub result: Option<MatchResult>,
+}
+
+#[derive(Serialize)]
+struct MatchReport {
+    id: usize,
+    stage: Stage,
+    round: u32,
+    players: [Option<String>; 2],
+    map: Option<String>,
+    scores: Option<[i32; 2]>,
+    winner: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TournamentReport<'a> {
+    name: &'a str,
+    format: BracketFormat,
+    champion: Option<&'a str>,
+    matches: Vec<MatchReport>,
+}
+
+/// A single or double elimination bracket the server plays through match by
+/// match. Byes fill the bracket up to a power of two; the grand final is a
+/// single match with no bracket reset.
+#[derive(Clone, Debug)]
+pub struct Tournament {
+    pub config: TournamentConfig,
+    pub matches: Vec<BracketMatch>,
+    played: usize,
+}
+
+impl Tournament {
+    pub fn new(config: TournamentConfig) -> Self {
+        let matches = build_bracket(config.players.len(), config.format);
+        Self {
+            config,
+            matches,
+            played: 0,
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        TournamentConfig::load(path).map(Self::new)
+    }
+
+    pub fn entrant_name(&self, entrant: Entrant) -> Option<&str> {
+        match entrant {
+            Entrant::Player(seed) => Some(&self.config.players[seed]),
+            Entrant::Bye => Some("bye"),
+            Entrant::Pending => None,
+        }
+    }
+
+    fn resolve(&self, slot: Slot) -> Entrant {
+        match slot {
+            Slot::Seed(seed) if seed < self.config.players.len() => Entrant::Player(seed),
+            Slot::Seed(_) => Entrant::Bye,
+            Slot::Winner(idx) => self.outcome(idx).0,
+            Slot::Loser(idx) => self.outcome(idx).1,
+        }
+    }
+
+    pub fn entrants(&self, idx: usize) -> [Entrant; 2] {
+        let slots = self.matches[idx].slots;
+        [self.resolve(slots[0]), self.resolve(slots[1])]
+    }
+
+    /// Winner and loser of a match; byes advance the other side untouched.
+    pub fn outcome(&self, idx: usize) -> (Entrant, Entrant) {
+        let [a, b] = self.entrants(idx);
+        match (a, b) {
+            (Entrant::Pending, _) | (_, Entrant::Pending) => (Entrant::Pending, Entrant::Pending),
+            (Entrant::Player(_), Entrant::Player(_)) => match &self.matches[idx].result {
+                Some(result) if result.winner == 0 => (a, b),
+                Some(_) => (b, a),
+                None => (Entrant::Pending, Entrant::Pending),
+            },
+            (Entrant::Player(_), Entrant::Bye) => (a, Entrant::Bye),
+            (Entrant::Bye, _) => (b, Entrant::Bye),
+        }
+    }
+
+    /// The next match with two players and no result, in bracket order.
+    pub fn current(&self) -> Option<usize> {
+        (0..self.matches.len()).find(|&idx| {
+            self.matches[idx].result.is_none()
+                && matches!(self.entrants(idx), [Entrant::Player(_), Entrant::Player(_)])
+        })
+    }
+
+    pub fn current_players(&self) -> Option<[&str; 2]> {
+        let idx = self.current()?;
+        match self.entrants(idx) {
+            [Entrant::Player(a), Entrant::Player(b)] => {
+                Some([&self.config.players[a], &self.config.players[b]])
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_current_player(&self, name: &str) -> bool {
+        self.current_players()
+            .is_some_and(|names| names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+    }
+
+    pub fn current_map(&self) -> &str {
+        &self.config.maps[self.played % self.config.maps.len()]
+    }
+
+    pub fn champion(&self) -> Option<&str> {
+        let last = self.matches.len().checked_sub(1)?;
+        match self.outcome(last).0 {
+            Entrant::Player(seed) => Some(&self.config.players[seed]),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current().is_none()
+    }
+
+    /// Records the current match; `scores` pairs each player name with their score.
+    pub fn record(&mut self, winner: &str, scores: &[(String, i32)]) -> Result<(), String> {
+        let idx = self.current().ok_or("the tournament is over")?;
+        let Some(names) = self.current_players() else {
+            return Err("the tournament is over".to_string());
+        };
+        let winner = names
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(winner))
+            .ok_or_else(|| format!("{} is not playing this match", winner))?;
+        let score_of = |name: &str| {
+            scores
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map_or(0, |(_, score)| *score)
+        };
+        let scores = [score_of(names[0]), score_of(names[1])];
+        let map = self.current_map().to_string();
+
+        self.matches[idx].result = Some(MatchResult {
+            map,
+            scores,
+            winner,
+        });
+        self.played += 1;
+        Ok(())
+    }
+
+    pub fn status_lines(&self) -> Vec<String> {
+        let current = self.current();
+        let mut lines = vec![format!(
+            "{} ({:?} elimination, {} players)",
+            self.config.name,
+            self.config.format,
+            self.config.players.len()
+        )];
+        for (idx, m) in self.matches.iter().enumerate() {
+            let [a, b] = self.entrants(idx);
+            if a == Entrant::Bye || b == Entrant::Bye {
+                continue;
+            }
+            let name = |e: Entrant| self.entrant_name(e).unwrap_or("TBD").to_string();
+            let state = match &m.result {
+                Some(r) => format!("{} - {}", r.scores[0], r.scores[1]),
+                None if current == Some(idx) => format!("now on {}", self.current_map()),
+                None => String::new(),
+            };
+            lines.push(format!(
+                "  #{} {:?} round {}: {} vs {} {}",
+                idx + 1,
+                m.stage,
+                m.round,
+                name(a),
+                name(b),
+                state
+            ));
+        }
+        if let Some(champion) = self.champion() {
+            lines.push(format!("Champion: {}", champion));
+        }
+        lines
+    }
+
+    pub fn results_json(&self) -> Result<String, String> {
+        let matches = (0..self.matches.len())
+            .filter_map(|idx| {
+                let entrants = self.entrants(idx);
+                if entrants.contains(&Entrant::Bye) {
+                    return None;
+                }
+                let m = &self.matches[idx];
+                let result = m.result.as_ref();
+                Some(MatchReport {
+                    id: idx + 1,
+                    stage: m.stage,
+                    round: m.round,
+                    players: entrants.map(|e| self.entrant_name(e).map(str::to_string)),
+                    map: result.map(|r| r.map.clone()),
+                    scores: result.map(|r| r.scores),
+                    winner: result
+                        .and_then(|_| self.entrant_name(self.outcome(idx).0).map(str::to_string)),
+                })
+            })
+            .collect();
+        let report = TournamentReport {
+            name: &self.config.name,
+            format: self.config.format,
+            champion: self.champion(),
+            matches,
+        };
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+    }
+
+    pub fn write_results(&self) -> Result<(), String> {
+        let json = self.results_json()?;
+        std::fs::write(&self.config.results_file, json)
+            .map_err(|e| format!("{}: {}", self.config.results_file, e))
+    }
+}
+
+/// Bracket positions so the top seeds meet last: 1v8, 4v5, 2v7, 3v6, ...
+fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let len = order.len() * 2;
+        order = order.iter().flat_map(|&s| [s, len - 1 - s]).collect();
+    }
+    order
+}
+
+fn build_bracket(players: usize, format: BracketFormat) -> Vec<BracketMatch> {
+    let size = players.next_power_of_two().max(2);
+    let rounds = size.trailing_zeros() as usize;
+    let mut matches = Vec::new();
+    let mut push = |stage: Stage, round: usize, slots: [Slot; 2]| {
+        matches.push(BracketMatch {
+            stage,
+            round: round as u32,
+            slots,
+            result: None,
+        });
+        matches.len() - 1
+    };
+
+    let mut winners: Vec<Vec<usize>> = vec![seed_order(size)
+        .chunks(2)
+        .map(|pair| {
+            push(
+                Stage::Winners,
+                1,
+                [Slot::Seed(pair[0]), Slot::Seed(pair[1])],
+            )
+        })
+        .collect()];
+    for round in 2..=rounds {
+        let previous = winners.last().unwrap().clone();
+        winners.push(
+            previous
+                .chunks(2)
+                .map(|pair| {
+                    push(
+                        Stage::Winners,
+                        round,
+                        [Slot::Winner(pair[0]), Slot::Winner(pair[1])],
+                    )
+                })
+                .collect(),
+        );
+    }
+    let winners_final = winners[rounds - 1][0];
+    if format == BracketFormat::Single {
+        return matches;
+    }
+
+    // Losers bracket: the first round pairs the opening losers, then each
+    // winners round drops its losers in against the survivors, with a
+    // consolidation round in between.
+    let losers_champion = if rounds >= 2 {
+        let mut round = 1;
+        let mut survivors: Vec<usize> = winners[0]
+            .chunks(2)
+            .map(|pair| {
+                push(
+                    Stage::Losers,
+                    round,
+                    [Slot::Loser(pair[0]), Slot::Loser(pair[1])],
+                )
+            })
+            .collect();
+        for (wr, dropped) in winners.iter().enumerate().skip(1) {
+            round += 1;
+            // Alternate the drop order so early opponents don't meet again.
+            let dropped: Vec<usize> = if wr % 2 == 1 {
+                dropped.iter().rev().copied().collect()
+            } else {
+                dropped.clone()
+            };
+            survivors = survivors
+                .iter()
+                .zip(dropped)
+                .map(|(&s, d)| push(Stage::Losers, round, [Slot::Winner(s), Slot::Loser(d)]))
+                .collect();
+            if wr < rounds - 1 {
+                round += 1;
+                survivors = survivors
+                    .chunks(2)
+                    .map(|pair| {
+                        push(
+                            Stage::Losers,
+                            round,
+                            [Slot::Winner(pair[0]), Slot::Winner(pair[1])],
+                        )
+                    })
+                    .collect();
+            }
+        }
+        Slot::Winner(survivors[0])
+    } else {
+        Slot::Loser(winners_final)
+    };
+
+    push(
+        Stage::GrandFinal,
+        1,
+        [Slot::Winner(winners_final), losers_champion],
+    );
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(players: &[&str], format: BracketFormat) -> TournamentConfig {
+        TournamentConfig {
+            name: "Test Cup".to_string(),
+            format,
+            players: players.iter().map(|p| p.to_string()).collect(),
+            maps: vec!["0-arena".to_string(), "1-arena".to_string()],
+            results_file: DEFAULT_RESULTS_FILE.to_string(),
+        }
+    }
+
+    fn play(t: &mut Tournament, winner: &str) {
+        let names = t.current_players().unwrap().map(str::to_string);
+        let scores: Vec<(String, i32)> = names
+            .iter()
+            .map(|n| (n.clone(), if n == winner { 10 } else { 3 }))
+            .collect();
+        t.record(winner, &scores).unwrap();
+    }
+
+    #[test]
+    fn test_single_elimination_with_bye() {
+        let mut t = Tournament::new(config(&["Alice", "Bob", "Carol"], BracketFormat::Single));
+        assert_eq!(t.current_players(), Some(["Bob", "Carol"]));
+        assert_eq!(t.current_map(), "0-arena");
+        assert!(t.is_current_player("carol"));
+        assert!(t.record("Alice", &[]).is_err());
+
+        play(&mut t, "Carol");
+        assert_eq!(t.current_players(), Some(["Alice", "Carol"]));
+        assert_eq!(t.current_map(), "1-arena");
+        play(&mut t, "Alice");
+        assert!(t.is_finished());
+        assert_eq!(t.champion(), Some("Alice"));
+
+        let json = t.results_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["champion"], "Alice");
+        assert_eq!(value["matches"].as_array().unwrap().len(), 2);
+        assert_eq!(value["matches"][0]["scores"][1], 10);
+    }
+
+    #[test]
+    fn test_double_elimination_losers_bracket() {
+        let mut t = Tournament::new(config(&["A", "B", "C", "D"], BracketFormat::Double));
+        assert_eq!(t.matches.len(), 6);
+        assert_eq!(t.current_players(), Some(["A", "D"]));
+        play(&mut t, "A");
+        play(&mut t, "B");
+        // Winners final, then the opening losers meet.
+        assert_eq!(t.current_players(), Some(["A", "B"]));
+        play(&mut t, "A");
+        assert_eq!(t.current_players(), Some(["D", "C"]));
+        play(&mut t, "C");
+        assert_eq!(t.current_players(), Some(["C", "B"]));
+        play(&mut t, "B");
+        assert_eq!(t.matches[5].stage, Stage::GrandFinal);
+        assert_eq!(t.current_players(), Some(["A", "B"]));
+        play(&mut t, "B");
+        assert_eq!(t.champion(), Some("B"));
+    }
+
+    #[test]
+    fn test_two_player_double_elimination_is_a_rematch() {
+        let mut t = Tournament::new(config(&["A", "B"], BracketFormat::Double));
+        play(&mut t, "B");
+        assert_eq!(t.current_players(), Some(["B", "A"]));
+        play(&mut t, "A");
+        assert_eq!(t.champion(), Some("A"));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let ok =
+            r#"{"name": "Cup", "format": "double", "players": ["A", "B"], "maps": ["0-arena"]}"#;
+        let parsed = TournamentConfig::parse(ok).unwrap();
+        assert_eq!(parsed.format, BracketFormat::Double);
+        assert_eq!(parsed.results_file, DEFAULT_RESULTS_FILE);
+
+        assert!(
+            TournamentConfig::parse(r#"{"name": "Cup", "players": ["A"], "maps": ["m"]}"#).is_err()
+        );
+        assert!(TournamentConfig::parse(
+            r#"{"name": "Cup", "players": ["A", "a"], "maps": ["m"]}"#
+        )
+        .is_err());
+        assert!(
+            TournamentConfig::parse(r#"{"name": "Cup", "players": ["A", "B"], "maps": []}"#)
+                .is_err()
+        );
+    }
+}
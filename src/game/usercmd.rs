@@ -9,6 +9,8 @@ pub struct UserCmd {
 pub const BUTTON_ATTACK: u8 = 1;
 pub const BUTTON_JUMP: u8 = 2;
 pub const BUTTON_CROUCH: u8 = 4;
+pub const BUTTON_USE_ITEM: u8 = 8;
+pub const BUTTON_HOOK: u8 = 16;
 
 impl UserCmd {
     pub fn new() -> Self {
@@ -43,6 +45,12 @@ impl UserCmd {
         if input.shoot {
             cmd.buttons |= BUTTON_ATTACK;
         }
+        if input.use_item {
+            cmd.buttons |= BUTTON_USE_ITEM;
+        }
+        if input.hook {
+            cmd.buttons |= BUTTON_HOOK;
+        }
 
         let angle = input.aim_angle;
         cmd.angles = (angle, 0.0);
@@ -73,6 +81,12 @@ impl UserCmd {
         if input.shoot {
             cmd.buttons |= BUTTON_ATTACK;
         }
+        if input.use_item {
+            cmd.buttons |= BUTTON_USE_ITEM;
+        }
+        if input.hook {
+            cmd.buttons |= BUTTON_HOOK;
+        }
 
         let angle = input.aim_angle;
         cmd.angles = (angle, 0.0);
@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+
+pub const WARMUP_COUNTDOWN: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MatchPhase {
+    /// Free play while players ready up; frags and the clock don't count.
+    Warmup,
+    Countdown(f32),
+    Live,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WarmupEvent {
+    /// Everyone is ready and the countdown to the match begins.
+    CountdownStarted,
+    Countdown(u32),
+    /// Someone unreadied or left during the countdown.
+    Aborted,
+    /// The match goes live; items, scores and the clock start fresh.
+    Started,
+}
+
+impl WarmupEvent {
+    pub fn message(&self) -> Option<String> {
+        match *self {
+            WarmupEvent::CountdownStarted => Some("Prepare to fight!".to_string()),
+            WarmupEvent::Countdown(_) => None,
+            WarmupEvent::Aborted => Some("Countdown aborted, back to warmup".to_string()),
+            WarmupEvent::Started => Some("FIGHT!".to_string()),
+        }
+    }
+
+    pub fn announcement(&self) -> Option<&'static str> {
+        match *self {
+            WarmupEvent::CountdownStarted => Some("prepare"),
+            WarmupEvent::Countdown(3) => Some("three"),
+            WarmupEvent::Countdown(2) => Some("two"),
+            WarmupEvent::Countdown(1) => Some("one"),
+            WarmupEvent::Started => Some("fight"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WarmupState {
+    pub phase: MatchPhase,
+    /// Players that need to be ready before the countdown starts.
+    pub min_ready: usize,
+    /// Ids of the players that are ready, kept sorted.
+    pub ready: Vec<u16>,
+}
+
+impl Default for WarmupState {
+    fn default() -> Self {
+        Self::live()
+    }
+}
+
+impl WarmupState {
+    pub fn new(min_ready: usize) -> Self {
+        Self {
+            phase: MatchPhase::Warmup,
+            min_ready,
+            ready: Vec::new(),
+        }
+    }
+
+    /// No warmup at all, the match is live from the first tick.
+    pub fn live() -> Self {
+        Self {
+            phase: MatchPhase::Live,
+            min_ready: 0,
+            ready: Vec::new(),
+        }
+    }
+
+    /// Reads `g_warmup` and `g_minready`.
+    pub fn from_cvar() -> Self {
+        if crate::cvar::get_cvar_integer("g_warmup") == 0 {
+            return Self::live();
+        }
+        Self::new(crate::cvar::get_cvar_integer("g_minready").max(1) as usize)
+    }
+
+    /// Back to warmup with nobody ready; stays live when warmup is off.
+    pub fn reset(&mut self) {
+        if self.min_ready > 0 {
+            self.phase = MatchPhase::Warmup;
+        }
+        self.ready.clear();
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.phase == MatchPhase::Live
+    }
+
+    pub fn countdown(&self) -> Option<u32> {
+        match self.phase {
+            MatchPhase::Countdown(t) => Some(t.ceil() as u32),
+            _ => None,
+        }
+    }
+
+    pub fn is_ready(&self, player_id: u16) -> bool {
+        self.ready.contains(&player_id)
+    }
+
+    /// Returns false once the match is live and readiness no longer matters.
+    pub fn set_ready(&mut self, player_id: u16, ready: bool) -> bool {
+        if self.is_live() {
+            return false;
+        }
+        match (self.ready.binary_search(&player_id), ready) {
+            (Err(idx), true) => self.ready.insert(idx, player_id),
+            (Ok(idx), false) => {
+                self.ready.remove(idx);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    pub fn status_text(&self, player_count: usize) -> String {
+        match self.phase {
+            MatchPhase::Warmup => {
+                let needed = self.min_ready.max(player_count);
+                format!("Warmup - {}/{} ready", self.ready.len(), needed)
+            }
+            MatchPhase::Countdown(t) => format!("Match begins in {}", t.ceil() as u32),
+            MatchPhase::Live => String::new(),
+        }
+    }
+
+    /// `players` are the ids currently in the game; the countdown starts once
+    /// at least `min_ready` of them are in and all of them are ready.
+    pub fn update(&mut self, dt: f32, players: &[u16]) -> Vec<WarmupEvent> {
+        let mut events = Vec::new();
+        self.ready.retain(|id| players.contains(id));
+        let all_ready =
+            players.len() >= self.min_ready && players.iter().all(|id| self.is_ready(*id));
+
+        match self.phase {
+            MatchPhase::Warmup => {
+                if all_ready {
+                    self.phase = MatchPhase::Countdown(WARMUP_COUNTDOWN);
+                    events.push(WarmupEvent::CountdownStarted);
+                }
+            }
+            MatchPhase::Countdown(t) => {
+                if !all_ready {
+                    self.phase = MatchPhase::Warmup;
+                    events.push(WarmupEvent::Aborted);
+                    return events;
+                }
+                let next = t - dt;
+                if next <= 0.0 {
+                    self.phase = MatchPhase::Live;
+                    self.ready.clear();
+                    events.push(WarmupEvent::Started);
+                } else {
+                    let secs = next.ceil() as u32;
+                    if secs < t.ceil() as u32 {
+                        events.push(WarmupEvent::Countdown(secs));
+                    }
+                    self.phase = MatchPhase::Countdown(next);
+                }
+            }
+            MatchPhase::Live => {}
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(state: &mut WarmupState, players: &[u16], secs: f32) -> Vec<WarmupEvent> {
+        let mut events = Vec::new();
+        for _ in 0..(secs * 10.0) as usize {
+            events.extend(state.update(0.1, players));
+        }
+        events
+    }
+
+    #[test]
+    fn test_waits_for_min_ready_and_everyone() {
+        let mut state = WarmupState::new(2);
+        state.set_ready(1, true);
+        assert!(state.update(0.1, &[1]).is_empty());
+        assert!(state.update(0.1, &[1, 2]).is_empty());
+
+        state.set_ready(2, true);
+        assert_eq!(
+            state.update(0.1, &[1, 2]),
+            vec![WarmupEvent::CountdownStarted]
+        );
+        assert_eq!(state.countdown(), Some(10));
+    }
+
+    #[test]
+    fn test_countdown_announcements_and_start() {
+        let mut state = WarmupState::new(2);
+        state.set_ready(1, true);
+        state.set_ready(2, true);
+        let events = run(&mut state, &[1, 2], 11.0);
+
+        assert_eq!(events[0], WarmupEvent::CountdownStarted);
+        assert!(events.contains(&WarmupEvent::Countdown(9)));
+        let announced: Vec<_> = events.iter().filter_map(|e| e.announcement()).collect();
+        assert_eq!(announced, vec!["prepare", "three", "two", "one", "fight"]);
+        assert!(state.is_live());
+        assert!(state.ready.is_empty());
+        assert!(!state.set_ready(1, false));
+    }
+
+    #[test]
+    fn test_unready_or_leave_aborts_countdown() {
+        let mut state = WarmupState::new(2);
+        state.set_ready(1, true);
+        state.set_ready(2, true);
+        run(&mut state, &[1, 2], 3.0);
+
+        state.set_ready(2, false);
+        assert_eq!(state.update(0.1, &[1, 2]), vec![WarmupEvent::Aborted]);
+        assert_eq!(state.phase, MatchPhase::Warmup);
+
+        state.set_ready(2, true);
+        run(&mut state, &[1, 2], 1.0);
+        assert_eq!(state.update(0.1, &[1]), vec![WarmupEvent::Aborted]);
+        assert_eq!(state.ready, vec![1]);
+    }
+
+    #[test]
+    fn test_disabled_warmup_stays_live() {
+        let mut state = WarmupState::live();
+        assert!(state.update(0.1, &[1]).is_empty());
+        state.reset();
+        assert!(state.is_live());
+    }
+}
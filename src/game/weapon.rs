@@ -12,54 +12,54 @@ pub enum Weapon {
     Railgun = 6,
     Plasmagun = 7,
     BFG = 8,
+    Nailgun = 9,
+    ProxLauncher = 10,
+    Chaingun = 11,
 }
 
+pub const WEAPON_COUNT: usize = 12;
+
 impl Weapon {
-    pub fn damage(&self) -> i32 {
-        use super::constants::*;
-        match self {
-            Weapon::Gauntlet => DAMAGE_GAUNTLET,
-            Weapon::MachineGun => DAMAGE_MACHINEGUN,
-            Weapon::Shotgun => DAMAGE_SHOTGUN,
-            Weapon::GrenadeLauncher => DAMAGE_GRENADE,
-            Weapon::RocketLauncher => DAMAGE_ROCKET,
-            Weapon::Lightning => DAMAGE_SHAFT,
-            Weapon::Railgun => DAMAGE_RAIL,
-            Weapon::Plasmagun => DAMAGE_PLASMA,
-            Weapon::BFG => DAMAGE_BFG,
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Weapon::Gauntlet),
+            1 => Some(Weapon::MachineGun),
+            2 => Some(Weapon::Shotgun),
+            3 => Some(Weapon::GrenadeLauncher),
+            4 => Some(Weapon::RocketLauncher),
+            5 => Some(Weapon::Lightning),
+            6 => Some(Weapon::Railgun),
+            7 => Some(Weapon::Plasmagun),
+            8 => Some(Weapon::BFG),
+            9 => Some(Weapon::Nailgun),
+            10 => Some(Weapon::ProxLauncher),
+            11 => Some(Weapon::Chaingun),
+            _ => None,
         }
     }
 
+    pub fn damage(&self) -> i32 {
+        super::weapon_defs::get(*self).damage
+    }
+
     pub fn refire_time_seconds(&self) -> f32 {
-        match self {
-            Weapon::Gauntlet => 0.4,
-            Weapon::MachineGun => 0.1,
-            Weapon::Shotgun => 1.0,
-            Weapon::GrenadeLauncher => 0.8,
-            Weapon::RocketLauncher => 0.8,
-            Weapon::Lightning => 0.05,
-            Weapon::Railgun => 1.5,
-            Weapon::Plasmagun => 0.1,
-            Weapon::BFG => 0.2,
-        }
+        super::weapon_defs::get(*self).refire
     }
 
     pub fn switch_time_seconds(&self) -> f32 {
-        0.45
+        super::weapon_defs::get(*self).switch_time
     }
 
     pub fn ammo_per_shot(&self) -> u8 {
-        match self {
-            Weapon::Gauntlet => 0,
-            Weapon::MachineGun => 1,
-            Weapon::Shotgun => 1,
-            Weapon::GrenadeLauncher => 1,
-            Weapon::RocketLauncher => 1,
-            Weapon::Lightning => 1,
-            Weapon::Railgun => 1,
-            Weapon::Plasmagun => 1,
-            Weapon::BFG => 1,
-        }
+        super::weapon_defs::get(*self).ammo_per_shot
+    }
+
+    pub fn projectile_speed(&self) -> f32 {
+        super::weapon_defs::get(*self).projectile_speed
+    }
+
+    pub fn splash_radius(&self) -> f32 {
+        super::weapon_defs::get(*self).splash_radius
     }
 
     pub fn name(&self) -> &'static str {
@@ -73,6 +73,9 @@ impl Weapon {
             Weapon::Railgun => "Railgun",
             Weapon::Plasmagun => "Plasma Gun",
             Weapon::BFG => "BFG10K",
+            Weapon::Nailgun => "Nailgun",
+            Weapon::ProxLauncher => "Prox Launcher",
+            Weapon::Chaingun => "Chaingun",
         }
     }
 
@@ -87,6 +90,9 @@ impl Weapon {
             Weapon::Railgun => Some(super::map::ItemType::Railgun),
             Weapon::Plasmagun => Some(super::map::ItemType::Plasmagun),
             Weapon::BFG => Some(super::map::ItemType::BFG),
+            Weapon::Nailgun => Some(super::map::ItemType::Nailgun),
+            Weapon::ProxLauncher => Some(super::map::ItemType::ProxLauncher),
+            Weapon::Chaingun => Some(super::map::ItemType::Chaingun),
         }
     }
 }
@@ -0,0 +1,219 @@
+use super::weapon::{Weapon, WEAPON_COUNT};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+pub const DEFAULT_WEAPONS_FILE: &str = "weapons.json";
+
+/// Tunable numbers for one weapon, as written in the weapons file.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeaponDef {
+    pub weapon: Weapon,
+    pub damage: i32,
+    /// Seconds between shots.
+    pub refire: f32,
+    pub switch_time: f32,
+    pub ammo_per_shot: u8,
+    /// Launch speed in pixels per frame, 0 for hitscan weapons.
+    pub projectile_speed: f32,
+    pub splash_radius: f32,
+}
+
+impl WeaponDef {
+    pub fn builtin(weapon: Weapon) -> Self {
+        use super::constants::*;
+        let (damage, refire, ammo_per_shot, projectile_speed, splash_radius) = match weapon {
+            Weapon::Gauntlet => (DAMAGE_GAUNTLET, 0.4, 0, 0.0, 0.0),
+            Weapon::MachineGun => (DAMAGE_MACHINEGUN, 0.1, 1, 0.0, 0.0),
+            Weapon::Shotgun => (DAMAGE_SHOTGUN, 1.0, 1, 0.0, 0.0),
+            Weapon::GrenadeLauncher => (DAMAGE_GRENADE, 0.8, 1, 16.0, 150.0),
+            Weapon::RocketLauncher => (DAMAGE_ROCKET, 0.8, 1, 15.0, 120.0),
+            Weapon::Lightning => (DAMAGE_SHAFT, 0.05, 1, 0.0, 0.0),
+            Weapon::Railgun => (DAMAGE_RAIL, 1.5, 1, 200.0, 0.0),
+            Weapon::Plasmagun => (DAMAGE_PLASMA, 0.1, 1, 33.0, 70.0),
+            Weapon::BFG => (DAMAGE_BFG, 0.2, 1, 33.0, 150.0),
+            Weapon::Nailgun => (DAMAGE_NAIL, 1.0, 1, 12.0, 0.0),
+            Weapon::ProxLauncher => (DAMAGE_PROX, 0.8, 1, 14.0, 150.0),
+            Weapon::Chaingun => (DAMAGE_CHAINGUN, CHAINGUN_REFIRE_FAST, 1, 0.0, 0.0),
+        };
+        Self {
+            weapon,
+            damage,
+            refire,
+            switch_time: 0.45,
+            ammo_per_shot,
+            projectile_speed,
+            splash_radius,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let name = self.weapon.name();
+        if self.damage < 0 {
+            return Err(format!("{}: damage must not be negative", name));
+        }
+        if !self.refire.is_finite() || self.refire <= 0.0 {
+            return Err(format!("{}: refire must be above zero", name));
+        }
+        for (field, value) in [
+            ("switch_time", self.switch_time),
+            ("projectile_speed", self.projectile_speed),
+            ("splash_radius", self.splash_radius),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("{}: {} must not be negative", name, field));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every weapon's definition, indexed by weapon number once validated.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeaponTable {
+    pub weapons: Vec<WeaponDef>,
+}
+
+impl Default for WeaponTable {
+    fn default() -> Self {
+        Self {
+            weapons: (0..WEAPON_COUNT as u8)
+                .filter_map(Weapon::from_u8)
+                .map(WeaponDef::builtin)
+                .collect(),
+        }
+    }
+}
+
+impl WeaponTable {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let table: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        table.checked()
+    }
+
+    /// Validates the table and puts it in weapon order.
+    pub fn checked(mut self) -> Result<Self, String> {
+        self.validate()?;
+        self.weapons.sort_by_key(|def| def.weapon as u8);
+        Ok(self)
+    }
+
+    /// Reads `path`, or returns the built-in table when there is no such file.
+    pub fn load(path: &str) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => Self::parse(&json).map_err(|e| format!("{}: {}", path, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path, e)),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for weapon in (0..WEAPON_COUNT as u8).filter_map(Weapon::from_u8) {
+            match self
+                .weapons
+                .iter()
+                .filter(|def| def.weapon == weapon)
+                .count()
+            {
+                0 => return Err(format!("{} is missing", weapon.name())),
+                1 => {}
+                _ => return Err(format!("{} is listed twice", weapon.name())),
+            }
+        }
+        self.weapons.iter().try_for_each(WeaponDef::validate)
+    }
+
+    /// FNV-1a over the table's JSON, so two sides can tell whether they agree
+    /// without sending the whole table.
+    pub fn checksum(&self) -> u32 {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        json.bytes().fold(0x811c_9dc5, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+        })
+    }
+
+    pub fn get(&self, weapon: Weapon) -> WeaponDef {
+        self.weapons[weapon as usize]
+    }
+}
+
+/// The table in play, and the one loaded from disk to go back to after a
+/// server has replaced it. `None` means the built-in values.
+static ACTIVE: RwLock<Option<WeaponTable>> = RwLock::new(None);
+static LOCAL: RwLock<Option<WeaponTable>> = RwLock::new(None);
+
+pub fn get(weapon: Weapon) -> WeaponDef {
+    match ACTIVE.read().unwrap().as_ref() {
+        Some(table) => table.get(weapon),
+        None => WeaponDef::builtin(weapon),
+    }
+}
+
+pub fn active() -> WeaponTable {
+    ACTIVE.read().unwrap().clone().unwrap_or_default()
+}
+
+pub fn checksum() -> u32 {
+    active().checksum()
+}
+
+/// Makes `table` the one in play until [`restore_local`].
+pub fn install(table: WeaponTable) {
+    *ACTIVE.write().unwrap() = Some(table);
+}
+
+/// Makes `table` both the one in play and the one [`restore_local`] returns to.
+pub fn install_local(table: WeaponTable) {
+    *LOCAL.write().unwrap() = Some(table.clone());
+    install(table);
+}
+
+pub fn restore_local() {
+    *ACTIVE.write().unwrap() = LOCAL.read().unwrap().clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_file_matches_builtin() {
+        let table = WeaponTable::parse(include_str!("../../weapons.json")).unwrap();
+        assert_eq!(table, WeaponTable::default());
+        assert_eq!(table.checksum(), WeaponTable::default().checksum());
+    }
+
+    #[test]
+    fn test_parse_sorts_and_validates() {
+        let mut table = WeaponTable::default();
+        table.weapons.reverse();
+        table.weapons[0].damage = 150;
+        let parsed = WeaponTable::parse(&serde_json::to_string(&table).unwrap()).unwrap();
+        assert_eq!(parsed.get(Weapon::Chaingun).damage, 150);
+        assert_eq!(
+            parsed.get(Weapon::Gauntlet),
+            WeaponDef::builtin(Weapon::Gauntlet)
+        );
+        assert_ne!(parsed.checksum(), WeaponTable::default().checksum());
+
+        let mut missing = WeaponTable::default();
+        missing.weapons.remove(4);
+        assert_eq!(
+            missing.validate().unwrap_err(),
+            "Rocket Launcher is missing"
+        );
+
+        let mut twice = WeaponTable::default();
+        twice.weapons[3] = twice.weapons[6];
+        assert!(twice.validate().is_err());
+
+        let mut bad = WeaponTable::default();
+        bad.weapons[1].refire = 0.0;
+        assert_eq!(
+            bad.validate().unwrap_err(),
+            "Machine Gun: refire must be above zero"
+        );
+        bad.weapons[1].refire = 0.1;
+        bad.weapons[2].splash_radius = -1.0;
+        assert!(bad.validate().is_err());
+    }
+}
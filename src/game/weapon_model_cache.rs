@@ -32,6 +32,9 @@ impl WeaponModelCache {
             Weapon::Railgun => "models/weapons2/railgun/railgun.md3",
             Weapon::Plasmagun => "models/weapons2/plasma/plasma.md3",
             Weapon::BFG => "models/weapons2/bfg/bfg.md3",
+            Weapon::Nailgun => "models/weapons/nailgun/nailgun.md3",
+            Weapon::ProxLauncher => "models/weapons/proxmine/proxmine.md3",
+            Weapon::Chaingun => "models/weapons/vulcan/vulcan.md3",
         };
         get_resource_path(relative)
     }
@@ -39,6 +42,7 @@ impl WeaponModelCache {
     fn extra_paths(weapon: Weapon) -> Vec<String> {
         let relatives: Vec<&str> = match weapon {
             Weapon::MachineGun => vec!["models/weapons2/machinegun/machinegun_barrel.md3"],
+            Weapon::Chaingun => vec!["models/weapons/vulcan/vulcan_barrel.md3"],
             _ => Vec::new(),
         };
         relatives.iter().map(|p| get_resource_path(p)).collect()
@@ -73,6 +77,9 @@ impl WeaponModelCache {
                 "models/weapons2/bfg/f_bfg.png",
                 "models/weapons2/bfg/f_bfg2.png",
             ],
+            Weapon::Nailgun => vec!["models/weapons/nailgun/nailgun.png"],
+            Weapon::ProxLauncher => vec!["models/weapons/proxmine/proxmine.png"],
+            Weapon::Chaingun => vec!["models/weapons/vulcan/vulcan.png"],
         }
     }
 
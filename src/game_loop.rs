@@ -1,12 +1,13 @@
 use crate::audio;
 use crate::bot_handler::BotHandler;
-use crate::game::{player::Player, weapon::Weapon, GameState};
+use crate::game::{gametype::ScoreEntry, player::Player, weapon::Weapon, GameState};
 use crate::hud_scoreboard::HudScoreboard;
 use crate::input::{Input, LocalMultiplayerInput};
 use crate::profiler;
 use crate::profiler_display;
 use crate::render::Camera;
 use crate::weapon_handler::WeaponHandler;
+use crate::{log_debug, log_info, log_trace, log_warn};
 use macroquad::prelude::*;
 
 pub struct GameLoop {
@@ -58,13 +59,13 @@ impl GameLoop {
     }
 
     pub async fn initialize_game(&mut self) {
-        eprintln!("[INIT] Starting game initialization...");
+        log_info!(Game, "Starting game initialization...");
         self.game_state.weapon_hit_texture_cache.load_all().await;
         self.game_state.muzzle_flash_cache.load_all().await;
         self.setup_players().await;
-        eprintln!("[INIT] About to preload assets...");
+        log_info!(Game, "About to preload assets...");
         self.preload_assets().await;
-        eprintln!("[INIT] Game initialization complete!");
+        log_info!(Game, "Game initialization complete!");
     }
 
     async fn setup_players(&mut self) {
@@ -114,16 +115,20 @@ impl GameLoop {
             player2.spawn(spawn_x + 100.0, spawn_y, &self.game_state.map);
         }
 
-        player1.has_weapon = [true, true, false, false, false, false, true, false, false];
-        player1.ammo = [255, 100, 0, 0, 0, 0, 50, 0, 0];
+        player1.has_weapon = [
+            true, true, false, false, false, false, true, false, false, false, false, false,
+        ];
+        player1.ammo = [255, 100, 0, 0, 0, 0, 50, 0, 0, 0, 0, 0];
         player1.weapon = Weapon::Railgun;
 
-        player2.has_weapon = [true, true, false, false, false, false, false, false, false];
-        player2.ammo = [255, 100, 0, 0, 0, 0, 0, 0, 0];
+        player2.has_weapon = [
+            true, true, false, false, false, false, false, false, false, false, false, false,
+        ];
+        player2.ammo = [255, 100, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
         player2.weapon = Weapon::MachineGun;
 
-        self.game_state.players.push(player1);
-        self.game_state.players.push(player2);
+        self.join_game(player1);
+        self.join_game(player2);
     }
 
     async fn setup_single_player_and_bots(&mut self) {
@@ -143,10 +148,12 @@ impl GameLoop {
         };
 
         local_player.spawn(spawn_x, spawn_y, &self.game_state.map);
-        local_player.has_weapon = [true, true, false, false, false, false, true, false, false];
-        local_player.ammo = [255, 100, 0, 0, 0, 0, 50, 0, 0];
+        local_player.has_weapon = [
+            true, true, false, false, false, false, true, false, false, false, false, false,
+        ];
+        local_player.ammo = [255, 100, 0, 0, 0, 0, 50, 0, 0, 0, 0, 0];
         local_player.weapon = Weapon::Railgun;
-        self.game_state.players.push(local_player);
+        self.join_game(local_player);
 
         if self.game_state.story_mode.is_some() {
             return;
@@ -176,7 +183,14 @@ impl GameLoop {
             "sorlag",
         ];
 
-        let num_bots = (self.game_state.map.spawn_points.len() - 1).min(all_bot_skins.len());
+        let max_bots = self
+            .game_state
+            .gametype
+            .max_players()
+            .map_or(usize::MAX, |max| max.saturating_sub(1));
+        let num_bots = (self.game_state.map.spawn_points.len() - 1)
+            .min(all_bot_skins.len())
+            .min(max_bots);
         let mut used_skins = Vec::new();
 
         for i in 0..num_bots {
@@ -195,10 +209,21 @@ impl GameLoop {
             let spawn_point =
                 &self.game_state.map.spawn_points[(i + 1) % self.game_state.map.spawn_points.len()];
             bot.spawn(spawn_point.x, spawn_point.y, &self.game_state.map);
-            bot.has_weapon = [true, true, false, false, false, false, false, false, false];
-            bot.ammo = [255, 50, 0, 0, 0, 0, 0, 0, 0];
-            self.game_state.players.push(bot);
+            bot.has_weapon = [
+                true, true, false, false, false, false, false, false, false, false, false, false,
+            ];
+            bot.ammo = [255, 50, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+            self.join_game(bot);
+        }
+    }
+
+    fn join_game(&mut self, mut player: Player) {
+        let entries = ScoreEntry::from_players(&self.game_state.players);
+        player.team = self.game_state.gametype.pick_team(&entries);
+        if let Some(loadout) = self.game_state.spawn_loadout() {
+            player.apply_loadout(&loadout);
         }
+        self.game_state.players.push(player);
     }
 
     async fn preload_player_models(&mut self, env_skin: &str) {
@@ -243,19 +268,19 @@ impl GameLoop {
         self.preload_weapon_models().await;
         self.game_state.tile_textures.load_default_textures().await;
 
-        eprintln!("[PRELOAD] Loading tile shaders...");
+        log_info!(Render, "Loading tile shaders...");
         self.load_tile_shaders().await;
-        eprintln!("[PRELOAD] ✓ Tile shaders loaded!");
+        log_info!(Render, "✓ Tile shaders loaded!");
 
-        eprintln!("[PRELOAD] Loading border textures...");
+        log_info!(Render, "Loading border textures...");
         self.game_state.border_renderer.load_border_textures().await;
-        eprintln!("[PRELOAD] ✓ Border textures loaded!");
+        log_info!(Render, "✓ Border textures loaded!");
     }
 
     async fn preload_item_models(&mut self) {
         use crate::game::item_model::ItemModelType;
 
-        eprintln!("[PRELOAD] Starting item models preload...");
+        log_info!(Render, "Starting item models preload...");
 
         let item_types = [
             ItemModelType::HealthMedium,
@@ -270,22 +295,27 @@ impl GameLoop {
             ItemModelType::WeaponRailgun,
             ItemModelType::WeaponPlasmagun,
             ItemModelType::WeaponBFG,
+            ItemModelType::WeaponNailgun,
+            ItemModelType::WeaponProxLauncher,
+            ItemModelType::WeaponChaingun,
             ItemModelType::PowerupQuad,
             ItemModelType::PowerupRegen,
             ItemModelType::PowerupBattleSuit,
             ItemModelType::PowerupFlight,
             ItemModelType::PowerupHaste,
             ItemModelType::PowerupInvis,
+            ItemModelType::HoldableMedkit,
+            ItemModelType::HoldableTeleporter,
         ];
 
         for item_type in item_types {
             match self.game_state.item_model_cache.load(item_type).await {
-                Ok(_) => eprintln!("[PRELOAD] ✓ Loaded {:?}", item_type),
-                Err(e) => eprintln!("[PRELOAD] ✗ Failed to load {:?}: {}", item_type, e),
+                Ok(_) => log_info!(Render, "✓ Loaded {:?}", item_type),
+                Err(e) => log_warn!(Render, "✗ Failed to load {:?}: {}", item_type, e),
             }
         }
 
-        eprintln!("[PRELOAD] Item models preload complete!");
+        log_info!(Render, "Item models preload complete!");
     }
 
     async fn preload_projectile_models(&mut self) {
@@ -305,6 +335,9 @@ impl GameLoop {
             Weapon::Railgun,
             Weapon::Plasmagun,
             Weapon::BFG,
+            Weapon::Nailgun,
+            Weapon::ProxLauncher,
+            Weapon::Chaingun,
         ];
 
         for weapon in weapons {
@@ -317,10 +350,7 @@ impl GameLoop {
             if let Ok(shaders) =
                 serde_json::from_str::<Vec<crate::game::tile_shader::TileShader>>(&content)
             {
-                eprintln!(
-                    "[Shaders] Loading {} custom shaders from JSON",
-                    shaders.len()
-                );
+                log_info!(Render, "Loading {} custom shaders from JSON", shaders.len());
 
                 for shader in shaders {
                     if !shader.base_texture.is_empty() {
@@ -342,18 +372,19 @@ impl GameLoop {
                     self.game_state.shader_renderer.add_shader(shader);
                 }
 
-                eprintln!("[Shaders] ✓ Custom shaders loaded!");
+                log_info!(Render, "✓ Custom shaders loaded!");
             }
         } else {
-            eprintln!("[Shaders] No tile_shaders.json found, skipping");
+            log_info!(Render, "No tile_shaders.json found, skipping");
         }
 
         use crate::game::q3_shader_parser::Q3ShaderParser;
         let mut parser = Q3ShaderParser::new();
         parser.load_all_shader_files();
 
-        eprintln!(
-            "[Shaders] Loaded {} Q3 shaders from .shader files",
+        log_info!(
+            Render,
+            "Loaded {} Q3 shaders from .shader files",
             parser.get_all_shaders().len()
         );
 
@@ -439,6 +470,30 @@ impl GameLoop {
             console.print("Match ended\n");
         }
 
+        if let Some(pause) = console.pause_request.take() {
+            if let Err(e) = self.game_state.request_pause(pause) {
+                console.print(&format!(
+                    "Can't {}: {}\n",
+                    if pause { "pause" } else { "unpause" },
+                    e
+                ));
+            }
+        }
+
+        if console.ready_request {
+            console.ready_request = false;
+            if let Err(e) = self.game_state.toggle_ready() {
+                console.print(&format!("Can't ready: {}\n", e));
+            }
+        }
+
+        if console.kill_request {
+            console.kill_request = false;
+            if let Err(e) = self.game_state.suicide() {
+                console.print(&format!("Can't kill: {}\n", e));
+            }
+        }
+
         self.game_state.update_network();
 
         let current_time = get_time();
@@ -478,11 +533,21 @@ impl GameLoop {
         if !console.is_open() {
             self.handle_defrag_keys();
             self.handle_debug_keys().await;
+
+            if is_key_pressed(KeyCode::Enter) && self.game_state.game_results.show {
+                self.game_state.show_match_stats = !self.game_state.show_match_stats;
+            }
+
+            if is_key_pressed(KeyCode::Y) && !self.game_state.warmup.is_live() {
+                if let Err(e) = self.game_state.toggle_ready() {
+                    log_warn!(Net, "Can't ready: {}", e);
+                }
+            }
         }
 
         self.handle_camera(dt);
 
-        let shoot_actions = if !self.game_state.game_results.show {
+        let shoot_actions = if !self.game_state.game_results.show && !self.game_state.is_paused() {
             if self.game_state.is_multiplayer {
                 let shoot_data = if let Some(ref client) = self.game_state.network_client {
                     client.player_id().and_then(|player_id| {
@@ -565,8 +630,9 @@ impl GameLoop {
                 } else {
                     0.0
                 };
-                println!(
-                    "[FPS] {:.1} fps (avg over {:.0}ms)",
+                log_debug!(
+                    Render,
+                    "{:.1} fps (avg over {:.0}ms)",
                     avg_fps,
                     time_span * 1000.0
                 );
@@ -582,7 +648,7 @@ impl GameLoop {
         if let Some(defrag) = &mut self.game_state.defrag_mode {
             if is_key_pressed(KeyCode::R) {
                 defrag.reset();
-                println!("[Defrag] Run reset!");
+                log_info!(Game, "Run reset!");
 
                 if let Some(player) = self.game_state.players.get_mut(0) {
                     let (spawn_x, spawn_y) = defrag.start_pos;
@@ -603,7 +669,7 @@ impl GameLoop {
                     player.y = spawn_y;
                     player.vel_x = 0.0;
                     player.vel_y = 0.0;
-                    println!("[Defrag] Respawned at checkpoint");
+                    log_info!(Game, "Respawned at checkpoint");
                 }
             }
         }
@@ -614,6 +680,9 @@ impl GameLoop {
 
         if self.game_state.is_multiplayer {
             self.input.update(ignore_mouse_delta);
+            if self.game_state.is_paused() {
+                return;
+            }
             let cmd = crate::game::usercmd::UserCmd::from_input(
                 &self.input,
                 screen_width(),
@@ -641,6 +710,12 @@ impl GameLoop {
                     if self.input.crouch {
                         buttons |= 4;
                     }
+                    if self.input.use_item {
+                        buttons |= 8;
+                    }
+                    if self.input.hook {
+                        buttons |= 16;
+                    }
 
                     let angle = self.input.aim_angle;
 
@@ -653,7 +728,7 @@ impl GameLoop {
                         if macroquad::prelude::get_time() - LAST_INPUT_PRINT > 2.0
                             && macroquad::prelude::get_time() - LAST_NONZERO_INPUT < 0.5
                         {
-                            println!("[INPUT] right={:.1} buttons={}", move_right, buttons);
+                            log_trace!(Net, "right={:.1} buttons={}", move_right, buttons);
                             LAST_INPUT_PRINT = macroquad::prelude::get_time();
                         }
                     }
@@ -662,8 +737,8 @@ impl GameLoop {
                         .send_input(move_forward, move_right, angle, buttons)
                         .ok();
 
-                    if let Some(predicted) =
-                        network_client.predict_local_player(&self.game_state.map)
+                    if let Some(predicted) = network_client
+                        .predict_local_player(&self.game_state.map, &self.game_state.physics)
                     {
                         if let Some(player) = self
                             .game_state
@@ -678,6 +753,8 @@ impl GameLoop {
                             player.angle = angle;
                             player.was_in_air = predicted.was_in_air;
                             player.crouch = self.input.crouch;
+                            player.grapple = predicted.grapple;
+                            player.jump_time = predicted.jump_time;
 
                             if let Some((corr_x, corr_y)) = network_client
                                 .get_prediction_mut()
@@ -729,10 +806,15 @@ impl GameLoop {
                             player.x,
                             player.y,
                         );
-                        let pmove_events = player.pmove(&cmd, dt, &self.game_state.map);
+                        let use_item = player.use_item_pressed(&cmd);
+                        let pmove_events =
+                            player.pmove(&cmd, dt, &self.game_state.map, &self.game_state.physics);
                         for event in pmove_events {
                             self.game_state.audio_events.push(event);
                         }
+                        if use_item {
+                            self.game_state.use_holdable(player_idx);
+                        }
                     }
                 }
             } else {
@@ -744,10 +826,15 @@ impl GameLoop {
                 );
 
                 if let Some(player) = self.game_state.players.get_mut(0) {
-                    let pmove_events = player.pmove(&cmd, dt, &self.game_state.map);
+                    let use_item = player.use_item_pressed(&cmd);
+                    let pmove_events =
+                        player.pmove(&cmd, dt, &self.game_state.map, &self.game_state.physics);
                     for event in pmove_events {
                         self.game_state.audio_events.push(event);
                     }
+                    if use_item {
+                        self.game_state.use_holdable(0);
+                    }
                 }
             }
         }
@@ -796,8 +883,9 @@ impl GameLoop {
 
         if is_key_pressed(KeyCode::F3) {
             self.game_state.debug_hitboxes = !self.game_state.debug_hitboxes;
-            println!(
-                "[Debug] Hitbox debug: {}",
+            log_info!(
+                Render,
+                "Hitbox debug: {}",
                 if self.game_state.debug_hitboxes {
                     "ON"
                 } else {
@@ -812,8 +900,9 @@ impl GameLoop {
 
         if is_key_pressed(KeyCode::F10) {
             self.game_state.use_item_icons = !self.game_state.use_item_icons;
-            println!(
-                "[Debug] Item icons mode: {}",
+            log_info!(
+                Render,
+                "Item icons mode: {}",
                 if self.game_state.use_item_icons {
                     "ON (2D icons)"
                 } else {
@@ -824,8 +913,9 @@ impl GameLoop {
 
         if is_key_pressed(KeyCode::F11) {
             self.game_state.disable_shadows = !self.game_state.disable_shadows;
-            println!(
-                "[Perf] Shadows: {}",
+            log_info!(
+                Render,
+                "Shadows: {}",
                 if self.game_state.disable_shadows {
                     "OFF"
                 } else {
@@ -836,8 +926,9 @@ impl GameLoop {
 
         if is_key_pressed(KeyCode::F12) {
             self.game_state.disable_dynamic_lights = !self.game_state.disable_dynamic_lights;
-            println!(
-                "[Perf] Dynamic lights: {}",
+            log_info!(
+                Render,
+                "Dynamic lights: {}",
                 if self.game_state.disable_dynamic_lights {
                     "OFF"
                 } else {
@@ -848,8 +939,9 @@ impl GameLoop {
 
         if is_key_pressed(KeyCode::F6) {
             self.game_state.disable_particles = !self.game_state.disable_particles;
-            println!(
-                "[Perf] Particles: {}",
+            log_info!(
+                Render,
+                "Particles: {}",
                 if self.game_state.disable_particles {
                     "OFF"
                 } else {
@@ -860,8 +952,9 @@ impl GameLoop {
 
         if is_key_pressed(KeyCode::F2) {
             self.game_state.disable_deferred = !self.game_state.disable_deferred;
-            println!(
-                "[Perf] Deferred rendering: {}",
+            log_info!(
+                Render,
+                "Deferred rendering: {}",
                 if self.game_state.disable_deferred {
                     "OFF (forward)"
                 } else {
@@ -876,15 +969,16 @@ impl GameLoop {
             } else {
                 2.0
             };
-            println!("[Perf] Render scale: {}x", self.game_state.render_scale);
+            log_info!(Render, "Render scale: {}x", self.game_state.render_scale);
         }
 
         if is_key_pressed(KeyCode::Key1)
             && (is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift))
         {
             self.game_state.cartoon_shader = !self.game_state.cartoon_shader;
-            println!(
-                "[Visual] Cartoon shader: {}",
+            log_info!(
+                Render,
+                "Cartoon shader: {}",
                 if self.game_state.cartoon_shader {
                     "ON"
                 } else {
@@ -976,6 +1070,8 @@ impl GameLoop {
                         self.camera.tracking_projectile_id = Some(projectile.id);
                         self.camera
                             .follow_projectile_with_zoom(projectile.x, projectile.y);
+                    } else if let Some(teammate) = self.game_state.spectate_target(player.id) {
+                        self.camera.follow(teammate.x, teammate.y);
                     } else {
                         self.camera.follow(player.x, player.y);
                     }
@@ -998,6 +1094,9 @@ impl GameLoop {
 
     fn update_game_state(&mut self, dt: f32) {
         let _scope = profiler::scope("game_update");
+        if self.game_state.is_paused() {
+            return;
+        }
         self.game_state.update(dt);
     }
 
@@ -1085,10 +1184,25 @@ impl GameLoop {
         }
 
         let player_count = self.game_state.players.len();
+        if let Some(max) = self.game_state.gametype.max_players() {
+            if player_count >= max {
+                log_info!(
+                    Game,
+                    "{} is limited to {} players",
+                    self.game_state.gametype.kind().name(),
+                    max
+                );
+                return;
+            }
+        }
         let bot_id = (player_count + 1) as u16;
 
         let mut bot = crate::game::player::Player::new(bot_id, model.to_string(), true);
         bot.model = model.to_string();
+        bot.team = self
+            .game_state
+            .gametype
+            .pick_team(&ScoreEntry::from_players(&self.game_state.players));
 
         let spawn_idx = player_count % self.game_state.map.spawn_points.len().max(1);
         if let Some(spawn_point) = self.game_state.map.spawn_points.get(spawn_idx) {
@@ -1098,8 +1212,13 @@ impl GameLoop {
             bot.spawn(spawn_x, spawn_y, &self.game_state.map);
         }
 
-        bot.has_weapon = [true, true, false, false, false, false, false, false, false];
-        bot.ammo = [255, 50, 0, 0, 0, 0, 0, 0, 0];
+        bot.has_weapon = [
+            true, true, false, false, false, false, false, false, false, false, false, false,
+        ];
+        bot.ammo = [255, 50, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        if let Some(loadout) = self.game_state.spawn_loadout() {
+            bot.apply_loadout(&loadout);
+        }
 
         #[cfg(target_arch = "wasm32")]
         {
@@ -1119,8 +1238,9 @@ impl GameLoop {
         self.audio.load_player_sounds(model).await;
 
         self.game_state.players.push(bot);
-        println!(
-            "[GameLoop] Added bot: {} (total bots: {})",
+        log_info!(
+            Bot,
+            "Added bot: {} (total bots: {})",
             model,
             self.game_state.players.iter().filter(|p| p.is_bot).count()
         );
@@ -1136,14 +1256,15 @@ impl GameLoop {
             if let Some(bot_index) = self.game_state.players.iter().rposition(|p| p.is_bot) {
                 let bot_name = self.game_state.players[bot_index].name.clone();
                 self.game_state.players.remove(bot_index);
-                println!(
-                    "[GameLoop] Removed bot: {} (F4). Remaining bots: {}",
+                log_info!(
+                    Bot,
+                    "Removed bot: {} (F4). Remaining bots: {}",
                     bot_name,
                     bot_count - 1
                 );
             }
         } else {
-            println!("[GameLoop] No bots to remove (F4)");
+            log_info!(Bot, "No bots to remove (F4)");
         }
     }
 
@@ -1158,14 +1279,14 @@ impl GameLoop {
                 if let Some(ref mut ai) = player.bot_ai {
                     ai.afk = !ai.afk;
                     let status = if ai.afk { "AFK" } else { "Active" };
-                    println!("[GameLoop] Bot {} is now {}", player.name, status);
+                    log_info!(Bot, "Bot {} is now {}", player.name, status);
                     toggled_count += 1;
                 }
             }
         }
 
         if toggled_count == 0 {
-            println!("[GameLoop] No bots to toggle AFK");
+            log_info!(Bot, "No bots to toggle AFK");
         }
     }
 
@@ -1227,7 +1348,7 @@ impl GameLoop {
     }
 
     async fn load_next_story_level(&mut self, map_name: &str) -> bool {
-        println!("[Story] Loading next level: {}", map_name);
+        log_info!(Game, "Loading next level: {}", map_name);
 
         let story_backup = self.game_state.story_mode.clone();
 
@@ -1,3 +1,7 @@
+use crate::game::ctf::{self, FlagStatus};
+use crate::game::gametype::{self, ScoreboardLayout};
+use crate::game::pause::PausePhase;
+use crate::game::player::Player;
 use crate::game::GameState;
 use crate::input::Input;
 use crate::render;
@@ -24,6 +28,56 @@ impl HudScoreboard {
         } else {
             Self::render_single_player_hud(game_state);
         }
+
+        if game_state.is_paused() {
+            Self::render_pause_banner(game_state);
+        }
+    }
+
+    fn render_pause_banner(game_state: &GameState) {
+        let center_x = screen_width() * 0.5;
+        let banner_y = screen_height() * 0.3;
+        draw_rectangle(
+            0.0,
+            banner_y - 50.0,
+            screen_width(),
+            80.0,
+            Color::from_rgba(0, 0, 0, 160),
+        );
+
+        let (title, detail) = match game_state.pause.phase {
+            PausePhase::Paused { by, remaining } => {
+                let caller = match by {
+                    Some(id) => game_state
+                        .players
+                        .iter()
+                        .find(|p| p.id == id)
+                        .map(|p| format!("Timeout called by {}", p.name))
+                        .unwrap_or_else(|| "Timeout".to_string()),
+                    None => "Paused by the server".to_string(),
+                };
+                let detail = match remaining {
+                    Some(secs) => format!("{} - {}s left", caller, secs.ceil() as u32),
+                    None => caller,
+                };
+                ("PAUSED".to_string(), detail)
+            }
+            PausePhase::Unpausing(secs) => {
+                (format!("RESUMING IN {}", secs.ceil() as u32), String::new())
+            }
+            PausePhase::Running => return,
+        };
+
+        let dims = measure_text(&title, None, 48, 1.0);
+        draw_text(&title, center_x - dims.width * 0.5, banner_y, 48.0, YELLOW);
+        let dims = measure_text(&detail, None, 20, 1.0);
+        draw_text(
+            &detail,
+            center_x - dims.width * 0.5,
+            banner_y + 22.0,
+            20.0,
+            WHITE,
+        );
     }
 
     fn render_local_multiplayer_hud(game_state: &GameState, leader_frags: i32) {
@@ -40,8 +94,9 @@ impl HudScoreboard {
                 &player1.has_weapon,
                 &player1.ammo,
                 game_state.match_time,
-                game_state.time_limit,
+                game_state.match_rules.time_limit(game_state.time_limit),
             );
+            Self::render_holdable(player1, screen_height() - 120.0);
         }
 
         if let Some(player2) = game_state.players.get(1) {
@@ -56,32 +111,207 @@ impl HudScoreboard {
                 &player2.has_weapon,
                 &player2.ammo,
             );
+            Self::render_holdable(player2, 130.0);
+        }
+    }
+
+    /// Icon for the carried holdable at the right edge of the screen.
+    fn render_holdable(player: &Player, y: f32) {
+        if let Some(holdable) = player.holdable {
+            render::draw_item_icon(screen_width() - 40.0, y, &holdable.item_type(), 48.0, WHITE);
         }
     }
 
     fn render_single_player_hud(game_state: &GameState) {
         if let Some(player) = game_state.players.get(0) {
-            let competitor_frags = game_state
-                .players
-                .iter()
-                .filter(|p| p.id != player.id && !p.dead)
-                .map(|p| p.frags)
-                .max()
-                .unwrap_or(0);
+            let (score, competitor_frags) = if game_state.gametype.is_team_game() {
+                let team_scores = game_state.team_scores();
+                (
+                    team_scores.get(player.team),
+                    team_scores.get(gametype::other_team(player.team)),
+                )
+            } else {
+                let competitor_frags = game_state
+                    .players
+                    .iter()
+                    .filter(|p| p.id != player.id && !p.dead)
+                    .map(|p| p.frags)
+                    .max()
+                    .unwrap_or(0);
+                (player.frags, competitor_frags)
+            };
 
             render::draw_hud(
                 player.health,
                 player.armor,
                 player.ammo[player.weapon as usize],
                 player.weapon.name(),
-                player.frags,
+                score,
                 player.weapon as u8,
                 competitor_frags,
                 &player.has_weapon,
                 &player.ammo,
                 game_state.match_time,
-                game_state.time_limit,
+                game_state.match_rules.time_limit(game_state.time_limit),
             );
+            Self::render_holdable(player, screen_height() - 120.0);
+
+            if game_state.gametype.uses_flags() {
+                Self::render_flag_status(game_state, player.id);
+            }
+            if !game_state.warmup.is_live() {
+                Self::render_warmup_status(game_state, player.id);
+            } else if game_state.gametype.is_round_based() {
+                Self::render_round_status(game_state, player);
+            } else if let Some(status) = game_state.match_rules.status_text() {
+                let dims = measure_text(&status, None, 20, 1.0);
+                draw_text(
+                    &status,
+                    screen_width() * 0.5 - dims.width * 0.5,
+                    58.0,
+                    20.0,
+                    YELLOW,
+                );
+            }
+        }
+    }
+
+    fn render_warmup_status(game_state: &GameState, local_id: u16) {
+        let warmup = &game_state.warmup;
+        let center_x = screen_width() * 0.5;
+
+        let status = warmup.status_text(game_state.players.len());
+        let size = 20.0;
+        let dims = measure_text(&status, None, size as u16, 1.0);
+        draw_text(
+            &status,
+            center_x - dims.width * 0.5,
+            58.0,
+            size,
+            Color::from_rgba(255, 255, 255, 220),
+        );
+
+        if let Some(secs) = warmup.countdown() {
+            let text = secs.to_string();
+            let dims = measure_text(&text, None, 96, 1.0);
+            draw_text(
+                &text,
+                center_x - dims.width * 0.5,
+                screen_height() * 0.35,
+                96.0,
+                YELLOW,
+            );
+            return;
+        }
+
+        let (text, color) = if warmup.is_ready(local_id) {
+            ("READY - waiting for the others", GREEN)
+        } else {
+            ("WARMUP - press Y or type 'ready' when ready", YELLOW)
+        };
+        let dims = measure_text(text, None, 22, 1.0);
+        draw_text(
+            text,
+            center_x - dims.width * 0.5,
+            screen_height() - 120.0,
+            22.0,
+            color,
+        );
+    }
+
+    fn render_round_status(game_state: &GameState, local: &Player) {
+        let round = &game_state.round;
+        let center_x = screen_width() * 0.5;
+
+        let status = round.status_text();
+        let size = 20.0;
+        let dims = measure_text(&status, None, size as u16, 1.0);
+        draw_text(
+            &status,
+            center_x - dims.width * 0.5,
+            58.0,
+            size,
+            Color::from_rgba(255, 255, 255, 220),
+        );
+
+        if let Some(secs) = round.countdown() {
+            let text = secs.to_string();
+            let dims = measure_text(&text, None, 96, 1.0);
+            draw_text(
+                &text,
+                center_x - dims.width * 0.5,
+                screen_height() * 0.35,
+                96.0,
+                YELLOW,
+            );
+        }
+
+        if local.frozen {
+            let text = if local.thaw_progress > 0.0 {
+                format!("Frozen - thawing {}%", (local.thaw_progress * 100.0) as u32)
+            } else {
+                "Frozen - wait for a teammate to thaw you".to_string()
+            };
+            let dims = measure_text(&text, None, 22, 1.0);
+            draw_text(
+                &text,
+                center_x - dims.width * 0.5,
+                screen_height() - 120.0,
+                22.0,
+                Color::from_rgba(140, 210, 255, 255),
+            );
+        } else if local.dead {
+            let text = match game_state.spectate_target(local.id) {
+                Some(teammate) => format!("Eliminated - following {}", teammate.name),
+                None => "Eliminated - wait for the next round".to_string(),
+            };
+            let dims = measure_text(&text, None, 22, 1.0);
+            draw_text(
+                &text,
+                center_x - dims.width * 0.5,
+                screen_height() - 120.0,
+                22.0,
+                Color::from_rgba(255, 200, 80, 255),
+            );
+        }
+    }
+
+    fn render_flag_status(game_state: &GameState, local_id: u16) {
+        let center_x = screen_width() * 0.5;
+        let y = 44.0;
+
+        for (i, team) in [gametype::TEAM_RED, gametype::TEAM_BLUE].iter().enumerate() {
+            let Some(flag) = game_state.ctf.flag(*team) else {
+                continue;
+            };
+            let x = center_x - 52.0 + i as f32 * 60.0;
+
+            draw_rectangle(x, y, 44.0, 40.0, Color::from_rgba(0, 0, 0, 140));
+            match flag.status {
+                FlagStatus::AtBase => {
+                    ctf::draw_flag(x + 14.0, y + 22.0, *team, 0.9);
+                }
+                FlagStatus::Carried(id) => {
+                    let pulse = ((get_time() * 6.0).sin() * 0.5 + 0.5) as f32;
+                    let outline = if id == local_id {
+                        Color::new(1.0, 0.9, 0.2, 0.5 + pulse * 0.5)
+                    } else {
+                        Color::new(1.0, 1.0, 1.0, 0.3 + pulse * 0.5)
+                    };
+                    draw_rectangle_lines(x, y, 44.0, 40.0, 2.0, outline);
+                    ctf::draw_flag(x + 14.0, y + 22.0, *team, 0.9);
+                }
+                FlagStatus::Dropped => {
+                    ctf::draw_flag(x + 8.0, y + 22.0, *team, 0.6);
+                    draw_text(
+                        &format!("{}", flag.return_timer.ceil() as i32),
+                        x + 22.0,
+                        y + 30.0,
+                        20.0,
+                        WHITE,
+                    );
+                }
+            }
         }
     }
 
@@ -220,8 +450,15 @@ impl HudScoreboard {
 
     pub fn render_scoreboard(game_state: &GameState) {
         if is_key_down(KeyCode::Tab) {
+            let layout = game_state.gametype.scoreboard_layout();
+            let rows = game_state.players.len()
+                + if layout == ScoreboardLayout::Teams {
+                    2
+                } else {
+                    0
+                };
             let board_width = 460.0;
-            let board_height = 300.0;
+            let board_height = (300.0f32).max(110.0 + rows as f32 * 30.0);
             let board_x = screen_width() / 2.0 - board_width / 2.0;
             let board_y = screen_height() / 2.0 - board_height / 2.0;
 
@@ -234,7 +471,23 @@ impl HudScoreboard {
             );
             draw_rectangle_lines(board_x, board_y, board_width, board_height, 2.0, WHITE);
 
-            draw_text("SCOREBOARD", board_x + 120.0, board_y + 30.0, 24.0, YELLOW);
+            let mut title = match layout {
+                ScoreboardLayout::FreeForAll => "SCOREBOARD".to_string(),
+                _ => game_state.gametype.kind().name().to_uppercase(),
+            };
+            if !game_state.mutators.is_empty() {
+                title.push_str(&format!(
+                    " [{}]",
+                    game_state.mutators.names().to_uppercase()
+                ));
+            }
+            if game_state.gametype.is_round_based() {
+                title.push_str(&format!("  ROUND {}", game_state.round.round));
+                if game_state.round.round_limit > 0 {
+                    title.push_str(&format!(" / FIRST TO {}", game_state.round.round_limit));
+                }
+            }
+            draw_text(&title, board_x + 120.0, board_y + 30.0, 24.0, YELLOW);
             draw_text("Player", board_x + 20.0, board_y + 60.0, 20.0, WHITE);
             draw_text("Frags", board_x + 200.0, board_y + 60.0, 20.0, WHITE);
             draw_text("Deaths", board_x + 260.0, board_y + 60.0, 20.0, WHITE);
@@ -243,75 +496,110 @@ impl HudScoreboard {
             let mut sorted_players = game_state.players.clone();
             sorted_players.sort_by(|a, b| b.frags.cmp(&a.frags));
 
-            for (i, player) in sorted_players.iter().enumerate() {
-                let y = board_y + 90.0 + (i as f32 * 30.0);
-                let color = if player.is_bot { RED } else { GREEN };
-                draw_text(&player.name, board_x + 20.0, y, 18.0, color);
+            let mut y = board_y + 90.0;
+            if layout == ScoreboardLayout::Teams {
+                let team_scores = game_state.team_scores();
+                for (team, color) in [
+                    (gametype::TEAM_RED, Color::from_rgba(255, 80, 80, 255)),
+                    (gametype::TEAM_BLUE, Color::from_rgba(80, 140, 255, 255)),
+                ] {
+                    draw_text(
+                        &format!(
+                            "{} TEAM  {}",
+                            gametype::team_name(team).to_uppercase(),
+                            team_scores.get(team)
+                        ),
+                        board_x + 20.0,
+                        y,
+                        20.0,
+                        color,
+                    );
+                    y += 30.0;
+                    for player in sorted_players.iter().filter(|p| p.team == team) {
+                        Self::draw_scoreboard_row(game_state, player, board_x, y);
+                        y += 30.0;
+                    }
+                }
+            } else {
+                for player in &sorted_players {
+                    Self::draw_scoreboard_row(game_state, player, board_x, y);
+                    y += 30.0;
+                }
+            }
+        }
+    }
+
+    fn draw_scoreboard_row(
+        game_state: &GameState,
+        player: &crate::game::player::Player,
+        board_x: f32,
+        y: f32,
+    ) {
+        let color = if player.is_bot { RED } else { GREEN };
+        draw_text(&player.name, board_x + 20.0, y, 18.0, color);
+        draw_text(
+            &format!("{}", player.frags),
+            board_x + 210.0,
+            y,
+            18.0,
+            WHITE,
+        );
+        draw_text(
+            &format!("{}", player.deaths),
+            board_x + 270.0,
+            y,
+            18.0,
+            WHITE,
+        );
+
+        let mut award_x = board_x + 330.0;
+        let icon_size = 20.0;
+        let icon_y = y - 16.0;
+
+        if player.excellent_count > 0 {
+            if let Some(excellent_tex) = game_state.award_icon_cache.excellent.as_ref() {
+                draw_texture_ex(
+                    excellent_tex,
+                    award_x,
+                    icon_y,
+                    WHITE,
+                    DrawTextureParams {
+                        dest_size: Some(Vec2::new(icon_size, icon_size)),
+                        ..Default::default()
+                    },
+                );
+                award_x += icon_size + 2.0;
                 draw_text(
-                    &format!("{}", player.frags),
-                    board_x + 210.0,
+                    &format!("{}", player.excellent_count),
+                    award_x,
                     y,
-                    18.0,
+                    16.0,
+                    Color::from_rgba(255, 220, 100, 255),
+                );
+                award_x += 25.0;
+            }
+        }
+
+        if player.impressive_count > 0 {
+            if let Some(impressive_tex) = game_state.award_icon_cache.impressive.as_ref() {
+                draw_texture_ex(
+                    impressive_tex,
+                    award_x,
+                    icon_y,
                     WHITE,
+                    DrawTextureParams {
+                        dest_size: Some(Vec2::new(icon_size, icon_size)),
+                        ..Default::default()
+                    },
                 );
+                award_x += icon_size + 2.0;
                 draw_text(
-                    &format!("{}", player.deaths),
-                    board_x + 270.0,
+                    &format!("{}", player.impressive_count),
+                    award_x,
                     y,
-                    18.0,
-                    WHITE,
+                    16.0,
+                    Color::from_rgba(255, 220, 100, 255),
                 );
-
-                let mut award_x = board_x + 330.0;
-                let icon_size = 20.0;
-                let icon_y = y - 16.0;
-
-                if player.excellent_count > 0 {
-                    if let Some(excellent_tex) = game_state.award_icon_cache.excellent.as_ref() {
-                        draw_texture_ex(
-                            excellent_tex,
-                            award_x,
-                            icon_y,
-                            WHITE,
-                            DrawTextureParams {
-                                dest_size: Some(Vec2::new(icon_size, icon_size)),
-                                ..Default::default()
-                            },
-                        );
-                        award_x += icon_size + 2.0;
-                        draw_text(
-                            &format!("{}", player.excellent_count),
-                            award_x,
-                            y,
-                            16.0,
-                            Color::from_rgba(255, 220, 100, 255),
-                        );
-                        award_x += 25.0;
-                    }
-                }
-
-                if player.impressive_count > 0 {
-                    if let Some(impressive_tex) = game_state.award_icon_cache.impressive.as_ref() {
-                        draw_texture_ex(
-                            impressive_tex,
-                            award_x,
-                            icon_y,
-                            WHITE,
-                            DrawTextureParams {
-                                dest_size: Some(Vec2::new(icon_size, icon_size)),
-                                ..Default::default()
-                            },
-                        );
-                        award_x += icon_size + 2.0;
-                        draw_text(
-                            &format!("{}", player.impressive_count),
-                            award_x,
-                            y,
-                            16.0,
-                            Color::from_rgba(255, 220, 100, 255),
-                        );
-                    }
-                }
             }
         }
     }
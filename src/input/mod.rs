@@ -31,6 +31,66 @@ pub fn center_mouse_cursor() {
 #[cfg(not(target_os = "macos"))]
 pub fn center_mouse_cursor() {}
 
+const BIND_KEYS: [(&str, KeyCode); 36] = [
+    ("A", KeyCode::A),
+    ("B", KeyCode::B),
+    ("C", KeyCode::C),
+    ("D", KeyCode::D),
+    ("E", KeyCode::E),
+    ("F", KeyCode::F),
+    ("G", KeyCode::G),
+    ("H", KeyCode::H),
+    ("I", KeyCode::I),
+    ("J", KeyCode::J),
+    ("K", KeyCode::K),
+    ("L", KeyCode::L),
+    ("M", KeyCode::M),
+    ("N", KeyCode::N),
+    ("O", KeyCode::O),
+    ("P", KeyCode::P),
+    ("Q", KeyCode::Q),
+    ("R", KeyCode::R),
+    ("S", KeyCode::S),
+    ("T", KeyCode::T),
+    ("U", KeyCode::U),
+    ("V", KeyCode::V),
+    ("W", KeyCode::W),
+    ("X", KeyCode::X),
+    ("Y", KeyCode::Y),
+    ("Z", KeyCode::Z),
+    ("SPACE", KeyCode::Space),
+    ("SHIFT", KeyCode::LeftShift),
+    ("CTRL", KeyCode::LeftControl),
+    ("ALT", KeyCode::LeftAlt),
+    ("TAB", KeyCode::Tab),
+    ("ENTER", KeyCode::Enter),
+    ("UPARROW", KeyCode::Up),
+    ("DOWNARROW", KeyCode::Down),
+    ("LEFTARROW", KeyCode::Left),
+    ("RIGHTARROW", KeyCode::Right),
+];
+
+/// True when a key or mouse button is a name `bind` accepts, e.g. "MOUSE2",
+/// "Q" or "SHIFT".
+pub fn is_bind_name(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    matches!(name.as_str(), "MOUSE1" | "MOUSE2" | "MOUSE3")
+        || BIND_KEYS.iter().any(|(key, _)| *key == name)
+}
+
+/// Whether the key or mouse button a bind names is held.
+pub fn bind_down(name: &str) -> bool {
+    match name.to_ascii_uppercase().as_str() {
+        "MOUSE1" => is_mouse_button_down(MouseButton::Left),
+        "MOUSE2" => is_mouse_button_down(MouseButton::Right),
+        "MOUSE3" => is_mouse_button_down(MouseButton::Middle),
+        name => BIND_KEYS
+            .iter()
+            .find(|(key, _)| *key == name)
+            .is_some_and(|(_, code)| is_key_down(*code)),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Input {
     pub move_left: bool,
@@ -38,6 +98,8 @@ pub struct Input {
     pub jump: bool,
     pub crouch: bool,
     pub shoot: bool,
+    pub use_item: bool,
+    pub hook: bool,
     pub aim_angle: f32,
     pub aim_x: f32,
     pub aim_y: f32,
@@ -58,6 +120,8 @@ pub struct PlayerInput {
     pub jump: bool,
     pub crouch: bool,
     pub shoot: bool,
+    pub use_item: bool,
+    pub hook: bool,
     pub aim_angle: f32,
     pub aim_x: f32,
     pub aim_y: f32,
@@ -73,6 +137,8 @@ impl Input {
             jump: false,
             crouch: false,
             shoot: false,
+            use_item: false,
+            hook: false,
             aim_angle: 0.0,
             aim_x: 1.0,
             aim_y: 0.0,
@@ -104,6 +170,8 @@ impl Input {
             || self.keys_held.contains(&KeyCode::S)
             || self.keys_held.contains(&KeyCode::Down);
         self.shoot = is_mouse_button_down(MouseButton::Left);
+        self.use_item = self.keys_held.contains(&KeyCode::E);
+        self.hook = bind_down(&crate::cvar::get_cvar_string("cl_hookkey"));
 
         let sensitivity = crate::cvar::get_cvar_float("sensitivity");
         let m_yaw = crate::cvar::get_cvar_float("m_yaw");
@@ -153,6 +221,12 @@ impl Input {
             self.weapon_switch = Some(7);
         } else if is_key_pressed(KeyCode::Key9) {
             self.weapon_switch = Some(8);
+        } else if is_key_pressed(KeyCode::Key0) {
+            self.weapon_switch = Some(9);
+        } else if is_key_pressed(KeyCode::Minus) {
+            self.weapon_switch = Some(10);
+        } else if is_key_pressed(KeyCode::Equal) {
+            self.weapon_switch = Some(11);
         }
     }
 }
@@ -171,6 +245,8 @@ impl LocalMultiplayerInput {
         self.player1.jump = is_key_down(KeyCode::W);
         self.player1.crouch = is_key_down(KeyCode::S) || is_key_down(KeyCode::LeftShift);
         self.player1.shoot = is_key_down(KeyCode::Space);
+        self.player1.use_item = is_key_down(KeyCode::E);
+        self.player1.hook = is_key_down(KeyCode::C);
 
         let old_flip = self.player1.flip_x;
         if self.player1.move_left && !self.player1.move_right {
@@ -188,6 +264,8 @@ impl LocalMultiplayerInput {
         self.player2.jump = is_mouse_button_down(MouseButton::Right);
         self.player2.crouch = is_key_down(KeyCode::Down);
         self.player2.shoot = is_mouse_button_down(MouseButton::Left);
+        self.player2.use_item = is_key_down(KeyCode::RightShift);
+        self.player2.hook = is_mouse_button_down(MouseButton::Middle);
 
         self.player1.weapon_switch = None;
         if is_key_pressed(KeyCode::Key1) {
@@ -285,6 +363,8 @@ impl PlayerInput {
             jump: false,
             crouch: false,
             shoot: false,
+            use_item: false,
+            hook: false,
             aim_angle: 0.0,
             aim_x: 1.0,
             aim_y: 0.0,
@@ -11,6 +11,7 @@ pub mod console;
 pub mod cvar;
 pub mod game_loop;
 pub mod hud_scoreboard;
+pub mod logging;
 pub mod menu;
 pub mod resource_path;
 pub mod weapon_handler;
@@ -0,0 +1,488 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+const MAX_CONSOLE_LINES: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error = 0,
+    Warn = 1,
+    Info = 2,
+    Debug = 3,
+    Trace = 4,
+}
+
+impl LogLevel {
+    pub fn from_developer(developer: i32) -> Self {
+        match developer {
+            i32::MIN..=0 => LogLevel::Info,
+            1 => LogLevel::Debug,
+            _ => LogLevel::Trace,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warn,
+            2 => LogLevel::Info,
+            3 => LogLevel::Debug,
+            _ => LogLevel::Trace,
+        }
+    }
+
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR: ",
+            LogLevel::Warn => "WARNING: ",
+            _ => "",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Subsystem {
+    Net,
+    Game,
+    Bot,
+    Render,
+    Audio,
+}
+
+impl Subsystem {
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Subsystem::Net => "NET",
+            Subsystem::Game => "GAME",
+            Subsystem::Bot => "BOT",
+            Subsystem::Render => "RENDER",
+            Subsystem::Audio => "AUDIO",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    time: f64,
+    wall: &'a str,
+    level: LogLevel,
+    subsystem: Subsystem,
+    msg: &'a str,
+}
+
+// logfile: 0 = off, 1 = buffered, 2 = flush every line, 3 = append, 4 = append + flush
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FileMode {
+    enabled: bool,
+    flush: bool,
+    append: bool,
+}
+
+impl FileMode {
+    fn from_cvar(logfile: i32) -> Self {
+        Self {
+            enabled: logfile > 0,
+            flush: logfile == 2 || logfile >= 4,
+            append: logfile >= 3,
+        }
+    }
+}
+
+pub struct Logger {
+    base_path: String,
+    file_mode: FileMode,
+    json: bool,
+    file: Option<BufWriter<File>>,
+    json_file: Option<BufWriter<File>>,
+    echo: bool,
+    capture_console: bool,
+    console_lines: VecDeque<String>,
+}
+
+impl Logger {
+    pub fn new(base_path: &str) -> Self {
+        Self {
+            base_path: base_path.to_string(),
+            file_mode: FileMode::from_cvar(0),
+            json: false,
+            file: None,
+            json_file: None,
+            echo: true,
+            capture_console: false,
+            console_lines: VecDeque::new(),
+        }
+    }
+
+    pub fn log_path(&self) -> String {
+        format!("{}.log", self.base_path)
+    }
+
+    pub fn json_path(&self) -> String {
+        format!("{}.jsonl", self.base_path)
+    }
+
+    pub fn set_echo(&mut self, echo: bool) {
+        self.echo = echo;
+    }
+
+    pub fn set_capture_console(&mut self, capture: bool) {
+        self.capture_console = capture;
+        if !capture {
+            self.console_lines.clear();
+        }
+    }
+
+    pub fn configure(&mut self, logfile: i32, json: bool) {
+        let mode = FileMode::from_cvar(logfile);
+        let mode_changed = mode != self.file_mode;
+        let json_changed = json != self.json;
+        self.file_mode = mode;
+        self.json = json;
+
+        if mode_changed || json_changed {
+            self.flush();
+            self.file = None;
+            self.json_file = None;
+        }
+
+        if mode.enabled && self.file.is_none() {
+            self.file = Self::open(&self.log_path(), mode.append);
+        }
+        if mode.enabled && json && self.json_file.is_none() {
+            self.json_file = Self::open(&self.json_path(), mode.append);
+        }
+    }
+
+    fn open(path: &str, append: bool) -> Option<BufWriter<File>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut options = OpenOptions::new();
+            options.create(true);
+            if append {
+                options.append(true);
+            } else {
+                options.write(true).truncate(true);
+            }
+            match options.open(path) {
+                Ok(file) => Some(BufWriter::new(file)),
+                Err(e) => {
+                    eprintln!("Couldn't open log file {}: {}", path, e);
+                    None
+                }
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (path, append);
+            None
+        }
+    }
+
+    pub fn write(&mut self, time: f64, level: LogLevel, subsystem: Subsystem, msg: &str) {
+        let line = format_line(time, level, subsystem, msg);
+
+        if self.echo {
+            if level <= LogLevel::Warn {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+
+        if self.capture_console {
+            self.console_lines.push_back(line.clone());
+            if self.console_lines.len() > MAX_CONSOLE_LINES {
+                self.console_lines.pop_front();
+            }
+        }
+
+        if self.file.is_none() && self.json_file.is_none() {
+            return;
+        }
+
+        let wall = crate::network::get_absolute_time();
+
+        if let Some(file) = self.file.as_mut() {
+            let _ = writeln!(file, "{} {}", wall, line);
+            if self.file_mode.flush {
+                let _ = file.flush();
+            }
+        }
+
+        if let Some(file) = self.json_file.as_mut() {
+            let record = JsonRecord {
+                time,
+                wall: &wall,
+                level,
+                subsystem,
+                msg,
+            };
+            if let Ok(json) = serde_json::to_string(&record) {
+                let _ = writeln!(file, "{}", json);
+                if self.file_mode.flush {
+                    let _ = file.flush();
+                }
+            }
+        }
+    }
+
+    pub fn drain_console(&mut self) -> Vec<String> {
+        self.console_lines.drain(..).collect()
+    }
+
+    pub fn flush(&mut self) {
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.flush();
+        }
+        if let Some(file) = self.json_file.as_mut() {
+            let _ = file.flush();
+        }
+    }
+}
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+pub fn format_line(time: f64, level: LogLevel, subsystem: Subsystem, msg: &str) -> String {
+    format!(
+        "[{:.3}] [{}] {}{}",
+        time,
+        subsystem.tag(),
+        level.prefix(),
+        msg
+    )
+}
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+lazy_static::lazy_static! {
+    static ref LOGGER: Mutex<Logger> = Mutex::new(Logger::new("qconsole"));
+}
+
+pub fn init(base_path: &str) {
+    if let Ok(mut logger) = LOGGER.lock() {
+        logger.flush();
+        *logger = Logger::new(base_path);
+    }
+    update();
+}
+
+pub fn update() {
+    let developer = crate::cvar::get_cvar_integer("developer");
+    MAX_LEVEL.store(LogLevel::from_developer(developer) as u8, Ordering::Relaxed);
+
+    let logfile = crate::cvar::get_cvar_integer("logfile");
+    let json = crate::cvar::get_cvar_bool("log_json");
+    if let Ok(mut logger) = LOGGER.lock() {
+        logger.configure(logfile, json);
+    }
+}
+
+pub fn max_level() -> LogLevel {
+    LogLevel::from_u8(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+pub fn enabled(level: LogLevel) -> bool {
+    level <= max_level()
+}
+
+pub fn log(level: LogLevel, subsystem: Subsystem, args: std::fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+    let msg = args.to_string();
+    let time = crate::network::get_network_time();
+    if let Ok(mut logger) = LOGGER.lock() {
+        logger.write(time, level, subsystem, &msg);
+    }
+}
+
+pub fn set_capture_console(capture: bool) {
+    if let Ok(mut logger) = LOGGER.lock() {
+        logger.set_capture_console(capture);
+    }
+}
+
+pub fn drain_console() -> Vec<String> {
+    LOGGER
+        .lock()
+        .map(|mut logger| logger.drain_console())
+        .unwrap_or_default()
+}
+
+pub fn flush() {
+    if let Ok(mut logger) = LOGGER.lock() {
+        logger.flush();
+    }
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($subsystem:ident, $($arg:tt)*) => {
+        $crate::logging::log(
+            $crate::logging::LogLevel::Error,
+            $crate::logging::Subsystem::$subsystem,
+            format_args!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($subsystem:ident, $($arg:tt)*) => {
+        $crate::logging::log(
+            $crate::logging::LogLevel::Warn,
+            $crate::logging::Subsystem::$subsystem,
+            format_args!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($subsystem:ident, $($arg:tt)*) => {
+        $crate::logging::log(
+            $crate::logging::LogLevel::Info,
+            $crate::logging::Subsystem::$subsystem,
+            format_args!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($subsystem:ident, $($arg:tt)*) => {
+        if $crate::logging::enabled($crate::logging::LogLevel::Debug) {
+            $crate::logging::log(
+                $crate::logging::LogLevel::Debug,
+                $crate::logging::Subsystem::$subsystem,
+                format_args!($($arg)*),
+            )
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! log_trace {
+    ($subsystem:ident, $($arg:tt)*) => {
+        if $crate::logging::enabled($crate::logging::LogLevel::Trace) {
+            $crate::logging::log(
+                $crate::logging::LogLevel::Trace,
+                $crate::logging::Subsystem::$subsystem,
+                format_args!($($arg)*),
+            )
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_base(name: &str) -> String {
+        let dir = std::env::temp_dir();
+        dir.join(format!("sas_log_{}_{}", name, std::process::id()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn test_developer_levels() {
+        assert_eq!(LogLevel::from_developer(0), LogLevel::Info);
+        assert_eq!(LogLevel::from_developer(1), LogLevel::Debug);
+        assert_eq!(LogLevel::from_developer(2), LogLevel::Trace);
+        assert!(LogLevel::Warn < LogLevel::Info);
+    }
+
+    #[test]
+    fn test_line_format() {
+        assert_eq!(
+            format_line(1.5, LogLevel::Info, Subsystem::Net, "Client 1 connected"),
+            "[1.500] [NET] Client 1 connected"
+        );
+        assert_eq!(
+            format_line(2.0, LogLevel::Warn, Subsystem::Bot, "stuck"),
+            "[2.000] [BOT] WARNING: stuck"
+        );
+    }
+
+    #[test]
+    fn test_file_and_json_output() {
+        let base = temp_base("files");
+        let mut logger = Logger::new(&base);
+        logger.set_echo(false);
+        logger.configure(2, true);
+
+        logger.write(3.25, LogLevel::Info, Subsystem::Game, "match \"started\"");
+        logger.write(4.0, LogLevel::Error, Subsystem::Audio, "no device");
+
+        let text = std::fs::read_to_string(logger.log_path()).unwrap();
+        assert!(text.contains("[3.250] [GAME] match \"started\"\n"));
+        assert!(text.contains("[4.000] [AUDIO] ERROR: no device\n"));
+
+        let json = std::fs::read_to_string(logger.json_path()).unwrap();
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["level"], "info");
+        assert_eq!(record["subsystem"], "game");
+        assert_eq!(record["msg"], "match \"started\"");
+        assert_eq!(record["time"], 3.25);
+
+        logger.configure(0, false);
+        logger.write(5.0, LogLevel::Info, Subsystem::Net, "not written");
+        let text = std::fs::read_to_string(logger.log_path()).unwrap();
+        assert!(!text.contains("not written"));
+
+        let _ = std::fs::remove_file(logger.log_path());
+        let _ = std::fs::remove_file(logger.json_path());
+    }
+
+    #[test]
+    fn test_append_mode_keeps_old_log() {
+        let base = temp_base("append");
+        let mut logger = Logger::new(&base);
+        logger.set_echo(false);
+        logger.configure(2, false);
+        logger.write(1.0, LogLevel::Info, Subsystem::Net, "first session");
+        drop(logger);
+
+        let mut logger = Logger::new(&base);
+        logger.set_echo(false);
+        logger.configure(4, false);
+        logger.write(2.0, LogLevel::Info, Subsystem::Net, "second session");
+
+        let text = std::fs::read_to_string(logger.log_path()).unwrap();
+        assert!(text.contains("first session"));
+        assert!(text.contains("second session"));
+
+        let _ = std::fs::remove_file(logger.log_path());
+    }
+
+    #[test]
+    fn test_console_capture_is_bounded() {
+        let mut logger = Logger::new(&temp_base("console"));
+        logger.set_echo(false);
+        logger.write(0.0, LogLevel::Info, Subsystem::Render, "ignored");
+        assert!(logger.drain_console().is_empty());
+
+        logger.set_capture_console(true);
+        for i in 0..MAX_CONSOLE_LINES + 10 {
+            logger.write(0.0, LogLevel::Info, Subsystem::Render, &i.to_string());
+        }
+        let lines = logger.drain_console();
+        assert_eq!(lines.len(), MAX_CONSOLE_LINES);
+        assert!(lines[0].ends_with(" 10"));
+        assert!(logger.drain_console().is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+use crate::{log_info, log_warn};
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_BAN_FILE: &str = "bans.txt";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BanEntry {
+    pub addr: IpAddr,
+    pub mask_bits: u8,
+    pub expires: u64,
+}
+
+impl BanEntry {
+    pub fn parse(mask: &str, expires: u64) -> Result<Self, String> {
+        let (ip_str, bits_str) = match mask.split_once('/') {
+            Some((ip, bits)) => (ip, Some(bits)),
+            None => (mask, None),
+        };
+
+        let addr: IpAddr = ip_str
+            .parse()
+            .map_err(|_| format!("Invalid address '{}'", ip_str))?;
+        let max_bits = if addr.is_ipv4() { 32 } else { 128 };
+
+        let mask_bits = match bits_str {
+            Some(bits) => bits
+                .parse::<u8>()
+                .ok()
+                .filter(|b| *b <= max_bits)
+                .ok_or_else(|| format!("Invalid mask '/{}'", bits))?,
+            None => max_bits,
+        };
+
+        Ok(Self {
+            addr: network_address(addr, mask_bits),
+            mask_bits,
+            expires,
+        })
+    }
+
+    pub fn matches(&self, addr: &IpAddr) -> bool {
+        match (self.addr, addr) {
+            (IpAddr::V4(ban), IpAddr::V4(ip)) => {
+                prefix_matches(&ban.octets(), &ip.octets(), self.mask_bits)
+            }
+            (IpAddr::V6(ban), IpAddr::V6(ip)) => {
+                prefix_matches(&ban.octets(), &ip.octets(), self.mask_bits)
+            }
+            (IpAddr::V4(ban), IpAddr::V6(ip)) => match ip.to_ipv4_mapped() {
+                Some(ip) => prefix_matches(&ban.octets(), &ip.octets(), self.mask_bits),
+                None => false,
+            },
+            (IpAddr::V6(_), IpAddr::V4(_)) => false,
+        }
+    }
+
+    pub fn is_permanent(&self) -> bool {
+        self.expires == 0
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        !self.is_permanent() && now >= self.expires
+    }
+
+    pub fn mask_string(&self) -> String {
+        format!("{}/{}", self.addr, self.mask_bits)
+    }
+}
+
+/// `addr` with the host bits below the prefix cleared, so `10.1.2.3/24`
+/// and `10.1.2.0/24` name the same ban.
+fn network_address(addr: IpAddr, bits: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(ip) => {
+            let mask = u32::MAX.checked_shl(32 - bits as u32).unwrap_or(0);
+            IpAddr::V4((u32::from(ip) & mask).into())
+        }
+        IpAddr::V6(ip) => {
+            let mask = u128::MAX.checked_shl(128 - bits as u32).unwrap_or(0);
+            IpAddr::V6((u128::from(ip) & mask).into())
+        }
+    }
+}
+
+fn prefix_matches(a: &[u8], b: &[u8], bits: u8) -> bool {
+    let full_bytes = (bits / 8) as usize;
+    let rem_bits = bits % 8;
+
+    if a[..full_bytes] != b[..full_bytes] {
+        return false;
+    }
+
+    if rem_bits > 0 {
+        let mask = 0xFFu8 << (8 - rem_bits);
+        return (a[full_bytes] & mask) == (b[full_bytes] & mask);
+    }
+
+    true
+}
+
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn parse_duration(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (num, mult) = match s.chars().last() {
+        Some('s') => (&s[..s.len() - 1], 1),
+        Some('m') => (&s[..s.len() - 1], 60),
+        Some('h') => (&s[..s.len() - 1], 3600),
+        Some('d') => (&s[..s.len() - 1], 86400),
+        Some('w') => (&s[..s.len() - 1], 604800),
+        _ => (s, 60),
+    };
+
+    let n = num
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid duration '{}'", s))?;
+    n.checked_mul(mult)
+        .ok_or_else(|| format!("Duration '{}' is too long", s))
+}
+
+#[derive(Clone, Debug)]
+pub struct BanList {
+    pub entries: Vec<BanEntry>,
+    pub filter_ban: bool,
+    path: Option<String>,
+}
+
+impl BanList {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            filter_ban: true,
+            path: None,
+        }
+    }
+
+    pub fn load(path: &str) -> Self {
+        let mut list = Self::new();
+        list.path = Some(path.to_string());
+
+        if let Ok(content) = std::fs::read_to_string(path) {
+            let now = unix_time();
+            for line in content.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with("//") {
+                    continue;
+                }
+
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let expires = parts
+                    .get(1)
+                    .and_then(|e| e.parse::<u64>().ok())
+                    .unwrap_or(0);
+
+                match BanEntry::parse(parts[0], expires) {
+                    Ok(entry) if !entry.is_expired(now) => list.entries.push(entry),
+                    Ok(_) => {}
+                    Err(e) => log_warn!(Net, "Skipping ban entry '{}': {}", line, e),
+                }
+            }
+            log_info!(
+                Net,
+                "Loaded {} ban entries from {}",
+                list.entries.len(),
+                path
+            );
+        }
+
+        list
+    }
+
+    pub fn save(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(path) = &self.path {
+                let mut content = String::from("// address/mask expires(unix time, 0 = never)\n");
+                for entry in &self.entries {
+                    content.push_str(&format!("{} {}\n", entry.mask_string(), entry.expires));
+                }
+                if let Err(e) = std::fs::write(path, content) {
+                    log_warn!(Net, "Failed to write ban list {}: {}", path, e);
+                }
+            }
+        }
+    }
+
+    pub fn add(&mut self, mask: &str, duration_secs: Option<u64>) -> Result<BanEntry, String> {
+        let expires = duration_secs
+            .map(|d| unix_time().saturating_add(d))
+            .unwrap_or(0);
+        let entry = BanEntry::parse(mask, expires)?;
+
+        self.entries
+            .retain(|e| !(e.addr == entry.addr && e.mask_bits == entry.mask_bits));
+        self.entries.push(entry.clone());
+        self.save();
+        Ok(entry)
+    }
+
+    pub fn remove(&mut self, mask: &str) -> Result<BanEntry, String> {
+        let target = BanEntry::parse(mask, 0)?;
+        let idx = self
+            .entries
+            .iter()
+            .position(|e| e.addr == target.addr && e.mask_bits == target.mask_bits)
+            .ok_or_else(|| format!("No ban entry for {}", target.mask_string()))?;
+
+        let entry = self.entries.remove(idx);
+        self.save();
+        Ok(entry)
+    }
+
+    pub fn prune_expired(&mut self) {
+        let now = unix_time();
+        let before = self.entries.len();
+        self.entries.retain(|e| !e.is_expired(now));
+        if self.entries.len() != before {
+            self.save();
+        }
+    }
+
+    pub fn check(&self, addr: &IpAddr) -> Result<(), String> {
+        let now = unix_time();
+        let listed = self
+            .entries
+            .iter()
+            .any(|e| !e.is_expired(now) && e.matches(addr));
+
+        if self.filter_ban && listed {
+            Err("You are banned from this server".to_string())
+        } else if !self.filter_ban && !listed {
+            Err("This server is private".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for BanList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_matching() {
+        let entry = BanEntry::parse("192.168.1.0/24", 0).unwrap();
+        assert!(entry.matches(&"192.168.1.77".parse().unwrap()));
+        assert!(!entry.matches(&"192.168.2.1".parse().unwrap()));
+
+        let entry = BanEntry::parse("10.0.0.0/12", 0).unwrap();
+        assert!(entry.matches(&"10.15.3.4".parse().unwrap()));
+        assert!(!entry.matches(&"10.16.0.1".parse().unwrap()));
+
+        let entry = BanEntry::parse("127.0.0.1", 0).unwrap();
+        assert_eq!(entry.mask_bits, 32);
+        assert!(entry.matches(&"127.0.0.1".parse().unwrap()));
+        assert!(entry.matches(&"::ffff:127.0.0.1".parse().unwrap()));
+        assert!(!entry.matches(&"127.0.0.2".parse().unwrap()));
+
+        assert!(BanEntry::parse("1.2.3.4/33", 0).is_err());
+        assert!(BanEntry::parse("not-an-ip", 0).is_err());
+    }
+
+    #[test]
+    fn test_duration_parsing() {
+        assert_eq!(parse_duration("30").unwrap(), 1800);
+        assert_eq!(parse_duration("45s").unwrap(), 45);
+        assert_eq!(parse_duration("2h").unwrap(), 7200);
+        assert_eq!(parse_duration("1d").unwrap(), 86400);
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("99999999999999w").is_err());
+
+        let entry = BanList::new().add("1.2.3.4", Some(u64::MAX)).unwrap();
+        assert!(!entry.is_expired(unix_time()));
+    }
+
+    #[test]
+    fn test_filter_modes() {
+        let mut list = BanList::new();
+        list.add("10.0.0.0/8", None).unwrap();
+
+        let inside: IpAddr = "10.1.2.3".parse().unwrap();
+        let outside: IpAddr = "8.8.8.8".parse().unwrap();
+
+        assert!(list.check(&inside).is_err());
+        assert!(list.check(&outside).is_ok());
+
+        list.filter_ban = false;
+        assert!(list.check(&inside).is_ok());
+        assert!(list.check(&outside).is_err());
+    }
+
+    #[test]
+    fn test_expired_entries_ignored() {
+        let mut list = BanList::new();
+        list.entries
+            .push(BanEntry::parse("1.2.3.4", unix_time() - 10).unwrap());
+        assert!(list.check(&"1.2.3.4".parse().unwrap()).is_ok());
+
+        list.prune_expired();
+        assert!(list.entries.is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut list = BanList::new();
+        list.add("1.2.3.0/24", Some(60)).unwrap();
+        assert!(list.remove("1.2.3.4").is_err());
+        assert!(list.remove("1.2.3.0/24").is_ok());
+        assert!(list.entries.is_empty());
+
+        let entry = list.add("10.1.2.3/24", None).unwrap();
+        assert_eq!(entry.mask_string(), "10.1.2.0/24");
+        assert!(entry.matches(&"10.1.2.200".parse().unwrap()));
+        assert!(list.remove("10.1.2.0/24").is_ok());
+        assert!(list.entries.is_empty());
+
+        let entry = BanEntry::parse("2001:db8::1/32", 0).unwrap();
+        assert_eq!(entry.mask_string(), "2001:db8::/32");
+        assert_eq!(
+            BanEntry::parse("1.2.3.4/0", 0).unwrap().mask_string(),
+            "0.0.0.0/0"
+        );
+    }
+}
@@ -6,9 +6,12 @@ use super::protocol::{
     deserialize_message, serialize_message, NetAddr, NetChan, UdpNetworking, MAX_PACKETLEN,
 };
 use super::{NetMessage, NetworkConfig, PlayerState, ProjectileState};
+use crate::{log_debug, log_info, log_warn};
 use std::io;
 use std::net::SocketAddr;
 
+const SNAPSHOT_ACK_INTERVAL: f64 = 0.5;
+
 pub struct NetworkClient {
     config: NetworkConfig,
     connected: bool,
@@ -33,6 +36,8 @@ pub struct NetworkClient {
     extrapolated_snapshot: bool,
     ping_samples: [u32; 16],
     ping_index: usize,
+    last_received_tick: Option<u32>,
+    last_ack_sent: f64,
 }
 
 #[derive(Clone, Debug)]
@@ -68,6 +73,8 @@ impl NetworkClient {
             extrapolated_snapshot: false,
             ping_samples: [0; 16],
             ping_index: 0,
+            last_received_tick: None,
+            last_ack_sent: 0.0,
         }
     }
 
@@ -98,11 +105,7 @@ impl NetworkClient {
             .send_to(&send_buf, &server_addr)
             .map_err(|e| format!("Failed to send connect request: {}", e))?;
 
-        println!(
-            "[{}] Connecting to {}...",
-            super::get_absolute_time(),
-            server_address
-        );
+        log_info!(Net, "Connecting to {}...", server_address);
         Ok(())
     }
 
@@ -123,7 +126,7 @@ impl NetworkClient {
         self.player_id = None;
         self.net_chan = None;
         self.server_addr = None;
-        println!("[{}] Disconnected from server", super::get_absolute_time());
+        log_info!(Net, "Disconnected from server");
     }
 
     pub fn update(&mut self) -> Vec<NetMessage> {
@@ -143,12 +146,13 @@ impl NetworkClient {
                     break;
                 }
                 Err(e) => {
-                    eprintln!("[{}] Client recv error: {}", super::get_absolute_time(), e);
+                    log_warn!(Net, "Client recv error: {}", e);
                     break;
                 }
             }
         }
 
+        self.send_snapshot_ack();
         self.send_heartbeat();
 
         messages
@@ -179,17 +183,9 @@ impl NetworkClient {
                         ));
                         self.player_id = Some(player_id);
                         self.connected = true;
-                        println!(
-                            "[{}] Connected to server as player {}",
-                            super::get_absolute_time(),
-                            player_id
-                        );
+                        log_info!(Net, "Connected to server as player {}", player_id);
                     } else {
-                        println!(
-                            "[{}] Connection rejected: {}",
-                            super::get_absolute_time(),
-                            reason
-                        );
+                        log_info!(Net, "Connection rejected: {}", reason);
                     }
                     return Some(vec![NetMessage::ConnectResponse {
                         player_id,
@@ -212,6 +208,7 @@ impl NetworkClient {
                         } => {
                             self.update_server_time(*tick);
                             self.net_stats.record_snapshot(*tick);
+                            self.last_received_tick = Some(*tick);
 
                             let alive_players: Vec<_> =
                                 players.iter().filter(|p| !p.is_dead).cloned().collect();
@@ -250,6 +247,7 @@ impl NetworkClient {
 
                             self.update_server_time(*tick);
                             self.net_stats.record_snapshot(*tick);
+                            self.last_received_tick = Some(*tick);
 
                             let base_snapshot = self.received_snapshots.get(base_message_num);
 
@@ -292,8 +290,11 @@ impl NetworkClient {
                                     self.last_snapshot = Some(snapshot);
                                 }
                             } else {
-                                println!("[{}] *** [CLIENT] No baseline for message {}, waiting for full snapshot ***", 
-                                    super::get_absolute_time(), base_message_num);
+                                log_warn!(
+                                    Net,
+                                    "No baseline for message {}, waiting for full snapshot",
+                                    base_message_num
+                                );
                             }
                         }
                         _ => {}
@@ -306,6 +307,23 @@ impl NetworkClient {
         None
     }
 
+    fn send_snapshot_ack(&mut self) {
+        let current_time = super::get_network_time();
+        if current_time - self.last_ack_sent < SNAPSHOT_ACK_INTERVAL {
+            return;
+        }
+
+        if let (Some(player_id), Some(tick)) = (self.player_id, self.last_received_tick.take()) {
+            let msg = NetMessage::SnapshotAck {
+                player_id,
+                acknowledged_tick: tick,
+            };
+            if self.send_message(msg).is_ok() {
+                self.last_ack_sent = current_time;
+            }
+        }
+    }
+
     fn send_heartbeat(&mut self) {
         let current_time = super::get_network_time();
         if current_time - self.last_heartbeat_sent > 5.0 {
@@ -383,8 +401,9 @@ impl NetworkClient {
                 static mut LAST_SEND_PRINT: f64 = 0.0;
                 unsafe {
                     if super::get_network_time() - LAST_SEND_PRINT > 2.0 {
-                        println!(
-                            "[INPUT] Sending {} commands, latest right={:.1} buttons={}",
+                        log_debug!(
+                            Net,
+                            "Sending {} commands, latest right={:.1} buttons={}",
                             commands_to_send.len(),
                             commands_to_send.last().map(|c| c.move_right).unwrap_or(0.0),
                             commands_to_send.last().map(|c| c.buttons).unwrap_or(0)
@@ -575,6 +594,7 @@ impl NetworkClient {
     pub fn predict_local_player(
         &mut self,
         map: &crate::game::map::Map,
+        physics: &crate::game::physics::PhysicsProfile,
     ) -> Option<PredictedPlayerState> {
         let snapshot = self.last_snapshot.as_ref()?;
         let local_player_id = self.player_id?;
@@ -597,16 +617,24 @@ impl NetworkClient {
             &base_state_owned,
             &recent_cmds,
             map,
+            physics,
             current_ms,
         );
 
         static mut LAST_PRED_PRINT: f64 = 0.0;
         unsafe {
             if super::get_network_time() - LAST_PRED_PRINT > 2.0 {
-                println!("[PREDICT] base_cmd={} current={} cmds={} base_pos=({:.1},{:.1}) pred_pos=({:.1},{:.1})",
-                    base_state_owned.command_time, current_ms, recent_cmds.len(),
-                    base_state_owned.position.0, base_state_owned.position.1,
-                    predicted.x, predicted.y);
+                log_debug!(
+                    Net,
+                    "Predict base_cmd={} current={} cmds={} base_pos=({:.1},{:.1}) pred_pos=({:.1},{:.1})",
+                    base_state_owned.command_time,
+                    current_ms,
+                    recent_cmds.len(),
+                    base_state_owned.position.0,
+                    base_state_owned.position.1,
+                    predicted.x,
+                    predicted.y
+                );
                 LAST_PRED_PRINT = super::get_network_time();
             }
         }
@@ -618,7 +646,7 @@ impl NetworkClient {
             static mut LAST_ERR_PRINT: f64 = 0.0;
             unsafe {
                 if super::get_network_time() - LAST_ERR_PRINT > 2.0 {
-                    println!("[PREDICT] ERROR: {:.1}px", err.magnitude);
+                    log_debug!(Net, "Prediction error: {:.1}px", err.magnitude);
                     LAST_ERR_PRINT = super::get_network_time();
                 }
             }
@@ -683,8 +711,20 @@ impl NetworkClient {
                 if let Some(deaths) = delta.deaths {
                     player.deaths = deaths;
                 }
-                if let Some(quad) = delta.powerup_quad {
-                    player.powerup_quad = quad;
+                if let Some(team) = delta.team {
+                    player.team = team;
+                }
+                if let Some(powerups) = delta.powerups {
+                    player.powerups = powerups;
+                }
+                if let Some(holdable) = delta.holdable {
+                    player.holdable = holdable;
+                }
+                if let Some(grapple) = delta.grapple {
+                    player.grapple = grapple;
+                }
+                if let Some(jump_time) = delta.jump_time {
+                    player.jump_time = jump_time;
                 }
                 if let Some(on_ground) = delta.on_ground {
                     player.on_ground = on_ground;
@@ -698,6 +738,12 @@ impl NetworkClient {
                 if let Some(dead) = delta.is_dead {
                     player.is_dead = dead;
                 }
+                if let Some(frozen) = delta.is_frozen {
+                    player.is_frozen = frozen;
+                }
+                if let Some(progress) = delta.thaw_progress {
+                    player.thaw_progress = progress;
+                }
             } else {
                 let new_player = PlayerState {
                     player_id: delta.player_id,
@@ -708,14 +754,20 @@ impl NetworkClient {
                     armor: delta.armor.unwrap_or(0),
                     weapon: delta.weapon.unwrap_or(0),
                     command_time: delta.command_time.unwrap_or(0),
-                    ammo: delta.ammo.unwrap_or([0; 10]),
+                    ammo: delta.ammo.unwrap_or([0; crate::game::weapon::WEAPON_COUNT]),
                     frags: delta.frags.unwrap_or(0),
                     deaths: delta.deaths.unwrap_or(0),
-                    powerup_quad: delta.powerup_quad.unwrap_or(0),
+                    team: delta.team.unwrap_or(0),
+                    powerups: delta.powerups.unwrap_or_default(),
+                    holdable: delta.holdable.flatten(),
+                    grapple: delta.grapple.unwrap_or_default(),
+                    jump_time: delta.jump_time.unwrap_or(0.0),
                     on_ground: delta.on_ground.unwrap_or(true),
                     is_crouching: delta.is_crouching.unwrap_or(false),
                     is_attacking: delta.is_attacking.unwrap_or(false),
                     is_dead: delta.is_dead.unwrap_or(false),
+                    is_frozen: delta.is_frozen.unwrap_or(false),
+                    thaw_progress: delta.thaw_progress.unwrap_or(0.0),
                 };
                 // println!("[{}] *** [CLIENT] Delta contains NEW PLAYER {} - adding! ***",
                 //     super::get_absolute_time(), delta.player_id);
@@ -730,8 +782,9 @@ impl NetworkClient {
         projectiles.extend(new_projectiles.iter().cloned());
 
         if !removed_projectiles.is_empty() {
-            println!(
-                "[CLIENT] Removed {} projectiles: {:?}, before={} after={}",
+            log_debug!(
+                Net,
+                "Removed {} projectiles: {:?}, before={} after={}",
                 removed_projectiles.len(),
                 removed_projectiles,
                 before_count,
@@ -1,7 +1,10 @@
 use super::prediction::UserCommand;
 use super::PlayerState;
 use crate::game::bg_pmove::{pmove, PmoveCmd, PmoveState};
+use crate::game::grapple::Grapple;
 use crate::game::map::Map;
+use crate::game::physics::PhysicsProfile;
+use crate::game::usercmd::BUTTON_HOOK;
 
 #[derive(Clone, Debug)]
 pub struct PredictedPlayerState {
@@ -14,6 +17,8 @@ pub struct PredictedPlayerState {
     pub command_time: u32,
     pub hit_jumppad: bool,
     pub landed: bool,
+    pub grapple: Grapple,
+    pub jump_time: f32,
 }
 
 #[derive(Clone, Debug)]
@@ -46,6 +51,7 @@ impl ClientPrediction {
         base_snapshot: &PlayerState,
         commands: &[UserCommand],
         map: &Map,
+        physics: &PhysicsProfile,
         current_time: u32,
     ) -> PredictedPlayerState {
         let mut pmove_state = PmoveState {
@@ -54,18 +60,23 @@ impl ClientPrediction {
             vel_x: base_snapshot.velocity.0,
             vel_y: base_snapshot.velocity.1,
             was_in_air: !base_snapshot.on_ground,
+            jump_time: base_snapshot.jump_time,
+            fall_speed: 0.0,
         };
 
         let mut last_angle = base_snapshot.angle;
         let mut last_cmd_time = base_snapshot.command_time;
         let mut hit_jumppad = false;
         let mut landed = false;
+        let mut grapple = base_snapshot.grapple;
 
         let mut last_input = PmoveCmd {
             move_right: 0.0,
             jump: false,
             crouch: false,
             haste_active: false,
+            flight_active: false,
+            grapple: None,
         };
         for cmd in commands {
             if cmd.server_time <= base_snapshot.command_time {
@@ -76,16 +87,26 @@ impl ClientPrediction {
                 break;
             }
 
+            let dt_ms = (cmd.server_time.saturating_sub(last_cmd_time)).clamp(1, 100) as f32;
+            let dt = dt_ms * 0.001;
+
+            grapple.update(
+                (cmd.buttons & BUTTON_HOOK as u32) != 0,
+                cmd.angles,
+                pmove_state.x,
+                pmove_state.y,
+                dt,
+                map,
+            );
             let pmove_cmd = PmoveCmd {
                 move_right: cmd.move_right,
                 jump: (cmd.buttons & 2) != 0,
                 crouch: (cmd.buttons & 4) != 0,
-                haste_active: false,
+                haste_active: base_snapshot.powerups.haste > 0,
+                flight_active: base_snapshot.powerups.flight > 0,
+                grapple: grapple.anchor(),
             };
-
-            let dt_ms = (cmd.server_time.saturating_sub(last_cmd_time)).clamp(1, 100) as f32;
-            let dt = dt_ms * 0.001;
-            let result = pmove(&pmove_state, &pmove_cmd, dt, map);
+            let result = pmove(&pmove_state, &pmove_cmd, dt, map, physics);
 
             if result.hit_jumppad {
                 hit_jumppad = true;
@@ -100,6 +121,8 @@ impl ClientPrediction {
                 vel_x: result.new_vel_x,
                 vel_y: result.new_vel_y,
                 was_in_air: result.new_was_in_air,
+                jump_time: result.new_jump_time,
+                fall_speed: result.new_fall_speed,
             };
 
             last_angle = cmd.angles;
@@ -110,7 +133,7 @@ impl ClientPrediction {
         if last_cmd_time < current_time {
             let dt_ms = (current_time - last_cmd_time).clamp(1, 100) as f32;
             let dt = dt_ms * 0.001;
-            let result = pmove(&pmove_state, &last_input, dt, map);
+            let result = pmove(&pmove_state, &last_input, dt, map, physics);
 
             if result.hit_jumppad {
                 hit_jumppad = true;
@@ -125,6 +148,8 @@ impl ClientPrediction {
                 vel_x: result.new_vel_x,
                 vel_y: result.new_vel_y,
                 was_in_air: result.new_was_in_air,
+                jump_time: result.new_jump_time,
+                fall_speed: result.new_fall_speed,
             };
         }
 
@@ -138,6 +163,8 @@ impl ClientPrediction {
             command_time: last_cmd_time,
             hit_jumppad,
             landed,
+            grapple,
+            jump_time: pmove_state.jump_time,
         };
 
         self.predicted_state = Some(predicted.clone());
@@ -241,7 +268,13 @@ mod tests {
             ..Default::default()
         };
 
-        let predicted = prediction.predict_player_movement(&base_state, &[], &map, 1000);
+        let predicted = prediction.predict_player_movement(
+            &base_state,
+            &[],
+            &map,
+            &PhysicsProfile::default(),
+            1000,
+        );
 
         assert!((predicted.x - 100.0).abs() < 0.1);
         assert!((predicted.y - 100.0).abs() < 0.1);
@@ -272,7 +305,13 @@ mod tests {
             });
         }
 
-        let predicted = prediction.predict_player_movement(&base_state, &commands, &map, 160);
+        let predicted = prediction.predict_player_movement(
+            &base_state,
+            &commands,
+            &map,
+            &PhysicsProfile::default(),
+            160,
+        );
 
         assert!(predicted.x > 100.0, "Player should have moved right");
     }
@@ -291,6 +330,8 @@ mod tests {
             command_time: 1000,
             hit_jumppad: false,
             landed: false,
+            grapple: Grapple::Idle,
+            jump_time: 0.0,
         };
 
         let server_state = PlayerState {
@@ -1,3 +1,4 @@
+use crate::log_info;
 use std::collections::VecDeque;
 
 pub struct NetDebug {
@@ -102,9 +103,9 @@ impl NetDebug {
 
     pub fn log_packet_send(&mut self, sequence: u32, size: usize, message_type: &str) {
         if self.show_packets {
-            eprintln!(
-                "[{:.3}] [NET] send s={} size={} type={}",
-                super::get_network_time(),
+            log_info!(
+                Net,
+                "send s={} size={} type={}",
                 sequence,
                 size,
                 message_type
@@ -127,9 +128,9 @@ impl NetDebug {
 
     pub fn log_packet_recv(&mut self, sequence: u32, size: usize, message_type: &str) {
         if self.show_packets {
-            eprintln!(
-                "[{:.3}] [NET] recv s={} size={} type={}",
-                super::get_network_time(),
+            log_info!(
+                Net,
+                "recv s={} size={} type={}",
                 sequence,
                 size,
                 message_type
@@ -152,9 +153,9 @@ impl NetDebug {
 
     pub fn log_out_of_order(&mut self, sequence: u32, expected: u32) {
         if self.show_drop {
-            eprintln!(
-                "[{:.3}] [NET] Out of order packet {} (expected {})",
-                super::get_network_time(),
+            log_info!(
+                Net,
+                "Out of order packet {} (expected {})",
                 sequence,
                 expected
             );
@@ -174,12 +175,7 @@ impl NetDebug {
 
     pub fn log_dropped_packets(&mut self, count: u32, at_sequence: u32) {
         if self.show_drop {
-            eprintln!(
-                "[{:.3}] [NET] Dropped {} packets at sequence {}",
-                super::get_network_time(),
-                count,
-                at_sequence
-            );
+            log_info!(Net, "Dropped {} packets at sequence {}", count, at_sequence);
         }
 
         let error = SyncError {
@@ -204,9 +200,15 @@ impl NetDebug {
             ((local_pos.0 - server_pos.0).powi(2) + (local_pos.1 - server_pos.1).powi(2)).sqrt();
 
         if self.show_sync {
-            eprintln!(
-                "[{:.3}] [SYNC] Position mismatch p{} local=({:.1},{:.1}) server=({:.1},{:.1}) dist={:.1}",
-                super::get_network_time(), player_id, local_pos.0, local_pos.1, server_pos.0, server_pos.1, distance
+            log_info!(
+                Net,
+                "sync: Position mismatch p{} local=({:.1},{:.1}) server=({:.1},{:.1}) dist={:.1}",
+                player_id,
+                local_pos.0,
+                local_pos.1,
+                server_pos.0,
+                server_pos.1,
+                distance
             );
         }
 
@@ -237,11 +239,19 @@ impl NetDebug {
         input_sequence: u32,
     ) {
         if self.show_physics {
-            eprintln!(
-                "[{:.3}] [PHYS] {:?} p{} seq={} pos=({:.1},{:.1}) vel=({:.2},{:.2}) ground={} coll_x={} coll_y={}",
-                super::get_network_time(), location, player_id, input_sequence, 
-                position.0, position.1, velocity.0, velocity.1,
-                on_ground, collided_x, collided_y
+            log_info!(
+                Net,
+                "phys: {:?} p{} seq={} pos=({:.1},{:.1}) vel=({:.2},{:.2}) ground={} coll_x={} coll_y={}",
+                location,
+                player_id,
+                input_sequence,
+                position.0,
+                position.1,
+                velocity.0,
+                velocity.1,
+                on_ground,
+                collided_x,
+                collided_y
             );
         }
 
@@ -275,9 +285,16 @@ impl NetDebug {
         );
 
         if self.show_sync && (vel_diff.0 > 0.5 || vel_diff.1 > 0.5) {
-            eprintln!(
-                "[{:.3}] [SYNC] Velocity mismatch p{} local=({:.2},{:.2}) server=({:.2},{:.2}) diff=({:.2},{:.2})",
-                super::get_network_time(), player_id, local_vel.0, local_vel.1, server_vel.0, server_vel.1, vel_diff.0, vel_diff.1
+            log_info!(
+                Net,
+                "sync: Velocity mismatch p{} local=({:.2},{:.2}) server=({:.2},{:.2}) diff=({:.2},{:.2})",
+                player_id,
+                local_vel.0,
+                local_vel.1,
+                server_vel.0,
+                server_vel.1,
+                vel_diff.0,
+                vel_diff.1
             );
         }
 
@@ -304,9 +321,9 @@ impl NetDebug {
         normal: (f32, f32),
     ) {
         if self.show_collision {
-            eprintln!(
-                "[{:.3}] [COLL] p{} {} at ({:.1},{:.1}) normal=({:.2},{:.2})",
-                super::get_network_time(),
+            log_info!(
+                Net,
+                "coll: p{} {} at ({:.1},{:.1}) normal=({:.2},{:.2})",
                 player_id,
                 collision_type,
                 position.0,
@@ -329,13 +346,19 @@ impl NetDebug {
         let impulse_magnitude = (delta_vel.0 * delta_vel.0 + delta_vel.1 * delta_vel.1).sqrt();
 
         if self.show_physics || impulse_magnitude > 5.0 {
-            eprintln!(
-                "[{:.3}] [IMPULSE] p{} {} at ({:.1},{:.1}) vel ({:.2},{:.2}) -> ({:.2},{:.2}) delta=({:.2},{:.2}) mag={:.2}",
-                super::get_network_time(), player_id, impulse_type,
-                position.0, position.1,
-                old_vel.0, old_vel.1,
-                new_vel.0, new_vel.1,
-                delta_vel.0, delta_vel.1,
+            log_info!(
+                Net,
+                "impulse: p{} {} at ({:.1},{:.1}) vel ({:.2},{:.2}) -> ({:.2},{:.2}) delta=({:.2},{:.2}) mag={:.2}",
+                player_id,
+                impulse_type,
+                position.0,
+                position.1,
+                old_vel.0,
+                old_vel.1,
+                new_vel.0,
+                new_vel.1,
+                delta_vel.0,
+                delta_vel.1,
                 impulse_magnitude
             );
         }
@@ -0,0 +1,445 @@
+use super::PlayerInputCmd;
+use crate::game::usercmd::{
+    BUTTON_ATTACK, BUTTON_CROUCH, BUTTON_HOOK, BUTTON_JUMP, BUTTON_USE_ITEM,
+};
+
+pub const VALID_BUTTONS_MASK: u32 =
+    (BUTTON_ATTACK | BUTTON_JUMP | BUTTON_CROUCH | BUTTON_USE_ITEM | BUTTON_HOOK) as u32;
+pub const MAX_TIME_BUDGET_MS: f64 = 250.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputRejection {
+    NonFinite,
+    Duplicate,
+    Old,
+    TooFast,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InputValidationStats {
+    pub accepted: u32,
+    pub clamped: u32,
+    pub rejected_non_finite: u32,
+    pub dropped_duplicate: u32,
+    pub dropped_old: u32,
+    pub throttled: u32,
+}
+
+impl InputValidationStats {
+    pub fn total_rejected(&self) -> u32 {
+        self.rejected_non_finite + self.dropped_duplicate + self.dropped_old + self.throttled
+    }
+
+    pub fn get_summary(&self) -> String {
+        format!(
+            "accepted={} clamped={} nonfinite={} dup={} old={} throttled={}",
+            self.accepted,
+            self.clamped,
+            self.rejected_non_finite,
+            self.dropped_duplicate,
+            self.dropped_old,
+            self.throttled
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct InputValidator {
+    last_sequence: Option<u32>,
+    last_cmd_time: Option<u32>,
+    time_budget_ms: f64,
+    last_real_time: f64,
+    pub stats: InputValidationStats,
+    logged_stats: InputValidationStats,
+}
+
+impl InputValidator {
+    pub fn new() -> Self {
+        Self {
+            last_sequence: None,
+            last_cmd_time: None,
+            time_budget_ms: MAX_TIME_BUDGET_MS,
+            last_real_time: 0.0,
+            stats: InputValidationStats::default(),
+            logged_stats: InputValidationStats::default(),
+        }
+    }
+
+    pub fn validate_sequence(&mut self, input_sequence: u32) -> Result<(), InputRejection> {
+        if let Some(last) = self.last_sequence {
+            if input_sequence == last {
+                self.stats.dropped_duplicate += 1;
+                return Err(InputRejection::Duplicate);
+            }
+            if input_sequence < last {
+                self.stats.dropped_old += 1;
+                return Err(InputRejection::Old);
+            }
+        }
+        self.last_sequence = Some(input_sequence);
+        Ok(())
+    }
+
+    pub fn validate_command(
+        &mut self,
+        cmd: &PlayerInputCmd,
+        now: f64,
+    ) -> Result<PlayerInputCmd, InputRejection> {
+        if !cmd.move_forward.is_finite() || !cmd.move_right.is_finite() || !cmd.angle.is_finite() {
+            self.stats.rejected_non_finite += 1;
+            return Err(InputRejection::NonFinite);
+        }
+
+        if let Some(last_time) = self.last_cmd_time {
+            if cmd.server_time == last_time {
+                self.stats.dropped_duplicate += 1;
+                return Err(InputRejection::Duplicate);
+            }
+            if cmd.server_time < last_time {
+                self.stats.dropped_old += 1;
+                return Err(InputRejection::Old);
+            }
+        }
+
+        self.refill_budget(now);
+
+        if let Some(last_time) = self.last_cmd_time {
+            // A stall or clock resync can leave a gap longer than the whole
+            // budget; charge at most a full budget so the player recovers.
+            let delta = ((cmd.server_time - last_time) as f64).min(MAX_TIME_BUDGET_MS);
+            if delta > self.time_budget_ms {
+                self.stats.throttled += 1;
+                return Err(InputRejection::TooFast);
+            }
+            self.time_budget_ms -= delta;
+        }
+
+        self.last_cmd_time = Some(cmd.server_time);
+        self.stats.accepted += 1;
+
+        let sanitized = Self::sanitize(cmd);
+        if sanitized.move_forward != cmd.move_forward
+            || sanitized.move_right != cmd.move_right
+            || sanitized.angle != cmd.angle
+            || sanitized.buttons != cmd.buttons
+        {
+            self.stats.clamped += 1;
+        }
+
+        Ok(sanitized)
+    }
+
+    /// Checks a single `PlayerInput` message: its sequence number, then the command.
+    pub fn accept_input(
+        &mut self,
+        input_sequence: u32,
+        cmd: &PlayerInputCmd,
+        now: f64,
+    ) -> Option<PlayerInputCmd> {
+        self.validate_sequence(input_sequence).ok()?;
+        self.validate_command(cmd, now).ok()
+    }
+
+    /// Commands from a `PlayerInputBatch` that pass validation and have not
+    /// been executed yet, oldest first.
+    ///
+    /// Clients resend their last few commands in every batch, so commands at
+    /// or below the last accepted time are skipped without counting them as
+    /// long as the batch is in order. Only commands that go backwards within
+    /// the batch are counted as duplicate or old.
+    pub fn accept_batch(
+        &mut self,
+        commands: &[PlayerInputCmd],
+        last_executed_time: u32,
+        now: f64,
+    ) -> Vec<PlayerInputCmd> {
+        let mut accepted = Vec::new();
+        let mut previous: Option<u32> = None;
+        for cmd in commands {
+            let in_order = previous.is_none_or(|time| cmd.server_time > time);
+            previous = Some(cmd.server_time);
+            let resend = self
+                .last_cmd_time
+                .is_some_and(|last| cmd.server_time <= last);
+            if in_order && resend {
+                continue;
+            }
+            if let Ok(cmd) = self.validate_command(cmd, now) {
+                if cmd.server_time > last_executed_time {
+                    accepted.push(cmd);
+                }
+            }
+        }
+        accepted.sort_by_key(|cmd| cmd.server_time);
+        accepted
+    }
+
+    pub fn sanitize(cmd: &PlayerInputCmd) -> PlayerInputCmd {
+        let mut angle = cmd.angle;
+        if angle.abs() > std::f32::consts::PI {
+            angle = angle.rem_euclid(std::f32::consts::TAU);
+            if angle > std::f32::consts::PI {
+                angle -= std::f32::consts::TAU;
+            }
+        }
+
+        PlayerInputCmd {
+            move_forward: cmd.move_forward.clamp(-1.0, 1.0),
+            move_right: cmd.move_right.clamp(-1.0, 1.0),
+            angle,
+            buttons: cmd.buttons & VALID_BUTTONS_MASK,
+            server_time: cmd.server_time,
+        }
+    }
+
+    pub fn last_cmd_time(&self) -> Option<u32> {
+        self.last_cmd_time
+    }
+
+    pub fn take_unlogged_stats(&mut self) -> Option<InputValidationStats> {
+        if self.stats.total_rejected() == self.logged_stats.total_rejected()
+            && self.stats.clamped == self.logged_stats.clamped
+        {
+            return None;
+        }
+        self.logged_stats = self.stats;
+        Some(self.stats)
+    }
+
+    fn refill_budget(&mut self, now: f64) {
+        if self.last_real_time > 0.0 && now > self.last_real_time {
+            self.time_budget_ms += (now - self.last_real_time) * 1000.0;
+        }
+        if self.time_budget_ms > MAX_TIME_BUDGET_MS {
+            self.time_budget_ms = MAX_TIME_BUDGET_MS;
+        }
+        self.last_real_time = now;
+    }
+}
+
+impl Default for InputValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(server_time: u32) -> PlayerInputCmd {
+        PlayerInputCmd {
+            move_forward: 0.0,
+            move_right: 1.0,
+            angle: 0.0,
+            buttons: 0,
+            server_time,
+        }
+    }
+
+    #[test]
+    fn test_clamps_axes_and_buttons() {
+        let mut validator = InputValidator::new();
+        let forged = PlayerInputCmd {
+            move_forward: -40.0,
+            move_right: 1000.0,
+            angle: 3.0 * std::f32::consts::PI,
+            buttons: 0xFFFF_FFFF,
+            server_time: 100,
+        };
+
+        let result = validator.validate_command(&forged, 1.0).unwrap();
+
+        assert_eq!(result.move_forward, -1.0);
+        assert_eq!(result.move_right, 1.0);
+        assert!(result.angle.abs() <= std::f32::consts::PI + 0.0001);
+        assert_eq!(result.buttons, VALID_BUTTONS_MASK);
+        assert_eq!(validator.stats.clamped, 1);
+    }
+
+    #[test]
+    fn test_rejects_non_finite() {
+        let mut validator = InputValidator::new();
+        let mut forged = cmd(100);
+        forged.move_right = f32::NAN;
+        assert_eq!(
+            validator.validate_command(&forged, 1.0).err(),
+            Some(InputRejection::NonFinite)
+        );
+
+        forged.move_right = 0.0;
+        forged.angle = f32::INFINITY;
+        assert_eq!(
+            validator.validate_command(&forged, 1.0).err(),
+            Some(InputRejection::NonFinite)
+        );
+        assert_eq!(validator.stats.rejected_non_finite, 2);
+        assert_eq!(validator.last_cmd_time(), None);
+    }
+
+    #[test]
+    fn test_drops_duplicate_and_old() {
+        let mut validator = InputValidator::new();
+        assert!(validator.validate_command(&cmd(100), 1.0).is_ok());
+        assert_eq!(
+            validator.validate_command(&cmd(100), 1.0).err(),
+            Some(InputRejection::Duplicate)
+        );
+        assert_eq!(
+            validator.validate_command(&cmd(50), 1.0).err(),
+            Some(InputRejection::Old)
+        );
+        assert_eq!(validator.stats.dropped_duplicate, 1);
+        assert_eq!(validator.stats.dropped_old, 1);
+
+        assert!(validator.validate_sequence(5).is_ok());
+        assert_eq!(
+            validator.validate_sequence(5),
+            Err(InputRejection::Duplicate)
+        );
+        assert_eq!(validator.validate_sequence(4), Err(InputRejection::Old));
+    }
+
+    #[test]
+    fn test_throttles_command_time_faster_than_real_time() {
+        let mut validator = InputValidator::new();
+        let mut now = 1.0;
+        let mut server_time = 1000;
+        assert!(validator.validate_command(&cmd(server_time), now).is_ok());
+
+        let mut throttled = 0;
+        for _ in 0..120 {
+            now += 1.0 / 60.0;
+            server_time += 50;
+            if validator.validate_command(&cmd(server_time), now).is_err() {
+                throttled += 1;
+            }
+        }
+
+        assert!(throttled > 60, "only {} commands throttled", throttled);
+        assert_eq!(validator.stats.throttled, throttled);
+    }
+
+    #[test]
+    fn test_real_time_rate_is_accepted() {
+        let mut validator = InputValidator::new();
+        let mut now = 1.0;
+        let mut server_time = 1000;
+
+        for _ in 0..600 {
+            now += 1.0 / 60.0;
+            server_time += 16;
+            assert!(validator.validate_command(&cmd(server_time), now).is_ok());
+        }
+
+        assert_eq!(validator.stats.total_rejected(), 0);
+    }
+
+    #[test]
+    fn test_accept_input_checks_sequence_then_command() {
+        let mut validator = InputValidator::new();
+        let forged = PlayerInputCmd {
+            move_right: 500.0,
+            buttons: 0xFFFF_FFFF,
+            ..cmd(1000)
+        };
+
+        let accepted = validator.accept_input(1, &forged, 1.0).unwrap();
+        assert_eq!(accepted.move_right, 1.0);
+        assert_eq!(accepted.buttons, VALID_BUTTONS_MASK);
+
+        assert!(validator.accept_input(1, &cmd(1016), 1.0).is_none());
+        assert_eq!(validator.last_cmd_time(), Some(1000));
+
+        let nan = PlayerInputCmd {
+            move_right: f32::NAN,
+            ..cmd(1016)
+        };
+        assert!(validator.accept_input(2, &nan, 1.0).is_none());
+        assert!(validator.accept_input(3, &cmd(1016), 1.0).is_some());
+
+        assert_eq!(validator.stats.accepted, 2);
+        assert_eq!(validator.stats.clamped, 1);
+        assert_eq!(validator.stats.dropped_duplicate, 1);
+        assert_eq!(validator.stats.rejected_non_finite, 1);
+    }
+
+    #[test]
+    fn test_accept_batch_drops_forged_and_executed_commands() {
+        let mut validator = InputValidator::new();
+        assert_eq!(validator.accept_batch(&[cmd(2000)], 0, 1.0).len(), 1);
+
+        let replayed = [cmd(2000), cmd(2000), cmd(1500), cmd(10)];
+        assert!(validator.accept_batch(&replayed, 0, 1.0).is_empty());
+        assert_eq!(validator.stats.dropped_duplicate, 1);
+        assert_eq!(validator.stats.dropped_old, 2);
+
+        let nan = PlayerInputCmd {
+            angle: f32::INFINITY,
+            ..cmd(2016)
+        };
+        let accepted = validator.accept_batch(&[nan, cmd(2032), cmd(2048)], 2040, 1.0);
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(accepted[0].server_time, 2048);
+        assert_eq!(validator.stats.rejected_non_finite, 1);
+    }
+
+    #[test]
+    fn test_accept_batch_skips_resent_commands_silently() {
+        let mut validator = InputValidator::new();
+        let mut now = 1.0;
+        let mut window: Vec<PlayerInputCmd> = Vec::new();
+
+        for i in 0..30 {
+            now += 1.0 / 60.0;
+            window.push(cmd(1000 + i * 16));
+            if window.len() > 4 {
+                window.remove(0);
+            }
+            let accepted = validator.accept_batch(&window, 0, now);
+            assert_eq!(accepted.len(), 1);
+            assert_eq!(accepted[0].server_time, 1000 + i * 16);
+        }
+
+        assert_eq!(validator.stats.accepted, 30);
+        assert_eq!(validator.stats.total_rejected(), 0);
+    }
+
+    #[test]
+    fn test_recovers_after_stall_and_resync() {
+        let mut validator = InputValidator::new();
+        assert!(validator.validate_command(&cmd(1000), 1.0).is_ok());
+
+        assert!(validator.validate_command(&cmd(1600), 1.6).is_ok());
+
+        let mut now = 1.6;
+        let mut server_time = 3600;
+        let mut recovered = false;
+        for _ in 0..30 {
+            now += 1.0 / 60.0;
+            server_time += 16;
+            if validator.validate_command(&cmd(server_time), now).is_ok() {
+                recovered = true;
+                break;
+            }
+        }
+        assert!(recovered, "commands still throttled after a clock jump");
+
+        for _ in 0..60 {
+            now += 1.0 / 60.0;
+            server_time += 16;
+            assert!(validator.validate_command(&cmd(server_time), now).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_accept_batch_throttles_speed_hack() {
+        let mut validator = InputValidator::new();
+        let commands: Vec<PlayerInputCmd> = (0..60).map(|i| cmd(1000 + i * 50)).collect();
+
+        let accepted = validator.accept_batch(&commands, 0, 1.0);
+
+        assert!(accepted.len() < 10, "{} commands accepted", accepted.len());
+        assert!(validator.stats.throttled > 50);
+    }
+}
@@ -1,4 +1,5 @@
 use super::{PlayerState, ProjectileState};
+use crate::log_debug;
 use std::collections::VecDeque;
 
 const MAX_SNAPSHOTS: usize = 16;
@@ -86,8 +87,15 @@ impl SnapshotBuffer {
             static mut LAST_EXTRAP_PRINT: f64 = 0.0;
             unsafe {
                 if super::get_network_time() - LAST_EXTRAP_PRINT > 2.0 {
-                    println!("[INTERP] EXTRAPOLATING player {} render={:.3} latest={:.3} ahead={:.0}ms snaps={}", 
-                        player_id, render_time, latest_snap.timestamp, time_ahead * 1000.0, self.snapshots.len());
+                    log_debug!(
+                        Net,
+                        "Extrapolating player {} render={:.3} latest={:.3} ahead={:.0}ms snaps={}",
+                        player_id,
+                        render_time,
+                        latest_snap.timestamp,
+                        time_ahead * 1000.0,
+                        self.snapshots.len()
+                    );
                     LAST_EXTRAP_PRINT = super::get_network_time();
                 }
             }
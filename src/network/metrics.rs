@@ -0,0 +1,267 @@
+use crate::{log_info, log_warn};
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub struct MetricsWriter {
+    out: String,
+    described: HashSet<String>,
+}
+
+impl MetricsWriter {
+    pub fn new() -> Self {
+        Self {
+            out: String::new(),
+            described: HashSet::new(),
+        }
+    }
+
+    pub fn gauge(&mut self, name: &str, help: &str, value: f64) {
+        self.sample(name, help, "gauge", &[], value);
+    }
+
+    pub fn counter(&mut self, name: &str, help: &str, value: f64) {
+        self.sample(name, help, "counter", &[], value);
+    }
+
+    pub fn gauge_with_labels(
+        &mut self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.sample(name, help, "gauge", labels, value);
+    }
+
+    pub fn counter_with_labels(
+        &mut self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.sample(name, help, "counter", labels, value);
+    }
+
+    fn sample(&mut self, name: &str, help: &str, kind: &str, labels: &[(&str, &str)], value: f64) {
+        if self.described.insert(name.to_string()) {
+            self.out.push_str(&format!("# HELP {} {}\n", name, help));
+            self.out.push_str(&format!("# TYPE {} {}\n", name, kind));
+        }
+
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let pairs: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+                .collect();
+            self.out.push('{');
+            self.out.push_str(&pairs.join(","));
+            self.out.push('}');
+        }
+        self.out.push(' ');
+        self.out.push_str(&format_value(value));
+        self.out.push('\n');
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+impl Default for MetricsWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+pub fn percentile(samples: &[f64], p: f64) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let rank = (p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank]
+}
+
+/// How often the game thread hands a fresh snapshot to the metrics thread.
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Serves the last published snapshot from its own thread, so a slow or idle
+/// scraper never holds up server ticks.
+pub struct MetricsServer {
+    addr: SocketAddr,
+    body: Arc<Mutex<String>>,
+    last_publish: Option<Instant>,
+}
+
+impl MetricsServer {
+    pub fn bind(addr: &str) -> Result<Self, String> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| format!("Failed to bind metrics on {}: {}", addr, e))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read metrics address: {}", e))?;
+
+        let body = Arc::new(Mutex::new(String::new()));
+        let snapshot = Arc::clone(&body);
+        thread::Builder::new()
+            .name("metrics".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => {
+                            let body = snapshot.lock().map(|b| b.clone()).unwrap_or_default();
+                            if let Err(e) = Self::respond(stream, &body) {
+                                log_warn!(Net, "Metrics request failed: {}", e);
+                            }
+                        }
+                        Err(e) => log_warn!(Net, "Metrics accept error: {}", e),
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start metrics thread: {}", e))?;
+
+        log_info!(Net, "Metrics endpoint listening on http://{}/metrics", addr);
+        Ok(Self {
+            addr: local_addr,
+            body,
+            last_publish: None,
+        })
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        Some(self.addr)
+    }
+
+    /// Renders a new snapshot for scrapers once `PUBLISH_INTERVAL` has passed.
+    pub fn publish<F: FnOnce() -> String>(&mut self, render: F) {
+        let now = Instant::now();
+        if self
+            .last_publish
+            .is_some_and(|last| now.duration_since(last) < PUBLISH_INTERVAL)
+        {
+            return;
+        }
+        self.last_publish = Some(now);
+        let body = render();
+        if let Ok(mut current) = self.body.lock() {
+            *current = body;
+        }
+    }
+
+    fn respond(stream: TcpStream, body: &str) -> io::Result<()> {
+        stream.set_read_timeout(Some(Duration::from_millis(200)))?;
+        stream.set_write_timeout(Some(Duration::from_millis(200)))?;
+
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+        let (status, content) = if path == "/metrics" || path == "/" {
+            ("200 OK", body)
+        } else {
+            ("404 Not Found", "not found\n")
+        };
+
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.0 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content.len(),
+            content
+        )?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_exposition_format() {
+        let mut metrics = MetricsWriter::new();
+        metrics.gauge("sas_clients_connected", "Connected clients", 3.0);
+        metrics.gauge_with_labels(
+            "sas_client_ping_ms",
+            "Ping",
+            &[("client", "1"), ("name", "Sa\"rge")],
+            42.5,
+        );
+        metrics.gauge_with_labels(
+            "sas_client_ping_ms",
+            "Ping",
+            &[("client", "2"), ("name", "Doom")],
+            10.0,
+        );
+
+        let text = metrics.finish();
+        assert!(text.contains("# TYPE sas_clients_connected gauge\nsas_clients_connected 3\n"));
+        assert!(text.contains("sas_client_ping_ms{client=\"1\",name=\"Sa\\\"rge\"} 42.5\n"));
+        assert!(text.contains("sas_client_ping_ms{client=\"2\",name=\"Doom\"} 10\n"));
+        assert_eq!(text.matches("# HELP sas_client_ping_ms").count(), 1);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<f64> = (1..=100).map(|v| v as f64).collect();
+        assert_eq!(percentile(&samples, 0.5), 51.0);
+        assert_eq!(percentile(&samples, 0.99), 99.0);
+        assert_eq!(percentile(&samples, 1.0), 100.0);
+        assert_eq!(percentile(&[], 0.5), 0.0);
+    }
+
+    #[test]
+    fn test_http_endpoint() {
+        let mut server = MetricsServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        server.publish(|| {
+            let mut metrics = MetricsWriter::new();
+            metrics.counter("sas_test_total", "Test counter", 7.0);
+            metrics.finish()
+        });
+        server.publish(|| panic!("published again within the interval"));
+
+        // An idle connection only holds up the metrics thread, not publishing.
+        let _idle = TcpStream::connect(addr).unwrap();
+        let mut client = TcpStream::connect(addr).unwrap();
+        client
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.0 200 OK"));
+        assert!(response.contains("text/plain; version=0.0.4"));
+        assert!(response.ends_with("sas_test_total 7\n"));
+    }
+}
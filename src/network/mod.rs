@@ -1,7 +1,10 @@
+pub mod ban_list;
 pub mod client;
 pub mod client_prediction;
 pub mod debug;
+pub mod input_validation;
 pub mod interpolation;
+pub mod metrics;
 pub mod net_hud;
 pub mod net_stats;
 pub mod prediction;
@@ -11,10 +14,13 @@ pub mod server;
 pub mod snapshot_delta;
 pub mod trajectory;
 
+pub use ban_list::{BanEntry, BanList};
 pub use client::NetworkClient;
 pub use client_prediction::{ClientPrediction, PredictedPlayerState, PredictionError};
 pub use debug::NetDebug;
+pub use input_validation::{InputRejection, InputValidationStats, InputValidator};
 pub use interpolation::{InterpolatedPlayer, InterpolatedProjectile, SnapshotBuffer};
+pub use metrics::{MetricsServer, MetricsWriter};
 pub use net_hud::NetHud;
 pub use net_stats::NetStats;
 pub use prediction::{CommandBuffer, UserCommand, CMD_BACKUP};
@@ -162,6 +168,7 @@ pub enum NetMessage {
         gibbed: bool,
         position: (f32, f32),
         velocity: (f32, f32),
+        means: crate::game::means_of_death::MeansOfDeath,
     },
     PlayerGibbed {
         player_id: u16,
@@ -178,12 +185,50 @@ pub enum NetMessage {
     ServerInfo {
         map_name: String,
         gametype: u8,
+        mutators: u8,
         max_players: u8,
         current_players: u8,
+        weapons_checksum: u32,
+        /// The server's weapon table, sent when g_enforceweapons is on.
+        weapons: Option<crate::game::weapon_defs::WeaponTable>,
+        physics: crate::game::physics::PhysicsProfile,
     },
     MapChange {
         map_name: String,
     },
+    FlagStatus {
+        flags: Vec<crate::game::ctf::Flag>,
+        red_captures: i32,
+        blue_captures: i32,
+        events: Vec<crate::game::ctf::FlagEvent>,
+    },
+    RoundStatus {
+        phase: crate::game::round::RoundPhase,
+        round: u32,
+        red_wins: i32,
+        blue_wins: i32,
+        events: Vec<crate::game::round::RoundEvent>,
+    },
+    MatchStatus {
+        phase: crate::game::warmup::MatchPhase,
+        ready: Vec<u16>,
+        min_ready: u8,
+        events: Vec<crate::game::warmup::WarmupEvent>,
+    },
+    PauseStatus {
+        phase: crate::game::pause::PausePhase,
+        events: Vec<crate::game::pause::PauseEvent>,
+    },
+    LimitStatus {
+        match_time: f32,
+        overtime: u32,
+        sudden_death: bool,
+        ended: Option<crate::game::match_rules::EndReason>,
+        events: Vec<crate::game::match_rules::LimitEvent>,
+    },
+    MatchStats {
+        players: Vec<crate::game::match_stats::PlayerStats>,
+    },
     Heartbeat,
     Acknowledgement {
         sequence: u32,
@@ -199,14 +244,20 @@ pub struct PlayerState {
     pub health: i32,
     pub armor: i32,
     pub weapon: u8,
-    pub ammo: [u16; 10],
+    pub ammo: [u16; crate::game::weapon::WEAPON_COUNT],
     pub frags: i32,
     pub deaths: i32,
-    pub powerup_quad: u16,
+    pub team: u8,
+    pub powerups: crate::game::pickup::PowerUps,
+    pub holdable: Option<crate::game::pickup::Holdable>,
+    pub grapple: crate::game::grapple::Grapple,
+    pub jump_time: f32,
     pub on_ground: bool,
     pub is_crouching: bool,
     pub is_attacking: bool,
     pub is_dead: bool,
+    pub is_frozen: bool,
+    pub thaw_progress: f32,
     pub command_time: u32,
 }
 
@@ -221,14 +272,20 @@ impl Default for PlayerState {
             health: 0,
             armor: 0,
             weapon: 0,
-            ammo: [0; 10],
+            ammo: [0; crate::game::weapon::WEAPON_COUNT],
             frags: 0,
             deaths: 0,
-            powerup_quad: 0,
+            team: 0,
+            powerups: Default::default(),
+            holdable: None,
+            grapple: Default::default(),
+            jump_time: 0.0,
             on_ground: false,
             is_crouching: false,
             is_attacking: false,
             is_dead: false,
+            is_frozen: false,
+            thaw_progress: 0.0,
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::NetMessage;
+use crate::log_warn;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::io;
 use std::net::{SocketAddr, UdpSocket};
@@ -131,9 +132,9 @@ impl NetChan {
         }
 
         if sequence <= self.incoming_sequence {
-            eprintln!(
-                "[{:.3}] Out of order packet {} at {}",
-                super::get_network_time(),
+            log_warn!(
+                Net,
+                "Out of order packet {} at {}",
                 sequence,
                 self.incoming_sequence
             );
@@ -142,12 +143,7 @@ impl NetChan {
 
         self.dropped = sequence - (self.incoming_sequence + 1);
         if self.dropped > 0 {
-            eprintln!(
-                "[{:.3}] Dropped {} packets at {}",
-                super::get_network_time(),
-                self.dropped,
-                sequence
-            );
+            log_warn!(Net, "Dropped {} packets at {}", self.dropped, sequence);
         }
 
         if is_fragment {
@@ -160,9 +156,9 @@ impl NetChan {
             }
 
             if fragment_start != self.fragment_length {
-                eprintln!(
-                    "[{:.3}] Missed fragment at {}, expected {}",
-                    super::get_network_time(),
+                log_warn!(
+                    Net,
+                    "Missed fragment at {}, expected {}",
                     fragment_start,
                     self.fragment_length
                 );
@@ -1,8 +1,12 @@
+use super::ban_list::BanList;
+use super::metrics::MetricsWriter;
+use super::net_stats::NetStats;
 use super::protocol::{
     deserialize_message, serialize_message, NetAddr, NetChan, UdpNetworking, MAX_PACKETLEN,
 };
 use super::snapshot_delta::SnapshotDelta;
 use super::{NetMessage, NetworkConfig, PlayerState, PACKET_BACKUP};
+use crate::{log_info, log_warn};
 use std::collections::HashMap;
 use std::io;
 use std::net::SocketAddr;
@@ -18,6 +22,20 @@ pub struct NetworkServer {
     last_tick_time: f64,
     delta_generator: SnapshotDelta,
     use_delta_compression: bool,
+    ban_list: BanList,
+    stats: ServerStats,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ServerStats {
+    pub packets_received: u64,
+    pub packets_sent: u64,
+    pub packets_dropped: u64,
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+    pub snapshots_sent: u64,
+    pub snapshot_bytes_sent: u64,
+    pub connections_rejected: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -47,6 +65,7 @@ struct ClientInfo {
     last_heartbeat: f64,
     snapshot_history: [Option<ClientSnapshot>; PACKET_BACKUP],
     delta_message: u32,
+    net_stats: NetStats,
 }
 
 impl NetworkServer {
@@ -62,6 +81,8 @@ impl NetworkServer {
             last_tick_time: 0.0,
             delta_generator: SnapshotDelta::new(),
             use_delta_compression: false,
+            ban_list: BanList::new(),
+            stats: ServerStats::default(),
         }
     }
 
@@ -74,11 +95,7 @@ impl NetworkServer {
         self.running = true;
         self.last_tick_time = super::get_network_time();
 
-        println!(
-            "[{:.3}] Server started on {}",
-            super::get_network_time(),
-            bind_addr
-        );
+        log_info!(Net, "Server started on {}", bind_addr);
         Ok(())
     }
 
@@ -95,7 +112,7 @@ impl NetworkServer {
 
         self.running = false;
         self.clients.clear();
-        println!("[{:.3}] Server stopped", super::get_network_time());
+        log_info!(Net, "Server stopped");
     }
 
     pub fn update(&mut self) -> (Vec<(u16, NetMessage)>, Vec<u16>) {
@@ -114,11 +131,7 @@ impl NetworkServer {
                     break;
                 }
                 Err(e) => {
-                    eprintln!(
-                        "[{:.3}] Server recv error: {}",
-                        super::get_network_time(),
-                        e
-                    );
+                    log_warn!(Net, "Server recv error: {}", e);
                     break;
                 }
             }
@@ -135,9 +148,36 @@ impl NetworkServer {
 
         if let Some(id) = client_id {
             if let Some(client) = self.clients.get_mut(&id) {
+                self.stats.packets_received += 1;
+                self.stats.bytes_received += data.len() as u64;
+                client.net_stats.record_incoming(data.len());
+
                 if let Some(payload) = client.net_chan.process_packet(data) {
+                    if client.net_chan.dropped > 0 {
+                        self.stats.packets_dropped += client.net_chan.dropped as u64;
+                        client.net_stats.record_packet_loss(client.net_chan.dropped);
+                    }
+
                     if let Ok(msg) = deserialize_message(&payload) {
                         client.last_heartbeat = super::get_network_time();
+
+                        if let NetMessage::SnapshotAck {
+                            acknowledged_tick, ..
+                        } = msg
+                        {
+                            let sent_time = client
+                                .snapshot_history
+                                .iter()
+                                .flatten()
+                                .find(|s| s.tick == acknowledged_tick)
+                                .map(|s| s.sent_time);
+                            if let Some(sent_time) = sent_time {
+                                let ping = (super::get_network_time() - sent_time) * 1000.0;
+                                client.net_stats.record_ping(ping.max(0.0) as u32);
+                            }
+                            return None;
+                        }
+
                         return Some(vec![(id, msg)]);
                     }
                 }
@@ -164,26 +204,18 @@ impl NetworkServer {
         addr: SocketAddr,
     ) -> Vec<(u16, NetMessage)> {
         if protocol_version != self.config.protocol_version {
-            let response = NetMessage::ConnectResponse {
-                player_id: 0,
-                accepted: false,
-                reason: "Protocol version mismatch".to_string(),
-            };
-            if let Ok(data) = serialize_message(&response) {
-                self.networking.send_to(&data, &addr).ok();
-            }
+            self.reject_connection(&addr, "Protocol version mismatch");
+            return Vec::new();
+        }
+
+        if let Err(reason) = self.ban_list.check(&addr.ip()) {
+            log_info!(Net, "Rejected {} ({}): {}", addr, player_name, reason);
+            self.reject_connection(&addr, &reason);
             return Vec::new();
         }
 
         if self.clients.len() >= self.config.max_players as usize {
-            let response = NetMessage::ConnectResponse {
-                player_id: 0,
-                accepted: false,
-                reason: "Server full".to_string(),
-            };
-            if let Ok(data) = serialize_message(&response) {
-                self.networking.send_to(&data, &addr).ok();
-            }
+            self.reject_connection(&addr, "Server full");
             return Vec::new();
         }
 
@@ -204,6 +236,7 @@ impl NetworkServer {
                 None, None, None, None,
             ],
             delta_message: 0,
+            net_stats: NetStats::new(),
         };
 
         self.clients.insert(client_id, client_info);
@@ -216,12 +249,7 @@ impl NetworkServer {
 
         self.send_to(client_id, response).ok();
 
-        println!(
-            "[{:.3}] Client {} connected: {}",
-            super::get_network_time(),
-            client_id,
-            player_name
-        );
+        log_info!(Net, "Client {} connected: {}", client_id, player_name);
 
         vec![(
             client_id,
@@ -232,6 +260,22 @@ impl NetworkServer {
         )]
     }
 
+    fn reject_connection(&mut self, addr: &SocketAddr, reason: &str) {
+        self.stats.connections_rejected += 1;
+        let response = NetMessage::ConnectResponse {
+            player_id: 0,
+            accepted: false,
+            reason: reason.to_string(),
+        };
+        if let Ok(data) = serialize_message(&response) {
+            let mut send_buf = Vec::with_capacity(data.len() + 6);
+            send_buf.extend_from_slice(&[0, 0, 0, 1]);
+            send_buf.extend_from_slice(&[0, 0]);
+            send_buf.extend_from_slice(&data);
+            self.networking.send_to(&send_buf, addr).ok();
+        }
+    }
+
     fn find_client_by_addr(&self, addr: &SocketAddr) -> Option<u16> {
         for (id, client) in self.clients.iter() {
             if client.net_chan.remote_address.addr == *addr {
@@ -254,7 +298,7 @@ impl NetworkServer {
         }
 
         for id in &disconnected {
-            println!("[{:.3}] Client {} timed out", super::get_network_time(), id);
+            log_info!(Net, "Client {} timed out", id);
             self.clients.remove(id);
         }
 
@@ -282,14 +326,21 @@ impl NetworkServer {
     }
 
     pub fn send_to(&mut self, client_id: u16, msg: NetMessage) -> Result<(), String> {
+        self.transmit(client_id, &msg).map(|_| ())
+    }
+
+    fn transmit(&mut self, client_id: u16, msg: &NetMessage) -> Result<usize, String> {
         if let Some(client) = self.clients.get_mut(&client_id) {
             if let Some(socket) = self.networking.socket() {
-                let data = serialize_message(&msg)?;
+                let data = serialize_message(msg)?;
                 client
                     .net_chan
                     .transmit(socket, &data)
                     .map_err(|e| format!("Failed to send to client {}: {}", client_id, e))?;
-                Ok(())
+                client.net_stats.record_outgoing(data.len());
+                self.stats.packets_sent += 1;
+                self.stats.bytes_sent += data.len() as u64;
+                Ok(data.len())
             } else {
                 Err("Socket not initialized".to_string())
             }
@@ -356,7 +407,10 @@ impl NetworkServer {
             client.snapshot_history[index] = Some(snapshot_with_seq);
         }
 
-        self.send_to(client_id, msg)
+        let bytes = self.transmit(client_id, &msg)?;
+        self.stats.snapshots_sent += 1;
+        self.stats.snapshot_bytes_sent += bytes as u64;
+        Ok(())
     }
 
     fn create_delta_message_from_baseline(
@@ -430,12 +484,7 @@ impl NetworkServer {
         };
         self.send_to(client_id, msg).ok();
         self.clients.remove(&client_id);
-        println!(
-            "[{:.3}] Client {} disconnected: {}",
-            super::get_network_time(),
-            client_id,
-            reason
-        );
+        log_info!(Net, "Client {} disconnected: {}", client_id, reason);
     }
 
     pub fn is_running(&self) -> bool {
@@ -446,10 +495,133 @@ impl NetworkServer {
         self.clients.len()
     }
 
+    pub fn max_players(&self) -> u8 {
+        self.config.max_players
+    }
+
     pub fn get_client_name(&self, client_id: u16) -> Option<String> {
         self.clients.get(&client_id).map(|c| c.player_name.clone())
     }
 
+    pub fn get_client_addr(&self, client_id: u16) -> Option<SocketAddr> {
+        self.clients
+            .get(&client_id)
+            .map(|c| c.net_chan.remote_address.addr)
+    }
+
+    pub fn client_ids(&self) -> Vec<u16> {
+        self.clients.keys().copied().collect()
+    }
+
+    pub fn stats(&self) -> &ServerStats {
+        &self.stats
+    }
+
+    pub fn client_stats(&self, client_id: u16) -> Option<&NetStats> {
+        self.clients.get(&client_id).map(|c| &c.net_stats)
+    }
+
+    pub fn write_metrics(&self, metrics: &mut MetricsWriter) {
+        metrics.gauge(
+            "sas_clients_connected",
+            "Connected network clients",
+            self.clients.len() as f64,
+        );
+        metrics.gauge(
+            "sas_clients_max",
+            "Maximum number of clients",
+            self.config.max_players as f64,
+        );
+
+        let mut client_ids: Vec<u16> = self.clients.keys().copied().collect();
+        client_ids.sort();
+        for client_id in client_ids {
+            let client = &self.clients[&client_id];
+            let id = client_id.to_string();
+            let labels = [
+                ("client", id.as_str()),
+                ("name", client.player_name.as_str()),
+            ];
+            metrics.gauge_with_labels(
+                "sas_client_ping_ms",
+                "Client round trip time measured from snapshot acknowledgements",
+                &labels,
+                client.net_stats.ping as f64,
+            );
+            metrics.gauge_with_labels(
+                "sas_client_packet_loss_percent",
+                "Client to server packet loss",
+                &labels,
+                client.net_stats.packet_loss as f64,
+            );
+            metrics.gauge_with_labels(
+                "sas_client_incoming_bytes_per_second",
+                "Bytes per second received from client",
+                &labels,
+                client.net_stats.incoming_rate as f64,
+            );
+            metrics.gauge_with_labels(
+                "sas_client_outgoing_bytes_per_second",
+                "Bytes per second sent to client",
+                &labels,
+                client.net_stats.outgoing_rate as f64,
+            );
+        }
+
+        metrics.counter(
+            "sas_packets_received_total",
+            "Packets received from connected clients",
+            self.stats.packets_received as f64,
+        );
+        metrics.counter(
+            "sas_packets_sent_total",
+            "Packets sent to connected clients",
+            self.stats.packets_sent as f64,
+        );
+        metrics.counter(
+            "sas_packets_dropped_total",
+            "Client packets detected as dropped by sequence gaps",
+            self.stats.packets_dropped as f64,
+        );
+        metrics.counter(
+            "sas_bytes_received_total",
+            "Bytes received from connected clients",
+            self.stats.bytes_received as f64,
+        );
+        metrics.counter(
+            "sas_bytes_sent_total",
+            "Bytes sent to connected clients",
+            self.stats.bytes_sent as f64,
+        );
+        metrics.counter(
+            "sas_snapshots_sent_total",
+            "Snapshots sent to clients",
+            self.stats.snapshots_sent as f64,
+        );
+        metrics.counter(
+            "sas_snapshot_bytes_sent_total",
+            "Snapshot payload bytes sent to clients",
+            self.stats.snapshot_bytes_sent as f64,
+        );
+        metrics.counter(
+            "sas_connections_rejected_total",
+            "Connection attempts rejected",
+            self.stats.connections_rejected as f64,
+        );
+    }
+
+    pub fn ban_list(&self) -> &BanList {
+        &self.ban_list
+    }
+
+    pub fn ban_list_mut(&mut self) -> &mut BanList {
+        &mut self.ban_list
+    }
+
+    pub fn set_ban_list(&mut self, ban_list: BanList) {
+        self.ban_list = ban_list;
+    }
+
     pub fn current_tick(&self) -> u32 {
         self.current_tick
     }
@@ -87,8 +87,28 @@ impl SnapshotDelta {
             } else {
                 None
             },
-            powerup_quad: if old.powerup_quad != new.powerup_quad {
-                Some(new.powerup_quad)
+            team: if old.team != new.team {
+                Some(new.team)
+            } else {
+                None
+            },
+            powerups: if old.powerups != new.powerups {
+                Some(new.powerups)
+            } else {
+                None
+            },
+            holdable: if old.holdable != new.holdable {
+                Some(new.holdable)
+            } else {
+                None
+            },
+            grapple: if old.grapple != new.grapple {
+                Some(new.grapple)
+            } else {
+                None
+            },
+            jump_time: if old.jump_time != new.jump_time {
+                Some(new.jump_time)
             } else {
                 None
             },
@@ -112,6 +132,16 @@ impl SnapshotDelta {
             } else {
                 None
             },
+            is_frozen: if old.is_frozen != new.is_frozen {
+                Some(new.is_frozen)
+            } else {
+                None
+            },
+            thaw_progress: if old.thaw_progress != new.thaw_progress {
+                Some(new.thaw_progress)
+            } else {
+                None
+            },
         }
     }
 
@@ -162,14 +192,20 @@ pub struct PlayerStateDelta {
     pub health: Option<i32>,
     pub armor: Option<i32>,
     pub weapon: Option<u8>,
-    pub ammo: Option<[u16; 10]>,
+    pub ammo: Option<[u16; crate::game::weapon::WEAPON_COUNT]>,
     pub frags: Option<i32>,
     pub deaths: Option<i32>,
-    pub powerup_quad: Option<u16>,
+    pub team: Option<u8>,
+    pub powerups: Option<crate::game::pickup::PowerUps>,
+    pub holdable: Option<Option<crate::game::pickup::Holdable>>,
+    pub grapple: Option<crate::game::grapple::Grapple>,
+    pub jump_time: Option<f32>,
     pub on_ground: Option<bool>,
     pub is_crouching: Option<bool>,
     pub is_attacking: Option<bool>,
     pub is_dead: Option<bool>,
+    pub is_frozen: Option<bool>,
+    pub thaw_progress: Option<f32>,
 }
 
 impl PlayerStateDelta {
@@ -208,7 +244,19 @@ impl PlayerStateDelta {
         if self.deaths.is_some() {
             count += 1;
         }
-        if self.powerup_quad.is_some() {
+        if self.team.is_some() {
+            count += 1;
+        }
+        if self.powerups.is_some() {
+            count += 1;
+        }
+        if self.holdable.is_some() {
+            count += 1;
+        }
+        if self.grapple.is_some() {
+            count += 1;
+        }
+        if self.jump_time.is_some() {
             count += 1;
         }
         if self.on_ground.is_some() {
@@ -220,6 +268,12 @@ impl PlayerStateDelta {
         if self.is_attacking.is_some() {
             count += 1;
         }
+        if self.is_frozen.is_some() {
+            count += 1;
+        }
+        if self.thaw_progress.is_some() {
+            count += 1;
+        }
         count
     }
 
@@ -316,15 +370,24 @@ mod tests {
             health: 100,
             armor: 50,
             weapon: 2,
-            ammo: [10, 20, 5, 5, 5, 0, 0, 0, 0, 0],
+            ammo: [10, 20, 5, 5, 5, 0, 0, 0, 0, 0, 0, 0],
             frags: 5,
             deaths: 2,
-            powerup_quad: 100,
+            team: 1,
+            powerups: crate::game::pickup::PowerUps {
+                quad: 100,
+                ..Default::default()
+            },
+            holdable: Some(crate::game::pickup::Holdable::Medkit),
+            grapple: crate::game::grapple::Grapple::Attached { x: 64.0, y: 32.0 },
+            jump_time: 0.25,
             on_ground: true,
             is_crouching: false,
             is_attacking: true,
             command_time: 0,
             is_dead: false,
+            is_frozen: false,
+            thaw_progress: 0.0,
         };
 
         let delta = delta_gen.compare_players(dummy, &player);
@@ -1,4 +1,6 @@
 use crate::count_shader;
+use crate::game::weapon::WEAPON_COUNT;
+use crate::{log_debug, log_info, log_warn};
 use macroquad::prelude::*;
 use std::sync::OnceLock;
 
@@ -34,6 +36,9 @@ pub struct HudIcons {
     pub weapon_railgun: Texture2D,
     pub weapon_plasma: Texture2D,
     pub weapon_bfg: Texture2D,
+    pub weapon_nailgun: Texture2D,
+    pub weapon_prox: Texture2D,
+    pub weapon_chaingun: Texture2D,
     pub ammo_machinegun: Texture2D,
     pub ammo_shotgun: Texture2D,
     pub ammo_grenade: Texture2D,
@@ -42,6 +47,9 @@ pub struct HudIcons {
     pub ammo_railgun: Texture2D,
     pub ammo_plasma: Texture2D,
     pub ammo_bfg: Texture2D,
+    pub ammo_nailgun: Texture2D,
+    pub ammo_prox: Texture2D,
+    pub ammo_chaingun: Texture2D,
 }
 
 pub struct ItemIcons {
@@ -61,12 +69,17 @@ pub struct ItemIcons {
     pub weapon_railgun: Texture2D,
     pub weapon_plasma: Texture2D,
     pub weapon_bfg: Texture2D,
+    pub weapon_nailgun: Texture2D,
+    pub weapon_prox: Texture2D,
+    pub weapon_chaingun: Texture2D,
     pub quad: Texture2D,
     pub regen: Texture2D,
     pub battle: Texture2D,
     pub flight: Texture2D,
     pub haste: Texture2D,
     pub invis: Texture2D,
+    pub medkit: Texture2D,
+    pub teleporter: Texture2D,
 }
 
 const PROPB_HEIGHT: f32 = 36.0;
@@ -236,10 +249,10 @@ pub async fn load_custom_font() {
     match load_ttf_font_from_bytes(font_bytes) {
         Ok(font) => {
             let _ = CUSTOM_FONT.set(font);
-            println!("[Font] Loaded custom font: Monaco");
+            log_info!(Render, "Loaded custom font: Monaco");
         }
         Err(e) => {
-            println!("[Font] Failed to load custom font: {}", e);
+            log_warn!(Render, "Failed to load custom font: {}", e);
         }
     }
 }
@@ -284,7 +297,7 @@ pub async fn load_q3_numbers() {
     };
 
     let _ = Q3_NUMBERS.set(numbers);
-    println!("[Q3HUD] Loaded Q3-style number textures");
+    log_info!(Render, "Loaded Q3-style number textures");
 }
 
 pub async fn load_q3_bigchars() {
@@ -293,10 +306,10 @@ pub async fn load_q3_bigchars() {
         Ok(tex) => {
             tex.set_filter(FilterMode::Linear);
             let _ = Q3_BIGCHARS.set(tex);
-            println!("[Q3HUD] Loaded Q3 bigchars font");
+            log_info!(Render, "Loaded Q3 bigchars font");
         }
         Err(e) => {
-            println!("[Q3HUD] Failed to load bigchars: {}", e);
+            log_warn!(Render, "Failed to load bigchars: {}", e);
         }
     }
 }
@@ -307,10 +320,10 @@ pub async fn load_q3_font2_prop() {
         Ok(tex) => {
             tex.set_filter(FilterMode::Linear);
             let _ = Q3_FONT2_PROP.set(tex);
-            println!("[Q3HUD] Loaded Q3 font2_prop");
+            log_info!(Render, "Loaded Q3 font2_prop");
         }
         Err(e) => {
-            println!("[Q3HUD] Failed to load font2_prop: {}", e);
+            log_warn!(Render, "Failed to load font2_prop: {}", e);
         }
     }
 }
@@ -321,10 +334,10 @@ pub async fn load_q3_font1_prop() {
         Ok(tex) => {
             tex.set_filter(FilterMode::Linear);
             let _ = Q3_FONT1_PROP.set(tex);
-            println!("[Q3HUD] Loaded Q3 font1_prop");
+            log_info!(Render, "Loaded Q3 font1_prop");
         }
         Err(e) => {
-            println!("[Q3HUD] Failed to load font1_prop: {}", e);
+            log_warn!(Render, "Failed to load font1_prop: {}", e);
         }
     }
 }
@@ -360,6 +373,9 @@ pub async fn load_hud_icons() {
         weapon_railgun: load_icon("q3-resources/icons/iconw_railgun.png").await,
         weapon_plasma: load_icon("q3-resources/icons/iconw_plasma.png").await,
         weapon_bfg: load_icon("q3-resources/icons/iconw_bfg.png").await,
+        weapon_nailgun: load_icon("q3-resources/icons/iconw_nailgun.png").await,
+        weapon_prox: load_icon("q3-resources/icons/iconw_proxlauncher.png").await,
+        weapon_chaingun: load_icon("q3-resources/icons/iconw_chaingun.png").await,
         ammo_machinegun: load_icon("q3-resources/icons/icona_machinegun.png").await,
         ammo_shotgun: load_icon("q3-resources/icons/icona_shotgun.png").await,
         ammo_grenade: load_icon("q3-resources/icons/icona_grenade.png").await,
@@ -368,10 +384,13 @@ pub async fn load_hud_icons() {
         ammo_railgun: load_icon("q3-resources/icons/icona_railgun.png").await,
         ammo_plasma: load_icon("q3-resources/icons/icona_plasma.png").await,
         ammo_bfg: load_icon("q3-resources/icons/icona_bfg.png").await,
+        ammo_nailgun: load_icon("q3-resources/icons/icona_nailgun.png").await,
+        ammo_prox: load_icon("q3-resources/icons/icona_proxlauncher.png").await,
+        ammo_chaingun: load_icon("q3-resources/icons/icona_chaingun.png").await,
     };
 
     let _ = HUD_ICONS.set(icons);
-    println!("[Q3HUD] Loaded HUD icons");
+    log_info!(Render, "Loaded HUD icons");
 }
 
 pub async fn load_item_icons() {
@@ -405,16 +424,21 @@ pub async fn load_item_icons() {
         weapon_railgun: load_icon("q3-resources/icons/iconw_railgun.png").await,
         weapon_plasma: load_icon("q3-resources/icons/iconw_plasma.png").await,
         weapon_bfg: load_icon("q3-resources/icons/iconw_bfg.png").await,
+        weapon_nailgun: load_icon("q3-resources/icons/iconw_nailgun.png").await,
+        weapon_prox: load_icon("q3-resources/icons/iconw_proxlauncher.png").await,
+        weapon_chaingun: load_icon("q3-resources/icons/iconw_chaingun.png").await,
         quad: load_icon("q3-resources/icons/quad.png").await,
         regen: load_icon("q3-resources/icons/regen.png").await,
         battle: load_icon("q3-resources/icons/envirosuit.png").await,
         flight: load_icon("q3-resources/icons/flight.png").await,
         haste: load_icon("q3-resources/icons/haste.png").await,
         invis: load_icon("q3-resources/icons/invis.png").await,
+        medkit: load_icon("q3-resources/icons/medkit.png").await,
+        teleporter: load_icon("q3-resources/icons/teleporter.png").await,
     };
 
     let _ = ITEM_ICONS.set(icons);
-    println!("[ItemIcons] Loaded item icons");
+    log_info!(Render, "Loaded item icons");
 }
 
 pub fn get_item_icons() -> Option<&'static ItemIcons> {
@@ -790,8 +814,8 @@ pub fn draw_hud(
     frags: i32,
     weapon: u8,
     leader_frags: i32,
-    has_weapon: &[bool; 9],
-    ammo_counts: &[u8; 9],
+    has_weapon: &[bool; WEAPON_COUNT],
+    ammo_counts: &[u8; WEAPON_COUNT],
     match_time: f32,
     time_limit: f32,
 ) {
@@ -933,6 +957,9 @@ fn get_ammo_icon(weapon: u8) -> Option<&'static Texture2D> {
         6 => &icons.ammo_railgun,
         7 => &icons.ammo_plasma,
         8 => &icons.ammo_bfg,
+        9 => &icons.ammo_nailgun,
+        10 => &icons.ammo_prox,
+        11 => &icons.ammo_chaingun,
         _ => &icons.ammo_machinegun,
     })
 }
@@ -949,6 +976,9 @@ fn get_weapon_icon(weapon: u8) -> Option<&'static Texture2D> {
         6 => &icons.weapon_railgun,
         7 => &icons.weapon_plasma,
         8 => &icons.weapon_bfg,
+        9 => &icons.weapon_nailgun,
+        10 => &icons.weapon_prox,
+        11 => &icons.weapon_chaingun,
         _ => &icons.weapon_gauntlet,
     })
 }
@@ -997,14 +1027,14 @@ fn draw_large_q3_number(x: f32, y: f32, number: i32, color: Color, size: f32) {
 fn draw_weapon_icons_with_ammo(
     current_weapon: u8,
     y: f32,
-    has_weapon: &[bool; 9],
-    ammo_counts: &[u8; 9],
+    has_weapon: &[bool; WEAPON_COUNT],
+    ammo_counts: &[u8; WEAPON_COUNT],
 ) {
     let screen_w = screen_width();
     let icon_size = 28.0;
     let icon_spacing = 35.0;
 
-    let available_weapons: Vec<u8> = (1..=8)
+    let available_weapons: Vec<u8> = (1..WEAPON_COUNT as u8)
         .filter(|&weapon_id| {
             let idx = weapon_id as usize;
             has_weapon[idx] && (ammo_counts[idx] > 0 || weapon_id == 1)
@@ -1096,9 +1126,13 @@ fn draw_match_timer_and_scores(
     let screen_w = screen_width();
     let y = 30.0;
 
-    let time_remaining = (time_limit - match_time).max(0.0);
-    let minutes = (time_remaining / 60.0) as u32;
-    let seconds = (time_remaining % 60.0) as u32;
+    let shown_time = if time_limit > 0.0 {
+        (time_limit - match_time).max(0.0)
+    } else {
+        match_time
+    };
+    let minutes = (shown_time / 60.0) as u32;
+    let seconds = (shown_time % 60.0) as u32;
 
     let score_text = format!(
         "{} {:02}:{:02} {}",
@@ -1231,8 +1265,9 @@ pub fn draw_main_menu(selected: usize, items: &[&str], _hover_idx: Option<usize>
 }
 
 pub fn draw_map_select_menu(selected: usize, items: &[&str], _hover_idx: Option<usize>) {
-    println!(
-        "[RENDER] draw_map_select_menu called with {} items: {:?}",
+    log_debug!(
+        Render,
+        "draw_map_select_menu called with {} items: {:?}",
         items.len(),
         items
     );
@@ -1257,7 +1292,7 @@ pub fn draw_map_select_menu(selected: usize, items: &[&str], _hover_idx: Option<
             32.0,
             Color::from_rgba(255, 100, 100, 255),
         );
-        println!("[RENDER] WARNING: No items to display!");
+        log_warn!(Render, "No items to display!");
         return;
     }
 
@@ -1266,7 +1301,7 @@ pub fn draw_map_select_menu(selected: usize, items: &[&str], _hover_idx: Option<
     let start_y = h * 0.5 - (items.len() as f32 * (item_h + 12.0)) * 0.5;
 
     for (i, label) in items.iter().enumerate() {
-        println!("[RENDER] Drawing item {}: '{}'", i, label);
+        log_debug!(Render, "Drawing item {}: '{}'", i, label);
         let y = start_y + (i as f32) * (item_h + 12.0);
         let x = w * 0.5 - item_w * 0.5;
 
@@ -1292,8 +1327,9 @@ pub fn draw_map_select_menu(selected: usize, items: &[&str], _hover_idx: Option<
 }
 
 pub fn draw_1v1_map_select_menu(selected: usize, items: &[&str], _hover_idx: Option<usize>) {
-    println!(
-        "[RENDER] draw_1v1_map_select_menu called with {} items: {:?}",
+    log_debug!(
+        Render,
+        "draw_1v1_map_select_menu called with {} items: {:?}",
         items.len(),
         items
     );
@@ -1325,7 +1361,7 @@ pub fn draw_1v1_map_select_menu(selected: usize, items: &[&str], _hover_idx: Opt
             32.0,
             Color::from_rgba(255, 100, 100, 255),
         );
-        println!("[RENDER] WARNING: No items to display!");
+        log_warn!(Render, "No items to display!");
         return;
     }
 
@@ -1334,7 +1370,7 @@ pub fn draw_1v1_map_select_menu(selected: usize, items: &[&str], _hover_idx: Opt
     let start_y = h * 0.5 - (items.len() as f32 * (item_h + 12.0)) * 0.5 + 20.0;
 
     for (i, label) in items.iter().enumerate() {
-        println!("[RENDER] Drawing item {}: '{}'", i, label);
+        log_debug!(Render, "Drawing item {}: '{}'", i, label);
         let y = start_y + (i as f32) * (item_h + 12.0);
         let x = w * 0.5 - item_w * 0.5;
 
@@ -1498,8 +1534,8 @@ pub fn draw_hud_player2(
     _frags: i32,
     weapon: u8,
     _leader_frags: i32,
-    has_weapon: &[bool; 9],
-    ammo_counts: &[u8; 9],
+    has_weapon: &[bool; WEAPON_COUNT],
+    ammo_counts: &[u8; WEAPON_COUNT],
 ) {
     let screen_w = screen_width();
 
@@ -1577,14 +1613,14 @@ pub fn draw_hud_player2(
 fn draw_weapon_icons_player2_with_ammo(
     current_weapon: u8,
     y: f32,
-    has_weapon: &[bool; 9],
-    ammo_counts: &[u8; 9],
+    has_weapon: &[bool; WEAPON_COUNT],
+    ammo_counts: &[u8; WEAPON_COUNT],
 ) {
     let screen_w = screen_width();
     let icon_size = 28.0;
     let icon_spacing = 35.0;
 
-    let available_weapons: Vec<u8> = (1..=8)
+    let available_weapons: Vec<u8> = (1..WEAPON_COUNT as u8)
         .filter(|&weapon_id| {
             let idx = weapon_id as usize;
             has_weapon[idx] && (ammo_counts[idx] > 0 || weapon_id == 1)
@@ -1686,12 +1722,17 @@ pub fn get_item_icon_for_type(
         ItemType::Railgun => Some(&icons.weapon_railgun),
         ItemType::Plasmagun => Some(&icons.weapon_plasma),
         ItemType::BFG => Some(&icons.weapon_bfg),
+        ItemType::Nailgun => Some(&icons.weapon_nailgun),
+        ItemType::ProxLauncher => Some(&icons.weapon_prox),
+        ItemType::Chaingun => Some(&icons.weapon_chaingun),
         ItemType::Quad => Some(&icons.quad),
         ItemType::Regen => Some(&icons.regen),
         ItemType::Battle => Some(&icons.battle),
         ItemType::Flight => Some(&icons.flight),
         ItemType::Haste => Some(&icons.haste),
         ItemType::Invis => Some(&icons.invis),
+        ItemType::Medkit => Some(&icons.medkit),
+        ItemType::Teleporter => Some(&icons.teleporter),
     }
 }
 
@@ -1,5 +1,9 @@
 use crate::audio;
-use crate::game::{constants::*, weapon::Weapon, GameState};
+use crate::game::{
+    constants::*,
+    weapon::{Weapon, WEAPON_COUNT},
+    GameState,
+};
 use crate::input::{Input, LocalMultiplayerInput};
 use macroquad::prelude::*;
 
@@ -30,7 +34,10 @@ impl WeaponHandler {
                 if player.ammo[weapon_idx] >= player.weapon.ammo_per_shot()
                     || player.weapon as u8 == 0
                 {
-                    player.refire = player.weapon.refire_time_seconds();
+                    player.refire = player.refire_time();
+                    if player.weapon == Weapon::Chaingun {
+                        player.spin_barrel();
+                    }
                     if player.weapon as u8 > 0 {
                         player.ammo[weapon_idx] =
                             player.ammo[weapon_idx].saturating_sub(player.weapon.ammo_per_shot());
@@ -78,17 +85,30 @@ impl WeaponHandler {
         }
 
         if let Some((shoot_x, shoot_y, angle, weapon_u8)) = projectile_to_create {
-            let projectile = crate::game::projectile::Projectile::new(
-                shoot_x,
-                shoot_y,
-                angle,
-                player_id,
-                player_weapon,
-                player_vel_x,
-                player_vel_y,
-            );
-            let projectile = game_state.create_projectile_with_id(projectile);
-            game_state.projectiles.push(projectile);
+            let projectiles = if player_weapon == Weapon::Nailgun {
+                crate::game::projectile::Projectile::nailgun_spread(
+                    shoot_x,
+                    shoot_y,
+                    angle,
+                    player_id,
+                    player_vel_x,
+                    player_vel_y,
+                )
+            } else {
+                vec![crate::game::projectile::Projectile::new(
+                    shoot_x,
+                    shoot_y,
+                    angle,
+                    player_id,
+                    player_weapon,
+                    player_vel_x,
+                    player_vel_y,
+                )]
+            };
+            for projectile in projectiles {
+                let projectile = game_state.create_projectile_with_id(projectile);
+                game_state.projectiles.push(projectile);
+            }
             return Some((shoot_x, shoot_y, angle, weapon_u8));
         }
 
@@ -123,7 +143,7 @@ impl WeaponHandler {
                             if player.ammo[weapon_idx] >= player.weapon.ammo_per_shot()
                                 || player.weapon as u8 == 0
                             {
-                                player.refire = player.weapon.refire_time_seconds();
+                                player.refire = player.refire_time();
                                 if player.weapon as u8 > 0 {
                                     player.ammo[weapon_idx] = player.ammo[weapon_idx]
                                         .saturating_sub(player.weapon.ammo_per_shot());
@@ -186,7 +206,7 @@ impl WeaponHandler {
                 if player.ammo[weapon_idx] >= player.weapon.ammo_per_shot()
                     || player.weapon as u8 == 0
                 {
-                    player.refire = player.weapon.refire_time_seconds();
+                    player.refire = player.refire_time();
                     if player.weapon as u8 > 0 {
                         player.ammo[weapon_idx] =
                             player.ammo[weapon_idx].saturating_sub(player.weapon.ammo_per_shot());
@@ -320,59 +340,35 @@ impl WeaponHandler {
         if let Some(weapon_idx) = weapon_switch {
             let new_weapon = if weapon_idx == 255 {
                 let current = player.weapon as usize;
-                let mut next = if current == 0 { 8 } else { current - 1 };
+                let mut next = if current == 0 {
+                    WEAPON_COUNT - 1
+                } else {
+                    current - 1
+                };
                 while next != current {
                     if player.has_weapon[next] {
                         break;
                     }
-                    next = if next == 0 { 8 } else { next - 1 };
-                }
-                match next {
-                    0 => Weapon::Gauntlet,
-                    1 => Weapon::MachineGun,
-                    2 => Weapon::Shotgun,
-                    3 => Weapon::GrenadeLauncher,
-                    4 => Weapon::RocketLauncher,
-                    5 => Weapon::Lightning,
-                    6 => Weapon::Railgun,
-                    7 => Weapon::Plasmagun,
-                    8 => Weapon::BFG,
-                    _ => Weapon::Gauntlet,
+                    next = if next == 0 {
+                        WEAPON_COUNT - 1
+                    } else {
+                        next - 1
+                    };
                 }
+                Weapon::from_u8(next as u8).unwrap_or(Weapon::Gauntlet)
             } else if weapon_idx == 254 {
                 let current = player.weapon as usize;
-                let mut next = if current == 8 { 0 } else { current + 1 };
+                let mut next = (current + 1) % WEAPON_COUNT;
                 while next != current {
                     if player.has_weapon[next] {
                         break;
                     }
-                    next = if next == 8 { 0 } else { next + 1 };
-                }
-                match next {
-                    0 => Weapon::Gauntlet,
-                    1 => Weapon::MachineGun,
-                    2 => Weapon::Shotgun,
-                    3 => Weapon::GrenadeLauncher,
-                    4 => Weapon::RocketLauncher,
-                    5 => Weapon::Lightning,
-                    6 => Weapon::Railgun,
-                    7 => Weapon::Plasmagun,
-                    8 => Weapon::BFG,
-                    _ => Weapon::Gauntlet,
-                }
-            } else if weapon_idx < 9 && player.has_weapon[weapon_idx as usize] {
-                match weapon_idx {
-                    0 => Weapon::Gauntlet,
-                    1 => Weapon::MachineGun,
-                    2 => Weapon::Shotgun,
-                    3 => Weapon::GrenadeLauncher,
-                    4 => Weapon::RocketLauncher,
-                    5 => Weapon::Lightning,
-                    6 => Weapon::Railgun,
-                    7 => Weapon::Plasmagun,
-                    8 => Weapon::BFG,
-                    _ => Weapon::Gauntlet,
+                    next = (next + 1) % WEAPON_COUNT;
                 }
+                Weapon::from_u8(next as u8).unwrap_or(Weapon::Gauntlet)
+            } else if (weapon_idx as usize) < WEAPON_COUNT && player.has_weapon[weapon_idx as usize]
+            {
+                Weapon::from_u8(weapon_idx).unwrap_or(Weapon::Gauntlet)
             } else {
                 return;
             };
@@ -395,6 +391,8 @@ impl WeaponHandler {
         shoot_actions: Vec<(f32, f32, f32, u16, Weapon)>,
     ) {
         for (shoot_x, shoot_y, angle, player_id, weapon) in shoot_actions {
+            game_state.match_stats.record_shot(player_id, weapon);
+
             let (muzzle_x, muzzle_y) = if matches!(weapon, Weapon::Railgun) {
                 (shoot_x, shoot_y)
             } else {
@@ -429,7 +427,11 @@ impl WeaponHandler {
         weapon: Weapon,
     ) {
         match weapon {
-            Weapon::RocketLauncher | Weapon::GrenadeLauncher | Weapon::Plasmagun | Weapon::BFG => {
+            Weapon::RocketLauncher
+            | Weapon::GrenadeLauncher
+            | Weapon::Plasmagun
+            | Weapon::BFG
+            | Weapon::ProxLauncher => {
                 let player_idx = game_state.players.iter().position(|p| p.id == player_id);
                 let (vel_x, vel_y, has_quad) = if let Some(idx) = player_idx {
                     (
@@ -456,8 +458,28 @@ impl WeaponHandler {
             Weapon::Shotgun => {
                 Self::handle_shotgun_fire(game_state, shoot_x, shoot_y, angle, player_id);
             }
-            Weapon::MachineGun => {
-                Self::handle_machinegun_fire(game_state, shoot_x, shoot_y, angle, player_id);
+            Weapon::Nailgun => {
+                let (vel_x, vel_y, has_quad) = game_state
+                    .players
+                    .iter()
+                    .find(|p| p.id == player_id)
+                    .map(|p| (p.vel_x, p.vel_y, p.powerups.quad > 0))
+                    .unwrap_or((0.0, 0.0, false));
+
+                for mut nail in crate::game::projectile::Projectile::nailgun_spread(
+                    shoot_x, shoot_y, angle, player_id, vel_x, vel_y,
+                ) {
+                    if has_quad {
+                        nail.damage *= 3;
+                    }
+                    let nail = game_state.create_projectile_with_id(nail);
+                    game_state.projectiles.push(nail);
+                }
+            }
+            Weapon::MachineGun | Weapon::Chaingun => {
+                Self::handle_machinegun_fire(
+                    game_state, shoot_x, shoot_y, angle, player_id, weapon,
+                );
             }
             Weapon::Lightning | Weapon::Gauntlet => {
                 Self::handle_lightning_gauntlet_fire(
@@ -763,16 +785,25 @@ impl WeaponHandler {
         shoot_y: f32,
         angle: f32,
         player_id: u16,
+        weapon: Weapon,
     ) {
         if let Some(idx) = game_state.players.iter().position(|p| p.id == player_id) {
-            let p = &mut game_state.players[idx];
-            p.barrel_spin_speed += crate::game::constants::BARREL_SPIN_ACCEL_IMPULSE;
-            if p.barrel_spin_speed > crate::game::constants::BARREL_SPIN_MAX_SPEED {
-                p.barrel_spin_speed = crate::game::constants::BARREL_SPIN_MAX_SPEED;
-            }
+            game_state.players[idx].spin_barrel();
         }
+        let spread = if weapon == Weapon::Chaingun {
+            0.09
+        } else {
+            0.05
+        };
         let rays = crate::game::hitscan::fire_hitscan(
-            shoot_x, shoot_y, angle, 1000.0, 0.05, 1, player_id, 7,
+            shoot_x,
+            shoot_y,
+            angle,
+            1000.0,
+            spread,
+            1,
+            player_id,
+            weapon.damage(),
         );
 
         for (start_x, start_y, ray_angle, owner, mut dmg) in rays {
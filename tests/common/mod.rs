@@ -1,7 +1,12 @@
 use sas::game::bg_pmove::{pmove, PmoveCmd, PmoveState};
 use sas::game::map::Map;
+use sas::game::physics::PhysicsProfile;
+use sas::game::pickup::PowerUps;
 use sas::game::usercmd::UserCmd;
-use sas::network::{NetMessage, NetworkConfig, NetworkServer, PlayerState};
+use sas::network::{
+    get_network_time, InputValidator, NetMessage, NetworkConfig, NetworkServer, PlayerInputCmd,
+    PlayerState,
+};
 use std::collections::HashMap;
 use std::process::Command;
 
@@ -76,12 +81,25 @@ pub struct ServerPlayer {
     pub health: i32,
     pub armor: i32,
     pub weapon: u8,
-    pub ammo: [u16; 10],
+    pub ammo: [u16; sas::game::weapon::WEAPON_COUNT],
     pub frags: i32,
     pub deaths: i32,
-    pub powerup_quad: u16,
+    pub powerups: PowerUps,
     pub last_cmd: UserCmd,
     pub last_executed_time: u32,
+    pub input_validator: InputValidator,
+}
+
+impl ServerPlayer {
+    fn apply_cmd(&mut self, cmd: &PlayerInputCmd) {
+        self.angle = cmd.angle;
+        self.last_cmd = UserCmd {
+            right: cmd.move_right,
+            buttons: cmd.buttons as u8,
+            angles: (cmd.angle, 0.0),
+            server_time: cmd.server_time,
+        };
+    }
 }
 
 impl TestServer {
@@ -145,6 +163,7 @@ impl TestServer {
                 self.players.remove(&client_id);
             }
             NetMessage::PlayerInput {
+                input_sequence,
                 move_forward,
                 move_right,
                 angle,
@@ -152,26 +171,26 @@ impl TestServer {
                 server_time,
                 ..
             } => {
-                self.update_player_input(
-                    client_id,
+                let cmd = PlayerInputCmd {
                     move_forward,
                     move_right,
                     angle,
                     buttons,
                     server_time,
-                );
+                };
+                if let Some(player) = self.players.get_mut(&client_id) {
+                    let now = get_network_time();
+                    if let Some(cmd) =
+                        player
+                            .input_validator
+                            .accept_input(input_sequence, &cmd, now)
+                    {
+                        player.apply_cmd(&cmd);
+                    }
+                }
             }
             NetMessage::PlayerInputBatch { commands, .. } => {
-                if let Some(last_cmd) = commands.last() {
-                    self.update_player_input(
-                        client_id,
-                        0.0,
-                        last_cmd.move_right,
-                        last_cmd.angle,
-                        last_cmd.buttons,
-                        last_cmd.server_time,
-                    );
-                }
+                self.update_player_input_batch(client_id, &commands);
             }
             _ => {}
         }
@@ -188,17 +207,20 @@ impl TestServer {
                 vel_x: 0.0,
                 vel_y: 0.0,
                 was_in_air: false,
+                jump_time: 0.0,
+                fall_speed: 0.0,
             },
             angle: 0.0,
             health: 100,
             armor: 0,
             weapon: 2,
-            ammo: [100, 50, 10, 10, 5, 0, 0, 0, 0, 0],
+            ammo: [100, 50, 10, 10, 5, 0, 0, 0, 0, 0, 0, 0],
             frags: 0,
             deaths: 0,
-            powerup_quad: 0,
+            powerups: PowerUps::default(),
             last_cmd: UserCmd::new(),
             last_executed_time: 0,
+            input_validator: InputValidator::new(),
         };
 
         self.players.insert(client_id, player);
@@ -215,23 +237,16 @@ impl TestServer {
         self.server.broadcast(respawn_msg).ok();
     }
 
-    fn update_player_input(
-        &mut self,
-        client_id: u16,
-        _move_forward: f32,
-        move_right: f32,
-        angle: f32,
-        buttons: u32,
-        server_time: u32,
-    ) {
+    fn update_player_input_batch(&mut self, client_id: u16, commands: &[PlayerInputCmd]) {
         if let Some(player) = self.players.get_mut(&client_id) {
-            player.angle = angle;
-            player.last_cmd = UserCmd {
-                right: move_right,
-                buttons: buttons as u8,
-                angles: (angle, 0.0),
-                server_time,
-            };
+            // Only the newest command is kept and replayed every tick, so
+            // nothing here counts as executed yet.
+            let accepted = player
+                .input_validator
+                .accept_batch(commands, 0, get_network_time());
+            if let Some(cmd) = accepted.last() {
+                player.apply_cmd(cmd);
+            }
         }
     }
 
@@ -250,15 +265,24 @@ impl TestServer {
                 jump: (cmd.buttons & 2) != 0,
                 crouch: (cmd.buttons & 4) != 0,
                 haste_active: false,
+                flight_active: false,
+                grapple: None,
             };
 
-            let result = pmove(&player.pmove_state, &pmove_cmd, dt, &self.map);
+            let result = pmove(
+                &player.pmove_state,
+                &pmove_cmd,
+                dt,
+                &self.map,
+                &PhysicsProfile::default(),
+            );
 
             player.pmove_state.x = result.new_x;
             player.pmove_state.y = result.new_y;
             player.pmove_state.vel_x = result.new_vel_x;
             player.pmove_state.vel_y = result.new_vel_y;
             player.pmove_state.was_in_air = result.new_was_in_air;
+            player.pmove_state.jump_time = result.new_jump_time;
         }
     }
 
@@ -278,11 +302,17 @@ impl TestServer {
                 ammo: p.ammo,
                 frags: p.frags,
                 deaths: p.deaths,
-                powerup_quad: p.powerup_quad,
+                team: 0,
+                powerups: p.powerups,
+                holdable: None,
+                grapple: Default::default(),
+                jump_time: p.pmove_state.jump_time,
                 on_ground: !p.pmove_state.was_in_air,
                 is_crouching: (p.last_cmd.buttons & 4) != 0,
                 is_attacking: false,
                 is_dead: false,
+                is_frozen: false,
+                thaw_progress: 0.0,
             })
             .collect();
 
@@ -1,7 +1,7 @@
 mod common;
 
 use common::TestServer;
-use sas::network::{NetworkClient, NetworkConfig};
+use sas::network::{NetMessage, NetworkClient, NetworkConfig};
 use std::thread;
 use std::time::Duration;
 
@@ -92,3 +92,61 @@ fn test_client_disconnect_and_reconnect() {
 
     assert!(client2.is_connected());
 }
+
+#[test]
+fn test_banned_address_is_rejected() {
+    const PORT: u16 = 27976;
+
+    let mut server = TestServer::new(PORT);
+    server.start().expect("Failed to start server");
+    server
+        .server
+        .ban_list_mut()
+        .add("127.0.0.0/8", None)
+        .expect("Failed to add ban");
+
+    thread::sleep(Duration::from_millis(100));
+
+    let mut client = NetworkClient::new(NetworkConfig::default());
+    client
+        .connect("Banned".to_string(), &format!("127.0.0.1:{}", PORT))
+        .expect("Failed to send connect");
+
+    thread::sleep(Duration::from_millis(50));
+    server.update();
+    thread::sleep(Duration::from_millis(50));
+    let messages = client.update();
+
+    assert!(!client.is_connected());
+    assert_eq!(server.server.client_count(), 0);
+
+    let rejection = messages.iter().find_map(|msg| match msg {
+        NetMessage::ConnectResponse {
+            accepted: false,
+            reason,
+            ..
+        } => Some(reason.clone()),
+        _ => None,
+    });
+    assert!(
+        rejection.is_some_and(|r| r.contains("banned")),
+        "Client should receive a ban reason"
+    );
+
+    server.server.ban_list_mut().filter_ban = false;
+
+    let mut allowed = NetworkClient::new(NetworkConfig::default());
+    allowed
+        .connect("Allowed".to_string(), &format!("127.0.0.1:{}", PORT))
+        .expect("Failed to send connect");
+
+    thread::sleep(Duration::from_millis(50));
+    server.update();
+    thread::sleep(Duration::from_millis(50));
+    allowed.update();
+
+    assert!(
+        allowed.is_connected(),
+        "Listed address should be allowed when g_filterban is 0"
+    );
+}
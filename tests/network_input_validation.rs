@@ -0,0 +1,149 @@
+mod common;
+
+use common::TestServer;
+use sas::network::{NetMessage, NetworkClient, NetworkConfig, PlayerInputCmd};
+use std::thread;
+use std::time::Duration;
+
+fn connect_client(server: &mut TestServer, port: u16) -> (NetworkClient, u16) {
+    let mut client = NetworkClient::new(NetworkConfig::default());
+    client
+        .connect("Cheater".to_string(), &format!("127.0.0.1:{}", port))
+        .expect("Client failed to connect");
+
+    thread::sleep(Duration::from_millis(50));
+    server.update();
+    thread::sleep(Duration::from_millis(50));
+    client.update();
+
+    assert!(client.is_connected(), "Client should be connected");
+    let player_id = client.player_id().expect("Client should have player ID");
+    (client, player_id)
+}
+
+fn send_batch(client: &mut NetworkClient, player_id: u16, commands: Vec<PlayerInputCmd>) {
+    client
+        .send_message(NetMessage::PlayerInputBatch {
+            player_id,
+            commands,
+        })
+        .expect("Failed to send batch");
+}
+
+fn pump(server: &mut TestServer, client: &mut NetworkClient) {
+    thread::sleep(Duration::from_millis(30));
+    server.update();
+    client.update();
+}
+
+fn cmd(move_right: f32, angle: f32, buttons: u32, server_time: u32) -> PlayerInputCmd {
+    PlayerInputCmd {
+        move_forward: 0.0,
+        move_right,
+        angle,
+        buttons,
+        server_time,
+    }
+}
+
+#[test]
+fn test_forged_axes_are_clamped_and_nan_rejected() {
+    const PORT: u16 = 27973;
+
+    let mut server = TestServer::new(PORT);
+    server.start().expect("Failed to start server");
+    thread::sleep(Duration::from_millis(100));
+
+    let (mut client, player_id) = connect_client(&mut server, PORT);
+
+    send_batch(
+        &mut client,
+        player_id,
+        vec![cmd(500.0, 0.5, 0xFFFF_FFFF, 1000)],
+    );
+    pump(&mut server, &mut client);
+
+    let player = &server.players[&player_id];
+    assert_eq!(player.last_cmd.right, 1.0, "move_right should be clamped");
+    assert_eq!(
+        player.last_cmd.buttons, 31,
+        "unknown button bits should be masked"
+    );
+
+    send_batch(
+        &mut client,
+        player_id,
+        vec![
+            cmd(f32::NAN, 0.0, 0, 1016),
+            cmd(-1.0, f32::INFINITY, 0, 1032),
+        ],
+    );
+    pump(&mut server, &mut client);
+
+    let stats = server.players[&player_id].input_validator.stats;
+    assert_eq!(stats.rejected_non_finite, 2);
+    assert_eq!(stats.clamped, 1);
+    assert_eq!(
+        server.players[&player_id].last_cmd.right, 1.0,
+        "Rejected commands must not overwrite last command"
+    );
+
+    server.simulate_physics(1.0 / 60.0);
+    let (x, y) = server.get_player_position(player_id).unwrap();
+    assert!(x.is_finite() && y.is_finite());
+}
+
+#[test]
+fn test_speed_hack_batch_is_throttled() {
+    const PORT: u16 = 27974;
+
+    let mut server = TestServer::new(PORT);
+    server.start().expect("Failed to start server");
+    thread::sleep(Duration::from_millis(100));
+
+    let (mut client, player_id) = connect_client(&mut server, PORT);
+
+    let commands: Vec<PlayerInputCmd> = (0..60).map(|i| cmd(1.0, 0.0, 0, 1000 + i * 50)).collect();
+    send_batch(&mut client, player_id, commands);
+    pump(&mut server, &mut client);
+
+    let stats = server.players[&player_id].input_validator.stats;
+    assert!(
+        stats.throttled > 50,
+        "3 seconds of command time in one packet should be throttled, got {}",
+        stats.throttled
+    );
+    assert!(stats.accepted < 10);
+}
+
+#[test]
+fn test_duplicate_and_old_commands_dropped() {
+    const PORT: u16 = 27975;
+
+    let mut server = TestServer::new(PORT);
+    server.start().expect("Failed to start server");
+    thread::sleep(Duration::from_millis(100));
+
+    let (mut client, player_id) = connect_client(&mut server, PORT);
+
+    send_batch(&mut client, player_id, vec![cmd(1.0, 0.0, 0, 2000)]);
+    pump(&mut server, &mut client);
+
+    send_batch(
+        &mut client,
+        player_id,
+        vec![
+            cmd(-1.0, 0.0, 0, 2000),
+            cmd(-1.0, 0.0, 0, 2000),
+            cmd(-1.0, 0.0, 0, 1500),
+            cmd(-1.0, 0.0, 0, 10),
+        ],
+    );
+    pump(&mut server, &mut client);
+
+    let stats = server.players[&player_id].input_validator.stats;
+    assert_eq!(stats.accepted, 1);
+    assert_eq!(stats.dropped_duplicate, 1);
+    assert_eq!(stats.dropped_old, 2);
+    assert_eq!(server.players[&player_id].last_cmd.right, 1.0);
+}
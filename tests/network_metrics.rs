@@ -0,0 +1,62 @@
+mod common;
+
+use common::TestServer;
+use sas::network::{MetricsWriter, NetworkClient, NetworkConfig};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_server_metrics_track_clients_and_traffic() {
+    const PORT: u16 = 27977;
+
+    let mut server = TestServer::new(PORT);
+    server.start().expect("Failed to start server");
+
+    thread::sleep(Duration::from_millis(100));
+
+    let mut client = NetworkClient::new(NetworkConfig::default());
+    client
+        .connect("Pinger".to_string(), &format!("127.0.0.1:{}", PORT))
+        .expect("Client failed to connect");
+
+    thread::sleep(Duration::from_millis(50));
+    server.update();
+    thread::sleep(Duration::from_millis(50));
+    client.update();
+
+    assert!(client.is_connected(), "Client should be connected");
+    let player_id = client.player_id().expect("Client should have player ID");
+
+    for _ in 0..60 {
+        server.update();
+        client.update();
+        thread::sleep(Duration::from_millis(16));
+    }
+
+    let stats = server.server.stats();
+    assert!(stats.packets_received > 0);
+    assert!(stats.snapshots_sent > 0);
+    assert!(stats.snapshot_bytes_sent > 0);
+    assert!(stats.bytes_sent >= stats.snapshot_bytes_sent);
+
+    let client_stats = server
+        .server
+        .client_stats(player_id)
+        .expect("Server should track client stats");
+    assert!(
+        client_stats.ping < 100,
+        "Loopback ping should be small, got {}",
+        client_stats.ping
+    );
+
+    let mut metrics = MetricsWriter::new();
+    server.server.write_metrics(&mut metrics);
+    let text = metrics.finish();
+
+    assert!(text.contains("sas_clients_connected 1\n"));
+    assert!(text.contains(&format!(
+        "sas_client_ping_ms{{client=\"{}\",name=\"Pinger\"}}",
+        player_id
+    )));
+    assert!(text.contains("# TYPE sas_snapshots_sent_total counter"));
+}
@@ -135,7 +135,7 @@ impl HelpPanel {
         sections.push(HelpSection {
             title: "GLOBAL CONTROLS (Always Available)".to_string(),
             lines: vec![
-                "0-8: Switch tools (0=Select, 1=Draw, 2=Erase, 3=Spawn, 4=Item, 5=Jump, 6=Teleport, 7=Light, 8=BG)".to_string(),
+                "0-9: Switch tools (0=Select, 1=Draw, 2=Erase, 3=Spawn, 4=Item, 5=Jump, 6=Teleport, 7=Light, 8=BG, 9=Flag, U=Liquid)".to_string(),
                 "WASD: Move camera".to_string(),
                 "G: Toggle grid".to_string(),
                 "P: Toggle properties panel".to_string(),
@@ -255,6 +255,31 @@ impl HelpPanel {
                     ],
                 });
             }
+            EditorTool::Flag => {
+                sections.push(HelpSection {
+                    title: "FLAG BASE TOOL (Current)".to_string(),
+                    lines: vec![
+                        "LMB: Place flag base (one per team, replaces existing)".to_string(),
+                        "T: Switch team (Red/Blue)".to_string(),
+                        "When selected:".to_string(),
+                        "  T: Switch team of the selected base".to_string(),
+                        "".to_string(),
+                    ],
+                });
+            }
+            EditorTool::Liquid => {
+                sections.push(HelpSection {
+                    title: "LIQUID TOOL (Current)".to_string(),
+                    lines: vec![
+                        "LMB: Fill tiles with liquid (clears walls)".to_string(),
+                        "Shift+LMB Drag: Fill in straight line".to_string(),
+                        "Q: Cycle brush size (1x1 -> 2x2 -> 4x4)".to_string(),
+                        "T: Cycle liquid (Water -> Slime -> Lava)".to_string(),
+                        "Erase tool (2) removes liquid".to_string(),
+                        "".to_string(),
+                    ],
+                });
+            }
             _ => {}
         }
 
@@ -16,12 +16,17 @@ pub struct ItemIcons {
     pub weapon_railgun: Option<Texture2D>,
     pub weapon_plasma: Option<Texture2D>,
     pub weapon_bfg: Option<Texture2D>,
+    pub weapon_nailgun: Option<Texture2D>,
+    pub weapon_prox: Option<Texture2D>,
+    pub weapon_chaingun: Option<Texture2D>,
     pub powerup_quad: Option<Texture2D>,
     pub powerup_regen: Option<Texture2D>,
     pub powerup_battle: Option<Texture2D>,
     pub powerup_flight: Option<Texture2D>,
     pub powerup_haste: Option<Texture2D>,
     pub powerup_invis: Option<Texture2D>,
+    pub holdable_medkit: Option<Texture2D>,
+    pub holdable_teleporter: Option<Texture2D>,
 }
 
 impl ItemIcons {
@@ -40,12 +45,17 @@ impl ItemIcons {
             weapon_railgun: None,
             weapon_plasma: None,
             weapon_bfg: None,
+            weapon_nailgun: None,
+            weapon_prox: None,
+            weapon_chaingun: None,
             powerup_quad: None,
             powerup_regen: None,
             powerup_battle: None,
             powerup_flight: None,
             powerup_haste: None,
             powerup_invis: None,
+            holdable_medkit: None,
+            holdable_teleporter: None,
         }
     }
 
@@ -63,12 +73,17 @@ impl ItemIcons {
         self.weapon_railgun = Self::load_icon("q3-resources/icons/iconw_railgun.png").await;
         self.weapon_plasma = Self::load_icon("q3-resources/icons/iconw_plasma.png").await;
         self.weapon_bfg = Self::load_icon("q3-resources/icons/iconw_bfg.png").await;
+        self.weapon_nailgun = Self::load_icon("q3-resources/icons/iconw_nailgun.png").await;
+        self.weapon_prox = Self::load_icon("q3-resources/icons/iconw_proxlauncher.png").await;
+        self.weapon_chaingun = Self::load_icon("q3-resources/icons/iconw_chaingun.png").await;
         self.powerup_quad = Self::load_icon("q3-resources/icons/quad.png").await;
         self.powerup_regen = Self::load_icon("q3-resources/icons/regen.png").await;
         self.powerup_battle = Self::load_icon("q3-resources/icons/envirosuit.png").await;
         self.powerup_flight = Self::load_icon("q3-resources/icons/flight.png").await;
         self.powerup_haste = Self::load_icon("q3-resources/icons/haste.png").await;
         self.powerup_invis = Self::load_icon("q3-resources/icons/invis.png").await;
+        self.holdable_medkit = Self::load_icon("q3-resources/icons/medkit.png").await;
+        self.holdable_teleporter = Self::load_icon("q3-resources/icons/teleporter.png").await;
     }
 
     async fn load_icon(path: &str) -> Option<Texture2D> {
@@ -98,12 +113,17 @@ impl ItemIcons {
             map::ItemType::Railgun => self.weapon_railgun.as_ref(),
             map::ItemType::Plasmagun => self.weapon_plasma.as_ref(),
             map::ItemType::BFG => self.weapon_bfg.as_ref(),
+            map::ItemType::Nailgun => self.weapon_nailgun.as_ref(),
+            map::ItemType::ProxLauncher => self.weapon_prox.as_ref(),
+            map::ItemType::Chaingun => self.weapon_chaingun.as_ref(),
             map::ItemType::Quad => self.powerup_quad.as_ref(),
             map::ItemType::Regen => self.powerup_regen.as_ref(),
             map::ItemType::Battle => self.powerup_battle.as_ref(),
             map::ItemType::Flight => self.powerup_flight.as_ref(),
             map::ItemType::Haste => self.powerup_haste.as_ref(),
             map::ItemType::Invis => self.powerup_invis.as_ref(),
+            map::ItemType::Medkit => self.holdable_medkit.as_ref(),
+            map::ItemType::Teleporter => self.holdable_teleporter.as_ref(),
         }
     }
 }
@@ -171,6 +171,9 @@ impl MapSelector {
                 | map::ItemType::Flight
                 | map::ItemType::Haste
                 | map::ItemType::Invis => Color::from_rgba(200, 100, 255, 255),
+                map::ItemType::Medkit | map::ItemType::Teleporter => {
+                    Color::from_rgba(255, 160, 60, 255)
+                }
                 _ => Color::from_rgba(100, 150, 255, 255),
             };
             draw_circle(px, py, radius, color);
@@ -34,6 +34,8 @@ pub struct EditorState {
     current_tool: EditorTool,
     current_texture: u16,
     current_item_type: ItemPlaceType,
+    current_flag_team: u8,
+    current_liquid: map::Liquid,
     map_name: String,
     show_grid: bool,
     show_help: bool,
@@ -90,6 +92,7 @@ impl EditorState {
                         detail_texture: None,
                         glow_texture: None,
                         blend_alpha: 1.0,
+                        liquid: None,
                     };
                     50
                 ];
@@ -122,6 +125,7 @@ impl EditorState {
                 teleporters: vec![],
                 lights: vec![],
                 background_elements: vec![],
+                flags: vec![],
             }
         };
 
@@ -133,6 +137,8 @@ impl EditorState {
             current_tool: EditorTool::Draw,
             current_texture: 1,
             current_item_type: ItemPlaceType::RocketLauncher,
+            current_flag_team: 1,
+            current_liquid: map::Liquid::Water,
             map_name: map_name.to_string(),
             show_grid: true,
             show_help: false,
@@ -504,6 +510,11 @@ impl EditorState {
                                 self.map.background_elements[*idx].x -= move_step;
                             }
                         }
+                        SelectedObject::Flag(idx) => {
+                            if *idx < self.map.flags.len() {
+                                self.map.flags[*idx].x -= move_step;
+                            }
+                        }
                     }
                 }
             }
@@ -549,6 +560,11 @@ impl EditorState {
                                 self.map.background_elements[*idx].x += move_step;
                             }
                         }
+                        SelectedObject::Flag(idx) => {
+                            if *idx < self.map.flags.len() {
+                                self.map.flags[*idx].x += move_step;
+                            }
+                        }
                     }
                 }
             }
@@ -594,6 +610,11 @@ impl EditorState {
                                 self.map.background_elements[*idx].y -= move_step;
                             }
                         }
+                        SelectedObject::Flag(idx) => {
+                            if *idx < self.map.flags.len() {
+                                self.map.flags[*idx].y -= move_step;
+                            }
+                        }
                     }
                 }
             }
@@ -639,6 +660,11 @@ impl EditorState {
                                 self.map.background_elements[*idx].y += move_step;
                             }
                         }
+                        SelectedObject::Flag(idx) => {
+                            if *idx < self.map.flags.len() {
+                                self.map.flags[*idx].y += move_step;
+                            }
+                        }
                     }
                 }
             }
@@ -704,13 +730,27 @@ impl EditorState {
             self.selected_object = None;
             self.selected_teleporter_index = None;
         }
+        if is_key_pressed(KeyCode::Key9) {
+            self.current_tool = EditorTool::Flag;
+            self.selected_object = None;
+            self.selected_teleporter_index = None;
+        }
+        if is_key_pressed(KeyCode::U) {
+            self.current_tool = EditorTool::Liquid;
+            self.selected_object = None;
+            self.selected_teleporter_index = None;
+            self.line_draw_start = None;
+        }
 
         if is_key_pressed(KeyCode::P) {
             self.show_properties = !self.show_properties;
         }
 
         if is_key_pressed(KeyCode::Q) {
-            if self.current_tool == EditorTool::Draw || self.current_tool == EditorTool::Erase {
+            if matches!(
+                self.current_tool,
+                EditorTool::Draw | EditorTool::Erase | EditorTool::Liquid
+            ) {
                 self.brush_size = match self.brush_size {
                     1 => 2,
                     2 => 4,
@@ -777,6 +817,11 @@ impl EditorState {
                             self.map.background_elements.remove(*idx);
                         }
                     }
+                    SelectedObject::Flag(idx) => {
+                        if *idx < self.map.flags.len() {
+                            self.map.flags.remove(*idx);
+                        }
+                    }
                 }
                 self.selected_object = None;
             }
@@ -855,6 +900,18 @@ impl EditorState {
             self.current_item_type = self.current_item_type.next();
         }
 
+        if is_key_pressed(KeyCode::T) {
+            if let Some(SelectedObject::Flag(idx)) = &self.selected_object {
+                if let Some(flag) = self.map.flags.get_mut(*idx) {
+                    flag.team = if flag.team == 1 { 2 } else { 1 };
+                }
+            } else if self.current_tool == EditorTool::Flag {
+                self.current_flag_team = if self.current_flag_team == 1 { 2 } else { 1 };
+            } else if self.current_tool == EditorTool::Liquid {
+                self.current_liquid = next_liquid(self.current_liquid);
+            }
+        }
+
         if self.current_tool == EditorTool::Background {
             if is_key_pressed(KeyCode::B) {
                 if is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl) {
@@ -1169,11 +1226,20 @@ impl EditorState {
                                 self.current_texture;
                             self.map.tiles[x as usize][y as usize].shader_name =
                                 self.current_shader.clone();
+                            self.map.tiles[x as usize][y as usize].liquid = None;
                             self.mark_lightmap_dirty();
                         }
                         EditorTool::Erase => {
                             self.map.tiles[x as usize][y as usize].solid = false;
                             self.map.tiles[x as usize][y as usize].texture_id = 0;
+                            self.map.tiles[x as usize][y as usize].liquid = None;
+                            self.mark_lightmap_dirty();
+                        }
+                        EditorTool::Liquid => {
+                            self.map.tiles[x as usize][y as usize].solid = false;
+                            self.map.tiles[x as usize][y as usize].texture_id = 0;
+                            self.map.tiles[x as usize][y as usize].liquid =
+                                Some(self.current_liquid);
                             self.mark_lightmap_dirty();
                         }
                         _ => {}
@@ -1195,7 +1261,10 @@ impl EditorState {
             self.last_bg_pos = None;
         }
 
-        if self.current_tool == EditorTool::Draw || self.current_tool == EditorTool::Erase {
+        if matches!(
+            self.current_tool,
+            EditorTool::Draw | EditorTool::Erase | EditorTool::Liquid
+        ) {
             if is_shift {
                 if is_mouse_button_pressed(MouseButton::Left) {
                     self.line_draw_start = Some((tile_x, tile_y));
@@ -1294,6 +1363,17 @@ impl EditorState {
                             }
                         }
                     }
+
+                    if self.selected_object.is_none() {
+                        for (idx, flag) in self.map.flags.iter().enumerate() {
+                            let dx = flag.x - world_x;
+                            let dy = flag.y - world_y;
+                            if dx * dx + dy * dy < 400.0 {
+                                self.selected_object = Some(SelectedObject::Flag(idx));
+                                break;
+                            }
+                        }
+                    }
                 }
                 EditorTool::SpawnPoint => {
                     self.map.spawn_points.push(map::SpawnPoint {
@@ -1326,12 +1406,17 @@ impl EditorState {
                         ItemPlaceType::Railgun => map::ItemType::Railgun,
                         ItemPlaceType::Plasmagun => map::ItemType::Plasmagun,
                         ItemPlaceType::BFG => map::ItemType::BFG,
+                        ItemPlaceType::Nailgun => map::ItemType::Nailgun,
+                        ItemPlaceType::ProxLauncher => map::ItemType::ProxLauncher,
+                        ItemPlaceType::Chaingun => map::ItemType::Chaingun,
                         ItemPlaceType::Quad => map::ItemType::Quad,
                         ItemPlaceType::Regen => map::ItemType::Regen,
                         ItemPlaceType::Battle => map::ItemType::Battle,
                         ItemPlaceType::Flight => map::ItemType::Flight,
                         ItemPlaceType::Haste => map::ItemType::Haste,
                         ItemPlaceType::Invis => map::ItemType::Invis,
+                        ItemPlaceType::Medkit => map::ItemType::Medkit,
+                        ItemPlaceType::Teleporter => map::ItemType::Teleporter,
                     };
                     let aligned_y = self.find_floor_y(world_x, world_y);
                     self.map.items.push(map::Item {
@@ -1403,6 +1488,16 @@ impl EditorState {
                 EditorTool::Background => {
                     self.draw_background_at(world_x, world_y);
                 }
+                EditorTool::Flag => {
+                    let team = self.current_flag_team;
+                    let aligned_y = self.find_floor_y(world_x, world_y);
+                    self.map.flags.retain(|f| f.team != team);
+                    self.map.flags.push(map::FlagBase {
+                        x: world_x,
+                        y: aligned_y,
+                        team,
+                    });
+                }
                 _ => {}
             }
         }
@@ -1432,6 +1527,12 @@ impl EditorState {
                 dx * dx + dy * dy > 900.0
             });
 
+            self.map.flags.retain(|flag| {
+                let dx = flag.x - world_x;
+                let dy = flag.y - world_y;
+                dx * dx + dy * dy > 400.0
+            });
+
             let lights_before = self.map.lights.len();
             self.map.lights.retain(|light| {
                 let dx = light.x - world_x;
@@ -1701,6 +1802,16 @@ impl EditorState {
                     );
                 }
 
+                if let Some(liquid) = self.map.tiles[x as usize][y as usize].liquid {
+                    draw_rectangle(
+                        ((x as f32 * TILE_WIDTH) - self.camera_x) * self.zoom,
+                        ((y as f32 * TILE_HEIGHT) - self.camera_y) * self.zoom,
+                        TILE_WIDTH * self.zoom,
+                        TILE_HEIGHT * self.zoom,
+                        liquid_color(liquid),
+                    );
+                }
+
                 if self.show_grid {
                     let screen_x = ((x as f32 * TILE_WIDTH) - self.camera_x) * self.zoom;
                     let screen_y = ((y as f32 * TILE_HEIGHT) - self.camera_y) * self.zoom;
@@ -1730,6 +1841,37 @@ impl EditorState {
             draw_text("S", screen_x - 4.0, screen_y + 4.0, 16.0, BLACK);
         }
 
+        for (idx, flag) in self.map.flags.iter().enumerate() {
+            let screen_x = (flag.x - self.camera_x) * self.zoom;
+            let screen_y = (flag.y - self.camera_y) * self.zoom;
+            let is_selected =
+                matches!(&self.selected_object, Some(SelectedObject::Flag(i)) if *i == idx);
+            let color = if flag.team == 1 {
+                Color::from_rgba(230, 40, 40, 255)
+            } else {
+                Color::from_rgba(40, 90, 240, 255)
+            };
+
+            if is_selected {
+                draw_circle(screen_x, screen_y, 18.0, Color::from_rgba(255, 255, 0, 200));
+            }
+            draw_rectangle(screen_x - 10.0, screen_y + 8.0, 20.0, 4.0, GRAY);
+            draw_line(
+                screen_x,
+                screen_y + 8.0,
+                screen_x,
+                screen_y - 16.0,
+                2.0,
+                WHITE,
+            );
+            draw_triangle(
+                vec2(screen_x, screen_y - 16.0),
+                vec2(screen_x + 14.0, screen_y - 11.0),
+                vec2(screen_x, screen_y - 6.0),
+                color,
+            );
+        }
+
         for (idx, item) in self.map.items.iter().enumerate() {
             let screen_x = (item.x - self.camera_x) * self.zoom;
             let screen_y = (item.y - self.camera_y) * self.zoom;
@@ -1764,6 +1906,7 @@ impl EditorState {
                     | map::ItemType::Flight
                     | map::ItemType::Haste
                     | map::ItemType::Invis => PURPLE,
+                    map::ItemType::Medkit | map::ItemType::Teleporter => ORANGE,
                     _ => BLUE,
                 };
                 draw_circle(screen_x, screen_y, 8.0, color);
@@ -1946,7 +2089,7 @@ impl EditorState {
             && tile_y < self.map.height as i32
         {
             match self.current_tool {
-                EditorTool::Draw | EditorTool::Erase => {
+                EditorTool::Draw | EditorTool::Erase | EditorTool::Liquid => {
                     let half_size = self.brush_size / 2;
 
                     for bx in 0..self.brush_size {
@@ -1962,10 +2105,10 @@ impl EditorState {
                                 let sx = ((x as f32 * TILE_WIDTH) - self.camera_x) * self.zoom;
                                 let sy = ((y as f32 * TILE_HEIGHT) - self.camera_y) * self.zoom;
 
-                                let color = if self.current_tool == EditorTool::Draw {
-                                    Color::from_rgba(255, 255, 255, 100)
-                                } else {
-                                    Color::from_rgba(255, 0, 0, 100)
+                                let color = match self.current_tool {
+                                    EditorTool::Draw => Color::from_rgba(255, 255, 255, 100),
+                                    EditorTool::Liquid => liquid_color(self.current_liquid),
+                                    _ => Color::from_rgba(255, 0, 0, 100),
                                 };
 
                                 draw_rectangle(
@@ -2670,12 +2813,17 @@ impl EditorState {
                             map::ItemType::Railgun => "Railgun",
                             map::ItemType::Plasmagun => "Plasma Gun",
                             map::ItemType::BFG => "BFG",
+                            map::ItemType::Nailgun => "Nailgun",
+                            map::ItemType::ProxLauncher => "Prox Launcher",
+                            map::ItemType::Chaingun => "Chaingun",
                             map::ItemType::Quad => "Quad Damage",
                             map::ItemType::Regen => "Regeneration",
                             map::ItemType::Battle => "Battle Suit",
                             map::ItemType::Flight => "Flight",
                             map::ItemType::Haste => "Haste",
                             map::ItemType::Invis => "Invisibility",
+                            map::ItemType::Medkit => "Medkit",
+                            map::ItemType::Teleporter => "Personal Teleporter",
                         };
                         (
                             "Item Properties".to_string(),
@@ -2773,6 +2921,22 @@ impl EditorState {
                         return;
                     }
                 }
+                SelectedObject::Flag(idx) => {
+                    if let Some(flag) = self.map.flags.get(*idx) {
+                        (
+                            "Flag Base Properties".to_string(),
+                            vec![
+                                format!("Team: {}", flag_team_name(flag.team)),
+                                format!("Position X: {:.1}", flag.x),
+                                format!("Position Y: {:.1}", flag.y),
+                                String::new(),
+                                "T: Switch team".to_string(),
+                            ],
+                        )
+                    } else {
+                        return;
+                    }
+                }
             };
 
             let panel_height = 60.0 + properties.len() as f32 * 22.0;
@@ -2868,6 +3032,13 @@ impl EditorState {
             EditorTool::Teleporter => "Teleporter (Click to place or edit)".to_string(),
             EditorTool::TeleporterDestination => "Set Teleporter Destination".to_string(),
             EditorTool::Light => "Light Source".to_string(),
+            EditorTool::Flag => format!("Flag Base ({})", flag_team_name(self.current_flag_team)),
+            EditorTool::Liquid => format!(
+                "Liquid ({}) Brush: {}x{}",
+                liquid_name(self.current_liquid),
+                self.brush_size,
+                self.brush_size
+            ),
             EditorTool::Background => {
                 let bg_name = if !self.background_textures.is_empty() {
                     &self.background_textures[self.current_bg_texture].name
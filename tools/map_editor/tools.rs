@@ -1,3 +1,6 @@
+use super::map::Liquid;
+use macroquad::prelude::Color;
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum EditorTool {
     Draw,
@@ -10,6 +13,8 @@ pub enum EditorTool {
     Light,
     Select,
     Background,
+    Flag,
+    Liquid,
 }
 
 #[derive(Clone, PartialEq)]
@@ -20,6 +25,39 @@ pub enum SelectedObject {
     Teleporter(usize),
     Light(usize),
     BackgroundElement(usize),
+    Flag(usize),
+}
+
+pub fn flag_team_name(team: u8) -> &'static str {
+    match team {
+        1 => "Red",
+        2 => "Blue",
+        _ => "None",
+    }
+}
+
+pub fn liquid_name(liquid: Liquid) -> &'static str {
+    match liquid {
+        Liquid::Water => "Water",
+        Liquid::Slime => "Slime",
+        Liquid::Lava => "Lava",
+    }
+}
+
+pub fn next_liquid(liquid: Liquid) -> Liquid {
+    match liquid {
+        Liquid::Water => Liquid::Slime,
+        Liquid::Slime => Liquid::Lava,
+        Liquid::Lava => Liquid::Water,
+    }
+}
+
+pub fn liquid_color(liquid: Liquid) -> Color {
+    match liquid {
+        Liquid::Water => Color::from_rgba(40, 90, 170, 110),
+        Liquid::Slime => Color::from_rgba(90, 170, 40, 140),
+        Liquid::Lava => Color::from_rgba(230, 80, 20, 200),
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -36,12 +74,17 @@ pub enum ItemPlaceType {
     Railgun,
     Plasmagun,
     BFG,
+    Nailgun,
+    ProxLauncher,
+    Chaingun,
     Quad,
     Regen,
     Battle,
     Flight,
     Haste,
     Invis,
+    Medkit,
+    Teleporter,
 }
 
 impl ItemPlaceType {
@@ -59,12 +102,17 @@ impl ItemPlaceType {
             ItemPlaceType::Railgun => "Railgun",
             ItemPlaceType::Plasmagun => "Plasmagun",
             ItemPlaceType::BFG => "BFG",
+            ItemPlaceType::Nailgun => "Nailgun",
+            ItemPlaceType::ProxLauncher => "Prox Launcher",
+            ItemPlaceType::Chaingun => "Chaingun",
             ItemPlaceType::Quad => "Quad",
             ItemPlaceType::Regen => "Regen",
             ItemPlaceType::Battle => "Battle",
             ItemPlaceType::Flight => "Flight",
             ItemPlaceType::Haste => "Haste",
             ItemPlaceType::Invis => "Invis",
+            ItemPlaceType::Medkit => "Medkit",
+            ItemPlaceType::Teleporter => "Teleporter",
         }
         .to_string()
     }
@@ -82,13 +130,18 @@ impl ItemPlaceType {
             ItemPlaceType::LightningGun => ItemPlaceType::Railgun,
             ItemPlaceType::Railgun => ItemPlaceType::Plasmagun,
             ItemPlaceType::Plasmagun => ItemPlaceType::BFG,
-            ItemPlaceType::BFG => ItemPlaceType::Quad,
+            ItemPlaceType::BFG => ItemPlaceType::Nailgun,
+            ItemPlaceType::Nailgun => ItemPlaceType::ProxLauncher,
+            ItemPlaceType::ProxLauncher => ItemPlaceType::Chaingun,
+            ItemPlaceType::Chaingun => ItemPlaceType::Quad,
             ItemPlaceType::Quad => ItemPlaceType::Regen,
             ItemPlaceType::Regen => ItemPlaceType::Battle,
             ItemPlaceType::Battle => ItemPlaceType::Flight,
             ItemPlaceType::Flight => ItemPlaceType::Haste,
             ItemPlaceType::Haste => ItemPlaceType::Invis,
-            ItemPlaceType::Invis => ItemPlaceType::Health25,
+            ItemPlaceType::Invis => ItemPlaceType::Medkit,
+            ItemPlaceType::Medkit => ItemPlaceType::Teleporter,
+            ItemPlaceType::Teleporter => ItemPlaceType::Health25,
         }
     }
 }